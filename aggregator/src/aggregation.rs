@@ -25,3 +25,4 @@ pub(crate) use rlc::RlcConfig;
 
 pub use circuit::AggregationCircuit;
 pub use config::AggregationConfig;
+pub use decoder::witgen::DecodeError;
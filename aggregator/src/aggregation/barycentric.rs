@@ -18,7 +18,7 @@ use num_bigint::{BigInt, Sign};
 use std::{iter::successors, sync::LazyLock};
 
 use crate::{
-    blob::BLOB_WIDTH,
+    blob::{PointEvaluationAssignments, BLOB_WIDTH},
     constants::{BITS, LIMBS},
 };
 
@@ -64,6 +64,13 @@ pub struct AssignedBarycentricEvaluationConfig {
     pub(crate) y_le: Vec<AssignedValue<Fr>>,
 }
 
+/// Constants that are loaded once and shared by the barycentric evaluation of every blob.
+struct BarycentricConstants {
+    one: CRTInteger<Fr>,
+    blob_width: CRTInteger<Fr>,
+    roots_of_unity: Vec<CRTInteger<Fr>>,
+}
+
 impl BarycentricEvaluationConfig {
     pub fn construct(range: RangeConfig<Fr>) -> Self {
         Self {
@@ -98,18 +105,52 @@ impl BarycentricEvaluationConfig {
         )
     }
 
+    /// Assign the barycentric evaluation of each blob in the batch, at the blob's own challenge
+    /// point.
     pub fn assign(
         &self,
         ctx: &mut Context<Fr>,
+        point_evaluations: &[PointEvaluationAssignments],
+    ) -> Vec<AssignedBarycentricEvaluationConfig> {
+        // some constants for later use.
+        let constants = BarycentricConstants {
+            one: self.scalar.load_constant(ctx, fe_to_biguint(&Fr::one())),
+            blob_width: self
+                .scalar
+                .load_constant(ctx, fe_to_biguint(&Fr::from(BLOB_WIDTH as u64))),
+            roots_of_unity: ROOTS_OF_UNITY
+                .iter()
+                .map(|x| self.scalar.load_constant(ctx, fe_to_biguint(x)))
+                .collect(),
+        };
+
+        point_evaluations
+            .iter()
+            .map(|point_evaluation| {
+                self.assign_blob(
+                    ctx,
+                    &constants,
+                    &point_evaluation.coefficients,
+                    point_evaluation.challenge_digest,
+                    point_evaluation.evaluation,
+                )
+            })
+            .collect()
+    }
+
+    fn assign_blob(
+        &self,
+        ctx: &mut Context<Fr>,
+        constants: &BarycentricConstants,
         blob: &[U256; BLOB_WIDTH],
         challenge_digest: U256,
         evaluation: U256,
     ) -> AssignedBarycentricEvaluationConfig {
-        // some constants for later use.
-        let one = self.scalar.load_constant(ctx, fe_to_biguint(&Fr::one()));
-        let blob_width = self
-            .scalar
-            .load_constant(ctx, fe_to_biguint(&Fr::from(BLOB_WIDTH as u64)));
+        let BarycentricConstants {
+            one,
+            blob_width,
+            roots_of_unity,
+        } = constants;
 
         let powers_of_256 =
             std::iter::successors(Some(Fr::one()), |coeff| Some(Fr::from(256) * coeff))
@@ -117,11 +158,6 @@ impl BarycentricEvaluationConfig {
                 .map(QuantumCell::Constant)
                 .collect::<Vec<_>>();
 
-        let roots_of_unity = ROOTS_OF_UNITY
-            .iter()
-            .map(|x| self.scalar.load_constant(ctx, fe_to_biguint(x)))
-            .collect::<Vec<_>>();
-
         ////////////////////////////////////////////////////////////////////////////////////////
         ////////////////////////////////// PRECHECKS z /////////////////////////////////////////
         ////////////////////////////////////////////////////////////////////////////////////////
@@ -309,11 +345,11 @@ impl BarycentricEvaluationConfig {
         let z_to_blob_width = (0..LOG_BLOB_WIDTH).fold(challenge_crt.clone(), |acc, _| {
             self.scalar.mul(ctx, &acc, &acc)
         });
-        let z_to_blob_width_minus_one = self.scalar.sub_no_carry(ctx, &z_to_blob_width, &one);
+        let z_to_blob_width_minus_one = self.scalar.sub_no_carry(ctx, &z_to_blob_width, one);
         let z_to_blob_width_minus_one = self.scalar.carry_mod(ctx, &z_to_blob_width_minus_one);
         let factor = self
             .scalar
-            .divide(ctx, &z_to_blob_width_minus_one, &blob_width);
+            .divide(ctx, &z_to_blob_width_minus_one, blob_width);
         evaluation_computed = self.scalar.mul(ctx, &evaluation_computed, &factor);
         evaluation_computed = self.scalar.carry_mod(ctx, &evaluation_computed);
 
@@ -395,12 +431,13 @@ mod tests {
             vec![0; 340],
             vec![10; 23],
         ]);
+        let coefficients = batch.get_coefficients()[0].map(|c| Scalar::from_raw(c.0));
 
         for z in 0..10 {
             let z = Scalar::from(u64::try_from(13241234 + z).unwrap());
             assert_eq!(
-                reth_point_evaluation(z, &batch.get_coefficients().map(|c| Scalar::from_raw(c.0))),
-                interpolate(z, &batch.get_coefficients().map(|c| Scalar::from_raw(c.0)))
+                reth_point_evaluation(z, &coefficients),
+                interpolate(z, &coefficients)
             );
         }
     }
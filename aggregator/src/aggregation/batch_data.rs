@@ -1,5 +1,4 @@
 use ethers_core::utils::keccak256;
-use halo2_ecc::bigint::CRTInteger;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     halo2curves::bn256::Fr,
@@ -13,8 +12,9 @@ use zkevm_circuits::{
 };
 
 use crate::{
-    aggregation::rlc::POWS_OF_256,
-    blob::{BatchData, BLOB_WIDTH, N_BYTES_U256},
+    aggregation::{rlc::POWS_OF_256, AssignedBarycentricEvaluationConfig},
+    batch::DataAvailability,
+    blob::{BatchData, BLOB_WIDTH, MAX_BLOBS_PER_BATCH, N_BYTES_U256},
     RlcConfig,
 };
 
@@ -59,7 +59,7 @@ pub struct BatchDataConfig<const N_SNARKS: usize> {
 pub struct AssignedBatchDataExport {
     pub num_valid_chunks: AssignedCell<Fr, Fr>,
     pub batch_data_len: AssignedCell<Fr, Fr>,
-    pub versioned_hash: Vec<AssignedCell<Fr, Fr>>,
+    pub versioned_hashes: Vec<Vec<AssignedCell<Fr, Fr>>>,
     pub chunk_data_digests: Vec<Vec<AssignedCell<Fr, Fr>>>,
     pub bytes_rlc: AssignedCell<Fr, Fr>,
 }
@@ -339,7 +339,7 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
                 let is_boundary = meta.query_advice(config.is_boundary, Rotation::cur());
 
                 // when is_boundary is set in the "digest RLC" section.
-                // this is also the last row of the "digest RLC" section.
+                // this is the case for the challenge digest rows of each blob.
                 let cond = is_hash * is_boundary;
 
                 // - metadata_digest: 32 bytes
@@ -381,13 +381,16 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
         // `core.rs`. Since these are already constrained, we can just use them as is.
         chunks_are_padding: &[AssignedCell<Fr, Fr>],
         batch_data: &BatchData<N_SNARKS>,
-        barycentric_assignments: &[CRTInteger<Fr>],
+        data_availability: DataAvailability,
+        barycentric_assignments: &[AssignedBarycentricEvaluationConfig],
     ) -> Result<AssignedBatchDataExport, Error> {
         self.load_range_tables(layouter)?;
 
         let assigned_rows = layouter.assign_region(
             || "BatchData rows",
-            |mut region| self.assign_rows(&mut region, challenge_value, batch_data),
+            |mut region| {
+                self.assign_rows(&mut region, challenge_value, batch_data, data_availability)
+            },
         )?;
 
         layouter.assign_region(
@@ -398,6 +401,7 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
                     challenge_value,
                     rlc_config,
                     chunks_are_padding,
+                    data_availability,
                     barycentric_assignments,
                     &assigned_rows,
                 )
@@ -415,13 +419,14 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
         region: &mut Region<Fr>,
         challenge_value: Challenges<Value<Fr>>,
         batch_data: &BatchData<N_SNARKS>,
+        data_availability: DataAvailability,
     ) -> Result<Vec<AssignedBatchDataConfig>, Error> {
         let n_rows_data = BatchData::<N_SNARKS>::n_rows_data();
         let n_rows_metadata = BatchData::<N_SNARKS>::n_rows_metadata();
         let n_rows_digest_rlc = BatchData::<N_SNARKS>::n_rows_digest_rlc();
         let n_rows_total = BatchData::<N_SNARKS>::n_rows();
 
-        let rows = batch_data.to_rows(challenge_value);
+        let rows = batch_data.to_rows(challenge_value, data_availability);
         assert_eq!(rows.len(), n_rows_total);
 
         // enable data selector
@@ -529,7 +534,8 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
         // The chunks_are_padding assigned cells are exports from the conditional constraints in
         // `core.rs`. Since these are already constrained, we can just use them as is.
         chunks_are_padding: &[AssignedCell<Fr, Fr>],
-        barycentric_assignments: &[CRTInteger<Fr>],
+        data_availability: DataAvailability,
+        barycentric_assignments: &[AssignedBarycentricEvaluationConfig],
         assigned_rows: &[AssignedBatchDataConfig],
     ) -> Result<AssignedBatchDataExport, Error> {
         let n_rows_metadata = BatchData::<N_SNARKS>::n_rows_metadata();
@@ -821,13 +827,30 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
             region.constrain_equal(row.chunk_idx.cell(), fixed_chunk_idx.cell())?;
         }
 
-        let challenge_digest_preimage_rlc_specified = &rows.last().unwrap().preimage_rlc;
-        let challenge_digest_rlc_specified = &rows.last().unwrap().digest_rlc;
-        let versioned_hash_rlc = &rows.get(n_rows_digest_rlc - 2).unwrap().digest_rlc;
-
-        // ensure that on the last row of this section the is_boundary is turned on
-        // which would enable the keccak table lookup for challenge_digest
-        region.constrain_equal(rows.last().unwrap().is_boundary.cell(), one.cell())?;
+        // the chunks' rows are followed by the versioned hash of each blob, and then the
+        // challenge digest of each blob.
+        let versioned_hash_rlcs = rows
+            .iter()
+            .skip(1 + N_SNARKS)
+            .take(MAX_BLOBS_PER_BATCH)
+            .map(|row| &row.digest_rlc)
+            .collect::<Vec<_>>();
+        let challenge_digest_rows = rows
+            .iter()
+            .skip(1 + N_SNARKS + MAX_BLOBS_PER_BATCH)
+            .collect::<Vec<_>>();
+        assert_eq!(challenge_digest_rows.len(), MAX_BLOBS_PER_BATCH);
+
+        // ensure that on the challenge digest rows of this section the is_boundary is turned on
+        // which would enable the keccak table lookup for each blob's challenge_digest. The
+        // challenges are not used for calldata DA, hence not looked up either.
+        let is_challenge_digest_looked_up = match data_availability {
+            DataAvailability::Blob => &one,
+            DataAvailability::Calldata => &zero,
+        };
+        for row in challenge_digest_rows.iter() {
+            region.constrain_equal(row.is_boundary.cell(), is_challenge_digest_looked_up.cell())?;
+        }
 
         let metadata_digest_rlc_computed =
             &assigned_rows.get(n_rows_metadata - 1).unwrap().digest_rlc;
@@ -888,7 +911,8 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
         ///////////////////////////////// DIGEST BYTES /////////////////////////////////
         ////////////////////////////////////////////////////////////////////////////////
 
-        let mut challenge_digest_preimage_keccak_rlc = zero.clone();
+        let mut common_preimage_keccak_rlc = zero.clone();
+        let mut versioned_hash_keccak_rlcs = Vec::with_capacity(MAX_BLOBS_PER_BATCH);
         let rows = assigned_rows
             .iter()
             .skip(n_rows_metadata + n_rows_data + n_rows_digest_rlc)
@@ -896,8 +920,8 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
             .collect::<Vec<_>>();
         for (i, digest_rlc_specified) in std::iter::once(metadata_digest_rlc_specified)
             .chain(chunk_digest_evm_rlcs)
-            .chain(std::iter::once(versioned_hash_rlc))
-            .chain(std::iter::once(challenge_digest_rlc_specified))
+            .chain(versioned_hash_rlcs)
+            .chain(challenge_digest_rows.iter().map(|row| &row.digest_rlc))
             .enumerate()
         {
             let digest_rows = rows
@@ -914,55 +938,72 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
             region.constrain_equal(digest_rlc_computed.cell(), digest_rlc_specified.cell())?;
 
             // compute the keccak input RLC:
-            // we do this only for the metadata and chunks, not for the blob row itself.
-            if i < N_SNARKS + 1 + 1 {
+            // - the metadata and chunks form the common prefix of every challenge digest preimage.
+            // - each blob's versioned hash completes the preimage of that blob's challenge digest.
+            // we do not do this for the challenge digest rows themselves.
+            if i < 1 + N_SNARKS {
                 let digest_keccak_rlc =
                     rlc_config.rlc(region, &digest_bytes, &r_keccak, &mut rlc_config_offset)?;
-                challenge_digest_preimage_keccak_rlc = rlc_config.mul_add(
+                common_preimage_keccak_rlc = rlc_config.mul_add(
                     region,
-                    &challenge_digest_preimage_keccak_rlc,
+                    &common_preimage_keccak_rlc,
                     &r32,
                     &digest_keccak_rlc,
                     &mut rlc_config_offset,
                 )?;
+            } else if i < 1 + N_SNARKS + MAX_BLOBS_PER_BATCH {
+                let digest_keccak_rlc =
+                    rlc_config.rlc(region, &digest_bytes, &r_keccak, &mut rlc_config_offset)?;
+                versioned_hash_keccak_rlcs.push(digest_keccak_rlc);
+            }
+        }
+        if data_availability == DataAvailability::Blob {
+            for (versioned_hash_keccak_rlc, row) in versioned_hash_keccak_rlcs
+                .iter()
+                .zip_eq(challenge_digest_rows.iter())
+            {
+                let challenge_digest_preimage_keccak_rlc = rlc_config.mul_add(
+                    region,
+                    &common_preimage_keccak_rlc,
+                    &r32,
+                    versioned_hash_keccak_rlc,
+                    &mut rlc_config_offset,
+                )?;
+                region.constrain_equal(
+                    challenge_digest_preimage_keccak_rlc.cell(),
+                    row.preimage_rlc.cell(),
+                )?;
             }
         }
-        region.constrain_equal(
-            challenge_digest_preimage_keccak_rlc.cell(),
-            challenge_digest_preimage_rlc_specified.cell(),
-        )?;
 
         ////////////////////////////////////////////////////////////////////////////////
         //////////////////////////////////// EXPORT ////////////////////////////////////
         ////////////////////////////////////////////////////////////////////////////////
 
-        let mut chunk_data_digests = Vec::with_capacity(N_SNARKS);
-        let chunk_data_digests_bytes = assigned_rows
+        // the "digest bytes" section holds the metadata digest, followed by the chunk data
+        // digests, the versioned hashes and the challenge digests.
+        let digest_bytes = assigned_rows
             .iter()
-            .skip(n_rows_metadata + n_rows_data + n_rows_digest_rlc + N_BYTES_U256)
-            .take(N_SNARKS * N_BYTES_U256)
+            .skip(n_rows_metadata + n_rows_data + n_rows_digest_rlc)
             .map(|row| row.byte.clone())
             .collect::<Vec<_>>();
-        for chunk in chunk_data_digests_bytes.chunks_exact(N_BYTES_U256) {
-            chunk_data_digests.push(chunk.to_vec());
-        }
-        let challenge_digest = assigned_rows
-            .iter()
-            .rev()
-            .take(N_BYTES_U256)
-            .map(|row| row.byte.clone())
-            .collect::<Vec<AssignedCell<Fr, Fr>>>();
+        let mut digests = digest_bytes
+            .chunks_exact(N_BYTES_U256)
+            .map(|digest| digest.to_vec())
+            .skip(1);
+        let chunk_data_digests = digests.by_ref().take(N_SNARKS).collect::<Vec<_>>();
+        let versioned_hashes = digests
+            .by_ref()
+            .take(MAX_BLOBS_PER_BATCH)
+            .collect::<Vec<_>>();
+        // the challenge digests are in little-endian order.
+        let challenge_digests = digests
+            .map(|digest| digest.into_iter().rev().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
         let export = AssignedBatchDataExport {
             num_valid_chunks,
             batch_data_len,
-            versioned_hash: assigned_rows
-                .iter()
-                .rev()
-                .skip(N_BYTES_U256)
-                .take(N_BYTES_U256)
-                .map(|row| row.byte.clone())
-                .rev()
-                .collect(),
+            versioned_hashes,
             chunk_data_digests,
             // bytes rlc is from the last row of the "chunk data" section.
             bytes_rlc: assigned_rows
@@ -976,40 +1017,53 @@ impl<const N_SNARKS: usize> BatchDataConfig<N_SNARKS> {
         //////////////////////////// CHALLENGE DIGEST CHECK ////////////////////////////
         ////////////////////////////////////////////////////////////////////////////////
 
-        assert_eq!(barycentric_assignments.len(), BLOB_WIDTH + 1);
-        let challenge_digest_crt = barycentric_assignments
-            .get(BLOB_WIDTH)
-            .expect("challenge digest CRT");
-        let challenge_digest_limb1 = rlc_config.inner_product(
-            region,
-            &challenge_digest[0..11],
-            &pows_of_256,
-            &mut rlc_config_offset,
-        )?;
-        let challenge_digest_limb2 = rlc_config.inner_product(
-            region,
-            &challenge_digest[11..22],
-            &pows_of_256,
-            &mut rlc_config_offset,
-        )?;
-        let challenge_digest_limb3 = rlc_config.inner_product(
-            region,
-            &challenge_digest[22..32],
-            &pows_of_256[0..10],
-            &mut rlc_config_offset,
-        )?;
-        region.constrain_equal(
-            challenge_digest_limb1.cell(),
-            challenge_digest_crt.truncation.limbs[0].cell(),
-        )?;
-        region.constrain_equal(
-            challenge_digest_limb2.cell(),
-            challenge_digest_crt.truncation.limbs[1].cell(),
-        )?;
-        region.constrain_equal(
-            challenge_digest_limb3.cell(),
-            challenge_digest_crt.truncation.limbs[2].cell(),
-        )?;
+        // the blobs are not evaluated at their challenge point for calldata DA.
+        if data_availability == DataAvailability::Calldata {
+            assert!(barycentric_assignments.is_empty());
+            return Ok(export);
+        }
+
+        assert_eq!(barycentric_assignments.len(), MAX_BLOBS_PER_BATCH);
+        for (challenge_digest, barycentric) in challenge_digests
+            .iter()
+            .zip_eq(barycentric_assignments.iter())
+        {
+            assert_eq!(barycentric.barycentric_assignments.len(), BLOB_WIDTH + 1);
+            let challenge_digest_crt = barycentric
+                .barycentric_assignments
+                .get(BLOB_WIDTH)
+                .expect("challenge digest CRT");
+            let challenge_digest_limb1 = rlc_config.inner_product(
+                region,
+                &challenge_digest[0..11],
+                &pows_of_256,
+                &mut rlc_config_offset,
+            )?;
+            let challenge_digest_limb2 = rlc_config.inner_product(
+                region,
+                &challenge_digest[11..22],
+                &pows_of_256,
+                &mut rlc_config_offset,
+            )?;
+            let challenge_digest_limb3 = rlc_config.inner_product(
+                region,
+                &challenge_digest[22..32],
+                &pows_of_256[0..10],
+                &mut rlc_config_offset,
+            )?;
+            region.constrain_equal(
+                challenge_digest_limb1.cell(),
+                challenge_digest_crt.truncation.limbs[0].cell(),
+            )?;
+            region.constrain_equal(
+                challenge_digest_limb2.cell(),
+                challenge_digest_crt.truncation.limbs[1].cell(),
+            )?;
+            region.constrain_equal(
+                challenge_digest_limb3.cell(),
+                challenge_digest_crt.truncation.limbs[2].cell(),
+            )?;
+        }
 
         Ok(export)
     }
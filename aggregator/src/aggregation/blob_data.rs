@@ -1,7 +1,4 @@
-use std::io::Write;
-
 use gadgets::util::Expr;
-use halo2_ecc::bigint::CRTInteger;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     halo2curves::bn256::Fr,
@@ -12,8 +9,11 @@ use itertools::Itertools;
 use zkevm_circuits::{table::U8Table, util::Challenges};
 
 use crate::{
-    aggregation::{decoder::witgen::init_zstd_encoder, rlc::POWS_OF_256},
-    blob::{BatchData, BLOB_WIDTH, N_BLOB_BYTES, N_DATA_BYTES_PER_COEFFICIENT},
+    aggregation::{rlc::POWS_OF_256, AssignedBarycentricEvaluationConfig},
+    batch::DataAvailability,
+    blob::{
+        BatchData, BLOB_WIDTH, MAX_BLOBS_PER_BATCH, N_BLOB_BYTES, N_DATA_BYTES_PER_COEFFICIENT,
+    },
     RlcConfig,
 };
 
@@ -23,32 +23,49 @@ use crate::{
 /// byte in each 32-bytes chunk to zero, i.e. effectively we use only 31 bytes.
 ///
 /// Since the check for the most-significant byte being zero is already done in the
-/// BarycentricConfig, in the BlobDataConfig we only represent the 31 meaningful bytes. Hence the
-/// BlobDataConfig has 4096 * 31 rows. Each row is a byte value and the purpose of the
-/// BlobDataConfig is to compute a random-linear combination of these bytes. These bytes are in
-/// fact the zstd encoded form of the raw batch data represented in BatchDataConfig.
+/// BarycentricConfig, in the BlobDataConfig we only represent the 31 meaningful bytes. A batch may
+/// span up to MAX_BLOBS_PER_BATCH blobs, hence the BlobDataConfig has MAX_BLOBS_PER_BATCH * 4096 *
+/// 31 rows, where the bytes of the blobs are laid out one after the other. Each row is a byte value
+/// and the purpose of the BlobDataConfig is to compute a random-linear combination of these bytes.
+///
+/// The first byte is the is_compressed flag, which is either 1 or 0. If set, the following bytes
+/// are the zstd encoded form of the raw batch data represented in BatchDataConfig, otherwise they
+/// are the raw batch data itself. The is_compressed flag is not part of the random-linear
+/// combination, but it is of the random-linear combination of the calldata, i.e. all the bytes,
+/// that is used if the batch data is made available via calldata instead of blobs.
 #[derive(Clone, Debug)]
 pub struct BlobDataConfig<const N_SNARKS: usize> {
-    /// Selector to mark the first row in the layout, enabled at offset=0.
+    /// Selector to mark the first row in the layout, enabled at offset=0. The is_compressed flag
+    /// is at offset=1.
     q_first: Selector,
-    /// Whether the row is enabled or not. We need exactly N_BLOB_BYTES rows, enabled from offset=1
-    /// to offset=N_BLOB_BYTES.
+    /// Whether the row is enabled or not. We need exactly MAX_BLOBS_PER_BATCH * N_BLOB_BYTES rows,
+    /// the first of which holds the is_compressed flag. Hence enabled from offset=2 to
+    /// offset=MAX_BLOBS_PER_BATCH * N_BLOB_BYTES.
     q_enabled: Selector,
     /// The byte value at this row.
     byte: Column<Advice>,
-    /// Whether or not this is a padded row. This can be the case if not all bytes in the blob
-    /// (4096 * 31) could be filled. Padded bytes must be 0 and bytes_rlc must continue while in
-    /// the padded region.
+    /// Whether or not this is a padded row. This can be the case if not all bytes in the blobs
+    /// (MAX_BLOBS_PER_BATCH * 4096 * 31) could be filled. Padded bytes must be 0 and bytes_rlc
+    /// must continue while in the padded region. A blob is used by the batch iff its first row is
+    /// not padded.
     is_padding: Column<Advice>,
     /// running RLC of bytes seen so far. It remains unchanged once padded territory starts.
     bytes_rlc: Column<Advice>,
-    /// running accumulator of the number of bytes in the blob.
+    /// running accumulator of the number of bytes in the blob, excluding the is_compressed flag.
     bytes_len: Column<Advice>,
+    /// running RLC of bytes seen so far, including the is_compressed flag. It remains unchanged
+    /// once padded territory starts.
+    calldata_rlc: Column<Advice>,
 }
 
 pub struct AssignedBlobDataExport {
+    pub is_compressed: AssignedCell<Fr, Fr>,
     pub bytes_rlc: AssignedCell<Fr, Fr>,
     pub bytes_len: AssignedCell<Fr, Fr>,
+    pub cooked_bytes_len: AssignedCell<Fr, Fr>,
+    pub calldata_rlc: AssignedCell<Fr, Fr>,
+    /// Whether each of the MAX_BLOBS_PER_BATCH blobs is used by the batch.
+    pub blobs_are_used: Vec<AssignedCell<Fr, Fr>>,
 }
 
 impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
@@ -64,11 +81,14 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
             is_padding: meta.advice_column(),
             bytes_rlc: meta.advice_column_in(SecondPhase),
             bytes_len: meta.advice_column(),
+            calldata_rlc: meta.advice_column_in(SecondPhase),
         };
 
         meta.enable_equality(config.byte);
+        meta.enable_equality(config.is_padding);
         meta.enable_equality(config.bytes_rlc);
         meta.enable_equality(config.bytes_len);
+        meta.enable_equality(config.calldata_rlc);
 
         meta.lookup("BlobDataConfig (0 < byte < 256)", |meta| {
             let byte_value = meta.query_advice(config.byte, Rotation::cur());
@@ -81,13 +101,28 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
             let byte = meta.query_advice(config.byte, Rotation::cur());
             let bytes_rlc = meta.query_advice(config.bytes_rlc, Rotation::cur());
             let bytes_len = meta.query_advice(config.bytes_len, Rotation::cur());
+            let calldata_rlc = meta.query_advice(config.calldata_rlc, Rotation::cur());
             let is_padding_next = meta.query_advice(config.is_padding, Rotation::next());
 
+            // the is_compressed flag at the next row.
+            let is_compressed = meta.query_advice(config.byte, Rotation::next());
+            let bytes_rlc_next = meta.query_advice(config.bytes_rlc, Rotation::next());
+            let bytes_len_next = meta.query_advice(config.bytes_len, Rotation::next());
+            let calldata_rlc_next = meta.query_advice(config.calldata_rlc, Rotation::next());
+
             vec![
                 is_first.expr() * byte,
                 is_first.expr() * bytes_rlc,
                 is_first.expr() * bytes_len,
+                is_first.expr() * calldata_rlc,
                 is_first.expr() * is_padding_next,
+                // is_compressed is boolean
+                is_first.expr() * is_compressed.expr() * (1.expr() - is_compressed.expr()),
+                // the is_compressed flag is not accumulated
+                is_first.expr() * bytes_rlc_next,
+                is_first.expr() * bytes_len_next,
+                // the is_compressed flag is accumulated in the calldata
+                is_first.expr() * (calldata_rlc_next - is_compressed),
             ]
         });
 
@@ -106,6 +141,9 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
             let bytes_len_curr = meta.query_advice(config.bytes_len, Rotation::cur());
             let bytes_len_prev = meta.query_advice(config.bytes_len, Rotation::prev());
 
+            let calldata_rlc_curr = meta.query_advice(config.calldata_rlc, Rotation::cur());
+            let calldata_rlc_prev = meta.query_advice(config.calldata_rlc, Rotation::prev());
+
             vec![
                 // if is_padding: byte == 0
                 is_enabled.expr() * is_padding_curr.expr() * byte.expr(),
@@ -130,6 +168,15 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
                 is_enabled.expr()
                     * is_padding_curr.expr()
                     * (bytes_len_curr.expr() - bytes_len_prev.expr()),
+                // calldata_rlc updates in the non-padded territory
+                is_enabled.expr()
+                    * (1.expr() - is_padding_curr.expr())
+                    * (calldata_rlc_prev.expr() * challenges.keccak_input() + byte.expr()
+                        - calldata_rlc_curr.expr()),
+                // calldata_rlc remains unchanged in padded territory
+                is_enabled.expr()
+                    * is_padding_curr.expr()
+                    * (calldata_rlc_curr.expr() - calldata_rlc_prev.expr()),
             ]
         });
 
@@ -144,29 +191,37 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
         challenge_value: Challenges<Value<Fr>>,
         rlc_config: &RlcConfig,
         batch_data: &BatchData<N_SNARKS>,
-        barycentric_assignments: &[CRTInteger<Fr>],
+        data_availability: DataAvailability,
+        barycentric_assignments: &[AssignedBarycentricEvaluationConfig],
     ) -> Result<AssignedBlobDataExport, Error> {
-        let (assigned_bytes, bytes_rlc, bytes_len) = layouter.assign_region(
-            || "BlobData bytes",
-            |mut region| self.assign_rows(&mut region, batch_data, &challenge_value),
-        )?;
+        let (assigned_bytes, blobs_are_padding, bytes_rlc, bytes_len, calldata_rlc) = layouter
+            .assign_region(
+                || "BlobData bytes",
+                |mut region| self.assign_rows(&mut region, batch_data, &challenge_value),
+            )?;
 
-        let cooked_bytes_len = layouter.assign_region(
+        let (cooked_bytes_len, blobs_are_used) = layouter.assign_region(
             || "BlobData internal checks",
             |mut region| {
                 self.assign_internal_checks(
                     &mut region,
                     rlc_config,
+                    data_availability,
                     barycentric_assignments,
                     &assigned_bytes,
+                    &blobs_are_padding,
                     &bytes_len,
                 )
             },
         )?;
 
         Ok(AssignedBlobDataExport {
+            is_compressed: assigned_bytes[0].clone(),
             bytes_rlc,
-            bytes_len: cooked_bytes_len,
+            bytes_len,
+            cooked_bytes_len,
+            calldata_rlc,
+            blobs_are_used,
         })
     }
 
@@ -178,31 +233,30 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
         challenges: &Challenges<Value<Fr>>,
     ) -> Result<
         (
+            Vec<AssignedCell<Fr, Fr>>,
             Vec<AssignedCell<Fr, Fr>>,
             AssignedCell<Fr, Fr>,
             AssignedCell<Fr, Fr>,
+            AssignedCell<Fr, Fr>,
         ),
         Error,
     > {
-        let batch_bytes = batch_data.get_batch_data_bytes();
-        let blob_bytes = {
-            let mut encoder = init_zstd_encoder(None);
-            encoder
-                .set_pledged_src_size(Some(batch_bytes.len() as u64))
-                .map_err(|_| Error::Synthesis)?;
-            encoder
-                .write_all(&batch_bytes)
-                .map_err(|_| Error::Synthesis)?;
-            encoder.finish().map_err(|_| Error::Synthesis)?
-        };
-        assert!(blob_bytes.len() <= N_BLOB_BYTES, "too many blob bytes");
+        let blob_bytes = batch_data.get_blob_payload();
+        let n_rows = MAX_BLOBS_PER_BATCH * N_BLOB_BYTES;
+        assert!(blob_bytes.len() <= n_rows, "too many blob bytes");
 
         self.q_first.enable(region, 0)?;
-        for i in 1..=N_BLOB_BYTES {
+        for i in 2..=n_rows {
             self.q_enabled.enable(region, i)?;
         }
 
-        for col in [self.byte, self.bytes_rlc, self.bytes_len, self.is_padding] {
+        for col in [
+            self.byte,
+            self.bytes_rlc,
+            self.bytes_len,
+            self.is_padding,
+            self.calldata_rlc,
+        ] {
             region.assign_advice(
                 || "advice at q_first=1",
                 col,
@@ -211,13 +265,21 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
             )?;
         }
 
-        let mut assigned_bytes = Vec::with_capacity(N_BLOB_BYTES);
+        let mut assigned_bytes = Vec::with_capacity(n_rows);
+        // the is_padding cell at the first row of each blob.
+        let mut blobs_are_padding = Vec::with_capacity(MAX_BLOBS_PER_BATCH);
         let mut bytes_rlc = Value::known(Fr::zero());
+        let mut calldata_rlc = Value::known(Fr::zero());
         let mut last_bytes_rlc = None;
         let mut last_bytes_len = None;
+        let mut last_calldata_rlc = None;
         for (i, &byte) in blob_bytes.iter().enumerate() {
             let byte_value = Value::known(Fr::from(byte as u64));
-            bytes_rlc = bytes_rlc * challenges.keccak_input() + byte_value;
+            // the is_compressed flag at i == 0 is not accumulated.
+            if i > 0 {
+                bytes_rlc = bytes_rlc * challenges.keccak_input() + byte_value;
+            }
+            calldata_rlc = calldata_rlc * challenges.keccak_input() + byte_value;
 
             assigned_bytes.push(region.assign_advice(
                 || "byte",
@@ -225,37 +287,50 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
                 i + 1,
                 || byte_value,
             )?);
-            region.assign_advice(
+            let is_padding = region.assign_advice(
                 || "is_padding",
                 self.is_padding,
                 i + 1,
                 || Value::known(Fr::zero()),
             )?;
+            if i % N_BLOB_BYTES == 0 {
+                blobs_are_padding.push(is_padding);
+            }
             last_bytes_rlc =
                 Some(region.assign_advice(|| "bytes_rlc", self.bytes_rlc, i + 1, || bytes_rlc)?);
             last_bytes_len = Some(region.assign_advice(
                 || "bytes_len",
                 self.bytes_len,
                 i + 1,
-                || Value::known(Fr::from(i as u64 + 1)),
+                || Value::known(Fr::from(i as u64)),
+            )?);
+            last_calldata_rlc = Some(region.assign_advice(
+                || "calldata_rlc",
+                self.calldata_rlc,
+                i + 1,
+                || calldata_rlc,
             )?);
         }
 
         let mut last_bytes_rlc = last_bytes_rlc.expect("at least 1 byte guaranteed");
         let mut last_bytes_len = last_bytes_len.expect("at least 1 byte guaranteed");
-        for i in blob_bytes.len()..N_BLOB_BYTES {
+        let mut last_calldata_rlc = last_calldata_rlc.expect("at least 1 byte guaranteed");
+        for i in blob_bytes.len()..n_rows {
             assigned_bytes.push(region.assign_advice(
                 || "byte",
                 self.byte,
                 i + 1,
                 || Value::known(Fr::zero()),
             )?);
-            region.assign_advice(
+            let is_padding = region.assign_advice(
                 || "is_padding",
                 self.is_padding,
                 i + 1,
                 || Value::known(Fr::one()),
             )?;
+            if i % N_BLOB_BYTES == 0 {
+                blobs_are_padding.push(is_padding);
+            }
             last_bytes_rlc = region.assign_advice(
                 || "bytes_rlc",
                 self.bytes_rlc,
@@ -268,19 +343,33 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
                 i + 1,
                 || last_bytes_len.value().cloned(),
             )?;
+            last_calldata_rlc = region.assign_advice(
+                || "calldata_rlc",
+                self.calldata_rlc,
+                i + 1,
+                || last_calldata_rlc.value().cloned(),
+            )?;
         }
 
-        Ok((assigned_bytes, last_bytes_rlc, last_bytes_len))
+        Ok((
+            assigned_bytes,
+            blobs_are_padding,
+            last_bytes_rlc,
+            last_bytes_len,
+            last_calldata_rlc,
+        ))
     }
 
     pub fn assign_internal_checks(
         &self,
         region: &mut Region<Fr>,
         rlc_config: &RlcConfig,
-        barycentric_assignments: &[CRTInteger<Fr>],
+        data_availability: DataAvailability,
+        barycentric_assignments: &[AssignedBarycentricEvaluationConfig],
         assigned_bytes: &[AssignedCell<Fr, Fr>],
+        blobs_are_padding: &[AssignedCell<Fr, Fr>],
         bytes_len: &AssignedCell<Fr, Fr>,
-    ) -> Result<AssignedCell<Fr, Fr>, Error> {
+    ) -> Result<(AssignedCell<Fr, Fr>, Vec<AssignedCell<Fr, Fr>>), Error> {
         rlc_config.init(region)?;
         let mut rlc_config_offset = 0;
 
@@ -311,13 +400,31 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
         //////////////////////////////////// LINKING ///////////////////////////////////
         ////////////////////////////////////////////////////////////////////////////////
 
-        assert_eq!(barycentric_assignments.len(), BLOB_WIDTH + 1);
-        let blob_crts = barycentric_assignments
-            .iter()
-            .take(BLOB_WIDTH)
-            .collect::<Vec<_>>();
-        let mut blob_fields: Vec<Vec<AssignedCell<Fr, Fr>>> = Vec::with_capacity(BLOB_WIDTH);
-        for chunk in assigned_bytes.chunks_exact(N_DATA_BYTES_PER_COEFFICIENT) {
+        // the blobs are only evaluated for blob DA, in which case the blobs' coefficients are
+        // linked to the bytes. For calldata DA, the bytes are committed to via the calldata RLC.
+        let blob_crts = match data_availability {
+            DataAvailability::Blob => {
+                // the blobs' coefficients, in the same order as the bytes are laid out.
+                assert_eq!(barycentric_assignments.len(), MAX_BLOBS_PER_BATCH);
+                barycentric_assignments
+                    .iter()
+                    .flat_map(|assignments| {
+                        assert_eq!(assignments.barycentric_assignments.len(), BLOB_WIDTH + 1);
+                        assignments.barycentric_assignments.iter().take(BLOB_WIDTH)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            DataAvailability::Calldata => {
+                assert!(barycentric_assignments.is_empty());
+                vec![]
+            }
+        };
+        let mut blob_fields: Vec<Vec<AssignedCell<Fr, Fr>>> =
+            Vec::with_capacity(MAX_BLOBS_PER_BATCH * BLOB_WIDTH);
+        for chunk in assigned_bytes
+            .chunks_exact(N_DATA_BYTES_PER_COEFFICIENT)
+            .take(blob_crts.len())
+        {
             // blob bytes are supposed to be deserialised in big-endianness. However, we
             // have the export from BarycentricConfig in little-endian bytes.
             blob_fields.push(chunk.iter().rev().cloned().collect());
@@ -352,6 +459,14 @@ impl<const N_SNARKS: usize> BlobDataConfig<N_SNARKS> {
         // by adding +1 to it before exporting.
         let cooked_bytes_len = rlc_config.add(region, bytes_len, &one, &mut rlc_config_offset)?;
 
-        Ok(cooked_bytes_len)
+        // a blob is used by the batch iff its first byte is not padding. As is_padding
+        // transitions from 0 to 1 only once, the used blobs are the first ones.
+        assert_eq!(blobs_are_padding.len(), MAX_BLOBS_PER_BATCH);
+        let blobs_are_used = blobs_are_padding
+            .iter()
+            .map(|is_padding| rlc_config.sub(region, &one, is_padding, &mut rlc_config_offset))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok((cooked_bytes_len, blobs_are_used))
     }
 }
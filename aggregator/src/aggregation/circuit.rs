@@ -1,4 +1,7 @@
-use crate::{blob::BatchData, witgen::MultiBlockProcessResult};
+use crate::{
+    blob::{BatchData, MAX_BLOBS_PER_BATCH},
+    witgen::MultiBlockProcessResult,
+};
 use ark_std::{end_timer, start_timer};
 use halo2_base::{Context, ContextParams};
 use halo2_proofs::{
@@ -28,14 +31,16 @@ use zkevm_circuits::util::Challenges;
 
 use crate::{
     aggregation::witgen::process,
-    batch::BatchHash,
+    batch::{BatchHash, DataAvailability},
     constants::{ACC_LEN, DIGEST_LEN},
-    core::{assign_batch_hashes, extract_proof_and_instances_with_pairing_check},
+    core::{
+        assign_batch_hashes, check_keccak_capacity, extract_proof_and_instances_with_pairing_check,
+    },
     util::parse_hash_digest_cells,
-    AssignedBarycentricEvaluationConfig, ConfigParams,
+    ConfigParams, PiHashLayout,
 };
 
-use super::AggregationConfig;
+use super::{AggregationConfig, AssignedBarycentricEvaluationConfig};
 
 /// Aggregation circuit that does not re-expose any public inputs from aggregated snarks
 #[derive(Clone)]
@@ -53,24 +58,53 @@ pub struct AggregationCircuit<const N_SNARKS: usize> {
     // batch hash circuit for which the snarks are generated
     // the chunks in this batch are also padded already
     pub batch_hash: BatchHash<N_SNARKS>,
+    // how the batch data is made available, which determines the layout of the circuit
+    pub data_availability: DataAvailability,
+    // the byte layouts of the chunk and batch pi hash preimages
+    pub chunk_pi_layout: PiHashLayout,
+    pub batch_pi_layout: PiHashLayout,
 }
 
 impl<const N_SNARKS: usize> AggregationCircuit<N_SNARKS> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         params: &ParamsKZG<Bn256>,
         snarks_with_padding: &[Snark],
         rng: impl Rng + Send,
         batch_hash: BatchHash<N_SNARKS>,
+        data_availability: DataAvailability,
+        chunk_pi_layout: PiHashLayout,
+        batch_pi_layout: PiHashLayout,
     ) -> Result<Self, snark_verifier::Error> {
         let timer = start_timer!(|| "generate aggregation circuit");
 
+        // sanity check: the batch is built for the data availability of the circuit
+        if batch_hash.data_availability != data_availability {
+            return Err(snark_verifier::Error::AssertionFailure(format!(
+                "batch built for {:?} DA, circuit configured for {data_availability:?} DA",
+                batch_hash.data_availability
+            )));
+        }
+
+        // sanity check: the batch is built with the pi hash layouts of the circuit
+        if batch_hash.chunk_pi_layout != chunk_pi_layout
+            || batch_hash.batch_pi_layout != batch_pi_layout
+        {
+            return Err(snark_verifier::Error::AssertionFailure(
+                "batch built with different pi hash layouts than the circuit".to_string(),
+            ));
+        }
+
+        // sanity check: the keccak table fits all the hashes of the batch
+        check_keccak_capacity(&batch_hash.extract_hash_preimages())?;
+
         // sanity check: snarks's public input matches chunk_hashes
         for (chunk, snark) in batch_hash
             .chunks_with_padding
             .iter()
             .zip(snarks_with_padding.iter())
         {
-            let chunk_hash_bytes = chunk.public_input_hash();
+            let chunk_hash_bytes = chunk.public_input_hash_with_layout(&chunk_pi_layout)?;
             let snark_hash_bytes = &snark.instances[0];
 
             assert_eq!(snark_hash_bytes.len(), ACC_LEN + DIGEST_LEN);
@@ -111,12 +145,30 @@ impl<const N_SNARKS: usize> AggregationCircuit<N_SNARKS> {
             flattened_instances,
             as_proof: Value::known(as_proof),
             batch_hash,
+            data_availability,
+            chunk_pi_layout,
+            batch_pi_layout,
         })
     }
 
     pub fn as_proof(&self) -> Value<&[u8]> {
         self.as_proof.as_ref().map(Vec::as_slice)
     }
+
+    // the blobs are evaluated at their challenge point for blob DA only, as the batch public
+    // input hash commits to the keccak hash of the calldata for calldata DA
+    fn assign_barycentric(
+        &self,
+        config: &AggregationConfig<N_SNARKS>,
+        ctx: &mut Context<Fr>,
+    ) -> Vec<AssignedBarycentricEvaluationConfig> {
+        match self.data_availability {
+            DataAvailability::Blob => config
+                .barycentric
+                .assign(ctx, &self.batch_hash.point_evaluation_assignments),
+            DataAvailability::Calldata => vec![],
+        }
+    }
 }
 
 impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
@@ -175,7 +227,7 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 |region| {
                     if first_pass {
                         first_pass = false;
-                        return Ok(AssignedBarycentricEvaluationConfig::default());
+                        return Ok(vec![]);
                     }
 
                     let mut ctx = Context::new(
@@ -187,14 +239,7 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                         },
                     );
 
-                    let barycentric = config.barycentric.assign(
-                        &mut ctx,
-                        &self.batch_hash.point_evaluation_assignments.coefficients,
-                        self.batch_hash
-                            .point_evaluation_assignments
-                            .challenge_digest,
-                        self.batch_hash.point_evaluation_assignments.evaluation,
-                    );
+                    let barycentric = self.assign_barycentric(&config, &mut ctx);
 
                     config.barycentric.scalar.range.finalize(&mut ctx);
                     ctx.print_stats(&["barycentric evaluation"]);
@@ -213,11 +258,7 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 |region| {
                     if first_pass {
                         first_pass = false;
-                        return Ok((
-                            vec![],
-                            vec![],
-                            AssignedBarycentricEvaluationConfig::default(),
-                        ));
+                        return Ok((vec![], vec![], vec![]));
                     }
 
                     // stores accumulators for all snarks, including the padded ones
@@ -269,14 +310,7 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                     loader.ctx_mut().print_stats(&["snark aggregation"]);
 
                     let mut ctx = Rc::into_inner(loader).unwrap().into_ctx();
-                    let barycentric = config.barycentric.assign(
-                        &mut ctx,
-                        &self.batch_hash.point_evaluation_assignments.coefficients,
-                        self.batch_hash
-                            .point_evaluation_assignments
-                            .challenge_digest,
-                        self.batch_hash.point_evaluation_assignments.evaluation,
-                    );
+                    let barycentric = self.assign_barycentric(&config, &mut ctx);
 
                     ctx.print_stats(&["barycentric"]);
 
@@ -311,11 +345,17 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
             // - batch_data_hash_preimage
             // - preimage for blob metadata
             // - preimage of chunk data digest (only for valid chunks)
-            // - preimage of challenge digest
+            // - preimage of challenge digest, for each blob, for blob DA
+            // - calldata, for calldata DA
             let preimages = self.batch_hash.extract_hash_preimages();
             assert_eq!(
                 preimages.len(),
-                4 + N_SNARKS + self.batch_hash.number_of_valid_chunks,
+                3 + N_SNARKS
+                    + self.batch_hash.number_of_valid_chunks
+                    + match self.data_availability {
+                        DataAvailability::Blob => MAX_BLOBS_PER_BATCH,
+                        DataAvailability::Calldata => 1,
+                    },
                 "error extracting preimages"
             );
             end_timer!(timer);
@@ -334,6 +374,11 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 challenges,
                 &chunks_are_valid,
                 self.batch_hash.number_of_valid_chunks,
+                self.data_availability,
+                self.batch_hash.num_blobs,
+                &self.chunk_pi_layout,
+                &self.batch_pi_layout,
+                &self.batch_hash.padded_batch_pi_hash_preimage(),
                 &preimages,
             )
             .map_err(|_e| Error::ConstraintSystemFailure)?;
@@ -350,7 +395,9 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
         // step 3: assert public inputs to the snarks are correct
         // ==============================================
         for (i, chunk) in chunk_pi_hash_digests.iter().enumerate() {
-            let hash = self.batch_hash.chunks_with_padding[i].public_input_hash();
+            let hash = self.batch_hash.chunks_with_padding[i]
+                .public_input_hash_with_layout(&self.chunk_pi_layout)
+                .map_err(|_| Error::Synthesis)?;
             for j in 0..DIGEST_LEN {
                 log::trace!("pi {:02x} {:?}", hash[j], chunk[j].value());
             }
@@ -423,10 +470,6 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
 
         // blob data config
         {
-            let barycentric_assignments = &barycentric.barycentric_assignments;
-            let challenge_le = &barycentric.z_le;
-            let evaluation_le = &barycentric.y_le;
-
             let batch_data = BatchData::from(&self.batch_hash);
 
             let blob_data_exports = config.blob_data_config.assign(
@@ -434,7 +477,8 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 challenges,
                 &config.rlc_config,
                 &batch_data,
-                barycentric_assignments,
+                self.data_availability,
+                &barycentric,
             )?;
 
             let batch_data_exports = config.batch_data_config.assign(
@@ -443,11 +487,23 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 &config.rlc_config,
                 &assigned_batch_hash.chunks_are_padding,
                 &batch_data,
-                barycentric_assignments,
+                self.data_availability,
+                &barycentric,
             )?;
 
-            let batch_bytes = batch_data.get_batch_data_bytes();
-            let encoded_batch_bytes = batch_data.get_encoded_batch_data_bytes();
+            // If the batch data is not compressed, the zstd decoder is assigned the default batch
+            // data instead, and its exports are not linked to the blob and batch data.
+            let decoded_batch_data = if batch_data.is_compressed {
+                batch_data.clone()
+            } else {
+                BatchData::default()
+            };
+            if !decoded_batch_data.is_compressed {
+                log::error!("the zstd decoder is assigned uncompressed batch data");
+                return Err(Error::Synthesis);
+            }
+            let batch_bytes = decoded_batch_data.get_batch_data_bytes();
+            let encoded_batch_bytes = decoded_batch_data.get_encoded_batch_data_bytes();
 
             let MultiBlockProcessResult {
                 witness_rows,
@@ -457,7 +513,11 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 sequence_info_arr,
                 address_table_rows: address_table_arr,
                 sequence_exec_results,
-            } = process(&encoded_batch_bytes, challenges.keccak_input());
+                huffman_codes,
+            } = process(&encoded_batch_bytes, challenges.keccak_input()).map_err(|e| {
+                log::error!("zstd witness generation failed: {e}");
+                Error::Synthesis
+            })?;
 
             // sanity check:
             let (recovered_bytes, sequence_exec_info_arr) = sequence_exec_results.into_iter().fold(
@@ -484,6 +544,7 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                 sequence_info_arr,
                 address_table_arr,
                 sequence_exec_info_arr,
+                huffman_codes,
                 &challenges,
                 20, // TODO: configure k for aggregation circuit instead of hard-coded here.
             )?;
@@ -491,6 +552,9 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
             layouter.assign_region(
                 || "consistency checks",
                 |mut region| -> Result<(), Error> {
+                    config.rlc_config.init(&mut region)?;
+                    let mut rlc_config_offset = 0;
+
                     region.constrain_equal(
                         assigned_batch_hash.num_valid_snarks.cell(),
                         batch_data_exports.num_valid_chunks.cell(),
@@ -510,51 +574,130 @@ impl<const N_SNARKS: usize> Circuit<Fr> for AggregationCircuit<N_SNARKS> {
                         }
                     }
 
-                    for (c, ec) in evaluation_le
-                        .iter()
-                        .zip_eq(assigned_batch_hash.blob.y.iter().rev())
-                    {
-                        log::trace!("blob y: {:?} {:?}", c.value(), ec.value());
-                        region.constrain_equal(c.cell(), ec.cell())?;
-                    }
-
-                    for (c, ec) in challenge_le
-                        .iter()
-                        .zip_eq(assigned_batch_hash.blob.z.iter().rev())
-                    {
-                        log::trace!("blob z: {:?} {:?}", c.value(), ec.value());
-                        region.constrain_equal(c.cell(), ec.cell())?;
-                    }
-
-                    for (c, ec) in batch_data_exports
-                        .versioned_hash
-                        .iter()
-                        .zip_eq(assigned_batch_hash.blob.versioned_hash.iter())
-                    {
-                        log::trace!("blob version hash: {:?} {:?}", c.value(), ec.value());
-                        region.constrain_equal(c.cell(), ec.cell())?;
+                    match self.data_availability {
+                        // the batch public input hash commits to y, z and the versioned hash of
+                        // each blob used by the batch, i.e. holding the blob payload
+                        DataAvailability::Blob => {
+                            for (c, ec) in blob_data_exports
+                                .blobs_are_used
+                                .iter()
+                                .zip_eq(assigned_batch_hash.blob.blobs_are_used.iter())
+                            {
+                                log::trace!("blob is used: {:?} {:?}", c.value(), ec.value());
+                                region.constrain_equal(c.cell(), ec.cell())?;
+                            }
+
+                            for (i, barycentric) in barycentric.iter().enumerate() {
+                                for (c, ec) in barycentric
+                                    .y_le
+                                    .iter()
+                                    .zip_eq(assigned_batch_hash.blob.y[i].iter().rev())
+                                {
+                                    log::trace!("blob {i} y: {:?} {:?}", c.value(), ec.value());
+                                    region.constrain_equal(c.cell(), ec.cell())?;
+                                }
+
+                                for (c, ec) in barycentric
+                                    .z_le
+                                    .iter()
+                                    .zip_eq(assigned_batch_hash.blob.z[i].iter().rev())
+                                {
+                                    log::trace!("blob {i} z: {:?} {:?}", c.value(), ec.value());
+                                    region.constrain_equal(c.cell(), ec.cell())?;
+                                }
+
+                                for (c, ec) in batch_data_exports.versioned_hashes[i]
+                                    .iter()
+                                    .zip_eq(assigned_batch_hash.blob.versioned_hashes[i].iter())
+                                {
+                                    log::trace!(
+                                        "blob {i} version hash: {:?} {:?}",
+                                        c.value(),
+                                        ec.value()
+                                    );
+                                    region.constrain_equal(c.cell(), ec.cell())?;
+                                }
+                            }
+                        }
+                        // the batch public input hash commits to the keccak hash of the calldata,
+                        // i.e. the blob payload including the is_compressed flag
+                        DataAvailability::Calldata => {
+                            let evm_word_challenge = config.rlc_config.read_challenge2(
+                                &mut region,
+                                challenges,
+                                &mut rlc_config_offset,
+                            )?;
+                            let calldata_hash_rlc = config.rlc_config.rlc(
+                                &mut region,
+                                &assigned_batch_hash.blob.calldata_hash,
+                                &evm_word_challenge,
+                                &mut rlc_config_offset,
+                            )?;
+                            config.rlc_config.lookup_keccak_rlcs(
+                                &mut region,
+                                &blob_data_exports.calldata_rlc,
+                                &calldata_hash_rlc,
+                                &blob_data_exports.cooked_bytes_len,
+                                &mut rlc_config_offset,
+                            )?;
+                        }
                     }
 
-                    // equate rlc (from blob data) with decoder's encoded_rlc
-                    region.constrain_equal(
-                        blob_data_exports.bytes_rlc.cell(),
-                        decoder_exports.encoded_rlc.cell(),
-                    )?;
-                    // equate len(blob_bytes) with decoder's encoded_len
-                    region.constrain_equal(
-                        blob_data_exports.bytes_len.cell(),
-                        decoder_exports.encoded_len.cell(),
-                    )?;
-                    // equate rlc (from batch data) with decoder's decoded_rlc
-                    region.constrain_equal(
-                        batch_data_exports.bytes_rlc.cell(),
-                        decoder_exports.decoded_rlc.cell(),
-                    )?;
-                    // equate len(batch_data) with decoder's decoded_len
-                    region.constrain_equal(
-                        batch_data_exports.batch_data_len.cell(),
-                        decoder_exports.decoded_len.cell(),
+                    // is_compressed is constrained to be boolean in the blob data config.
+                    let is_compressed = &blob_data_exports.is_compressed;
+                    let is_not_compressed = config.rlc_config.not(
+                        &mut region,
+                        is_compressed,
+                        &mut rlc_config_offset,
                     )?;
+                    for (a, b, cond) in [
+                        // if is_compressed: equate rlc (from blob data) with decoder's encoded_rlc
+                        (
+                            &blob_data_exports.bytes_rlc,
+                            &decoder_exports.encoded_rlc,
+                            is_compressed,
+                        ),
+                        // if is_compressed: equate len(blob_bytes) with decoder's encoded_len
+                        (
+                            &blob_data_exports.cooked_bytes_len,
+                            &decoder_exports.encoded_len,
+                            is_compressed,
+                        ),
+                        // if is_compressed: equate rlc (from batch data) with decoder's
+                        // decoded_rlc
+                        (
+                            &batch_data_exports.bytes_rlc,
+                            &decoder_exports.decoded_rlc,
+                            is_compressed,
+                        ),
+                        // if is_compressed: equate len(batch_data) with decoder's decoded_len
+                        (
+                            &batch_data_exports.batch_data_len,
+                            &decoder_exports.decoded_len,
+                            is_compressed,
+                        ),
+                        // if not is_compressed: equate rlc (from blob data) with rlc (from batch
+                        // data)
+                        (
+                            &blob_data_exports.bytes_rlc,
+                            &batch_data_exports.bytes_rlc,
+                            &is_not_compressed,
+                        ),
+                        // if not is_compressed: equate len(blob_bytes) with len(batch_data)
+                        (
+                            &blob_data_exports.bytes_len,
+                            &batch_data_exports.batch_data_len,
+                            &is_not_compressed,
+                        ),
+                    ] {
+                        config.rlc_config.conditional_enforce_equal(
+                            &mut region,
+                            a,
+                            b,
+                            cond,
+                            &mut rlc_config_offset,
+                        )?;
+                    }
 
                     Ok(())
                 },
@@ -11,7 +11,9 @@ use snark_verifier::{
     util::arithmetic::modulus,
 };
 use zkevm_circuits::{
-    keccak_circuit::{KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    keccak_circuit::{
+        keccak_packed_multi::get_num_rows_per_round, KeccakCircuitConfig, KeccakCircuitConfigArgs,
+    },
     table::{BitwiseOpTable, KeccakTable, Pow2Table, PowOfRandTable, RangeTable, U8Table},
     util::{Challenges, SubCircuitConfig},
 };
@@ -69,6 +71,7 @@ impl<const N_SNARKS: usize> AggregationConfig<N_SNARKS> {
             let keccak_circuit_config_args = KeccakCircuitConfigArgs {
                 keccak_table: keccak_table.clone(),
                 challenges: challenges_exprs,
+                num_rows_per_round: get_num_rows_per_round(),
             };
 
             (
@@ -27,13 +27,14 @@ use zkevm_circuits::{
 
 use self::{
     tables::{
-        BitstringTable, FixedLookupTag, FixedTable, FseTable, LiteralsHeaderTable,
-        SeqInstTable as SequenceInstructionTable,
+        BitstringTable, FixedLookupTag, FixedTable, FseTable, HuffmanCodesTable,
+        LiteralsHeaderTable, SeqInstTable as SequenceInstructionTable,
     },
     witgen::{
-        util::value_bits_le, AddressTableRow, BlockInfo, FseAuxiliaryTableData, FseTableKind,
-        SequenceExec, SequenceInfo, ZstdTag, ZstdWitnessRow, N_BITS_PER_BYTE, N_BITS_REPEAT_FLAG,
-        N_BITS_ZSTD_TAG, N_BLOCK_HEADER_BYTES, N_BLOCK_SIZE_TARGET,
+        util::value_bits_le, AddressTableRow, BlockInfo, BlockType, FseAuxiliaryTableData,
+        FseTableKind, HuffmanCodesData, SequenceExec, SequenceInfo, SymbolCompressionMode, ZstdTag,
+        ZstdWitnessRow, N_BITS_PER_BYTE, N_BITS_REPEAT_FLAG, N_BITS_ZSTD_TAG, N_BLOCK_HEADER_BYTES,
+        N_BLOCK_SIZE_TARGET,
     },
 };
 use super::util::BooleanAdvice;
@@ -66,6 +67,8 @@ pub struct DecoderConfig<const L: usize, const R: usize> {
     tag_config: TagConfig,
     /// Block related config.
     block_config: BlockConfig,
+    /// Config required while decoding the literals section.
+    literals_decoder: LiteralsDecoder,
     /// Decoding helpers for the sequences section header.
     sequences_header_decoder: SequencesHeaderDecoder,
     /// Config for reading and decoding bitstreams.
@@ -98,6 +101,8 @@ pub struct DecoderConfig<const L: usize, const R: usize> {
     bitstring_table_3: BitstringTable<3>,
     /// Helper table for decoding FSE tables.
     fse_table: FseTable<L, R>,
+    /// Helper table for decoding Huffman-coded literals.
+    huffman_codes_table: HuffmanCodesTable,
     /// Helper table for sequences as instructions.
     sequence_instruction_table: SequenceInstructionTable<Fr>,
     /// Helper table in the "output" region for accumulating the result of executing sequences.
@@ -165,6 +170,12 @@ struct TagConfig {
     is_fse_code: Column<Advice>,
     /// Degree reduction: SequencesData
     is_sequence_data: Column<Advice>,
+    /// Degree reduction: HuffmanCode
+    is_huffman_code: Column<Advice>,
+    /// Degree reduction: JumpTable
+    is_jump_table: Column<Advice>,
+    /// Degree reduction: Lstream
+    is_lstream: Column<Advice>,
     /// Degree reduction: Null
     is_null: Column<Advice>,
 }
@@ -202,6 +213,9 @@ impl TagConfig {
             is_sequence_header: meta.advice_column(),
             is_fse_code: meta.advice_column(),
             is_sequence_data: meta.advice_column(),
+            is_huffman_code: meta.advice_column(),
+            is_jump_table: meta.advice_column(),
+            is_lstream: meta.advice_column(),
             is_null: meta.advice_column(),
         }
     }
@@ -215,6 +229,12 @@ struct BlockConfig {
     block_idx: Column<Advice>,
     /// Whether this block is the last block in the zstd encoded data.
     is_last_block: Column<Advice>,
+    /// Whether this block is a Raw_Block, i.e. Block_Type == 0.
+    is_raw_block: Column<Advice>,
+    /// Whether this block is an RLE_Block, i.e. Block_Type == 1. The single byte following the
+    /// block header is repeated Block_Size times, so the byte_idx does not increment over the
+    /// block's content.
+    is_rle_block: Column<Advice>,
     /// The regenerated size of the block, i.e. the length of raw literals.
     regen_size: Column<Advice>,
     /// Helper boolean column to tell us whether we are in the block's contents. This field is not
@@ -226,15 +246,29 @@ struct BlockConfig {
     /// Helper gadget to know if the number of sequences is 0.
     is_empty_sequences: IsEqualConfig<Fr>,
     /// For sequence decoding, the tag=ZstdBlockSequenceHeader bytes tell us the Compression_Mode
-    /// utilised for Literals Lengths, Match Offsets and Match Lengths. We expect only 2
+    /// utilised for Literals Lengths, Match Offsets and Match Lengths. There are 4
     /// possibilities:
     /// 1. Predefined_Mode (value=0)
-    /// 2. Fse_Compressed_Mode (value=2)
+    /// 2. RLE_Mode (value=1)
+    /// 3. Fse_Compressed_Mode (value=2)
+    /// 4. Repeat_Mode (value=3)
     ///
-    /// Which means a single boolean flag is sufficient to take note of which compression mode is
-    /// utilised for each of the above purposes. The boolean flag will be set if we utilise the
-    /// Fse_Compressed_Mode.
+    /// The compression_modes flag is set when the FSE table is described in the
+    /// tag=ZstdBlockSequenceFseCode section, i.e. for the RLE_Mode and Fse_Compressed_Mode.
     compression_modes: [Column<Advice>; 3],
+    /// Boolean flags set when the RLE_Mode is utilised. The FSE table then has a single symbol,
+    /// described by a single byte in the tag=ZstdBlockSequenceFseCode section.
+    rle_modes: [Column<Advice>; 3],
+    /// Boolean flags set when the Repeat_Mode is utilised. The FSE table used in the previous
+    /// block with sequences is then re-used.
+    repeat_modes: [Column<Advice>; 3],
+    /// The index of the block in which the FSE table currently in use (LLT, MOT, MLT) was
+    /// described. This is carried forward across blocks, and is updated at the sequences section
+    /// header unless the Repeat_Mode is utilised.
+    fse_table_block_idx: [Column<Advice>; 3],
+    /// Whether the FSE table currently in use (LLT, MOT, MLT) is the predefined one. This is
+    /// carried forward along with fse_table_block_idx.
+    fse_table_is_predefined: [Column<Advice>; 3],
 }
 
 impl BlockConfig {
@@ -244,6 +278,8 @@ impl BlockConfig {
             block_len: meta.advice_column(),
             block_idx: meta.advice_column(),
             is_last_block: meta.advice_column(),
+            is_raw_block: meta.advice_column(),
+            is_rle_block: meta.advice_column(),
             regen_size: meta.advice_column(),
             is_block: meta.advice_column(),
             num_sequences,
@@ -258,54 +294,102 @@ impl BlockConfig {
                 meta.advice_column(),
                 meta.advice_column(),
             ],
+            rle_modes: [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ],
+            repeat_modes: [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ],
+            fse_table_block_idx: [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ],
+            fse_table_is_predefined: [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ],
         }
     }
 }
 
 impl BlockConfig {
-    fn is_predefined_llt(&self, meta: &mut VirtualCells<Fr>, rotation: Rotation) -> Expression<Fr> {
-        not::expr(meta.query_advice(self.compression_modes[0], rotation))
+    /// Whether none of the FSE tables is described in the tag=ZstdBlockSequenceFseCode section.
+    fn has_no_fse_code(&self, meta: &mut VirtualCells<Fr>, rotation: Rotation) -> Expression<Fr> {
+        and::expr(
+            self.compression_modes
+                .map(|column| not::expr(meta.query_advice(column, rotation))),
+        )
     }
 
-    fn is_predefined_mot(&self, meta: &mut VirtualCells<Fr>, rotation: Rotation) -> Expression<Fr> {
-        not::expr(meta.query_advice(self.compression_modes[1], rotation))
+    /// Selects the value of the per-table columns for the FSE table being decoded.
+    fn select_by_table_kind(
+        &self,
+        meta: &mut VirtualCells<Fr>,
+        fse_decoder: &FseDecoder,
+        columns: [Column<Advice>; 3],
+        rotation: Rotation,
+    ) -> Expression<Fr> {
+        sum::expr([
+            and::expr([
+                fse_decoder.is_llt(meta, rotation),
+                meta.query_advice(columns[0], rotation),
+            ]),
+            and::expr([
+                fse_decoder.is_mot(meta, rotation),
+                meta.query_advice(columns[1], rotation),
+            ]),
+            and::expr([
+                fse_decoder.is_mlt(meta, rotation),
+                meta.query_advice(columns[2], rotation),
+            ]),
+        ])
     }
 
-    fn is_predefined_mlt(&self, meta: &mut VirtualCells<Fr>, rotation: Rotation) -> Expression<Fr> {
-        not::expr(meta.query_advice(self.compression_modes[2], rotation))
+    fn is_predefined(
+        &self,
+        meta: &mut VirtualCells<Fr>,
+        fse_decoder: &FseDecoder,
+        rotation: Rotation,
+    ) -> Expression<Fr> {
+        self.select_by_table_kind(meta, fse_decoder, self.fse_table_is_predefined, rotation)
     }
 
-    fn are_predefined_all(
+    fn is_rle_mode(
         &self,
         meta: &mut VirtualCells<Fr>,
+        fse_decoder: &FseDecoder,
         rotation: Rotation,
     ) -> Expression<Fr> {
-        and::expr([
-            self.is_predefined_llt(meta, rotation),
-            self.is_predefined_mot(meta, rotation),
-            self.is_predefined_mlt(meta, rotation),
-        ])
+        self.select_by_table_kind(meta, fse_decoder, self.rle_modes, rotation)
     }
 
-    fn is_predefined(
+    fn fse_table_block_idx(
         &self,
         meta: &mut VirtualCells<Fr>,
         fse_decoder: &FseDecoder,
         rotation: Rotation,
     ) -> Expression<Fr> {
-        sum::expr([
-            and::expr([
-                fse_decoder.is_llt(meta, rotation),
-                self.is_predefined_llt(meta, rotation),
-            ]),
-            and::expr([
-                fse_decoder.is_mlt(meta, rotation),
-                self.is_predefined_mlt(meta, rotation),
-            ]),
-            and::expr([
-                fse_decoder.is_mot(meta, rotation),
-                self.is_predefined_mot(meta, rotation),
-            ]),
+        self.select_by_table_kind(meta, fse_decoder, self.fse_table_block_idx, rotation)
+    }
+
+    /// Whether the block is a Raw_Block or an RLE_Block, i.e. its content is processed as the
+    /// literals of a block without sequences.
+    fn is_raw_or_rle(&self, meta: &mut VirtualCells<Fr>, rotation: Rotation) -> Expression<Fr> {
+        meta.query_advice(self.is_raw_block, rotation)
+            + meta.query_advice(self.is_rle_block, rotation)
+    }
+
+    /// Whether we are processing the content of an RLE_Block.
+    fn is_rle_content(&self, meta: &mut VirtualCells<Fr>, rotation: Rotation) -> Expression<Fr> {
+        and::expr([
+            meta.query_advice(self.is_block, rotation),
+            meta.query_advice(self.is_rle_block, rotation),
         ])
     }
 
@@ -320,6 +404,67 @@ impl BlockConfig {
     }
 }
 
+#[derive(Clone, Debug)]
+struct LiteralsDecoder {
+    /// Whether the Huffman-coded literals are split over 4 streams. This is set in the literals
+    /// header and remains unchanged over the block.
+    is_4_streams: Column<Advice>,
+    /// The byte index of the last byte of the Huffman-coded literals section, i.e. the last byte
+    /// of the last literals stream.
+    literals_end_byte_idx: Column<Advice>,
+    /// The number of weights explicitly described in the Huffman tree description, i.e. the last
+    /// symbol of the Huffman tree.
+    n_weights: Column<Advice>,
+    /// The sizes of the first 3 literals streams as per the jump table.
+    stream_sizes: [Column<Advice>; 3],
+    /// The number of literals decoded by each of the first 3 literals streams, i.e.
+    /// ceil(regen_size / 4).
+    n_literals_per_stream: Column<Advice>,
+    /// The index of the block in which the Huffman tree used for decoding literals was described.
+    /// This is updated at a Compressed_Literals_Block and carried forward otherwise, since a
+    /// Treeless_Literals_Block re-uses the previous Huffman tree.
+    huffman_block_idx: Column<Advice>,
+    /// The index of the literals stream being decoded, in 0..4.
+    lstream_idx: Column<Advice>,
+    /// Whether a literal is decoded at this row. This is set for every row of tag
+    /// ZstdBlockLiteralsRawBytes and for every row of tag ZstdBlockLstream where a prefix code is
+    /// read from the bitstream.
+    is_literal: BooleanAdvice,
+    /// The 1-indexed position of the decoded literal within the block's literals. On rows where
+    /// no literal is decoded, this is the number of literals decoded so far.
+    literal_idx: Column<Advice>,
+    /// The decoded literal.
+    literal: Column<Advice>,
+    /// Whether this is the last literal of the block.
+    is_last_literal: BooleanAdvice,
+}
+
+impl LiteralsDecoder {
+    fn configure(meta: &mut ConstraintSystem<Fr>, q_enable: Column<Fixed>) -> Self {
+        Self {
+            is_4_streams: meta.advice_column(),
+            literals_end_byte_idx: meta.advice_column(),
+            n_weights: meta.advice_column(),
+            stream_sizes: [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ],
+            n_literals_per_stream: meta.advice_column(),
+            huffman_block_idx: meta.advice_column(),
+            lstream_idx: meta.advice_column(),
+            is_literal: BooleanAdvice::construct(meta, |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+            }),
+            literal_idx: meta.advice_column(),
+            literal: meta.advice_column(),
+            is_last_literal: BooleanAdvice::construct(meta, |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+            }),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct SequencesHeaderDecoder {
     /// Helper gadget to evaluate byte0 < 128.
@@ -729,6 +874,11 @@ pub struct FseDecoder {
     table_size: Column<Advice>,
     /// If the table_kind at this row is predefined table.
     is_predefined: Column<Advice>,
+    /// If the table_kind at this row uses the RLE_Mode, i.e. the FSE table has a single symbol.
+    is_rle: Column<Advice>,
+    /// The index of the block in which the FSE table of table_kind at this row was described.
+    /// This differs from the current block_idx when the Repeat_Mode is utilised.
+    table_block_idx: Column<Advice>,
     /// The incremental symbol for which probability is decoded.
     symbol: Column<Advice>,
     /// The value decoded as per variable bit-packing.
@@ -766,6 +916,8 @@ impl FseDecoder {
             table_kind: meta.advice_column(),
             table_size: meta.advice_column(),
             is_predefined: meta.advice_column(),
+            is_rle: meta.advice_column(),
+            table_block_idx: meta.advice_column(),
             symbol: meta.advice_column(),
             value_decoded,
             probability_acc: meta.advice_column(),
@@ -811,6 +963,18 @@ impl FseDecoder {
                 block_config.is_predefined(meta, &fse_decoder, Rotation::cur()),
             );
 
+            cb.require_equal(
+                "is_rle value",
+                meta.query_advice(fse_decoder.is_rle, Rotation::cur()),
+                block_config.is_rle_mode(meta, &fse_decoder, Rotation::cur()),
+            );
+
+            cb.require_equal(
+                "table_block_idx value",
+                meta.query_advice(fse_decoder.table_block_idx, Rotation::cur()),
+                block_config.fse_table_block_idx(meta, &fse_decoder, Rotation::cur()),
+            );
+
             cb.gate(condition)
         });
 
@@ -1119,6 +1283,14 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             pow2_table,
             bitwise_op_table,
         );
+        let huffman_codes_table = HuffmanCodesTable::configure(
+            meta,
+            q_enable,
+            u8_table,
+            range16,
+            range_block_len,
+            pow2_table,
+        );
         let sequence_instruction_table = SequenceInstructionTable::configure(meta);
 
         debug_assert!(meta.degree() <= 9);
@@ -1126,6 +1298,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         // Peripheral configs
         let tag_config = TagConfig::configure(meta, q_enable);
         let block_config = BlockConfig::configure(meta, q_enable);
+        let literals_decoder = LiteralsDecoder::configure(meta, q_enable);
         let sequences_header_decoder =
             SequencesHeaderDecoder::configure(meta, byte, q_enable, u8_table);
         let bitstream_decoder = BitstreamDecoder::configure(meta, q_enable, q_first, u8_table);
@@ -1143,11 +1316,11 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             challenges.keccak_input(),
             &LiteralTable::construct([
                 q_enable.into(),
-                tag_config.tag.into(),
+                literals_decoder.is_literal.column.into(),
                 block_config.block_idx.into(),
-                tag_config.tag_idx.into(),
-                byte.into(),
-                tag_config.is_change.column.into(),
+                literals_decoder.literal_idx.into(),
+                literals_decoder.literal.into(),
+                literals_decoder.is_last_literal.column.into(),
                 is_padding.column.into(),
             ]),
             &sequence_instruction_table,
@@ -1193,6 +1366,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             is_padding,
             tag_config,
             block_config,
+            literals_decoder,
             sequences_header_decoder,
             bitstream_decoder,
             fse_decoder,
@@ -1209,6 +1383,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             bitstring_table_2,
             bitstring_table_3,
             fse_table,
+            huffman_codes_table,
             lookups_enabled,
 
             sequence_instruction_table,
@@ -1251,10 +1426,15 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         is_tag!(is_zb_sequence_header, ZstdBlockSequenceHeader);
         is_tag!(is_zb_sequence_fse, ZstdBlockSequenceFseCode);
         is_tag!(is_zb_sequence_data, ZstdBlockSequenceData);
+        is_tag!(is_zb_huffman_code, ZstdBlockHuffmanCode);
+        is_tag!(is_zb_jump_table, ZstdBlockJumpTable);
+        is_tag!(is_zb_lstream, ZstdBlockLstream);
 
         is_prev_tag!(is_prev_frame_content_size, FrameContentSize);
         is_prev_tag!(is_prev_sequence_header, ZstdBlockSequenceHeader);
         is_prev_tag!(is_prev_sequence_data, ZstdBlockSequenceData);
+        is_prev_tag!(is_prev_raw_bytes, ZstdBlockLiteralsRawBytes);
+        is_prev_tag!(is_prev_lstream, ZstdBlockLstream);
 
         meta.lookup("DecoderConfig: 0 <= encoded byte < 256", |meta| {
             vec![(
@@ -1301,6 +1481,20 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 meta.query_advice(config.encoded_rlc, Rotation::cur()),
             );
 
+            // No Huffman tree has been described yet.
+            cb.require_zero(
+                "huffman_block_idx == 0",
+                meta.query_advice(config.literals_decoder.huffman_block_idx, Rotation::cur()),
+            );
+
+            // No FSE table has been described yet.
+            for column in config.block_config.fse_table_block_idx {
+                cb.require_zero(
+                    "fse_table_block_idx == 0",
+                    meta.query_advice(column, Rotation::cur()),
+                );
+            }
+
             cb.gate(condition)
         });
 
@@ -1386,8 +1580,48 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 config.tag_config.is_sequence_data,
                 is_zb_sequence_data(meta)
             );
+            degree_reduction_check!(config.tag_config.is_huffman_code, is_zb_huffman_code(meta));
+            degree_reduction_check!(config.tag_config.is_jump_table, is_zb_jump_table(meta));
+            degree_reduction_check!(config.tag_config.is_lstream, is_zb_lstream(meta));
             degree_reduction_check!(config.tag_config.is_null, is_null(meta));
 
+            // A literal is decoded at every row of the raw literals, and at every row of a
+            // literals stream where a prefix code is read, i.e. excluding the sentinel row.
+            cb.require_equal(
+                "is_literal == is_raw_bytes || (is_lstream && !is_nil && !is_change)",
+                config
+                    .literals_decoder
+                    .is_literal
+                    .expr_at(meta, Rotation::cur()),
+                is_zb_raw_block(meta)
+                    + and::expr([
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
+                        not::expr(config.bitstream_decoder.is_nil(meta, Rotation::cur())),
+                        not::expr(config.tag_config.is_change.expr_at(meta, Rotation::cur())),
+                    ]),
+            );
+            cb.condition(
+                config
+                    .literals_decoder
+                    .is_last_literal
+                    .expr_at(meta, Rotation::cur()),
+                |cb| {
+                    cb.require_equal(
+                        "is_last_literal: is_literal",
+                        config
+                            .literals_decoder
+                            .is_literal
+                            .expr_at(meta, Rotation::cur()),
+                        1.expr(),
+                    );
+                    cb.require_equal(
+                        "is_last_literal: literal_idx == regen_size",
+                        meta.query_advice(config.literals_decoder.literal_idx, Rotation::cur()),
+                        meta.query_advice(config.block_config.regen_size, Rotation::cur()),
+                    );
+                },
+            );
+
             // Lookups enabled check.
             macro_rules! lookups_enabled_check {
                 ($column:expr, $expr:expr) => {
@@ -1489,6 +1723,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     sum::expr([
                         meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                         meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                     ]),
                 ])
             );
@@ -1549,6 +1784,51 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     meta.query_advice(config.decoded_len, Rotation::prev()),
                 );
 
+                // The block index of the most recent Huffman tree description is carried
+                // forward, and may only be updated at the literals header.
+                cb.condition(
+                    not::expr(and::expr([
+                        meta.query_advice(config.tag_config.is_literals_header, Rotation::cur()),
+                        config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    ])),
+                    |cb| {
+                        cb.require_equal(
+                            "huffman_block_idx::cur == huffman_block_idx::prev",
+                            meta.query_advice(
+                                config.literals_decoder.huffman_block_idx,
+                                Rotation::cur(),
+                            ),
+                            meta.query_advice(
+                                config.literals_decoder.huffman_block_idx,
+                                Rotation::prev(),
+                            ),
+                        );
+                    },
+                );
+
+                // The FSE tables in use are carried forward, and may only be updated at the
+                // sequences section header.
+                cb.condition(
+                    not::expr(and::expr([
+                        meta.query_advice(config.tag_config.is_sequence_header, Rotation::cur()),
+                        config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    ])),
+                    |cb| {
+                        for column in config
+                            .block_config
+                            .fse_table_block_idx
+                            .into_iter()
+                            .chain(config.block_config.fse_table_is_predefined)
+                        {
+                            cb.require_equal(
+                                "fse_table::cur == fse_table::prev",
+                                meta.query_advice(column, Rotation::cur()),
+                                meta.query_advice(column, Rotation::prev()),
+                            );
+                        }
+                    },
+                );
+
                 cb.gate(condition)
             },
         );
@@ -1699,14 +1979,17 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 );
             }
 
-            // tag_idx increments with byte_idx.
+            // tag_idx increments with byte_idx. The only exception is the content of an
+            // RLE_Block, where the same byte is repeated, i.e. tag_idx increments while byte_idx
+            // remains the same.
             let byte_idx_delta = meta.query_advice(config.byte_idx, Rotation::cur())
                 - meta.query_advice(config.byte_idx, Rotation::prev());
             cb.require_equal(
-                "tag_idx::cur - tag_idx::prev == byte_idx::cur - byte_idx::prev",
+                "tag_idx::cur - tag_idx::prev == byte_idx::cur - byte_idx::prev (+1 if RLE)",
                 meta.query_advice(config.tag_config.tag_idx, Rotation::cur()),
                 meta.query_advice(config.tag_config.tag_idx, Rotation::prev())
-                    + byte_idx_delta.expr(),
+                    + byte_idx_delta.expr()
+                    + config.block_config.is_rle_content(meta, Rotation::cur()),
             );
 
             // tag_rlc is computed correctly, i.e. its accumulated with byte_idx increment, however
@@ -1759,9 +2042,17 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
             ]);
 
+            // The content of an RLE_Block is a single encoded byte, irrespective of tag_len.
+            let tag_len = meta.query_advice(config.tag_config.tag_len, Rotation::cur());
+            let exponent = select::expr(
+                config.block_config.is_rle_content(meta, Rotation::cur()),
+                1.expr(),
+                tag_len,
+            );
+
             [
                 1.expr(),                                                           // enabled
-                meta.query_advice(config.tag_config.tag_len, Rotation::cur()),      // exponent
+                exponent,                                                           // exponent
                 meta.query_advice(config.tag_config.rpow_tag_len, Rotation::cur()), // exponentiation
             ]
             .into_iter()
@@ -1954,17 +2245,52 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             let block_type_bit1 = config.bits[1].expr_at(meta, Rotation::cur());
             let block_type_bit2 = config.bits[2].expr_at(meta, Rotation::cur());
 
-            // We expect a Block_Type of Compressed_Block, i.e. Block_Type == 2.
+            // We expect a Block_Type of Raw_Block (0), RLE_Block (1) or Compressed_Block (2), i.e.
+            // the Reserved (3) Block_Type is invalid.
+            cb.require_zero(
+                "Block_Type is not Reserved",
+                block_type_bit1.expr() * block_type_bit2.expr(),
+            );
             cb.require_equal(
-                "Block_Type is Compressed_Block (bit 1)",
+                "is_raw_block assigned correctly",
+                meta.query_advice(config.block_config.is_raw_block, Rotation::cur()),
+                not::expr(block_type_bit1.expr() + block_type_bit2.expr()),
+            );
+            cb.require_equal(
+                "is_rle_block assigned correctly",
+                meta.query_advice(config.block_config.is_rle_block, Rotation::cur()),
                 block_type_bit1,
-                0.expr(),
             );
+
+            // A Compressed_Block starts with the literals header, whereas the content of a
+            // Raw_Block or RLE_Block is directly processed as the literals of a block without
+            // sequences. The regenerated size is then the Block_Size.
             cb.require_equal(
-                "Block_Type is Compressed_Block (bit 2)",
-                block_type_bit2,
-                1.expr(),
+                "tag_next depends on Block_Type",
+                meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
+                select::expr(
+                    block_type_bit2.expr(),
+                    ZstdTag::ZstdBlockLiteralsHeader.expr(),
+                    ZstdTag::ZstdBlockLiteralsRawBytes.expr(),
+                ),
             );
+            cb.condition(not::expr(block_type_bit2), |cb| {
+                let block_len = meta.query_advice(config.block_config.block_len, Rotation::cur());
+                cb.require_equal(
+                    "Raw_Block/RLE_Block: tag_len == Block_Size",
+                    meta.query_advice(config.tag_config.tag_len, Rotation(3)),
+                    block_len.expr(),
+                );
+                cb.require_equal(
+                    "Raw_Block/RLE_Block: regen_size == Block_Size",
+                    meta.query_advice(config.block_config.regen_size, Rotation(3)),
+                    block_len,
+                );
+                cb.require_zero(
+                    "Raw_Block/RLE_Block: num_sequences == 0",
+                    meta.query_advice(config.block_config.num_sequences, Rotation(3)),
+                );
+            });
 
             // is_last_block is assigned correctly.
             cb.require_equal(
@@ -1989,32 +2315,44 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 config.block_config.block_len,
                 config.block_config.block_idx,
                 config.block_config.is_last_block,
+                config.block_config.is_raw_block,
+                config.block_config.is_rle_block,
             ] {
                 cb.require_equal(
-                    "BlockHeader: block_idx/block_len/is_last_block",
+                    "BlockHeader: block_idx/block_len/is_last_block/block_type",
                     meta.query_advice(column, Rotation(0)),
                     meta.query_advice(column, Rotation(1)),
                 );
                 cb.require_equal(
-                    "BlockHeader: block_idx/block_len/is_last_block",
+                    "BlockHeader: block_idx/block_len/is_last_block/block_type",
                     meta.query_advice(column, Rotation(0)),
                     meta.query_advice(column, Rotation(2)),
                 );
             }
 
             // We now validate the end of the previous block.
-            // - tag=BlockHeader is preceded by tag in [FrameContentSize, SeqHeader, SeqData].
+            // - tag=BlockHeader is preceded by tag in [FrameContentSize, SeqHeader, SeqData,
+            //   RawBytes].
             // - if prev_tag=SequenceHeader: prev block had no sequences.
             // - if prev_tag=SequenceData: all sequences from prev block were decoded.
+            // - if prev_tag=RawBytes: prev block was a Raw_Block or RLE_Block.
             cb.require_equal(
-                "tag::prev in [FCS, SH, SD]",
+                "tag::prev in [FCS, SH, SD, RB]",
                 sum::expr([
                     is_prev_frame_content_size(meta),
                     is_prev_sequence_header(meta),
                     is_prev_sequence_data(meta),
+                    is_prev_raw_bytes(meta),
                 ]),
                 1.expr(),
             );
+            cb.condition(is_prev_raw_bytes(meta), |cb| {
+                cb.require_equal(
+                    "tag::prev=RawBytes",
+                    config.block_config.is_raw_or_rle(meta, Rotation::prev()),
+                    1.expr(),
+                );
+            });
             cb.condition(is_prev_sequence_header(meta), |cb| {
                 cb.require_equal(
                     "tag::prev=SeqHeader",
@@ -2082,6 +2420,18 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 meta.query_advice(config.block_config.block_idx, Rotation::prev()),
             );
 
+            // the block type remains unchanged.
+            for column in [
+                config.block_config.is_raw_block,
+                config.block_config.is_rle_block,
+            ] {
+                cb.require_equal(
+                    "block_type::cur == block_type::prev",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+
             // the number of sequences in the block remains the same.
             cb.require_equal(
                 "num_sequences::cur == num_sequences::prev",
@@ -2097,7 +2447,13 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             );
 
             // the compression modes are remembered throughout the block's context.
-            for column in config.block_config.compression_modes {
+            for column in config
+                .block_config
+                .compression_modes
+                .into_iter()
+                .chain(config.block_config.rle_modes)
+                .chain(config.block_config.repeat_modes)
+            {
                 cb.require_equal(
                     "compression_modes::cur == compression_modes::prev (during block)",
                     meta.query_advice(column, Rotation::cur()),
@@ -2105,6 +2461,23 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 );
             }
 
+            // the parameters of the Huffman-coded literals section remain unchanged.
+            for column in [
+                config.literals_decoder.is_4_streams,
+                config.literals_decoder.literals_end_byte_idx,
+                config.literals_decoder.n_weights,
+                config.literals_decoder.stream_sizes[0],
+                config.literals_decoder.stream_sizes[1],
+                config.literals_decoder.stream_sizes[2],
+                config.literals_decoder.n_literals_per_stream,
+            ] {
+                cb.require_equal(
+                    "literals section parameters unchanged (during block)",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+
             cb.gate(condition)
         });
 
@@ -2125,44 +2498,155 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             let literals_block_type_bit0 = config.bits[0].expr_at(meta, Rotation::cur());
             let literals_block_type_bit1 = config.bits[1].expr_at(meta, Rotation::cur());
 
-            // We expect a Raw_Literals_Block, i.e. bit0 and bit1 are both 0.
-            cb.require_zero("Raw_Literals_Block: bit0", literals_block_type_bit0);
-            cb.require_zero("Raw_Literals_Block: bit1", literals_block_type_bit1);
+            // We expect a Raw_Literals_Block (0), Compressed_Literals_Block (2) or
+            // Treeless_Literals_Block (3), i.e. RLE_Literals_Block (1) is not supported.
+            cb.require_zero(
+                "Literals_Block_Type is not RLE_Literals_Block",
+                literals_block_type_bit0.expr() * not::expr(literals_block_type_bit1.expr()),
+            );
+            let is_huffman = literals_block_type_bit1;
 
             let size_format_bit0 = config.bits[2].expr_at(meta, Rotation::cur());
             let size_format_bit1 = config.bits[3].expr_at(meta, Rotation::cur());
 
-            // - Size_Format is 00 or 10: Size_Format uses 1 bit, literals header is 1 byte
-            // - Size_Format is 01: Size_Format uses 2 bits, literals header is 2 bytes
-            // - Size_Format is 10: Size_Format uses 2 bits, literals header is 3 bytes
-            let expected_tag_len = select::expr(
-                not::expr(size_format_bit0.expr()),
-                1.expr(),
-                select::expr(size_format_bit1.expr(), 3.expr(), 2.expr()),
-            );
-            cb.require_equal(
-                "ZstdBlockLiteralsHeader: tag_len == expected_tag_len",
-                meta.query_advice(config.tag_config.tag_len, Rotation::cur()),
-                expected_tag_len,
-            );
+            cb.condition(not::expr(is_huffman.expr()), |cb| {
+                // - Size_Format is 00 or 10: Size_Format uses 1 bit, literals header is 1 byte
+                // - Size_Format is 01: Size_Format uses 2 bits, literals header is 2 bytes
+                // - Size_Format is 10: Size_Format uses 2 bits, literals header is 3 bytes
+                let expected_tag_len = select::expr(
+                    not::expr(size_format_bit0.expr()),
+                    1.expr(),
+                    select::expr(size_format_bit1.expr(), 3.expr(), 2.expr()),
+                );
+                cb.require_equal(
+                    "Raw_Literals_Block: tag_len == expected_tag_len",
+                    meta.query_advice(config.tag_config.tag_len, Rotation::cur()),
+                    expected_tag_len,
+                );
+
+                // The regenerated size is in fact the tag length of the
+                // ZstdBlockLiteralsRawBytes tag. But depending on how many bytes are in the
+                // literals header, we select the appropriate offset to read the tag_len from.
+                let regen_size = select::expr(
+                    size_format_bit0.expr() * not::expr(size_format_bit1.expr()),
+                    meta.query_advice(config.tag_config.tag_len, Rotation(2)),
+                    select::expr(
+                        size_format_bit0.expr() * size_format_bit1.expr(),
+                        meta.query_advice(config.tag_config.tag_len, Rotation(3)),
+                        meta.query_advice(config.tag_config.tag_len, Rotation(1)),
+                    ),
+                );
+                cb.require_equal(
+                    "Raw_Literals_Block: regen size check",
+                    regen_size,
+                    meta.query_advice(config.block_config.regen_size, Rotation::cur()),
+                );
+
+                cb.require_equal(
+                    "Raw_Literals_Block: tag_next",
+                    meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
+                    ZstdTag::ZstdBlockLiteralsRawBytes.expr(),
+                );
+            });
+
+            cb.condition(is_huffman.expr(), |cb| {
+                // - Size_Format is 00: 1 stream, sizes use 10 bits each, header is 3 bytes
+                // - Size_Format is 01: 4 streams, sizes use 10 bits each, header is 3 bytes
+                // - Size_Format is 10: 4 streams, sizes use 14 bits each, header is 4 bytes
+                // - Size_Format is 11: 4 streams, sizes use 18 bits each, header is 5 bytes
+                cb.require_equal(
+                    "Huffman-coded literals: tag_len == expected_tag_len",
+                    meta.query_advice(config.tag_config.tag_len, Rotation::cur()),
+                    select::expr(
+                        size_format_bit1.expr(),
+                        4.expr() + size_format_bit0.expr(),
+                        3.expr(),
+                    ),
+                );
+                let is_4_streams =
+                    meta.query_advice(config.literals_decoder.is_4_streams, Rotation::cur());
+                cb.require_equal(
+                    "Huffman-coded literals: is_4_streams",
+                    is_4_streams.expr(),
+                    not::expr(and::expr([
+                        not::expr(size_format_bit0.expr()),
+                        not::expr(size_format_bit1.expr()),
+                    ])),
+                );
+
+                // The regenerated size and the compressed size follow the literals block type and
+                // size format in the little-endian header, where the p-th bit of the header is
+                // found at bits[p % 8] of the (p / 8)-th byte.
+                let mut header_bit = |p: usize| {
+                    config.bits[p % N_BITS_PER_BYTE]
+                        .expr_at(meta, Rotation((p / N_BITS_PER_BYTE) as i32))
+                };
+                let sizes = [10, 14, 18].map(|n_bits| {
+                    let regen_size = (0..n_bits)
+                        .rev()
+                        .fold(0.expr(), |acc, i| acc * 2.expr() + header_bit(4 + i));
+                    let compressed_size = (0..n_bits).rev().fold(0.expr(), |acc, i| {
+                        acc * 2.expr() + header_bit(4 + n_bits + i)
+                    });
+                    (regen_size, compressed_size)
+                });
+                let select_size = |sizes: [Expression<Fr>; 3]| {
+                    let [size10, size14, size18] = sizes;
+                    select::expr(
+                        size_format_bit1.expr(),
+                        select::expr(size_format_bit0.expr(), size18, size14),
+                        size10,
+                    )
+                };
+                let regen_size = select_size(sizes.clone().map(|(regen_size, _)| regen_size));
+                let compressed_size =
+                    select_size(sizes.map(|(_, compressed_size)| compressed_size));
+                cb.require_equal(
+                    "Huffman-coded literals: regen size check",
+                    regen_size,
+                    meta.query_advice(config.block_config.regen_size, Rotation::cur()),
+                );
+                cb.require_equal(
+                    "Huffman-coded literals: literals_end_byte_idx",
+                    meta.query_advice(
+                        config.literals_decoder.literals_end_byte_idx,
+                        Rotation::cur(),
+                    ),
+                    meta.query_advice(config.byte_idx, Rotation::cur())
+                        + meta.query_advice(config.tag_config.tag_len, Rotation::cur())
+                        - 1.expr()
+                        + compressed_size,
+                );
+
+                // A Compressed_Literals_Block begins with the Huffman tree description, whereas a
+                // Treeless_Literals_Block begins with the jump table (4 streams) or the only
+                // literals stream.
+                cb.require_equal(
+                    "Huffman-coded literals: tag_next",
+                    meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
+                    select::expr(
+                        literals_block_type_bit0.expr(),
+                        select::expr(
+                            is_4_streams,
+                            ZstdTag::ZstdBlockJumpTable.expr(),
+                            ZstdTag::ZstdBlockLstream.expr(),
+                        ),
+                        ZstdTag::ZstdBlockHuffmanCode.expr(),
+                    ),
+                );
+            });
 
-            // The regenerated size is in fact the tag length of the ZstdBlockLiteralsRawBytes
-            // tag. But depending on how many bytes are in the literals header, we select the
-            // appropriate offset to read the tag_len from.
-            let regen_size = select::expr(
-                size_format_bit0.expr() * not::expr(size_format_bit1.expr()),
-                meta.query_advice(config.tag_config.tag_len, Rotation(2)),
+            // The Huffman tree described in a Compressed_Literals_Block is used to decode the
+            // literals of this and the subsequent Treeless_Literals_Blocks.
+            cb.require_equal(
+                "huffman_block_idx::cur == is_compressed ? block_idx : huffman_block_idx::prev",
+                meta.query_advice(config.literals_decoder.huffman_block_idx, Rotation::cur()),
                 select::expr(
-                    size_format_bit0.expr() * size_format_bit1.expr(),
-                    meta.query_advice(config.tag_config.tag_len, Rotation(3)),
-                    meta.query_advice(config.tag_config.tag_len, Rotation(1)),
+                    is_huffman * not::expr(literals_block_type_bit0),
+                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    meta.query_advice(config.literals_decoder.huffman_block_idx, Rotation::prev()),
                 ),
             );
-            cb.require_equal(
-                "regen size check",
-                regen_size,
-                meta.query_advice(config.block_config.regen_size, Rotation::cur()),
-            );
 
             cb.gate(condition)
         });
@@ -2173,6 +2657,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 let condition = and::expr([
                     meta.query_advice(config.tag_config.is_literals_header, Rotation::cur()),
                     config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    not::expr(config.bits[1].expr_at(meta, Rotation::cur())),
                 ]);
 
                 let size_format_bit0 = config.bits[2].expr_at(meta, Rotation::cur());
@@ -2227,14 +2712,528 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
 
             let mut cb = BaseConstraintBuilder::default();
 
+            // Every row is a new byte, except for the content of an RLE_Block where the first
+            // byte is repeated.
+            let is_rle_block = meta.query_advice(config.block_config.is_rle_block, Rotation::cur());
+            let is_change = config.tag_config.is_change.expr_at(meta, Rotation::cur());
+            cb.condition(not::expr(is_rle_block.expr()), |cb| {
+                cb.require_equal(
+                    "byte_idx::cur == byte_idx::prev + 1",
+                    meta.query_advice(config.byte_idx, Rotation::cur()),
+                    meta.query_advice(config.byte_idx, Rotation::prev()) + 1.expr(),
+                );
+            });
+            cb.condition(is_rle_block * not::expr(is_change.expr()), |cb| {
+                cb.require_equal(
+                    "RLE_Block: byte_idx::cur == byte_idx::prev",
+                    meta.query_advice(config.byte_idx, Rotation::cur()),
+                    meta.query_advice(config.byte_idx, Rotation::prev()),
+                );
+            });
+
+            // Every row decodes a literal, i.e. the byte itself.
             cb.require_equal(
-                "byte_idx::cur == byte_idx::prev + 1",
-                meta.query_advice(config.byte_idx, Rotation::cur()),
-                meta.query_advice(config.byte_idx, Rotation::prev()) + 1.expr(),
+                "RawBytes: literal == byte",
+                meta.query_advice(config.literals_decoder.literal, Rotation::cur()),
+                meta.query_advice(config.byte, Rotation::cur()),
+            );
+            cb.require_equal(
+                "RawBytes: literal_idx == tag_idx",
+                meta.query_advice(config.literals_decoder.literal_idx, Rotation::cur()),
+                meta.query_advice(config.tag_config.tag_idx, Rotation::cur()),
             );
 
-            cb.gate(condition)
-        });
+            // The literals of a Compressed_Block are followed by the sequences section, whereas a
+            // Raw_Block or RLE_Block ends here.
+            let is_raw_or_rle = config.block_config.is_raw_or_rle(meta, Rotation::cur());
+            let is_last_block =
+                meta.query_advice(config.block_config.is_last_block, Rotation::cur());
+            cb.condition(is_change, |cb| {
+                cb.require_equal(
+                    "RawBytes: tag_next",
+                    meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
+                    select::expr(
+                        is_raw_or_rle,
+                        select::expr(
+                            is_last_block,
+                            ZstdTag::Null.expr(),
+                            ZstdTag::BlockHeader.expr(),
+                        ),
+                        ZstdTag::ZstdBlockSequenceHeader.expr(),
+                    ),
+                );
+            });
+
+            cb.gate(condition)
+        });
+
+        debug_assert!(meta.degree() <= 9);
+
+        ///////////////////////////////////////////////////////////////////////////////////////////
+        ////////////////////////////// ZstdTag::ZstdBlockHuffmanCode //////////////////////////////
+        ///////////////////////////////////////////////////////////////////////////////////////////
+        meta.create_gate("DecoderConfig: tag ZstdBlockHuffmanCode", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(config.q_enable, Rotation::cur()),
+                meta.query_advice(config.tag_config.is_huffman_code, Rotation::cur()),
+                config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            // The Huffman tree description begins with a header byte. We only support the weights
+            // being directly represented, i.e. header >= 128, in which case the number of weights
+            // is header - 127. The weights follow with 4 bits each.
+            cb.require_equal(
+                "HuffmanCode: header >= 128",
+                config.bits[7].expr_at(meta, Rotation::cur()),
+                1.expr(),
+            );
+            let n_weights = meta.query_advice(config.literals_decoder.n_weights, Rotation::cur());
+            cb.require_equal(
+                "HuffmanCode: n_weights == header - 127",
+                n_weights.expr(),
+                meta.query_advice(config.byte, Rotation::cur()) - 127.expr(),
+            );
+
+            // tag_len == 1 + ceil(n_weights / 2)
+            cb.require_boolean(
+                "HuffmanCode: tag_len == 1 + ceil(n_weights / 2)",
+                (meta.query_advice(config.tag_config.tag_len, Rotation::cur()) - 1.expr())
+                    * 2.expr()
+                    - n_weights,
+            );
+
+            // The Huffman tree description is followed by the jump table (4 streams) or the only
+            // literals stream.
+            cb.require_equal(
+                "HuffmanCode: tag_next",
+                meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
+                select::expr(
+                    meta.query_advice(config.literals_decoder.is_4_streams, Rotation::cur()),
+                    ZstdTag::ZstdBlockJumpTable.expr(),
+                    ZstdTag::ZstdBlockLstream.expr(),
+                ),
+            );
+
+            cb.gate(condition)
+        });
+
+        meta.lookup_any(
+            "DecoderConfig: tag ZstdBlockHuffmanCode (last symbol)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_advice(config.tag_config.is_huffman_code, Rotation::cur()),
+                    config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                ]);
+
+                // The symbols 0..n_weights are explicitly described, and the last symbol, i.e.
+                // symbol == n_weights, has an implied weight.
+                [
+                    0.expr(), // q_first
+                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    meta.query_advice(config.literals_decoder.n_weights, Rotation::cur()),
+                    0.expr(), // is_padding
+                ]
+                .into_iter()
+                .zip_eq(config.huffman_codes_table.table_exprs_metadata(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.lookup_any(
+            "DecoderConfig: tag ZstdBlockHuffmanCode (weight in high nibble)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_advice(config.tag_config.is_huffman_code, Rotation::cur()),
+                    not::expr(config.tag_config.is_change.expr_at(meta, Rotation::cur())),
+                ]);
+
+                // The byte at tag_idx describes the weights of symbols 2 * (tag_idx - 2) and
+                // 2 * (tag_idx - 2) + 1, in its high and low nibbles respectively.
+                let symbol = (meta.query_advice(config.tag_config.tag_idx, Rotation::cur())
+                    - 2.expr())
+                    * 2.expr();
+                let bits = config.bits.map(|bit| bit.expr_at(meta, Rotation::cur()));
+                let weight = bits[4].expr()
+                    + bits[5].expr() * 2.expr()
+                    + bits[6].expr() * 4.expr()
+                    + bits[7].expr() * 8.expr();
+
+                [
+                    0.expr(), // q_first
+                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    symbol,
+                    weight,
+                    0.expr(), // is_padding
+                ]
+                .into_iter()
+                .zip_eq(config.huffman_codes_table.table_exprs_by_symbol(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.lookup_any(
+            "DecoderConfig: tag ZstdBlockHuffmanCode (weight in low nibble)",
+            |meta| {
+                // If the number of weights is odd, the low nibble of the last byte is ignored.
+                let tag_idx = meta.query_advice(config.tag_config.tag_idx, Rotation::cur());
+                let tag_len = meta.query_advice(config.tag_config.tag_len, Rotation::cur());
+                let is_last_byte = config.tag_config.tag_idx_eq_tag_len.expr_at(
+                    meta,
+                    Rotation::cur(),
+                    tag_idx.expr(),
+                    tag_len.expr(),
+                );
+                let is_odd = (tag_len - 1.expr()) * 2.expr()
+                    - meta.query_advice(config.literals_decoder.n_weights, Rotation::cur());
+                let condition = and::expr([
+                    meta.query_advice(config.tag_config.is_huffman_code, Rotation::cur()),
+                    not::expr(config.tag_config.is_change.expr_at(meta, Rotation::cur())),
+                    not::expr(is_last_byte * is_odd),
+                ]);
+
+                let symbol = (tag_idx - 2.expr()) * 2.expr() + 1.expr();
+                let bits = config.bits.map(|bit| bit.expr_at(meta, Rotation::cur()));
+                let weight = bits[0].expr()
+                    + bits[1].expr() * 2.expr()
+                    + bits[2].expr() * 4.expr()
+                    + bits[3].expr() * 8.expr();
+
+                [
+                    0.expr(), // q_first
+                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    symbol,
+                    weight,
+                    0.expr(), // is_padding
+                ]
+                .into_iter()
+                .zip_eq(config.huffman_codes_table.table_exprs_by_symbol(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        debug_assert!(meta.degree() <= 9);
+
+        ///////////////////////////////////////////////////////////////////////////////////////////
+        /////////////////////////////// ZstdTag::ZstdBlockJumpTable ///////////////////////////////
+        ///////////////////////////////////////////////////////////////////////////////////////////
+        meta.create_gate("DecoderConfig: tag ZstdBlockJumpTable", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(config.q_enable, Rotation::cur()),
+                meta.query_advice(config.tag_config.is_jump_table, Rotation::cur()),
+                config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            // The jump table is 6 bytes, i.e. the sizes of the first 3 literals streams as 2-byte
+            // little-endian values.
+            cb.require_equal(
+                "JumpTable: tag_len == 6",
+                meta.query_advice(config.tag_config.tag_len, Rotation::cur()),
+                6.expr(),
+            );
+            for (i, stream_size) in config.literals_decoder.stream_sizes.into_iter().enumerate() {
+                cb.require_equal(
+                    "JumpTable: stream_size",
+                    meta.query_advice(stream_size, Rotation::cur()),
+                    meta.query_advice(config.byte, Rotation(2 * i as i32))
+                        + meta.query_advice(config.byte, Rotation(2 * i as i32 + 1)) * 256.expr(),
+                );
+            }
+
+            // Each of the first 3 streams decodes ceil(regen_size / 4) literals, i.e.
+            // 4 * n_literals_per_stream - regen_size in [0, 4).
+            let diff = meta.query_advice(
+                config.literals_decoder.n_literals_per_stream,
+                Rotation::cur(),
+            ) * 4.expr()
+                - meta.query_advice(config.block_config.regen_size, Rotation::cur());
+            cb.require_zero(
+                "JumpTable: n_literals_per_stream == ceil(regen_size / 4)",
+                diff.expr()
+                    * (diff.expr() - 1.expr())
+                    * (diff.expr() - 2.expr())
+                    * (diff - 3.expr()),
+            );
+
+            cb.require_equal(
+                "JumpTable: tag_next",
+                meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
+                ZstdTag::ZstdBlockLstream.expr(),
+            );
+
+            cb.gate(condition)
+        });
+
+        debug_assert!(meta.degree() <= 9);
+
+        ///////////////////////////////////////////////////////////////////////////////////////////
+        //////////////////////////////// ZstdTag::ZstdBlockLstream ////////////////////////////////
+        ///////////////////////////////////////////////////////////////////////////////////////////
+        meta.create_gate(
+            "DecoderConfig: tag ZstdBlockLstream (sentinel row)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_fixed(config.q_enable, Rotation::cur()),
+                    meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
+                    config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                ]);
+
+                let mut cb = BaseConstraintBuilder::default();
+
+                // Similar to the tag=SequencesData, every literals stream is read from
+                // back-to-front and begins with the sentinel bitstring, i.e. 0-7
+                // number of 0s followed by a sentinel 1-bit.
+                cb.require_zero(
+                    "sentinel: is_nil=false",
+                    config.bitstream_decoder.is_nil(meta, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "sentinel: is_nb0=false",
+                    config.bitstream_decoder.is_nb0(meta, Rotation::cur()),
+                );
+                cb.require_equal(
+                    "sentinel: bitstring_value=1",
+                    meta.query_advice(config.bitstream_decoder.bitstring_value, Rotation::cur()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "sentinel: bit_index_end <= 7",
+                    config
+                        .bitstream_decoder
+                        .spans_one_byte(meta, Rotation::cur()),
+                    1.expr(),
+                );
+
+                // The streams are indexed 0..4, and the literals are indexed continuing over the
+                // streams.
+                let is_prev_lstream = is_prev_lstream(meta);
+                let lstream_idx =
+                    meta.query_advice(config.literals_decoder.lstream_idx, Rotation::cur());
+                let lstream_idx_prev =
+                    meta.query_advice(config.literals_decoder.lstream_idx, Rotation::prev());
+                let literal_idx_prev =
+                    meta.query_advice(config.literals_decoder.literal_idx, Rotation::prev());
+                cb.require_equal(
+                    "sentinel: lstream_idx",
+                    lstream_idx.expr(),
+                    select::expr(
+                        is_prev_lstream.expr(),
+                        lstream_idx_prev.expr() + 1.expr(),
+                        0.expr(),
+                    ),
+                );
+                cb.require_equal(
+                    "sentinel: literal_idx",
+                    meta.query_advice(config.literals_decoder.literal_idx, Rotation::cur()),
+                    select::expr(is_prev_lstream.expr(), literal_idx_prev.expr(), 0.expr()),
+                );
+
+                // Each of the first 3 streams decodes exactly n_literals_per_stream literals.
+                let n_literals_per_stream = meta.query_advice(
+                    config.literals_decoder.n_literals_per_stream,
+                    Rotation::cur(),
+                );
+                cb.condition(is_prev_lstream, |cb| {
+                    cb.require_equal(
+                        "previous stream decoded n_literals_per_stream literals",
+                        literal_idx_prev,
+                        (lstream_idx_prev + 1.expr()) * n_literals_per_stream,
+                    );
+                });
+
+                // The sizes of the first 3 streams are as per the jump table.
+                let is_4_streams =
+                    meta.query_advice(config.literals_decoder.is_4_streams, Rotation::cur());
+                let tag_len = meta.query_advice(config.tag_config.tag_len, Rotation::cur());
+                for (k, stream_size) in config.literals_decoder.stream_sizes.into_iter().enumerate()
+                {
+                    let is_stream_k = (0..4)
+                        .filter(|&j| j != k)
+                        .fold(1.expr(), |acc, j| acc * (lstream_idx.expr() - j.expr()));
+                    cb.require_zero(
+                        "stream k < 3: tag_len == stream_sizes[k]",
+                        is_4_streams.expr()
+                            * is_stream_k
+                            * (tag_len.expr() - meta.query_advice(stream_size, Rotation::cur())),
+                    );
+                }
+
+                // The last stream is followed by the sequences section. Note that tag_next is
+                // either ZstdBlockLstream or ZstdBlockSequenceHeader as per the tag
+                // transition table.
+                let is_last_stream = (ZstdTag::ZstdBlockLstream.expr()
+                    - meta.query_advice(config.tag_config.tag_next, Rotation::cur()))
+                    * Expression::Constant(
+                        Fr::from(
+                            ZstdTag::ZstdBlockLstream as u64
+                                - ZstdTag::ZstdBlockSequenceHeader as u64,
+                        )
+                        .invert()
+                        .unwrap(),
+                    );
+                cb.condition(is_last_stream.expr(), |cb| {
+                    cb.require_equal(
+                        "last stream: lstream_idx == 3 (4 streams) or 0 (1 stream)",
+                        lstream_idx.expr(),
+                        is_4_streams.expr() * 3.expr(),
+                    );
+                });
+                cb.condition(not::expr(is_last_stream), |cb| {
+                    cb.require_equal(
+                        "not last stream: is_4_streams",
+                        is_4_streams.expr(),
+                        1.expr(),
+                    );
+                    cb.require_zero(
+                        "not last stream: lstream_idx in [0, 3)",
+                        lstream_idx.expr()
+                            * (lstream_idx.expr() - 1.expr())
+                            * (lstream_idx - 2.expr()),
+                    );
+                });
+
+                cb.gate(condition)
+            },
+        );
+
+        meta.create_gate("DecoderConfig: tag ZstdBlockLstream", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(config.q_enable, Rotation::cur()),
+                meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
+                not::expr(config.tag_config.is_change.expr_at(meta, Rotation::cur())),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "lstream_idx::cur == lstream_idx::prev",
+                meta.query_advice(config.literals_decoder.lstream_idx, Rotation::cur()),
+                meta.query_advice(config.literals_decoder.lstream_idx, Rotation::prev()),
+            );
+            cb.require_equal(
+                "literal_idx::cur == literal_idx::prev + is_literal",
+                meta.query_advice(config.literals_decoder.literal_idx, Rotation::cur()),
+                meta.query_advice(config.literals_decoder.literal_idx, Rotation::prev())
+                    + config
+                        .literals_decoder
+                        .is_literal
+                        .expr_at(meta, Rotation::cur()),
+            );
+
+            cb.gate(condition)
+        });
+
+        meta.lookup_any(
+            "DecoderConfig: tag ZstdBlockLstream (prefix code to literal)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_fixed(config.q_enable, Rotation::cur()),
+                    meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
+                    config
+                        .literals_decoder
+                        .is_literal
+                        .expr_at(meta, Rotation::cur()),
+                ]);
+
+                // The bitstring read is a prefix code of the Huffman tree applicable to the
+                // block, and decodes to the literal.
+                let (bit_index_start, bit_index_end) = (
+                    meta.query_advice(config.bitstream_decoder.bit_index_start, Rotation::cur()),
+                    meta.query_advice(config.bitstream_decoder.bit_index_end, Rotation::cur()),
+                );
+
+                [
+                    0.expr(), // q_first
+                    meta.query_advice(config.literals_decoder.huffman_block_idx, Rotation::cur()),
+                    1.expr(), // has_code
+                    meta.query_advice(config.bitstream_decoder.bitstring_value, Rotation::cur()),
+                    bit_index_end - bit_index_start + 1.expr(),
+                    meta.query_advice(config.literals_decoder.literal, Rotation::cur()),
+                    0.expr(), // is_padding
+                ]
+                .into_iter()
+                .zip_eq(config.huffman_codes_table.table_exprs_by_code(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.create_gate("DecoderConfig: tag ZstdBlockLstream (last row)", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(config.q_enable, Rotation::cur()),
+                meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
+                config.tag_config.is_change.expr_at(meta, Rotation::next()),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            // bitstream was consumed completely (byte-aligned):
+            // - if not_nil(cur) -> bit_index_end == 7
+            // - if nil(cur) and not_nil(prev) -> bit_index_end == 15
+            // - if nil(cur) and nil(prev) -> not_nil(-2) and bit_index_end == 23
+            let (is_nil_curr, is_nil_prev, is_nil_prev_prev) = (
+                config.bitstream_decoder.is_nil(meta, Rotation::cur()),
+                config.bitstream_decoder.is_nil(meta, Rotation::prev()),
+                config.bitstream_decoder.is_nil(meta, Rotation(-2)),
+            );
+            cb.condition(not::expr(is_nil_curr.expr()), |cb| {
+                cb.require_equal(
+                    "is_not_nil: bit_index_end==7",
+                    meta.query_advice(config.bitstream_decoder.bit_index_end, Rotation::cur()),
+                    7.expr(),
+                );
+            });
+            cb.condition(
+                and::expr([is_nil_curr.expr(), not::expr(is_nil_prev.expr())]),
+                |cb| {
+                    cb.require_equal(
+                        "is_nil and is_not_nil(prev): bit_index_end==15",
+                        meta.query_advice(config.bitstream_decoder.bit_index_end, Rotation::prev()),
+                        15.expr(),
+                    );
+                },
+            );
+            cb.condition(and::expr([is_nil_curr, is_nil_prev]), |cb| {
+                cb.require_zero("is_nil and is_nil(prev): is_not_nil(-2)", is_nil_prev_prev);
+                cb.require_equal(
+                    "is_nil and is_nil(prev): bit_index_end==23",
+                    meta.query_advice(config.bitstream_decoder.bit_index_end, Rotation(-2)),
+                    23.expr(),
+                );
+            });
+
+            // The last stream ends at the end of the literals section, having decoded all the
+            // literals of the block.
+            let is_next_sequence_header = config
+                .tag_config
+                .tag_bits
+                .value_equals(ZstdTag::ZstdBlockSequenceHeader, Rotation::next())(
+                meta
+            );
+            cb.condition(is_next_sequence_header, |cb| {
+                cb.require_equal(
+                    "last stream: byte_idx == literals_end_byte_idx",
+                    meta.query_advice(config.byte_idx, Rotation::cur()),
+                    meta.query_advice(
+                        config.literals_decoder.literals_end_byte_idx,
+                        Rotation::cur(),
+                    ),
+                );
+                cb.require_equal(
+                    "last stream: literal_idx == regen_size",
+                    meta.query_advice(config.literals_decoder.literal_idx, Rotation::cur()),
+                    meta.query_advice(config.block_config.regen_size, Rotation::cur()),
+                );
+            });
+
+            cb.gate(condition)
+        });
 
         debug_assert!(meta.degree() <= 9);
 
@@ -2270,38 +3269,97 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 decoded_sequences_header.num_sequences,
             );
 
-            // The compression modes for literals length, match length and offsets are expected to
-            // be either Predefined_Mode or Fse_Compressed_Mode, i.e. compression mode==0 or
-            // compression_mode==2. i.e. bit0==0.
-            cb.require_zero("ll: bit0 == 0", decoded_sequences_header.comp_mode_bit0_ll);
-            cb.require_zero("om: bit0 == 0", decoded_sequences_header.comp_mode_bit0_om);
-            cb.require_zero("ml: bit0 == 0", decoded_sequences_header.comp_mode_bit0_ml);
+            // The compression modes for literals length, match length and offsets are decoded
+            // into the (compression_modes, rle_modes, repeat_modes) boolean flags, where:
+            //
+            // - Predefined_Mode     (bit1, bit0) = (0, 0) => (0, 0, 0)
+            // - RLE_Mode            (bit1, bit0) = (0, 1) => (1, 1, 0)
+            // - Fse_Compressed_Mode (bit1, bit0) = (1, 0) => (1, 0, 0)
+            // - Repeat_Mode         (bit1, bit0) = (1, 1) => (0, 0, 1)
+            //
+            // i.e. bit0 == rle_mode + repeat_mode and bit1 == compression_mode - rle_mode +
+            // repeat_mode.
+            let comp_mode_bits = [
+                (
+                    decoded_sequences_header.comp_mode_bit0_ll,
+                    decoded_sequences_header.comp_mode_bit1_ll,
+                ),
+                (
+                    decoded_sequences_header.comp_mode_bit0_om,
+                    decoded_sequences_header.comp_mode_bit1_om,
+                ),
+                (
+                    decoded_sequences_header.comp_mode_bit0_ml,
+                    decoded_sequences_header.comp_mode_bit1_ml,
+                ),
+            ];
+            for (idx, (bit0, bit1)) in comp_mode_bits.into_iter().enumerate() {
+                let (compression_mode, rle_mode, repeat_mode) = (
+                    meta.query_advice(config.block_config.compression_modes[idx], Rotation::cur()),
+                    meta.query_advice(config.block_config.rle_modes[idx], Rotation::cur()),
+                    meta.query_advice(config.block_config.repeat_modes[idx], Rotation::cur()),
+                );
+                cb.require_boolean("compression_mode is boolean", compression_mode.expr());
+                cb.require_boolean("rle_mode is boolean", rle_mode.expr());
+                cb.require_boolean("repeat_mode is boolean", repeat_mode.expr());
+                cb.require_zero(
+                    "rle_mode implies compression_mode",
+                    rle_mode.expr() * not::expr(compression_mode.expr()),
+                );
+                cb.require_zero(
+                    "repeat_mode excludes compression_mode",
+                    repeat_mode.expr() * compression_mode.expr(),
+                );
+                cb.require_equal(
+                    "block_config: compression mode bit0",
+                    bit0,
+                    rle_mode.expr() + repeat_mode.expr(),
+                );
+                cb.require_equal(
+                    "block_config: compression mode bit1",
+                    bit1,
+                    compression_mode.expr() - rle_mode.expr() + repeat_mode.expr(),
+                );
 
-            // Depending on bit1==0 or bit1==1 we know whether the compression mode is
-            // Predefined_Mode or Fse_Compressed_Mode. The compression_modes flag is set when
-            // Fse_Compressed_Mode is utilised.
-            cb.require_equal(
-                "block_config: compression_modes llt",
-                meta.query_advice(config.block_config.compression_modes[0], Rotation::cur()),
-                decoded_sequences_header.comp_mode_bit1_ll,
-            );
-            cb.require_equal(
-                "block_config: compression_modes mot",
-                meta.query_advice(config.block_config.compression_modes[1], Rotation::cur()),
-                decoded_sequences_header.comp_mode_bit1_om,
-            );
-            cb.require_equal(
-                "block_config: compression_modes mlt",
-                meta.query_advice(config.block_config.compression_modes[2], Rotation::cur()),
-                decoded_sequences_header.comp_mode_bit1_ml,
-            );
+                // Unless the Repeat_Mode is utilised, the FSE table in use is the one described
+                // in this block, or the predefined one if the Predefined_Mode is utilised.
+                cb.require_equal(
+                    "fse_table_block_idx::cur == is_repeat ? ::prev : block_idx",
+                    meta.query_advice(
+                        config.block_config.fse_table_block_idx[idx],
+                        Rotation::cur(),
+                    ),
+                    select::expr(
+                        repeat_mode.expr(),
+                        meta.query_advice(
+                            config.block_config.fse_table_block_idx[idx],
+                            Rotation::prev(),
+                        ),
+                        meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    ),
+                );
+                cb.require_equal(
+                    "fse_table_is_predefined::cur == is_repeat ? ::prev : !compression_mode",
+                    meta.query_advice(
+                        config.block_config.fse_table_is_predefined[idx],
+                        Rotation::cur(),
+                    ),
+                    select::expr(
+                        repeat_mode,
+                        meta.query_advice(
+                            config.block_config.fse_table_is_predefined[idx],
+                            Rotation::prev(),
+                        ),
+                        not::expr(compression_mode),
+                    ),
+                );
+            }
 
-            // If all the three LLT, MOT and MLT use the Predefined_Mode, we have no FSE tables to
-            // decode in the sequences section. And the tag=ZstdBlockSequenceHeader will
+            // If none of the three LLT, MOT and MLT is described in the FseCode section, i.e.
+            // the Predefined_Mode or Repeat_Mode is utilised for all of them, we have no FSE
+            // tables to decode in the sequences section. And the tag=ZstdBlockSequenceHeader will
             // immediately be followed by tag=ZstdBlockSequenceData.
-            let no_fse_tables = config
-                .block_config
-                .are_predefined_all(meta, Rotation::cur());
+            let no_fse_tables = config.block_config.has_no_fse_code(meta, Rotation::cur());
             cb.require_equal(
                 "SequenceHeader: tag_next=FseCode or tag_next=SequencesData",
                 meta.query_advice(config.tag_config.tag_next, Rotation::cur()),
@@ -2318,9 +3376,17 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         meta.lookup_any(
             "DecoderConfig: tag ZstdBlockSequenceHeader (sequence count)",
             |meta| {
+                // A Raw_Block or RLE_Block has no sequences section, however the empty sequence
+                // count is looked up at its first content row.
                 let condition = and::expr([
                     meta.query_fixed(config.q_enable, Rotation::cur()),
-                    meta.query_advice(config.tag_config.is_sequence_header, Rotation::cur()),
+                    sum::expr([
+                        meta.query_advice(config.tag_config.is_sequence_header, Rotation::cur()),
+                        and::expr([
+                            meta.query_advice(config.block_config.is_block, Rotation::cur()),
+                            config.block_config.is_raw_or_rle(meta, Rotation::cur()),
+                        ]),
+                    ]),
                     config.tag_config.is_change.expr_at(meta, Rotation::cur()),
                 ]);
                 let (block_idx, num_sequences) = (
@@ -2348,11 +3414,13 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         meta.create_gate(
             "DecoderConfig: tag ZstdBlockSequenceFseCode (first row)",
             |meta| {
-                // The first row of a ZstdBlockSequenceFseCode tag.
+                // The first row of a ZstdBlockSequenceFseCode tag, for an FSE table that uses the
+                // Fse_Compressed_Mode.
                 let condition = and::expr([
                     meta.query_fixed(q_enable, Rotation::cur()),
                     meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                     config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    not::expr(meta.query_advice(config.fse_decoder.is_rle, Rotation::cur())),
                 ]);
 
                 let mut cb = BaseConstraintBuilder::default();
@@ -2391,10 +3459,120 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                         .expr_at(meta, Rotation::next()),
                 );
 
-                // We will always start reading bits from the bitstream for the first symbol.
-                cb.require_zero(
-                    "fse(init): is_nil=0",
-                    config.bitstream_decoder.is_nil(meta, Rotation::next()),
+                // We will always start reading bits from the bitstream for the first symbol.
+                cb.require_zero(
+                    "fse(init): is_nil=0",
+                    config.bitstream_decoder.is_nil(meta, Rotation::next()),
+                );
+
+                cb.gate(condition)
+            },
+        );
+
+        meta.lookup_any(
+            "DecoderConfig: tag ZstdBlockSequenceFseCode (table kind)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
+                    config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                ]);
+
+                let (cmode_llt, cmode_mot, cmode_mlt) = (
+                    meta.query_advice(config.block_config.compression_modes[0], Rotation::cur()),
+                    meta.query_advice(config.block_config.compression_modes[1], Rotation::cur()),
+                    meta.query_advice(config.block_config.compression_modes[2], Rotation::cur()),
+                );
+
+                let cmodes_lc = (4.expr() * cmode_llt) + (2.expr() * cmode_mot) + cmode_mlt;
+                [
+                    FixedLookupTag::SeqTagOrder.expr(),
+                    cmodes_lc,
+                    meta.query_advice(config.tag_config.tag, Rotation::prev()), // tag_prev
+                    meta.query_advice(config.tag_config.tag, Rotation::cur()),  // tag_cur
+                    meta.query_advice(config.tag_config.tag_next, Rotation::cur()), // tag_next
+                    meta.query_advice(config.fse_decoder.table_kind, Rotation::cur()), // table_kind
+                    0.expr(),                                                   // unused
+                ]
+                .into_iter()
+                .zip_eq(config.fixed_table.table_exprs(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.lookup_any(
+            "DecoderConfig: tag ZstdBlockSequenceFseCode (table size)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
+                    config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    not::expr(meta.query_advice(config.fse_decoder.is_rle, Rotation::cur())),
+                ]);
+
+                // accuracy_log == 4bits + 5
+                let al = meta
+                    .query_advice(config.bitstream_decoder.bitstring_value, Rotation::cur())
+                    + 5.expr();
+                let table_size = meta.query_advice(config.fse_decoder.table_size, Rotation::cur());
+
+                // table_size == 1 << al
+                [al, table_size]
+                    .into_iter()
+                    .zip_eq(config.pow2_table.table_exprs(meta))
+                    .map(|(arg, table)| (condition.expr() * arg, table))
+                    .collect()
+            },
+        );
+
+        meta.create_gate(
+            "DecoderConfig: tag ZstdBlockSequenceFseCode (RLE_Mode)",
+            |meta| {
+                // For an FSE table that uses the RLE_Mode, the FseCode section consists of a
+                // single byte, i.e. the only symbol of the table.
+                let condition = and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
+                    config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    meta.query_advice(config.fse_decoder.is_rle, Rotation::cur()),
+                ]);
+
+                let mut cb = BaseConstraintBuilder::default();
+
+                cb.require_equal(
+                    "fse(rle): tag_len == 1",
+                    meta.query_advice(config.tag_config.tag_len, Rotation::cur()),
+                    1.expr(),
+                );
+
+                cb.require_zero(
+                    "fse(rle): bit_index_start == 0",
+                    meta.query_advice(config.bitstream_decoder.bit_index_start, Rotation::cur()),
+                );
+
+                cb.require_equal(
+                    "fse(rle): bit_index_end == 7",
+                    meta.query_advice(config.bitstream_decoder.bit_index_end, Rotation::cur()),
+                    7.expr(),
+                );
+
+                // The only symbol is allocated all states of the table with AL=0.
+                cb.require_equal(
+                    "fse(rle): table_size == 1",
+                    meta.query_advice(config.fse_decoder.table_size, Rotation::cur()),
+                    1.expr(),
+                );
+
+                cb.require_equal(
+                    "fse(rle): probability_acc == 1",
+                    meta.query_advice(config.fse_decoder.probability_acc, Rotation::cur()),
+                    1.expr(),
+                );
+
+                cb.require_equal(
+                    "fse(rle): symbol == byte",
+                    meta.query_advice(config.fse_decoder.symbol, Rotation::cur()),
+                    meta.query_advice(config.bitstream_decoder.bitstring_value, Rotation::cur()),
                 );
 
                 cb.gate(condition)
@@ -2402,60 +3580,34 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         );
 
         meta.lookup_any(
-            "DecoderConfig: tag ZstdBlockSequenceFseCode (table kind)",
+            "DecoderConfig: tag ZstdBlockSequenceFseCode (RLE_Mode symbol)",
             |meta| {
                 let condition = and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
                     meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                     config.tag_config.is_change.expr_at(meta, Rotation::cur()),
+                    meta.query_advice(config.fse_decoder.is_rle, Rotation::cur()),
                 ]);
 
-                let (cmode_llt, cmode_mot, cmode_mlt) = (
-                    meta.query_advice(config.block_config.compression_modes[0], Rotation::cur()),
-                    meta.query_advice(config.block_config.compression_modes[1], Rotation::cur()),
-                    meta.query_advice(config.block_config.compression_modes[2], Rotation::cur()),
-                );
-
-                let cmodes_lc = (4.expr() * cmode_llt) + (2.expr() * cmode_mot) + cmode_mlt;
                 [
-                    FixedLookupTag::SeqTagOrder.expr(),
-                    cmodes_lc,
-                    meta.query_advice(config.tag_config.tag, Rotation::prev()), // tag_prev
-                    meta.query_advice(config.tag_config.tag, Rotation::cur()),  // tag_cur
-                    meta.query_advice(config.tag_config.tag_next, Rotation::cur()), // tag_next
-                    meta.query_advice(config.fse_decoder.table_kind, Rotation::cur()), // table_kind
-                    0.expr(),                                                   // unused
+                    0.expr(), // q_first=0
+                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    meta.query_advice(config.fse_decoder.table_kind, Rotation::cur()),
+                    1.expr(), // table_size
+                    0.expr(), // is_predefined
+                    meta.query_advice(config.fse_decoder.symbol, Rotation::cur()),
+                    1.expr(), // symbol_count
+                    1.expr(), // symbol_count_acc
+                    0.expr(), // is_prob_less_than1
+                    0.expr(), // is_padding
                 ]
                 .into_iter()
-                .zip_eq(config.fixed_table.table_exprs(meta))
+                .zip_eq(config.fse_table.table_exprs_by_symbol(meta))
                 .map(|(arg, table)| (condition.expr() * arg, table))
                 .collect()
             },
         );
 
-        meta.lookup_any(
-            "DecoderConfig: tag ZstdBlockSequenceFseCode (table size)",
-            |meta| {
-                let condition = and::expr([
-                    meta.query_fixed(q_enable, Rotation::cur()),
-                    meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
-                    config.tag_config.is_change.expr_at(meta, Rotation::cur()),
-                ]);
-
-                // accuracy_log == 4bits + 5
-                let al = meta
-                    .query_advice(config.bitstream_decoder.bitstring_value, Rotation::cur())
-                    + 5.expr();
-                let table_size = meta.query_advice(config.fse_decoder.table_size, Rotation::cur());
-
-                // table_size == 1 << al
-                [al, table_size]
-                    .into_iter()
-                    .zip_eq(config.pow2_table.table_exprs(meta))
-                    .map(|(arg, table)| (condition.expr() * arg, table))
-                    .collect()
-            },
-        );
-
         meta.create_gate(
             "DecoderConfig: tag ZstdBlockSequenceFseCode (other rows)",
             |meta| {
@@ -3462,12 +4614,13 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 let (nb, table_size) = (
                     config
                         .bitstream_decoder
-                        .bitstring_len_unchecked(meta, Rotation::cur()),
+                        .bitstring_len(meta, Rotation::cur()),
                     meta.query_advice(config.fse_decoder.table_size, Rotation::cur()),
                 );
 
                 // When state is initialised, we must read AL number of bits.
-                // Since table_size == 1 << AL, we do a lookup to the pow2 table.
+                // Since table_size == 1 << AL, we do a lookup to the pow2 table. Note that an FSE
+                // table in the RLE_Mode has AL=0, i.e. we read nb=0 bits.
                 [nb, table_size]
                     .into_iter()
                     .zip_eq(config.pow2_table.table_exprs(meta))
@@ -3487,8 +4640,9 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                         .is_init_state(meta, Rotation::cur()),
                 ]);
 
+                // The FSE table may have been described in a previous block (Repeat_Mode).
                 let (block_idx, table_kind, table_size) = (
-                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    meta.query_advice(config.fse_decoder.table_block_idx, Rotation::cur()),
                     meta.query_advice(config.fse_decoder.table_kind, Rotation::cur()),
                     meta.query_advice(config.fse_decoder.table_size, Rotation::cur()),
                 );
@@ -3573,7 +4727,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 );
 
                 let (block_idx, table_kind, table_size, baseline, nb) = (
-                    meta.query_advice(config.block_config.block_idx, Rotation::cur()),
+                    meta.query_advice(config.fse_decoder.table_block_idx, Rotation::cur()),
                     meta.query_advice(config.fse_decoder.table_kind, Rotation::cur()),
                     meta.query_advice(config.fse_decoder.table_size, Rotation::cur()),
                     meta.query_advice(config.sequences_data_decoder.baseline, Rotation::cur()),
@@ -3641,6 +4795,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             // the following scenarios:
             // - end of block (is_last=true) with tag=SequenceData
             // - end of block (is_last=true) with tag=SequenceHeader and num_sequences=0
+            // - end of Raw_Block or RLE_Block (is_last=true) with tag=RawBytes
             // - the last tag ended OK
             cb.require_equal(
                 "is_null: block::is_last=true on the previous row",
@@ -3651,11 +4806,15 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 "is_null: tag::prev check",
                 meta.query_advice(config.tag_config.tag, Rotation::prev()),
                 select::expr(
-                    config
-                        .block_config
-                        .is_empty_sequences(meta, Rotation::prev()),
-                    ZstdTag::ZstdBlockSequenceHeader.expr(),
-                    ZstdTag::ZstdBlockSequenceData.expr(),
+                    config.block_config.is_raw_or_rle(meta, Rotation::prev()),
+                    ZstdTag::ZstdBlockLiteralsRawBytes.expr(),
+                    select::expr(
+                        config
+                            .block_config
+                            .is_empty_sequences(meta, Rotation::prev()),
+                        ZstdTag::ZstdBlockSequenceHeader.expr(),
+                        ZstdTag::ZstdBlockSequenceData.expr(),
+                    ),
                 ),
             );
             cb.require_equal(
@@ -3686,10 +4845,11 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 config.bitstream_decoder.is_nb0(meta, Rotation::cur()),
             );
             cb.require_equal(
-                "bitstream(is_nil) can occur in [FseCode, SequencesData] tags",
+                "bitstream(is_nil) can occur in [FseCode, SequencesData, Lstream] tags",
                 sum::expr([
                     meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                     meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                    meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                 ]),
                 1.expr(),
             );
@@ -3976,13 +5136,15 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     sum::expr([
                         meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                         meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                     ]),
                 ]);
 
                 let mut cb = BaseConstraintBuilder::default();
 
-                // We process bits instead of bytes for a few tags, namely, ZstdBlockSequenceFseCode
-                // and ZstdBlockSequenceData. In these tags, over adjacent rows we may experience:
+                // We process bits instead of bytes for a few tags, namely, ZstdBlockSequenceFseCode,
+                // ZstdBlockSequenceData and ZstdBlockLstream. In these tags, over adjacent rows we
+                // may experience:
                 // - byte_idx' == byte_idx
                 // - byte_idx' == byte_idx + 1
                 // depending on whether or not the bitstring read was byte-aligned.
@@ -4231,6 +5393,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 sum::expr([
                     meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                     meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                    meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                 ]),
             ]);
 
@@ -4281,6 +5444,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     sum::expr([
                         meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                         meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                     ]),
                 ]);
 
@@ -4371,6 +5535,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     sum::expr([
                         meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                         meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                     ]),
                 ]);
 
@@ -4425,6 +5590,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     sum::expr([
                         meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                         meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                     ]),
                 ]);
 
@@ -4516,6 +5682,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                     sum::expr([
                         meta.query_advice(config.tag_config.is_fse_code, Rotation::cur()),
                         meta.query_advice(config.tag_config.is_sequence_data, Rotation::cur()),
+                        meta.query_advice(config.tag_config.is_lstream, Rotation::cur()),
                     ]),
                 ]);
 
@@ -4578,6 +5745,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         sequence_info_arr: Vec<SequenceInfo>,
         address_table_arr: Vec<Vec<AddressTableRow>>,
         sequence_exec_info_arr: Vec<Vec<SequenceExec>>,
+        huffman_codes: Vec<HuffmanCodesData>,
         challenges: &Challenges<Value<Fr>>,
         k: u32,
     ) -> Result<AssignedDecoderConfigExports, Error> {
@@ -4601,6 +5769,8 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
         //////// Assign FSE and Bitstream Accumulation  /////////
         /////////////////////////////////////////////////////////
         self.fse_table.assign(layouter, fse_aux_tables, n_enabled)?;
+        self.huffman_codes_table
+            .assign(layouter, &huffman_codes, n_enabled)?;
         self.bitstring_table_1
             .assign(layouter, &block_info_arr, &witness_rows, n_enabled)?;
         self.bitstring_table_2
@@ -4624,12 +5794,28 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
             .state
             .block_idx;
         for curr_block_idx in 1..=max_block_idx {
-            let byte_idx = literal_header_rows
+            // Raw_Block and RLE_Block do not have a literals header, and the regenerated size of
+            // Huffman-coded literals is decoded in the literals header gate itself. We still
+            // assign the equivalent Raw_Literals_Block header (Size_Format=11) for the block's
+            // regenerated size, since the table expects a row for every block_idx.
+            let Some(byte_idx) = literal_header_rows
                 .iter()
                 .find(|r| r.state.block_idx == curr_block_idx)
-                .unwrap()
-                .encoded_data
-                .byte_idx;
+                .filter(|r| r.encoded_data.value_byte & 0b10 == 0)
+                .map(|r| r.encoded_data.byte_idx)
+            else {
+                let regen_size = block_info_arr[curr_block_idx as usize - 1].regen_size;
+                literal_headers.push((
+                    curr_block_idx,
+                    0,
+                    (
+                        0b1100 | ((regen_size & 0xf) << 4),
+                        (regen_size >> 4) & 0xff,
+                        (regen_size >> 12) & 0xff,
+                    ),
+                ));
+                continue;
+            };
 
             let literal_bytes = literal_header_rows
                 .iter()
@@ -4708,6 +5894,9 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 }
                 let mut last_byte_idx = 0u64;
                 let mut last_bit_start_idx = 0u64;
+                let mut huffman_block_idx = 0u64;
+                let mut fse_table_block_idx = [0usize; 3];
+                let mut fse_table_is_predefined = [false; 3];
 
                 /////////////////////////////////////////
                 ///////// Assign Witness Rows  //////////
@@ -4926,6 +6115,32 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                         || Value::known(Fr::from(is_fse_code as u64)),
                     )?;
 
+                    for (col, tag, annotation) in [
+                        (
+                            self.tag_config.is_huffman_code,
+                            ZstdTag::ZstdBlockHuffmanCode,
+                            "tag_config.is_huffman_code",
+                        ),
+                        (
+                            self.tag_config.is_jump_table,
+                            ZstdTag::ZstdBlockJumpTable,
+                            "tag_config.is_jump_table",
+                        ),
+                        (
+                            self.tag_config.is_lstream,
+                            ZstdTag::ZstdBlockLstream,
+                            "tag_config.is_lstream",
+                        ),
+                    ] {
+                        region.assign_advice(
+                            || annotation,
+                            col,
+                            i,
+                            || Value::known(Fr::from((row.state.tag == tag) as u64)),
+                        )?;
+                    }
+                    let is_lstream = row.state.tag == ZstdTag::ZstdBlockLstream;
+
                     let is_null = row.state.tag == ZstdTag::Null;
                     region.assign_advice(
                         || "tag_config.is_null",
@@ -4959,7 +6174,10 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                         || row.state.tag_rlc,
                     )?;
 
-                    let tag_len = row.state.tag_len as usize;
+                    let block_type = block_info_arr
+                        .get((row.state.block_idx as usize).wrapping_sub(1))
+                        .map_or(BlockType::default(), |info| info.block_type);
+                    let tag_len = row.encoded_tag_len(block_type) as usize;
                     if tag_len >= pow_of_rand.len() {
                         let mut last = *pow_of_rand.last().expect("Last pow_of_rand exists.");
                         for _ in pow_of_rand.len()..=tag_len {
@@ -5022,6 +6240,26 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                             i,
                             || Value::known(Fr::from(curr_block_info.is_last_block as u64)),
                         )?;
+                        region.assign_advice(
+                            || "block_config.is_raw_block",
+                            self.block_config.is_raw_block,
+                            i,
+                            || {
+                                Value::known(Fr::from(
+                                    (curr_block_info.block_type == BlockType::RawBlock) as u64,
+                                ))
+                            },
+                        )?;
+                        region.assign_advice(
+                            || "block_config.is_rle_block",
+                            self.block_config.is_rle_block,
+                            i,
+                            || {
+                                Value::known(Fr::from(
+                                    (curr_block_info.block_type == BlockType::RleBlock) as u64,
+                                ))
+                            },
+                        )?;
                         region.assign_advice(
                             || "block_config.is_block",
                             self.block_config.is_block,
@@ -5040,23 +6278,44 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                             i,
                             || Value::known(Fr::from(curr_block_info.regen_size)),
                         )?;
-                        let is_predefined = match row.fse_data.table_kind {
+                        let table_idx = match row.fse_data.table_kind {
                             // default: ignored case
-                            0 => false,
-                            // LLT
-                            1 => !curr_sequence_info.compression_mode[0],
-                            // MOT
-                            2 => !curr_sequence_info.compression_mode[1],
-                            // MLT
-                            3 => !curr_sequence_info.compression_mode[2],
+                            0 => None,
+                            // LLT, MOT, MLT
+                            kind @ 1..=3 => Some(kind as usize - 1),
                             _ => unreachable!("table_kind in [1, 2, 3]"),
                         };
-                        region.assign_advice(
-                            || "fse_decoder.is_predefined",
-                            self.fse_decoder.is_predefined,
-                            i,
-                            || Value::known(Fr::from(is_predefined)),
-                        )?;
+                        for (col, value, annotation) in [
+                            (
+                                self.fse_decoder.is_predefined,
+                                table_idx
+                                    .map_or(0, |idx| curr_sequence_info.is_predefined[idx] as u64),
+                                "fse_decoder.is_predefined",
+                            ),
+                            (
+                                self.fse_decoder.is_rle,
+                                table_idx.map_or(0, |idx| {
+                                    (curr_sequence_info.compression_mode[idx]
+                                        == SymbolCompressionMode::Rle)
+                                        as u64
+                                }),
+                                "fse_decoder.is_rle",
+                            ),
+                            (
+                                self.fse_decoder.table_block_idx,
+                                table_idx.map_or(0, |idx| {
+                                    curr_sequence_info.fse_table_block_idx[idx] as u64
+                                }),
+                                "fse_decoder.table_block_idx",
+                            ),
+                        ] {
+                            region.assign_advice(
+                                || annotation,
+                                col,
+                                i,
+                                || Value::known(Fr::from(value)),
+                            )?;
+                        }
 
                         let table_names = ["LLT", "MOT", "MLT"];
                         for (idx, (&table_name, &compression_mode)) in table_names
@@ -5064,12 +6323,27 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                             .zip_eq(curr_sequence_info.compression_mode.iter())
                             .enumerate()
                         {
-                            region.assign_advice(
-                                || table_name,
-                                self.block_config.compression_modes[idx],
-                                i,
-                                || Value::known(Fr::from(compression_mode as u64)),
-                            )?;
+                            for (col, value) in [
+                                (
+                                    self.block_config.compression_modes[idx],
+                                    compression_mode.has_fse_code(),
+                                ),
+                                (
+                                    self.block_config.rle_modes[idx],
+                                    compression_mode == SymbolCompressionMode::Rle,
+                                ),
+                                (
+                                    self.block_config.repeat_modes[idx],
+                                    compression_mode == SymbolCompressionMode::Repeat,
+                                ),
+                            ] {
+                                region.assign_advice(
+                                    || table_name,
+                                    col,
+                                    i,
+                                    || Value::known(Fr::from(value as u64)),
+                                )?;
+                            }
                         }
                         let is_empty_sequences =
                             IsEqualChip::construct(self.block_config.is_empty_sequences.clone());
@@ -5079,6 +6353,138 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                             Value::known(Fr::from(curr_sequence_info.num_sequences as u64)),
                             Value::known(Fr::zero()),
                         )?;
+
+                        let literals_info = curr_block_info.literals_info;
+                        let n_literals_per_stream = if literals_info.is_4_streams {
+                            (curr_block_info.regen_size + 3) / 4
+                        } else {
+                            0
+                        };
+                        for (col, value, annotation) in [
+                            (
+                                self.literals_decoder.is_4_streams,
+                                literals_info.is_4_streams as u64,
+                                "literals_decoder.is_4_streams",
+                            ),
+                            (
+                                self.literals_decoder.literals_end_byte_idx,
+                                if literals_info.is_huffman {
+                                    literals_info.compressed_size
+                                        + literal_header_rows
+                                            .iter()
+                                            .filter(|r| r.state.block_idx == block_idx)
+                                            .last()
+                                            .map_or(0, |r| r.encoded_data.byte_idx)
+                                } else {
+                                    0
+                                },
+                                "literals_decoder.literals_end_byte_idx",
+                            ),
+                            (
+                                self.literals_decoder.n_weights,
+                                literals_info.n_weights,
+                                "literals_decoder.n_weights",
+                            ),
+                            (
+                                self.literals_decoder.stream_sizes[0],
+                                literals_info.stream_sizes[0],
+                                "literals_decoder.stream_sizes[0]",
+                            ),
+                            (
+                                self.literals_decoder.stream_sizes[1],
+                                literals_info.stream_sizes[1],
+                                "literals_decoder.stream_sizes[1]",
+                            ),
+                            (
+                                self.literals_decoder.stream_sizes[2],
+                                literals_info.stream_sizes[2],
+                                "literals_decoder.stream_sizes[2]",
+                            ),
+                            (
+                                self.literals_decoder.n_literals_per_stream,
+                                n_literals_per_stream,
+                                "literals_decoder.n_literals_per_stream",
+                            ),
+                        ] {
+                            region.assign_advice(
+                                || annotation,
+                                col,
+                                i,
+                                || Value::known(Fr::from(value)),
+                            )?;
+                        }
+                    }
+
+                    /////////////////////////////////////////
+                    ///////// Assign FSE tables in use //////
+                    /////////////////////////////////////////
+                    if row.state.tag == ZstdTag::ZstdBlockSequenceHeader && row.state.is_tag_change
+                    {
+                        let curr_sequence_info = sequence_info_arr[block_idx as usize - 1];
+                        fse_table_block_idx = curr_sequence_info.fse_table_block_idx;
+                        fse_table_is_predefined = curr_sequence_info.is_predefined;
+                    }
+                    for idx in 0..3 {
+                        region.assign_advice(
+                            || "block_config.fse_table_block_idx",
+                            self.block_config.fse_table_block_idx[idx],
+                            i,
+                            || Value::known(Fr::from(fse_table_block_idx[idx] as u64)),
+                        )?;
+                        region.assign_advice(
+                            || "block_config.fse_table_is_predefined",
+                            self.block_config.fse_table_is_predefined[idx],
+                            i,
+                            || Value::known(Fr::from(fse_table_is_predefined[idx] as u64)),
+                        )?;
+                    }
+
+                    /////////////////////////////////////////
+                    ////// Assign Literals Decoder  /////////
+                    /////////////////////////////////////////
+                    if is_literals_header && row.state.is_tag_change {
+                        huffman_block_idx = block_info_arr[block_idx as usize - 1]
+                            .literals_info
+                            .huffman_block_idx;
+                    }
+                    for (col, value, annotation) in [
+                        (
+                            self.literals_decoder.huffman_block_idx,
+                            huffman_block_idx,
+                            "literals_decoder.huffman_block_idx",
+                        ),
+                        (
+                            self.literals_decoder.lstream_idx,
+                            row.literals_data.lstream_idx,
+                            "literals_decoder.lstream_idx",
+                        ),
+                        (
+                            self.literals_decoder.is_literal.column,
+                            row.literals_data.is_literal as u64,
+                            "literals_decoder.is_literal",
+                        ),
+                        (
+                            self.literals_decoder.literal_idx,
+                            row.literals_data.literal_idx,
+                            "literals_decoder.literal_idx",
+                        ),
+                        (
+                            self.literals_decoder.literal,
+                            row.literals_data.literal,
+                            "literals_decoder.literal",
+                        ),
+                        (
+                            self.literals_decoder.is_last_literal.column,
+                            row.literals_data.is_last_literal as u64,
+                            "literals_decoder.is_last_literal",
+                        ),
+                    ] {
+                        region.assign_advice(
+                            || annotation,
+                            col,
+                            i,
+                            || Value::known(Fr::from(value)),
+                        )?;
                     }
 
                     ////////////////////////////////////////////////////////////
@@ -5280,7 +6686,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                         && !row.bitstream_read_data.is_zero_bit_read
                         && row.bitstream_read_data.bit_end_idx >= 8
                         && row.bitstream_read_data.bit_end_idx <= 15
-                        && (is_fse_code || is_sequence_data);
+                        && (is_fse_code || is_sequence_data || is_lstream);
                     region.assign_advice(
                         || "lookups_enable.enable_bs_2_bytes",
                         self.lookups_enabled.enable_bs_2_bytes,
@@ -5293,7 +6699,10 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
                 // penultimate tag. We need to do one more round of RLC computation, to calculate
                 // the RLC taking into considering the ultimate tag as well.
                 let last_row = witness_rows.last().expect("last row exists");
-                let last_tag_len = last_row.state.tag_len as usize;
+                let last_block_type = block_info_arr
+                    .last()
+                    .map_or(BlockType::default(), |info| info.block_type);
+                let last_tag_len = last_row.encoded_tag_len(last_block_type) as usize;
                 let last_tag_rlc = last_row.state.tag_rlc;
                 if last_tag_len >= pow_of_rand.len() {
                     let mut last = *pow_of_rand.last().expect("Last pow_of_rand exists.");
@@ -5430,7 +6839,7 @@ impl<const L: usize, const R: usize> DecoderConfig<L, R> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        witgen::{init_zstd_encoder, process, MultiBlockProcessResult},
+        witgen::{init_zstd_encoder, process, DecodeError, MultiBlockProcessResult},
         DecoderConfig, DecoderConfigArgs,
     };
     use halo2_proofs::{
@@ -5450,6 +6859,8 @@ mod tests {
         raw: Vec<u8>,
         compressed: Vec<u8>,
         k: u32,
+        /// Swap two distinct weights of the first Huffman tree in the witness.
+        tamper_huffman_weights: bool,
     }
 
     impl<const L: usize, const R: usize> Circuit<Fr> for DecoderConfigTester<L, R> {
@@ -5507,7 +6918,19 @@ mod tests {
                 sequence_info_arr,
                 address_table_rows: address_table_arr,
                 sequence_exec_results,
-            } = process(&self.compressed, challenges.keccak_input());
+                mut huffman_codes,
+            } = process(&self.compressed, challenges.keccak_input())
+                .map_err(|_| Error::Synthesis)?;
+
+            if self.tamper_huffman_weights {
+                let weights = &mut huffman_codes[0].weights;
+                let first_weight = weights[0];
+                let i = weights
+                    .iter()
+                    .position(|&weight| weight != first_weight)
+                    .expect("Huffman tree with distinct weights");
+                weights.swap(0, i);
+            }
 
             let (recovered_bytes, sequence_exec_info_arr) = sequence_exec_results.into_iter().fold(
                 (Vec::new(), Vec::new()),
@@ -5535,6 +6958,7 @@ mod tests {
                 sequence_info_arr,
                 address_table_arr,
                 sequence_exec_info_arr,
+                huffman_codes,
                 &challenges,
                 self.k,
             )?;
@@ -5594,8 +7018,12 @@ mod tests {
         };
 
         let k = 18;
-        let decoder_config_tester: DecoderConfigTester<256, 256> =
-            DecoderConfigTester { raw, compressed, k };
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            ..Default::default()
+        };
         let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
         mock_prover.assert_satisfied_par();
     }
@@ -5635,8 +7063,12 @@ mod tests {
             raw.len()
         );
         let k = 18;
-        let decoder_config_tester: DecoderConfigTester<256, 256> =
-            DecoderConfigTester { raw, compressed, k };
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            ..Default::default()
+        };
         let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
         mock_prover.assert_satisfied_par();
 
@@ -5684,6 +7116,7 @@ mod tests {
             raw: batch_data,
             compressed: encoded_batch_data,
             k,
+            ..Default::default()
         };
         let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
         mock_prover.assert_satisfied_par();
@@ -5725,14 +7158,213 @@ mod tests {
             raw.len()
         );
         let k = 18;
-        let decoder_config_tester: DecoderConfigTester<256, 256> =
-            DecoderConfigTester { raw, compressed, k };
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            ..Default::default()
+        };
+        let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
+        mock_prover.assert_satisfied_par();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoder_config_high_compression_level() -> Result<(), std::io::Error> {
+        use zstd_encoder::zstd::stream::raw::CParameter;
+
+        let mut batch_files = fs::read_dir("./data/test_batches")?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, std::io::Error>>()?;
+        batch_files.sort();
+
+        let batches = batch_files
+            .iter()
+            .map(fs::read_to_string)
+            .filter_map(|data| data.ok())
+            .map(|data| hex::decode(data.trim_end()).expect("Failed to decode hex data"))
+            .collect::<Vec<Vec<u8>>>();
+
+        let raw = batches[1].clone();
+        let compressed = {
+            // At higher compression levels, the sequences section also utilises the RLE_Mode
+            // and the Repeat_Mode (across blocks) for the FSE tables.
+            let mut encoder = init_zstd_encoder(Some(1024 * 4));
+            encoder.set_parameter(CParameter::CompressionLevel(19))?;
+
+            // set source length, which will be reflected in the frame header.
+            encoder
+                .set_pledged_src_size(Some(raw.len() as u64))
+                .expect("Encoder src_size: raw.len()");
+
+            encoder.write_all(&raw).expect("Encoder wirte_all");
+            encoder.finish().expect("Encoder success")
+        };
+
+        println!(
+            "len(encoded)={:6}\tlen(decoded)={:6}",
+            compressed.len(),
+            raw.len()
+        );
+        let k = 18;
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            ..Default::default()
+        };
+        let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
+        mock_prover.assert_satisfied_par();
+
+        Ok(())
+    }
+
+    /// Batch data compressed with Huffman-coded literals, which the pinned encoder disables.
+    /// With a target block size, the encoder describes the Huffman tree in the first block of a
+    /// super-block and emits the following blocks with treeless literals.
+    fn huffman_literals_example() -> Result<(Vec<u8>, Vec<u8>), std::io::Error> {
+        let mut batch_files = fs::read_dir("./data/test_batches")?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, std::io::Error>>()?;
+        batch_files.sort();
+
+        let raw = hex::decode(fs::read_to_string(&batch_files[1])?.trim_end())
+            .expect("Failed to decode hex data");
+        let compressed = encode_with_huffman_literals(&raw)?;
+
+        Ok((raw, compressed))
+    }
+
+    /// Encode with the literals compression enabled and a target block size of 4 KiB.
+    fn encode_with_huffman_literals(raw: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        use zstd_encoder::zstd::{stream::raw::CParameter, zstd_safe::ParamSwitch};
+
+        let mut encoder = init_zstd_encoder(Some(1024 * 4));
+        encoder.set_parameter(CParameter::LiteralCompressionMode(ParamSwitch::Enable))?;
+
+        // set source length, which will be reflected in the frame header.
+        encoder
+            .set_pledged_src_size(Some(raw.len() as u64))
+            .expect("Encoder src_size: raw.len()");
+
+        encoder.write_all(raw).expect("Encoder wirte_all");
+        Ok(encoder.finish().expect("Encoder success"))
+    }
+
+    #[test]
+    fn test_decoder_config_huffman_literals() -> Result<(), std::io::Error> {
+        let (raw, compressed) = huffman_literals_example()?;
+
+        // both the Compressed_Literals_Block and the Treeless_Literals_Block are exercised.
+        let MultiBlockProcessResult { block_info_arr, .. } =
+            process::<Fr>(&compressed, Value::known(Fr::from(123456789))).unwrap();
+        let literals_infos = block_info_arr
+            .iter()
+            .filter(|info| info.literals_info.is_huffman)
+            .map(|info| (info.block_idx as u64, info.literals_info))
+            .collect::<Vec<_>>();
+        assert!(literals_infos
+            .iter()
+            .any(|(block_idx, info)| info.huffman_block_idx == *block_idx));
+        assert!(literals_infos
+            .iter()
+            .any(|(block_idx, info)| info.huffman_block_idx != *block_idx));
+
+        let k = 18;
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            ..Default::default()
+        };
         let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
         mock_prover.assert_satisfied_par();
 
         Ok(())
     }
 
+    #[test]
+    fn test_decoder_config_tampered_huffman_weights() -> Result<(), std::io::Error> {
+        let (raw, compressed) = huffman_literals_example()?;
+
+        let k = 18;
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            tamper_huffman_weights: true,
+        };
+        let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
+        assert!(mock_prover.verify_par().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoder_fse_compressed_huffman_weights() -> Result<(), std::io::Error> {
+        // Pseudo-random bytes skewed towards the small values: the literals use all 256 symbols,
+        // more than the 128 weights that can be directly represented, so the encoder describes
+        // the Huffman tree with FSE-compressed weights.
+        let mut state = 0x2545f4914f6cdd1du64;
+        let raw = (0..16384)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let r = state >> 48;
+                ((r * r) >> 24) as u8
+            })
+            .collect::<Vec<u8>>();
+        let compressed = encode_with_huffman_literals(&raw)?;
+
+        // The decoder circuit does not constrain FSE-compressed weights, so the witness generation
+        // reports them instead of producing a witness that the circuit rejects.
+        assert!(matches!(
+            process::<Fr>(&compressed, Value::known(Fr::from(123456789))),
+            Err(DecodeError::Unsupported { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoder_config_raw_rle_blocks() {
+        let raw_block = (0..300u32)
+            .map(|i| (i * 37 % 251) as u8)
+            .collect::<Vec<u8>>();
+        let rle_block = vec![0xab; 200];
+        let raw = [raw_block.clone(), rle_block].concat();
+
+        // Block_Header: Last_Block (1 bit), Block_Type (2 bits), Block_Size (21 bits).
+        let block_header = |last_block: u32, block_type: u32, block_size: u32| {
+            let value = last_block | (block_type << 1) | (block_size << 3);
+            value.to_le_bytes()[0..3].to_vec()
+        };
+
+        // Single segment, FrameContentSize over 2 bytes (offset by 256).
+        let fcs = (raw.len() - 256) as u16;
+        let compressed = [
+            vec![0x60],
+            fcs.to_le_bytes().to_vec(),
+            block_header(0, 0, 300),
+            raw_block,
+            block_header(1, 1, 200),
+            vec![0xab],
+        ]
+        .concat();
+
+        let k = 18;
+        let decoder_config_tester: DecoderConfigTester<256, 256> = DecoderConfigTester {
+            raw,
+            compressed,
+            k,
+            ..Default::default()
+        };
+        let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
+        mock_prover.assert_satisfied_par();
+    }
+
     #[test]
     #[ignore = "multi_blob: heavy"]
     fn test_decoder_config_large_multi_block() -> Result<(), std::io::Error> {
@@ -5773,6 +7405,7 @@ mod tests {
             raw: multi_batch_data,
             compressed: encoded_multi_batch_data,
             k,
+            ..Default::default()
         };
         let mock_prover = MockProver::<Fr>::run(k, &decoder_config_tester, vec![]).unwrap();
         mock_prover.assert_satisfied_par();
@@ -8,7 +8,7 @@ use halo2_proofs::{
 };
 use itertools::Itertools;
 use tables::SeqInstTable;
-use witgen::{SequenceExec, SequenceExecInfo, SequenceInfo, ZstdTag};
+use witgen::{SequenceExec, SequenceExecInfo, SequenceInfo};
 use zkevm_circuits::{
     evm_circuit::{BaseConstraintBuilder, ConstrainBuilderCommon},
     util::Field,
@@ -104,16 +104,17 @@ impl SequenceConfig {
 pub struct LiteralTable {
     // the enabled flag
     q_enabled: Column<Fixed>,
-    // the tag for current row in literal section
-    tag: Column<Advice>,
+    // the flag indicate a literal is decoded in current row, either
+    // a raw byte or a huffman-coded literal
+    is_literal: Column<Advice>,
     // the index of block which the literal section is in
     block_index: Column<Advice>,
-    // the 1-indexed byte of byte of literal section's raw bytes
+    // the 1-indexed position of the literal in literal section
     byte_index: Column<Advice>,
     // the corresponding char of current index
     char: Column<Advice>,
-    // the flag IN NEXT ROW is set to 1 indicate it is
-    // the last byte in current section
+    // the flag is set to 1 indicate it is the last literal
+    // in current section
     last_flag: Column<Advice>,
     // the flag should be 0 for a valid lookup row
     padding_flag: Column<Advice>,
@@ -132,7 +133,7 @@ impl LiteralTable {
                 let mut offset = 0usize;
 
                 for col in [
-                    self.tag,
+                    self.is_literal,
                     self.block_index,
                     self.byte_index,
                     self.char,
@@ -156,11 +157,11 @@ impl LiteralTable {
                         || Value::known(F::one()),
                     )?;
                     for (col, val) in [
-                        (self.tag, F::from(ZstdTag::ZstdBlockLiteralsRawBytes as u64)),
+                        (self.is_literal, F::one()),
                         (self.block_index, F::one()),
                         (self.byte_index, F::from(i as u64 + 1)),
                         (self.char, F::from(char)),
-                        (self.last_flag, F::zero()),
+                        (self.last_flag, F::from((i + 1 == literals.len()) as u64)),
                         (self.padding_flag, F::zero()),
                     ] {
                         region.assign_advice(
@@ -173,7 +174,14 @@ impl LiteralTable {
                     offset += 1;
                 }
 
-                for col in [self.byte_index, self.char, self.padding_flag] {
+                for col in [
+                    self.is_literal,
+                    self.block_index,
+                    self.byte_index,
+                    self.char,
+                    self.last_flag,
+                    self.padding_flag,
+                ] {
                     region.assign_advice(
                         || "flush dummy row for border",
                         col,
@@ -181,35 +189,18 @@ impl LiteralTable {
                         || Value::known(F::zero()),
                     )?;
                 }
-                region.assign_advice(
-                    || "set dummy border",
-                    self.tag,
-                    offset,
-                    || Value::known(F::from(ZstdTag::ZstdBlockLiteralsRawBytes as u64)),
-                )?;
-                region.assign_advice(
-                    || "set dummy border",
-                    self.block_index,
-                    offset,
-                    || Value::known(F::from(2)),
-                )?;
-                region.assign_advice(
-                    || "set dummy border",
-                    self.last_flag,
-                    offset,
-                    || Value::known(F::one()),
-                )?;
 
                 Ok(())
             },
         )
     }
 
-    /// construct table for rows: [q_enable, tag, blk_index, byte_index, char, last, padding]
+    /// construct table for rows: [q_enable, is_literal, blk_index, byte_index, char, last,
+    /// padding]
     pub fn construct(cols: [Column<Any>; 7]) -> Self {
         Self {
             q_enabled: cols[0].try_into().unwrap(),
-            tag: cols[1].try_into().unwrap(),
+            is_literal: cols[1].try_into().unwrap(),
             block_index: cols[2].try_into().unwrap(),
             byte_index: cols[3].try_into().unwrap(),
             char: cols[4].try_into().unwrap(),
@@ -218,14 +209,15 @@ impl LiteralTable {
         }
     }
 
-    /// export the exps for literal copying lookup: [tag, blk_ind, byte_ind, char, padding]
+    /// export the exps for literal copying lookup: [is_literal, blk_ind, byte_ind, char,
+    /// padding]
     pub fn lookup_tbl_for_lit_cp<F: Field>(
         &self,
         meta: &mut VirtualCells<'_, F>,
     ) -> [Expression<F>; 6] {
         [
             meta.query_fixed(self.q_enabled, Rotation::cur()),
-            meta.query_advice(self.tag, Rotation::cur()),
+            meta.query_advice(self.is_literal, Rotation::cur()),
             meta.query_advice(self.block_index, Rotation::cur()),
             meta.query_advice(self.byte_index, Rotation::cur()),
             meta.query_advice(self.char, Rotation::cur()),
@@ -233,17 +225,17 @@ impl LiteralTable {
         ]
     }
 
-    /// export the exps for literal size lookup: [tag, blk_ind, byte_ind, flag, padding]
+    /// export the exps for literal size lookup: [is_literal, blk_ind, byte_ind, flag, padding]
     pub fn lookup_tbl_for_lit_size<F: Field>(
         &self,
         meta: &mut VirtualCells<'_, F>,
     ) -> [Expression<F>; 6] {
         [
             meta.query_fixed(self.q_enabled, Rotation::cur()),
-            meta.query_advice(self.tag, Rotation::cur()),
+            meta.query_advice(self.is_literal, Rotation::cur()),
             meta.query_advice(self.block_index, Rotation::cur()),
             meta.query_advice(self.byte_index, Rotation::cur()),
-            meta.query_advice(self.last_flag, Rotation::next()),
+            meta.query_advice(self.last_flag, Rotation::cur()),
             meta.query_advice(self.padding_flag, Rotation::cur()),
         ]
     }
@@ -597,7 +589,7 @@ impl<F: Field> SeqExecConfig<F> {
                 .into_iter()
                 .zip_eq([
                     1.expr(),
-                    ZstdTag::ZstdBlockLiteralsRawBytes.expr(),
+                    1.expr(), // is_literal
                     block_index,
                     literal_pos,
                     cp_byte,
@@ -640,7 +632,7 @@ impl<F: Field> SeqExecConfig<F> {
                 .into_iter()
                 .zip_eq([
                     1.expr(),
-                    ZstdTag::ZstdBlockLiteralsRawBytes.expr(),
+                    1.expr(), // is_literal
                     block_index,
                     literal_pos_at_block_end,
                     1.expr(),
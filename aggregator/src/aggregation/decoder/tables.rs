@@ -8,6 +8,10 @@ pub use bitstring::BitstringTable;
 mod fse;
 pub use fse::FseTable;
 
+/// Huffman codes table.
+mod huffman;
+pub use huffman::HuffmanCodesTable;
+
 /// Decode the regenerated size from the literals header.
 mod literals_header;
 pub use literals_header::LiteralsHeaderTable;
@@ -502,7 +502,9 @@ impl<const N_BYTES: usize> BitstringTable<N_BYTES> {
 
                         let is_tag_ok = matches!(
                             witness_row.state.tag,
-                            ZstdTag::ZstdBlockSequenceFseCode | ZstdTag::ZstdBlockSequenceData
+                            ZstdTag::ZstdBlockSequenceFseCode
+                                | ZstdTag::ZstdBlockSequenceData
+                                | ZstdTag::ZstdBlockLstream
                         );
                         let bit_end_idx = witness_row.bitstream_read_data.bit_end_idx;
                         let is_bitstring_ok = (bit_end_idx >= ((N_BYTES - 1) * N_BITS_PER_BYTE))
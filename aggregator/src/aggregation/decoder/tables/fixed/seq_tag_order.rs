@@ -7,7 +7,9 @@ use crate::aggregation::decoder::{
 
 use super::FixedLookupValues;
 
-/// The possible orders are:
+/// A flag is set when the corresponding FSE table is described in the tag=FseCode section, i.e.
+/// for the RLE_Mode or Fse_Compressed_Mode. The FSE tables in Predefined_Mode or Repeat_Mode are
+/// not described. The possible orders are:
 ///
 /// - (1, 1, 1):
 ///     - SequenceHeader > FseCode > FseCode (LLT)
@@ -29,11 +31,11 @@ use super::FixedLookupValues;
 /// - (0, 0, 1):
 ///     - SequenceHeader > FseCode > SequenceData (MLT)
 pub struct RomSeqTagOrder {
-    /// Boolean flag to mark if LLT is Fse_Compressed_Mode or Predefined_Mode.
+    /// Boolean flag to mark if LLT is described in the tag=FseCode section.
     pub cmode_llt: bool,
-    /// Boolean flag to mark if MOT is Fse_Compressed_Mode or Predefined_Mode.
+    /// Boolean flag to mark if MOT is described in the tag=FseCode section.
     pub cmode_mot: bool,
-    /// Boolean flag to mark if MLT is Fse_Compressed_Mode or Predefined_Mode.
+    /// Boolean flag to mark if MLT is described in the tag=FseCode section.
     pub cmode_mlt: bool,
     /// Tag that was handled before the current tag.
     pub tag_prev: ZstdTag,
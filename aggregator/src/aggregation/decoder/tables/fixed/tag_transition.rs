@@ -20,8 +20,9 @@ pub struct RomTagTransition {
 impl FixedLookupValues for RomTagTransition {
     fn values() -> Vec<[Value<Fr>; 7]> {
         use ZstdTag::{
-            BlockHeader, FrameContentSize, FrameHeaderDescriptor, Null, ZstdBlockLiteralsHeader,
-            ZstdBlockLiteralsRawBytes, ZstdBlockSequenceData, ZstdBlockSequenceFseCode,
+            BlockHeader, FrameContentSize, FrameHeaderDescriptor, Null, ZstdBlockHuffmanCode,
+            ZstdBlockJumpTable, ZstdBlockLiteralsHeader, ZstdBlockLiteralsRawBytes,
+            ZstdBlockLstream, ZstdBlockSequenceData, ZstdBlockSequenceFseCode,
             ZstdBlockSequenceHeader,
         };
 
@@ -29,8 +30,19 @@ impl FixedLookupValues for RomTagTransition {
             (FrameHeaderDescriptor, FrameContentSize),
             (FrameContentSize, BlockHeader),
             (BlockHeader, ZstdBlockLiteralsHeader),
+            (BlockHeader, ZstdBlockLiteralsRawBytes), // Raw_Block or RLE_Block
             (ZstdBlockLiteralsHeader, ZstdBlockLiteralsRawBytes),
+            (ZstdBlockLiteralsHeader, ZstdBlockHuffmanCode), // Compressed_Literals_Block
+            (ZstdBlockLiteralsHeader, ZstdBlockJumpTable),   // Treeless_Literals_Block, 4 streams
+            (ZstdBlockLiteralsHeader, ZstdBlockLstream),     // Treeless_Literals_Block, 1 stream
+            (ZstdBlockHuffmanCode, ZstdBlockJumpTable),
+            (ZstdBlockHuffmanCode, ZstdBlockLstream),
+            (ZstdBlockJumpTable, ZstdBlockLstream),
+            (ZstdBlockLstream, ZstdBlockLstream),
+            (ZstdBlockLstream, ZstdBlockSequenceHeader),
             (ZstdBlockLiteralsRawBytes, ZstdBlockSequenceHeader),
+            (ZstdBlockLiteralsRawBytes, BlockHeader), // Raw_Block or RLE_Block, multi-block
+            (ZstdBlockLiteralsRawBytes, Null),        // Raw_Block or RLE_Block
             (ZstdBlockSequenceHeader, ZstdBlockSequenceFseCode),
             (ZstdBlockSequenceHeader, ZstdBlockSequenceData),
             (ZstdBlockSequenceFseCode, ZstdBlockSequenceFseCode),
@@ -0,0 +1,508 @@
+use gadgets::util::{and, not, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use zkevm_circuits::{
+    evm_circuit::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, Pow2Table, RangeTable, U8Table},
+};
+
+use crate::aggregation::{
+    decoder::witgen::{HuffmanCodesData, N_BLOCK_SIZE_TARGET},
+    util::BooleanAdvice,
+};
+
+/// The Huffman codes table verifies that the canonical prefix codes were assigned correctly to the
+/// symbols described by a Huffman tree description.
+///
+/// Every block with a Compressed_Literals_Block describes its own Huffman tree. For each such
+/// block, all symbols in 0..=last_symbol (including the symbols of weight 0) are laid out in the
+/// increasing order of (weight, symbol). The canonical prefix codes are then assigned in this
+/// order, i.e. symbols with a smaller weight (longer codes) are assigned the smaller codes:
+///
+/// - weight_pow == weight > 0 ? 2 ^ (weight - 1) : 0
+/// - start      == start::prev + weight_pow
+/// - code       == (start - weight_pow) / weight_pow
+/// - nb         == weight > 0 ? max_bits + 1 - weight : 0
+///
+/// where the weights sum up to sum(weight_pow) == 2 ^ max_bits.
+///
+/// | Block | Symbol | Weight | Code | Nb  | Start |
+/// |-------|--------|--------|------|-----|-------|
+/// | 0     | 0      | 0      | 0    | 0   | 0     | <- q_first
+/// |-------|--------|--------|------|-----|-------|
+/// | 1     | 3      | 0      | 0    | 0   | 0     | <- is_start
+/// | 1     | 4      | 1      | 0    | 4   | 1     |
+/// | 1     | 5      | 1      | 1    | 4   | 2     |
+/// | 1     | 2      | 2      | 1    | 3   | 4     |
+/// | 1     | 1      | 3      | 1    | 2   | 8     |
+/// | 1     | 0      | 4      | 1    | 1   | 16    |
+/// |-------|--------|--------|------|-----|-------|
+/// | 3     | ...    | ...    | ...  | ... | ...   | <- is_start
+/// |-------|--------|--------|------|-----|-------|
+/// | 0     | 0      | 0      | 0    | 0   | 0     | <- is_padding
+///
+/// The table does not verify that every symbol in 0..=last_symbol appears exactly once by itself.
+/// Instead we check that the table has last_symbol + 1 rows for the block with the symbols
+/// summing up to last_symbol * (last_symbol + 1) / 2. Along with the decoder looking up the
+/// (symbol, weight) tuple for each of the symbols 0..last_symbol, this pins down the implied
+/// weight of the last symbol.
+///
+/// For more details, refer the [Huffman tree description][doclink] section.
+///
+/// [doclink]: https://github.com/facebook/zstd/blob/dev/doc/zstd_compression_format.md#huffman-tree-description
+#[derive(Clone, Debug)]
+pub struct HuffmanCodesTable {
+    /// Fixed column to mark the first row of the table.
+    q_first: Column<Fixed>,
+    /// The block index in which the Huffman tree is described.
+    block_idx: Column<Advice>,
+    /// Boolean column to mark the first row of a block's Huffman codes.
+    is_start: BooleanAdvice,
+    /// The symbol, i.e. the literal byte.
+    symbol: Column<Advice>,
+    /// The weight of the symbol. A symbol with weight=0 does not appear in the literals.
+    weight: Column<Advice>,
+    /// Whether the symbol is assigned a prefix code, i.e. weight > 0.
+    has_code: BooleanAdvice,
+    /// Helper column for 2 ^ (weight - 1), or 0 if weight=0.
+    weight_pow: Column<Advice>,
+    /// Accumulator over weight_pow, i.e. the start of the next symbol's range of prefix codes.
+    start: Column<Advice>,
+    /// The canonical prefix code assigned to the symbol.
+    code: Column<Advice>,
+    /// The number of bits in the prefix code.
+    nb: Column<Advice>,
+    /// The largest symbol in the Huffman tree, i.e. the symbol with an implied weight.
+    last_symbol: Column<Advice>,
+    /// The maximum number of bits in a prefix code.
+    max_bits: Column<Advice>,
+    /// Accumulator over the number of symbols seen in the block.
+    n_symbols_acc: Column<Advice>,
+    /// Accumulator over the symbols seen in the block.
+    symbol_acc: Column<Advice>,
+    /// Boolean column to mark whether the row is a padded row.
+    is_padding: BooleanAdvice,
+}
+
+impl HuffmanCodesTable {
+    /// Configure the Huffman codes table.
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fr>,
+        q_enable: Column<Fixed>,
+        u8_table: U8Table,
+        range16: RangeTable<16>,
+        range_block_len: RangeTable<{ N_BLOCK_SIZE_TARGET as usize }>,
+        pow2_table: Pow2Table<20>,
+    ) -> Self {
+        let config = Self {
+            q_first: meta.fixed_column(),
+            block_idx: meta.advice_column(),
+            is_start: BooleanAdvice::construct(meta, |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+            }),
+            symbol: meta.advice_column(),
+            weight: meta.advice_column(),
+            has_code: BooleanAdvice::construct(meta, |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+            }),
+            weight_pow: meta.advice_column(),
+            start: meta.advice_column(),
+            code: meta.advice_column(),
+            nb: meta.advice_column(),
+            last_symbol: meta.advice_column(),
+            max_bits: meta.advice_column(),
+            n_symbols_acc: meta.advice_column(),
+            symbol_acc: meta.advice_column(),
+            is_padding: BooleanAdvice::construct(meta, |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+            }),
+        };
+
+        meta.create_gate("HuffmanCodesTable: first row", |meta| {
+            let condition = meta.query_fixed(config.q_first, Rotation::cur());
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "is_padding=0 on first row",
+                config.is_padding.expr_at(meta, Rotation::cur()),
+            );
+            cb.require_zero(
+                "block_idx=0 on first row",
+                meta.query_advice(config.block_idx, Rotation::cur()),
+            );
+            cb.require_equal(
+                "the second row is either the start of a block or padding",
+                sum::expr([
+                    config.is_start.expr_at(meta, Rotation::next()),
+                    config.is_padding.expr_at(meta, Rotation::next()),
+                ]),
+                1.expr(),
+            );
+
+            cb.gate(condition)
+        });
+
+        meta.create_gate("HuffmanCodesTable: all rows except the first row", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_fixed(config.q_first, Rotation::cur())),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            // padding transitions from 0 -> 1 only once.
+            let is_padding_cur = config.is_padding.expr_at(meta, Rotation::cur());
+            let is_padding_prev = config.is_padding.expr_at(meta, Rotation::prev());
+            cb.require_boolean(
+                "is_padding delta is boolean",
+                is_padding_cur.expr() - is_padding_prev,
+            );
+            cb.require_zero(
+                "padded row is not the start of a block",
+                is_padding_cur * config.is_start.expr_at(meta, Rotation::cur()),
+            );
+
+            cb.gate(condition)
+        });
+
+        meta.create_gate("HuffmanCodesTable: main gate", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_fixed(config.q_first, Rotation::cur())),
+                not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            let has_code = config.has_code.expr_at(meta, Rotation::cur());
+            let weight = meta.query_advice(config.weight, Rotation::cur());
+            let weight_pow = meta.query_advice(config.weight_pow, Rotation::cur());
+            let start = meta.query_advice(config.start, Rotation::cur());
+            let code = meta.query_advice(config.code, Rotation::cur());
+
+            // A symbol without a prefix code has weight=0. Otherwise weight > 0 is checked by the
+            // lookup of weight_pow == 2 ^ (weight - 1).
+            cb.condition(not::expr(has_code.expr()), |cb| {
+                cb.require_zero("weight=0 if no prefix code", weight.expr());
+                cb.require_zero("weight_pow=0 if no prefix code", weight_pow.expr());
+                cb.require_zero("code=0 if no prefix code", code.expr());
+            });
+            cb.condition(has_code.expr(), |cb| {
+                cb.require_equal(
+                    "code * weight_pow == start - weight_pow",
+                    code * weight_pow.expr(),
+                    start.expr() - weight_pow.expr(),
+                );
+            });
+            cb.require_equal(
+                "nb == max_bits + 1 - weight (if weight > 0)",
+                meta.query_advice(config.nb, Rotation::cur()),
+                has_code
+                    * (meta.query_advice(config.max_bits, Rotation::cur()) + 1.expr()
+                        - weight.expr()),
+            );
+
+            // The accumulators are initialised at the start of the block.
+            let is_start = config.is_start.expr_at(meta, Rotation::cur());
+            let symbol = meta.query_advice(config.symbol, Rotation::cur());
+            cb.condition(is_start.expr(), |cb| {
+                cb.require_equal("start == weight_pow", start.expr(), weight_pow.expr());
+                cb.require_equal(
+                    "n_symbols_acc == 1",
+                    meta.query_advice(config.n_symbols_acc, Rotation::cur()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "symbol_acc == symbol",
+                    meta.query_advice(config.symbol_acc, Rotation::cur()),
+                    symbol.expr(),
+                );
+            });
+
+            // The accumulators continue while processing the same block.
+            cb.condition(not::expr(is_start), |cb| {
+                for column in [config.block_idx, config.last_symbol, config.max_bits] {
+                    cb.require_equal(
+                        "block_idx/last_symbol/max_bits unchanged within block",
+                        meta.query_advice(column, Rotation::cur()),
+                        meta.query_advice(column, Rotation::prev()),
+                    );
+                }
+                cb.require_equal(
+                    "start == start::prev + weight_pow",
+                    start.expr(),
+                    meta.query_advice(config.start, Rotation::prev()) + weight_pow.expr(),
+                );
+                cb.require_equal(
+                    "n_symbols_acc == n_symbols_acc::prev + 1",
+                    meta.query_advice(config.n_symbols_acc, Rotation::cur()),
+                    meta.query_advice(config.n_symbols_acc, Rotation::prev()) + 1.expr(),
+                );
+                cb.require_equal(
+                    "symbol_acc == symbol_acc::prev + symbol",
+                    meta.query_advice(config.symbol_acc, Rotation::cur()),
+                    meta.query_advice(config.symbol_acc, Rotation::prev()) + symbol,
+                );
+            });
+
+            cb.gate(condition)
+        });
+
+        meta.create_gate("HuffmanCodesTable: end of block", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_fixed(config.q_first, Rotation::cur())),
+                not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+                sum::expr([
+                    config.is_start.expr_at(meta, Rotation::next()),
+                    config.is_padding.expr_at(meta, Rotation::next()),
+                ]),
+            ]);
+
+            let mut cb = BaseConstraintBuilder::default();
+
+            // All symbols in 0..=last_symbol have been assigned.
+            let last_symbol = meta.query_advice(config.last_symbol, Rotation::cur());
+            cb.require_equal(
+                "n_symbols_acc == last_symbol + 1",
+                meta.query_advice(config.n_symbols_acc, Rotation::cur()),
+                last_symbol.expr() + 1.expr(),
+            );
+            cb.require_equal(
+                "2 * symbol_acc == last_symbol * (last_symbol + 1)",
+                meta.query_advice(config.symbol_acc, Rotation::cur()) * 2.expr(),
+                last_symbol.expr() * (last_symbol + 1.expr()),
+            );
+
+            cb.gate(condition)
+        });
+
+        meta.lookup("HuffmanCodesTable: symbol is a byte", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+            ]);
+
+            vec![(
+                condition * meta.query_advice(config.symbol, Rotation::cur()),
+                u8_table.into(),
+            )]
+        });
+
+        meta.lookup("HuffmanCodesTable: weight < 16", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+            ]);
+
+            vec![(
+                condition * meta.query_advice(config.weight, Rotation::cur()),
+                range16.into(),
+            )]
+        });
+
+        meta.lookup(
+            "HuffmanCodesTable: (weight, symbol) strictly increasing within block",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    not::expr(meta.query_fixed(config.q_first, Rotation::cur())),
+                    not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+                    not::expr(config.is_start.expr_at(meta, Rotation::cur())),
+                ]);
+
+                let key = |meta: &mut VirtualCells<Fr>, rotation: Rotation| {
+                    meta.query_advice(config.weight, rotation) * 256.expr()
+                        + meta.query_advice(config.symbol, rotation)
+                };
+                let delta = key(meta, Rotation::cur()) - key(meta, Rotation::prev()) - 1.expr();
+
+                vec![(condition * delta, range_block_len.into())]
+            },
+        );
+
+        meta.lookup("HuffmanCodesTable: block_idx increases", |meta| {
+            let condition = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_fixed(config.q_first, Rotation::cur())),
+                config.is_start.expr_at(meta, Rotation::cur()),
+            ]);
+
+            let delta = meta.query_advice(config.block_idx, Rotation::cur())
+                - meta.query_advice(config.block_idx, Rotation::prev())
+                - 1.expr();
+
+            vec![(condition * delta, range_block_len.into())]
+        });
+
+        meta.lookup_any(
+            "HuffmanCodesTable: weight_pow == 2 ^ (weight - 1)",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+                    config.has_code.expr_at(meta, Rotation::cur()),
+                ]);
+
+                [
+                    meta.query_advice(config.weight, Rotation::cur()) - 1.expr(),
+                    meta.query_advice(config.weight_pow, Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(pow2_table.table_exprs(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        meta.lookup_any(
+            "HuffmanCodesTable: sum(weight_pow) == 2 ^ max_bits",
+            |meta| {
+                let condition = and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    not::expr(meta.query_fixed(config.q_first, Rotation::cur())),
+                    not::expr(config.is_padding.expr_at(meta, Rotation::cur())),
+                    sum::expr([
+                        config.is_start.expr_at(meta, Rotation::next()),
+                        config.is_padding.expr_at(meta, Rotation::next()),
+                    ]),
+                ]);
+
+                [
+                    meta.query_advice(config.max_bits, Rotation::cur()),
+                    meta.query_advice(config.start, Rotation::cur()),
+                ]
+                .into_iter()
+                .zip_eq(pow2_table.table_exprs(meta))
+                .map(|(arg, table)| (condition.expr() * arg, table))
+                .collect()
+            },
+        );
+
+        debug_assert!(meta.degree() <= 9);
+        debug_assert!(meta.clone().chunk_lookups().degree() <= 9);
+
+        config
+    }
+
+    /// Assign witness to the Huffman codes table.
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        data: &[HuffmanCodesData],
+        n_enabled: usize,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "HuffmanCodesTable",
+            |mut region| {
+                region.assign_fixed(|| "q_first", self.q_first, 0, || Value::known(Fr::one()))?;
+
+                // The first row is skipped.
+                let mut offset = 1;
+                for huffman_codes in data.iter() {
+                    let max_bits = huffman_codes.max_bits();
+                    let last_symbol = huffman_codes.weights.len() as u64 - 1;
+                    let codes = huffman_codes.parse_canonical();
+
+                    let mut start = 0u64;
+                    let mut symbol_acc = 0u64;
+                    for (i, (weight, symbol)) in huffman_codes
+                        .weights
+                        .iter()
+                        .enumerate()
+                        .map(|(symbol, &weight)| (weight, symbol as u64))
+                        .sorted()
+                        .enumerate()
+                    {
+                        let (code, nb) = codes
+                            .get(&symbol)
+                            .map_or((0, 0), |&(_weight, code, nb)| (code, nb));
+                        let weight_pow = if weight > 0 { 1 << (weight - 1) } else { 0 };
+                        start += weight_pow;
+                        symbol_acc += symbol;
+
+                        for (col, value, annotation) in [
+                            (self.block_idx, huffman_codes.block_idx, "block_idx"),
+                            (self.is_start.column, (i == 0) as u64, "is_start"),
+                            (self.symbol, symbol, "symbol"),
+                            (self.weight, weight, "weight"),
+                            (self.has_code.column, (weight > 0) as u64, "has_code"),
+                            (self.weight_pow, weight_pow, "weight_pow"),
+                            (self.start, start, "start"),
+                            (self.code, code, "code"),
+                            (self.nb, nb, "nb"),
+                            (self.last_symbol, last_symbol, "last_symbol"),
+                            (self.max_bits, max_bits, "max_bits"),
+                            (self.n_symbols_acc, (i + 1) as u64, "n_symbols_acc"),
+                            (self.symbol_acc, symbol_acc, "symbol_acc"),
+                        ] {
+                            region.assign_advice(
+                                || annotation,
+                                col,
+                                offset,
+                                || Value::known(Fr::from(value)),
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+
+                for offset in offset..n_enabled {
+                    region.assign_advice(
+                        || "is_padding",
+                        self.is_padding.column,
+                        offset,
+                        || Value::known(Fr::one()),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl HuffmanCodesTable {
+    /// Lookup table expressions for the (symbol, weight) tuple, as described by the Huffman tree
+    /// description.
+    pub fn table_exprs_by_symbol(&self, meta: &mut VirtualCells<Fr>) -> Vec<Expression<Fr>> {
+        vec![
+            meta.query_fixed(self.q_first, Rotation::cur()),
+            meta.query_advice(self.block_idx, Rotation::cur()),
+            meta.query_advice(self.symbol, Rotation::cur()),
+            meta.query_advice(self.weight, Rotation::cur()),
+            self.is_padding.expr_at(meta, Rotation::cur()),
+        ]
+    }
+
+    /// Lookup table expressions for the number of symbols with an explicit weight, i.e. the last
+    /// symbol, in the Huffman tree description of a block.
+    pub fn table_exprs_metadata(&self, meta: &mut VirtualCells<Fr>) -> Vec<Expression<Fr>> {
+        vec![
+            meta.query_fixed(self.q_first, Rotation::cur()),
+            meta.query_advice(self.block_idx, Rotation::cur()),
+            meta.query_advice(self.last_symbol, Rotation::cur()),
+            self.is_padding.expr_at(meta, Rotation::cur()),
+        ]
+    }
+
+    /// Lookup table expressions for the (code, nb) tuple that decodes to a symbol, i.e. a
+    /// literal, while reading the Huffman-coded literals streams.
+    pub fn table_exprs_by_code(&self, meta: &mut VirtualCells<Fr>) -> Vec<Expression<Fr>> {
+        vec![
+            meta.query_fixed(self.q_first, Rotation::cur()),
+            meta.query_advice(self.block_idx, Rotation::cur()),
+            self.has_code.expr_at(meta, Rotation::cur()),
+            meta.query_advice(self.code, Rotation::cur()),
+            meta.query_advice(self.nb, Rotation::cur()),
+            meta.query_advice(self.symbol, Rotation::cur()),
+            self.is_padding.expr_at(meta, Rotation::cur()),
+        ]
+    }
+}
@@ -26,7 +26,9 @@ pub struct LiteralsHeaderTable {
     q_first: Column<Fixed>,
     /// The block index in which we find this literals header. Since every block will have a
     /// literals header, and block_idx in 1..=n, we know that on the first row block_idx=1 and on
-    /// subsequent rows, block_idx increments by 1.
+    /// subsequent rows, block_idx increments by 1. Raw_Block and RLE_Block do not have a literals
+    /// header, for those (and for blocks with Huffman-coded literals) the equivalent
+    /// Raw_Literals_Block header is assigned.
     pub block_idx: Column<Advice>,
     /// The first byte of the literals header.
     pub byte0: Column<Advice>,
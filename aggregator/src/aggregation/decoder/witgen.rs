@@ -8,41 +8,57 @@ pub use params::*;
 mod types;
 pub use types::*;
 
+mod error;
+pub use error::*;
+
 pub mod util;
 use util::{be_bits_to_value, increment_idx, le_bits_to_value, value_bits_le};
 
 const CMOT_N: u64 = 31;
 
+/// The n_bytes bytes of the frame beginning at byte_offset.
+fn read_bytes(src: &[u8], byte_offset: usize, n_bytes: usize) -> Result<&[u8], DecodeError> {
+    src.get(byte_offset..(byte_offset + n_bytes))
+        .ok_or(DecodeError::UnexpectedEof {
+            byte_offset: src.len(),
+        })
+}
+
 /// FrameHeaderDescriptor and FrameContentSize
 fn process_frame_header<F: Field>(
     src: &[u8],
     byte_offset: usize,
     last_row: &ZstdWitnessRow<F>,
     randomness: Value<F>,
-) -> (usize, Vec<ZstdWitnessRow<F>>) {
+) -> Result<(usize, Vec<ZstdWitnessRow<F>>), DecodeError> {
     let fhd_byte = src
         .get(byte_offset)
-        .expect("FrameHeaderDescriptor byte should exist");
+        .ok_or(DecodeError::UnexpectedEof { byte_offset })?;
     let value_bits = value_bits_le(*fhd_byte);
 
-    assert_eq!(value_bits[0], 0, "dictionary ID should not exist");
-    assert_eq!(value_bits[1], 0, "dictionary ID should not exist");
-    assert_eq!(value_bits[2], 0, "content checksum should not exist");
-    assert_eq!(value_bits[3], 0, "reserved bit should not be set");
-    assert_eq!(value_bits[4], 0, "unused bit should not be set");
-    assert_eq!(value_bits[5], 1, "single segment expected");
+    if value_bits[0] != 0 || value_bits[1] != 0 {
+        return Err(DecodeError::DictionaryIdPresent);
+    }
+    if value_bits[2] != 0 {
+        return Err(DecodeError::ContentChecksumPresent);
+    }
+    if value_bits[3] != 0 || value_bits[4] != 0 {
+        return Err(DecodeError::FrameHeaderReservedBit);
+    }
+    if value_bits[5] != 1 {
+        return Err(DecodeError::NotSingleSegment);
+    }
 
     let fhd_value_rlc =
         last_row.encoded_data.value_rlc * randomness + Value::known(F::from(*fhd_byte as u64));
 
     // the number of bytes taken to represent FrameContentSize.
-    let fcs_tag_len: usize = match value_bits[7] * 2 + value_bits[6] {
-        0 => 1,
-        1 => 2,
-        2 => 4,
-        3 => 8,
-        _ => unreachable!("2-bit value"),
-    };
+    let fcs_tag_len: usize = 1 << (value_bits[7] * 2 + value_bits[6]);
+    if src.len() < byte_offset + 1 + fcs_tag_len {
+        return Err(DecodeError::UnexpectedEof {
+            byte_offset: src.len(),
+        });
+    }
 
     let fcs_bytes = src
         .iter()
@@ -70,7 +86,7 @@ fn process_frame_header<F: Field>(
         .collect::<Vec<Value<F>>>();
     let tag_rlc = *(tag_rlc_iter.clone().last().expect("Tag RLC expected"));
 
-    (
+    Ok((
         byte_offset + 1 + fcs_tag_len,
         std::iter::once(ZstdWitnessRow {
             state: ZstdState {
@@ -94,6 +110,7 @@ fn process_frame_header<F: Field>(
             decoded_data: DecodedData { decoded_len: fcs },
             bitstream_read_data: BitstreamReadRow::default(),
             fse_data: FseDecodingRow::default(),
+            literals_data: LiteralsDecodingRow::default(),
         })
         .chain(fcs_bytes.iter().zip(tag_rlc_iter.iter()).enumerate().map(
             |(i, (&value_byte, &tag_rlc_acc))| ZstdWitnessRow {
@@ -120,10 +137,11 @@ fn process_frame_header<F: Field>(
                 decoded_data: DecodedData { decoded_len: fcs },
                 bitstream_read_data: BitstreamReadRow::default(),
                 fse_data: FseDecodingRow::default(),
+                literals_data: LiteralsDecodingRow::default(),
             },
         ))
         .collect::<Vec<_>>(),
-    )
+    ))
 }
 
 #[derive(Debug, Clone)]
@@ -137,21 +155,33 @@ pub struct AggregateBlockResult<F> {
     pub address_table_rows: Vec<AddressTableRow>,
     pub sequence_exec_result: SequenceExecResult,
     pub repeated_offset: [usize; 3], // repeated offsets are carried forward between blocks.
+    pub repeated_fse_tables: [Option<FseAuxiliaryTableData>; 3], // FSE tables in use.
+    pub huffman_codes: Option<HuffmanCodesData>, // Huffman tree described in this block.
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_block<F: Field>(
     src: &[u8],
     decoded_bytes: &mut Vec<u8>,
     block_idx: u64,
     byte_offset: usize,
     last_row: &ZstdWitnessRow<F>,
+    last_block_type: BlockType,
     randomness: Value<F>,
     repeated_offset: [usize; 3],
-) -> AggregateBlockResult<F> {
+    repeated_fse_tables: &[Option<FseAuxiliaryTableData>; 3],
+    last_huffman_codes: Option<&HuffmanCodesData>,
+) -> Result<AggregateBlockResult<F>, DecodeError> {
     let mut witness_rows = vec![];
 
-    let (byte_offset, rows, mut block_info) =
-        process_block_header(src, block_idx, byte_offset, last_row, randomness);
+    let (byte_offset, rows, mut block_info) = process_block_header(
+        src,
+        block_idx,
+        byte_offset,
+        last_row,
+        last_block_type,
+        randomness,
+    )?;
     witness_rows.extend_from_slice(&rows);
 
     let last_row = rows.last().expect("last row expected to exist");
@@ -165,7 +195,10 @@ fn process_block<F: Field>(
         address_table_rows,
         sequence_exec_result,
         repeated_offset,
+        repeated_fse_tables,
         regen_size,
+        huffman_codes,
+        literals_info,
     } = match block_info.block_type {
         BlockType::ZstdCompressedBlock => process_block_zstd(
             src,
@@ -177,13 +210,30 @@ fn process_block<F: Field>(
             block_info.block_len,
             block_info.is_last_block,
             repeated_offset,
-        ),
-        _ => unreachable!("BlockType::ZstdCompressedBlock expected"),
+            repeated_fse_tables,
+            last_huffman_codes,
+        )?,
+        BlockType::RawBlock | BlockType::RleBlock => process_block_raw_rle(
+            src,
+            decoded_bytes,
+            block_idx,
+            byte_offset,
+            last_row,
+            randomness,
+            block_info.block_type,
+            block_info.block_len,
+            block_info.is_last_block,
+            repeated_offset,
+            repeated_fse_tables,
+            last_huffman_codes,
+        )?,
+        BlockType::Reserved => return Err(DecodeError::ReservedBlockType { block_idx }),
     };
     block_info.regen_size = regen_size;
+    block_info.literals_info = literals_info;
     witness_rows.extend_from_slice(&rows);
 
-    AggregateBlockResult {
+    Ok(AggregateBlockResult {
         offset: end_offset,
         witness_rows,
         block_info,
@@ -193,7 +243,9 @@ fn process_block<F: Field>(
         address_table_rows,
         sequence_exec_result,
         repeated_offset,
-    }
+        repeated_fse_tables,
+        huffman_codes,
+    })
 }
 
 fn process_block_header<F: Field>(
@@ -201,12 +253,18 @@ fn process_block_header<F: Field>(
     block_idx: u64,
     byte_offset: usize,
     last_row: &ZstdWitnessRow<F>,
+    last_block_type: BlockType,
     randomness: Value<F>,
-) -> (usize, Vec<ZstdWitnessRow<F>>, BlockInfo) {
+) -> Result<(usize, Vec<ZstdWitnessRow<F>>, BlockInfo), DecodeError> {
     let mut block_info = BlockInfo {
         block_idx: block_idx as usize,
         ..Default::default()
     };
+    if src.len() < byte_offset + N_BLOCK_HEADER_BYTES {
+        return Err(DecodeError::UnexpectedEof {
+            byte_offset: src.len(),
+        });
+    }
     let bh_bytes = src
         .iter()
         .skip(byte_offset)
@@ -218,11 +276,25 @@ fn process_block_header<F: Field>(
     block_info.block_len =
         (bh_bytes[2] as usize * 256 * 256 + bh_bytes[1] as usize * 256 + bh_bytes[0] as usize) >> 3;
 
+    // Raw and RLE blocks carry no literals header: their content is directly processed as the
+    // literals of a block without sequences.
     let tag_next = match block_info.block_type {
         BlockType::ZstdCompressedBlock => ZstdTag::ZstdBlockLiteralsHeader,
-        _ => unreachable!("BlockType::ZstdCompressedBlock expected"),
+        BlockType::RawBlock | BlockType::RleBlock => ZstdTag::ZstdBlockLiteralsRawBytes,
+        BlockType::Reserved => return Err(DecodeError::ReservedBlockType { block_idx }),
     };
 
+    // An RLE_Block consists of a single byte, irrespective of its Block_Size.
+    let n_content_bytes = match block_info.block_type {
+        BlockType::RleBlock => 1,
+        _ => block_info.block_len,
+    };
+    if src.len() < byte_offset + N_BLOCK_HEADER_BYTES + n_content_bytes {
+        return Err(DecodeError::UnexpectedEof {
+            byte_offset: src.len(),
+        });
+    }
+
     let tag_rlc_iter = bh_bytes
         .iter()
         .scan(Value::known(F::zero()), |acc, &byte| {
@@ -232,11 +304,11 @@ fn process_block_header<F: Field>(
         .collect::<Vec<Value<F>>>();
     let tag_rlc = *(tag_rlc_iter.clone().last().expect("Tag RLC expected"));
 
-    let multiplier =
-        (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
+    let multiplier = (0..last_row.encoded_tag_len(last_block_type))
+        .fold(Value::known(F::one()), |acc, _| acc * randomness);
     let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
 
-    (
+    Ok((
         byte_offset + N_BLOCK_HEADER_BYTES,
         bh_bytes
             .iter()
@@ -265,10 +337,11 @@ fn process_block_header<F: Field>(
                 bitstream_read_data: BitstreamReadRow::default(),
                 decoded_data: last_row.decoded_data.clone(),
                 fse_data: FseDecodingRow::default(),
+                literals_data: LiteralsDecodingRow::default(),
             })
             .collect::<Vec<_>>(),
         block_info,
-    )
+    ))
 }
 
 #[derive(Debug, Default, Clone)]
@@ -287,7 +360,10 @@ pub struct BlockProcessingResult<F> {
     pub address_table_rows: Vec<AddressTableRow>,
     pub sequence_exec_result: SequenceExecResult,
     pub repeated_offset: [usize; 3], // repeated offsets are carried forward between blocks
+    pub repeated_fse_tables: [Option<FseAuxiliaryTableData>; 3], // FSE tables in use
     pub regen_size: u64,
+    pub huffman_codes: Option<HuffmanCodesData>, // Huffman tree described in this block
+    pub literals_info: LiteralsInfo,
 }
 
 #[derive(Debug, Clone)]
@@ -296,6 +372,8 @@ pub struct LiteralsBlockResult<F> {
     pub witness_rows: Vec<ZstdWitnessRow<F>>,
     pub literals: Vec<u64>,
     pub regen_size: usize,
+    pub huffman_codes: Option<HuffmanCodesData>,
+    pub literals_info: LiteralsInfo,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -309,7 +387,9 @@ fn process_block_zstd<F: Field>(
     block_size: usize,
     last_block: bool,
     repeated_offset: [usize; 3],
-) -> BlockProcessingResult<F> {
+    repeated_fse_tables: &[Option<FseAuxiliaryTableData>; 3],
+    last_huffman_codes: Option<&HuffmanCodesData>,
+) -> Result<BlockProcessingResult<F>, DecodeError> {
     let expected_end_offset = byte_offset + block_size;
     let mut witness_rows = vec![];
 
@@ -318,8 +398,22 @@ fn process_block_zstd<F: Field>(
         offset: byte_offset,
         witness_rows: rows,
         regen_size,
-        compressed_size: _,
-    } = process_block_zstd_literals_header::<F>(src, block_idx, byte_offset, last_row, randomness);
+        compressed_size,
+        literals_block_type,
+        n_streams,
+    } = process_block_zstd_literals_header::<F>(src, block_idx, byte_offset, last_row, randomness)?;
+
+    let literals_end_offset = if literals_block_type == BlockType::RawBlock {
+        byte_offset + regen_size
+    } else {
+        byte_offset + compressed_size
+    };
+    if literals_end_offset > expected_end_offset {
+        return Err(DecodeError::CorruptedData {
+            block_idx,
+            reason: "literals section exceeds the block",
+        });
+    }
 
     witness_rows.extend_from_slice(&rows);
 
@@ -328,14 +422,35 @@ fn process_block_zstd<F: Field>(
         witness_rows: rows,
         literals,
         regen_size: _,
-    } = {
+        huffman_codes,
+        literals_info,
+    } = if literals_block_type != BlockType::RawBlock {
+        process_block_zstd_huffman_literals::<F>(
+            src,
+            block_idx,
+            byte_offset,
+            rows.last().expect("last row expected to exist"),
+            randomness,
+            literals_block_type,
+            n_streams,
+            regen_size,
+            compressed_size,
+            last_huffman_codes,
+        )?
+    } else {
         let last_row = rows.last().cloned().unwrap();
         let multiplier =
             (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
         let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
         let tag = ZstdTag::ZstdBlockLiteralsRawBytes;
         let tag_next = ZstdTag::ZstdBlockSequenceHeader;
-        let literals = src[byte_offset..(byte_offset + regen_size)].to_vec();
+        if regen_size == 0 {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "empty Raw_Literals_Block",
+            });
+        }
+        let literals = read_bytes(src, byte_offset, regen_size)?.to_vec();
         let tag_rlc_iter = literals.iter().scan(Value::known(F::zero()), |acc, &byte| {
             *acc = *acc * randomness + Value::known(F::from(byte as u64));
             Some(*acc)
@@ -373,10 +488,22 @@ fn process_block_zstd<F: Field>(
                     },
                     bitstream_read_data: BitstreamReadRow::default(),
                     fse_data: FseDecodingRow::default(),
+                    literals_data: LiteralsDecodingRow {
+                        is_literal: true,
+                        literal_idx: (i + 1) as u64,
+                        literal: value_byte as u64,
+                        is_last_literal: i + 1 == regen_size,
+                        ..Default::default()
+                    },
                 })
                 .collect::<Vec<_>>(),
             literals: literals.iter().map(|b| *b as u64).collect::<Vec<u64>>(),
             regen_size,
+            huffman_codes: None,
+            literals_info: LiteralsInfo {
+                huffman_block_idx: last_huffman_codes.map_or(0, |codes| codes.block_idx),
+                ..Default::default()
+            },
         }
     };
 
@@ -393,6 +520,7 @@ fn process_block_zstd<F: Field>(
         sequence_info,
         sequence_exec,
         repeated_offset,
+        repeated_fse_tables,
     } = process_sequences::<F>(
         src,
         decoded_bytes,
@@ -404,16 +532,18 @@ fn process_block_zstd<F: Field>(
         last_block,
         randomness,
         repeated_offset,
-    );
+        repeated_fse_tables,
+    )?;
 
-    // sanity check:
-    assert_eq!(
-        offset, expected_end_offset,
-        "end offset after tag=SequencesData mismatch"
-    );
+    if offset != expected_end_offset {
+        return Err(DecodeError::CorruptedData {
+            block_idx,
+            reason: "end offset after tag=SequencesData mismatch",
+        });
+    }
     witness_rows.extend_from_slice(&rows);
 
-    BlockProcessingResult {
+    Ok(BlockProcessingResult {
         offset,
         witness_rows,
         literals,
@@ -425,8 +555,627 @@ fn process_block_zstd<F: Field>(
             recovered_bytes: original_bytes,
         },
         repeated_offset,
+        repeated_fse_tables,
         regen_size: regen_size as u64,
+        huffman_codes,
+        literals_info,
+    })
+}
+
+/// Compressed_Literals_Block and Treeless_Literals_Block
+///
+/// A Compressed_Literals_Block begins with the Huffman tree description, whereas a
+/// Treeless_Literals_Block re-uses the Huffman tree described in the most recent
+/// Compressed_Literals_Block. The Huffman-coded literals follow, either as a single stream or
+/// as 4 streams preceded by a jump table specifying the sizes of the first 3 streams.
+#[allow(clippy::too_many_arguments)]
+fn process_block_zstd_huffman_literals<F: Field>(
+    src: &[u8],
+    block_idx: u64,
+    byte_offset: usize,
+    last_row: &ZstdWitnessRow<F>,
+    randomness: Value<F>,
+    literals_block_type: BlockType,
+    n_streams: usize,
+    regen_size: usize,
+    compressed_size: usize,
+    last_huffman_codes: Option<&HuffmanCodesData>,
+) -> Result<LiteralsBlockResult<F>, DecodeError> {
+    let end_offset = byte_offset + compressed_size;
+    let is_4_streams = n_streams == 4;
+    let tag_after_huffman_code = if is_4_streams {
+        ZstdTag::ZstdBlockJumpTable
+    } else {
+        ZstdTag::ZstdBlockLstream
+    };
+
+    let mut witness_rows: Vec<ZstdWitnessRow<F>> = vec![];
+    let mut byte_offset = byte_offset;
+    let mut last_row = last_row.clone();
+
+    // Huffman tree description
+    let (huffman_codes, new_huffman_codes) = if literals_block_type
+        == BlockType::ZstdCompressedBlock
+    {
+        let (n_bytes, huffman_codes) = HuffmanCodesData::reconstruct(src, block_idx, byte_offset)?;
+        if byte_offset + n_bytes > end_offset {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "Huffman tree description exceeds the literals section",
+            });
+        }
+        let rows = process_forward_bytes::<F>(
+            src,
+            block_idx,
+            byte_offset,
+            n_bytes,
+            ZstdTag::ZstdBlockHuffmanCode,
+            tag_after_huffman_code,
+            &last_row,
+            randomness,
+        )?;
+        witness_rows.extend_from_slice(&rows);
+        last_row = rows.last().cloned().expect("last row expected to exist");
+        byte_offset += n_bytes;
+        (huffman_codes.clone(), Some(huffman_codes))
+    } else {
+        let huffman_codes = last_huffman_codes
+            .cloned()
+            .ok_or(DecodeError::MissingHuffmanTree { block_idx })?;
+        (huffman_codes, None)
+    };
+
+    // Jump table
+    let stream_sizes = if is_4_streams {
+        if byte_offset + 6 > end_offset {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "jump table exceeds the literals section",
+            });
+        }
+        let rows = process_forward_bytes::<F>(
+            src,
+            block_idx,
+            byte_offset,
+            6,
+            ZstdTag::ZstdBlockJumpTable,
+            ZstdTag::ZstdBlockLstream,
+            &last_row,
+            randomness,
+        )?;
+        witness_rows.extend_from_slice(&rows);
+        last_row = rows.last().cloned().expect("last row expected to exist");
+        let jump_table = read_bytes(src, byte_offset, 6)?;
+        let stream_sizes =
+            [0, 1, 2].map(|i| jump_table[2 * i] as u64 + ((jump_table[2 * i + 1] as u64) << 8));
+        byte_offset += 6;
+        stream_sizes
+    } else {
+        [0; 3]
+    };
+
+    // Literals streams. Each of the first 3 streams (in case of 4 streams) decodes
+    // ceil(regen_size / 4) literals, while the last stream decodes the remaining literals.
+    let n_literals_per_stream = (regen_size + 3) / 4;
+    let stream_ends = if is_4_streams {
+        let mut stream_end = byte_offset;
+        let mut stream_ends = stream_sizes
+            .iter()
+            .map(|&stream_size| {
+                stream_end += stream_size as usize;
+                (stream_end, n_literals_per_stream)
+            })
+            .collect::<Vec<(usize, usize)>>();
+        if stream_end > end_offset || regen_size < 3 * n_literals_per_stream {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "jump table inconsistent with the literals section",
+            });
+        }
+        stream_ends.push((end_offset, regen_size - 3 * n_literals_per_stream));
+        stream_ends
+    } else {
+        vec![(end_offset, regen_size)]
+    };
+
+    let code_lookup = huffman_codes
+        .parse_canonical()
+        .into_iter()
+        .map(|(symbol, (_weight, code, nb))| ((nb, code), symbol))
+        .collect::<HashMap<(u64, u64), u64>>();
+    let mut literals = vec![];
+    for (lstream_idx, &(stream_end, n_literals)) in stream_ends.iter().enumerate() {
+        let rows = process_block_zstd_lstream::<F>(
+            src,
+            block_idx,
+            byte_offset,
+            stream_end,
+            lstream_idx as u64,
+            lstream_idx + 1 == stream_ends.len(),
+            n_literals,
+            &code_lookup,
+            huffman_codes.max_bits(),
+            &mut literals,
+            &last_row,
+            randomness,
+        )?;
+        witness_rows.extend_from_slice(&rows);
+        last_row = rows.last().cloned().expect("last row expected to exist");
+        byte_offset = stream_end;
+    }
+    if literals.len() != regen_size {
+        return Err(DecodeError::CorruptedData {
+            block_idx,
+            reason: "regenerated size mismatch",
+        });
     }
+
+    Ok(LiteralsBlockResult {
+        offset: end_offset,
+        witness_rows,
+        literals,
+        regen_size,
+        literals_info: LiteralsInfo {
+            is_huffman: true,
+            is_4_streams,
+            compressed_size: compressed_size as u64,
+            n_weights: huffman_codes.weights.len() as u64 - 1,
+            stream_sizes,
+            huffman_block_idx: huffman_codes.block_idx,
+        },
+        huffman_codes: new_huffman_codes,
+    })
+}
+
+/// Witness rows for a tag whose bytes are processed in the forward direction, one row per byte,
+/// i.e. the Huffman tree description and the jump table.
+#[allow(clippy::too_many_arguments)]
+fn process_forward_bytes<F: Field>(
+    src: &[u8],
+    block_idx: u64,
+    byte_offset: usize,
+    n_bytes: usize,
+    tag: ZstdTag,
+    tag_next: ZstdTag,
+    last_row: &ZstdWitnessRow<F>,
+    randomness: Value<F>,
+) -> Result<Vec<ZstdWitnessRow<F>>, DecodeError> {
+    let multiplier =
+        (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
+    let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
+    let bytes = read_bytes(src, byte_offset, n_bytes)?;
+    let tag_rlc_iter = bytes.iter().scan(Value::known(F::zero()), |acc, &byte| {
+        *acc = *acc * randomness + Value::known(F::from(byte as u64));
+        Some(*acc)
+    });
+    let tag_rlc = tag_rlc_iter.clone().last().expect("Tag RLC expected");
+
+    Ok(bytes
+        .iter()
+        .zip(tag_rlc_iter)
+        .enumerate()
+        .map(|(i, (&value_byte, tag_rlc_acc))| ZstdWitnessRow {
+            state: ZstdState {
+                tag,
+                tag_next,
+                block_idx,
+                max_tag_len: tag.max_len(),
+                tag_len: n_bytes as u64,
+                tag_idx: (i + 1) as u64,
+                is_tag_change: i == 0,
+                tag_rlc,
+                tag_rlc_acc,
+            },
+            encoded_data: EncodedData {
+                byte_idx: (byte_offset + i + 1) as u64,
+                encoded_len: last_row.encoded_data.encoded_len,
+                value_byte,
+                value_rlc,
+                reverse: false,
+                ..Default::default()
+            },
+            decoded_data: last_row.decoded_data.clone(),
+            bitstream_read_data: BitstreamReadRow::default(),
+            fse_data: FseDecodingRow::default(),
+            literals_data: LiteralsDecodingRow::default(),
+        })
+        .collect::<Vec<_>>())
+}
+
+/// A single Huffman-coded literals stream.
+///
+/// Similar to the sequences bitstream, the literals stream is read backwards, beginning with the
+/// leading zeros and the sentinel 1-bit. Every read operation then decodes a prefix code of
+/// num_bits bits into a literal.
+#[allow(clippy::too_many_arguments)]
+fn process_block_zstd_lstream<F: Field>(
+    src: &[u8],
+    block_idx: u64,
+    byte_offset: usize,
+    end_offset: usize,
+    lstream_idx: u64,
+    is_last_stream: bool,
+    n_literals: usize,
+    code_lookup: &HashMap<(u64, u64), u64>,
+    max_bits: u64,
+    literals: &mut Vec<u64>,
+    last_row: &ZstdWitnessRow<F>,
+    randomness: Value<F>,
+) -> Result<Vec<ZstdWitnessRow<F>>, DecodeError> {
+    if end_offset <= byte_offset {
+        return Err(DecodeError::CorruptedData {
+            block_idx,
+            reason: "empty literals stream",
+        });
+    }
+    let mut witness_rows = vec![];
+    let encoded_len = last_row.encoded_data.encoded_len;
+    let tag = ZstdTag::ZstdBlockLstream;
+    let tag_next = if is_last_stream {
+        ZstdTag::ZstdBlockSequenceHeader
+    } else {
+        ZstdTag::ZstdBlockLstream
+    };
+
+    let lstream_bytes = read_bytes(src, byte_offset, end_offset - byte_offset)?;
+    let lstream_bitstream = lstream_bytes
+        .iter()
+        .rev()
+        .flat_map(|v| {
+            let mut bits = value_bits_le(*v);
+            bits.reverse();
+            bits
+        })
+        .collect::<Vec<u8>>();
+
+    let n_lstream_bytes = end_offset - byte_offset;
+    let mut last_byte_idx: usize = 1;
+    let mut current_byte_idx: usize = 1;
+    let mut current_bit_idx: usize = 0;
+
+    let multiplier =
+        (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
+    let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
+
+    let tag_rlc_iter = lstream_bytes
+        .iter()
+        .scan(Value::known(F::zero()), |acc, &byte| {
+            *acc = *acc * randomness + Value::known(F::from(byte as u64));
+            Some(*acc)
+        });
+    let tag_rlc = tag_rlc_iter.clone().last().expect("Tag RLC must exist");
+    let mut tag_rlc_iter = tag_rlc_iter
+        .clone()
+        .collect::<Vec<Value<F>>>()
+        .into_iter()
+        .rev();
+    let mut next_tag_rlc_acc = tag_rlc_iter.next().unwrap();
+
+    let value_byte_at = |current_byte_idx: usize| {
+        if end_offset - current_byte_idx < src.len() {
+            src[end_offset - current_byte_idx]
+        } else {
+            src.last().cloned().unwrap()
+        }
+    };
+    let state = |current_byte_idx: usize, is_tag_change: bool, tag_rlc_acc: Value<F>| ZstdState {
+        tag,
+        tag_next,
+        block_idx,
+        max_tag_len: tag.max_len(),
+        tag_len: n_lstream_bytes as u64,
+        tag_idx: current_byte_idx as u64,
+        is_tag_change,
+        tag_rlc,
+        tag_rlc_acc,
+    };
+    let encoded_data = |current_byte_idx: usize| EncodedData {
+        byte_idx: (byte_offset + current_byte_idx) as u64,
+        encoded_len,
+        value_byte: value_byte_at(current_byte_idx),
+        value_rlc,
+        reverse: true,
+        reverse_len: n_lstream_bytes as u64,
+        reverse_idx: (n_lstream_bytes - (current_byte_idx - 1)) as u64,
+    };
+
+    let padding_end_idx =
+        lstream_bitstream
+            .iter()
+            .position(|&bit| bit == 1)
+            .ok_or(DecodeError::CorruptedData {
+                block_idx,
+                reason: "sentinel bit missing in literals stream",
+            })?;
+
+    // Add a witness row for leading 0s and the sentinel 1-bit
+    witness_rows.push(ZstdWitnessRow {
+        state: state(current_byte_idx, true, next_tag_rlc_acc),
+        encoded_data: encoded_data(current_byte_idx),
+        bitstream_read_data: BitstreamReadRow {
+            bit_start_idx: 0usize,
+            bit_end_idx: padding_end_idx,
+            bit_value: 1u64,
+            is_zero_bit_read: false,
+            ..Default::default()
+        },
+        decoded_data: last_row.decoded_data.clone(),
+        fse_data: FseDecodingRow::default(),
+        literals_data: LiteralsDecodingRow {
+            lstream_idx,
+            literal_idx: literals.len() as u64,
+            ..Default::default()
+        },
+    });
+
+    // Exclude the leading zero section and the sentinel 1-bit
+    for _ in 0..=padding_end_idx {
+        (current_byte_idx, current_bit_idx) = increment_idx(current_byte_idx, current_bit_idx);
+    }
+    if current_byte_idx > last_byte_idx {
+        next_tag_rlc_acc = tag_rlc_iter.next().unwrap();
+        last_byte_idx = current_byte_idx;
+    }
+
+    let bitstream_end_bit_idx = n_lstream_bytes * N_BITS_PER_BYTE;
+    for i in 0..n_literals {
+        // Peek max_bits bits (bits past the end of the stream are read as zeros) and find the
+        // prefix code among them.
+        let (nb, literal) = (1..=max_bits)
+            .find_map(|nb| {
+                let code = be_bits_to_value(
+                    &(0..nb as usize)
+                        .map(|j| {
+                            lstream_bitstream
+                                .get(current_bit_idx + j)
+                                .copied()
+                                .unwrap_or(0)
+                        })
+                        .collect::<Vec<u8>>(),
+                );
+                code_lookup
+                    .get(&(nb, code))
+                    .map(|&literal| (nb as usize, literal))
+            })
+            .ok_or(DecodeError::CorruptedData {
+                block_idx,
+                reason: "invalid Huffman code in literals stream",
+            })?;
+        if current_bit_idx + nb > bitstream_end_bit_idx {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "literals stream overflown",
+            });
+        }
+        let bitstring_value =
+            be_bits_to_value(&lstream_bitstream[current_bit_idx..(current_bit_idx + nb)]);
+        literals.push(literal);
+
+        let is_last_literal = i + 1 == n_literals;
+        let literals_data = LiteralsDecodingRow {
+            lstream_idx,
+            literal_idx: literals.len() as u64,
+            ..Default::default()
+        };
+
+        let from_bit_idx = current_bit_idx.rem_euclid(8);
+        let to_bit_idx = from_bit_idx + (nb - 1);
+
+        witness_rows.push(ZstdWitnessRow {
+            state: state(current_byte_idx, false, next_tag_rlc_acc),
+            encoded_data: encoded_data(current_byte_idx),
+            bitstream_read_data: BitstreamReadRow {
+                bit_start_idx: from_bit_idx,
+                bit_end_idx: to_bit_idx,
+                bit_value: bitstring_value,
+                is_zero_bit_read: false,
+                ..Default::default()
+            },
+            decoded_data: last_row.decoded_data.clone(),
+            fse_data: FseDecodingRow::default(),
+            literals_data: LiteralsDecodingRow {
+                is_literal: true,
+                literal,
+                is_last_literal: is_last_stream && is_last_literal,
+                ..literals_data.clone()
+            },
+        });
+
+        // A read operation covering an entire byte requires a nil row for each such byte, to
+        // ensure continuity of the value accumulators.
+        let multi_byte_boundaries: [usize; 2] = [15, 23];
+        let mut skipped_bits = 0usize;
+
+        for boundary in multi_byte_boundaries {
+            if to_bit_idx >= boundary {
+                for _ in 0..N_BITS_PER_BYTE {
+                    (current_byte_idx, current_bit_idx) =
+                        increment_idx(current_byte_idx, current_bit_idx);
+                }
+                if current_byte_idx > last_byte_idx && current_byte_idx <= n_lstream_bytes {
+                    next_tag_rlc_acc = tag_rlc_iter.next().unwrap();
+                    last_byte_idx = current_byte_idx;
+                }
+                skipped_bits += N_BITS_PER_BYTE;
+
+                let wrap_by = match to_bit_idx {
+                    15 => 8,
+                    16..=23 => 16,
+                    _ => {
+                        return Err(DecodeError::CorruptedData {
+                            block_idx,
+                            reason: "read operation spans more than 3 bytes of literals stream",
+                        })
+                    }
+                };
+                witness_rows.push(ZstdWitnessRow {
+                    state: state(current_byte_idx, false, next_tag_rlc_acc),
+                    encoded_data: encoded_data(current_byte_idx),
+                    bitstream_read_data: BitstreamReadRow {
+                        bit_start_idx: to_bit_idx - wrap_by,
+                        bit_end_idx: to_bit_idx - wrap_by,
+                        bit_value: 0,
+                        is_zero_bit_read: false,
+                        is_nil: true,
+                        ..Default::default()
+                    },
+                    decoded_data: last_row.decoded_data.clone(),
+                    fse_data: FseDecodingRow::default(),
+                    literals_data: literals_data.clone(),
+                });
+            }
+        }
+
+        for _ in 0..(nb - skipped_bits) {
+            (current_byte_idx, current_bit_idx) = increment_idx(current_byte_idx, current_bit_idx);
+        }
+        if current_byte_idx > last_byte_idx && current_byte_idx <= n_lstream_bytes {
+            next_tag_rlc_acc = tag_rlc_iter.next().unwrap();
+            last_byte_idx = current_byte_idx;
+        }
+    }
+
+    if current_bit_idx != bitstream_end_bit_idx {
+        return Err(DecodeError::CorruptedData {
+            block_idx,
+            reason: "literals stream not consumed entirely",
+        });
+    }
+
+    Ok(witness_rows)
+}
+
+/// Raw_Block and RLE_Block
+///
+/// The content of such a block is processed as the literals of a block without any sequences,
+/// i.e. the decoded bytes are exactly the literals. For a Raw_Block the literals are the
+/// Block_Size bytes that follow the block header, while for an RLE_Block the single byte that
+/// follows the block header is repeated Block_Size times. In the latter case the byte_idx does
+/// not increment over the repeated rows.
+#[allow(clippy::too_many_arguments)]
+fn process_block_raw_rle<F: Field>(
+    src: &[u8],
+    decoded_bytes: &mut Vec<u8>,
+    block_idx: u64,
+    byte_offset: usize,
+    last_row: &ZstdWitnessRow<F>,
+    randomness: Value<F>,
+    block_type: BlockType,
+    block_size: usize,
+    last_block: bool,
+    repeated_offset: [usize; 3],
+    repeated_fse_tables: &[Option<FseAuxiliaryTableData>; 3],
+    last_huffman_codes: Option<&HuffmanCodesData>,
+) -> Result<BlockProcessingResult<F>, DecodeError> {
+    if block_size == 0 {
+        return Err(DecodeError::EmptyBlock { block_idx });
+    }
+
+    let is_rle = block_type == BlockType::RleBlock;
+    let (end_offset, literals) = if is_rle {
+        let byte = read_bytes(src, byte_offset, 1)?[0];
+        (byte_offset + 1, vec![byte; block_size])
+    } else {
+        (
+            byte_offset + block_size,
+            read_bytes(src, byte_offset, block_size)?.to_vec(),
+        )
+    };
+
+    let multiplier =
+        (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
+    let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
+    let tag = ZstdTag::ZstdBlockLiteralsRawBytes;
+    let tag_next = if last_block {
+        ZstdTag::Null
+    } else {
+        ZstdTag::BlockHeader
+    };
+    let tag_rlc_iter = literals
+        .iter()
+        .take(end_offset - byte_offset)
+        .scan(Value::known(F::zero()), |acc, &byte| {
+            *acc = *acc * randomness + Value::known(F::from(byte as u64));
+            Some(*acc)
+        })
+        .collect::<Vec<Value<F>>>();
+    let tag_rlc = *tag_rlc_iter.last().expect("Tag RLC expected");
+
+    let witness_rows = literals
+        .iter()
+        .enumerate()
+        .map(|(i, &value_byte)| ZstdWitnessRow {
+            state: ZstdState {
+                tag,
+                tag_next,
+                block_idx,
+                max_tag_len: tag.max_len(),
+                tag_len: block_size as u64,
+                tag_idx: (i + 1) as u64,
+                is_tag_change: i == 0,
+                tag_rlc,
+                tag_rlc_acc: if is_rle { tag_rlc } else { tag_rlc_iter[i] },
+            },
+            encoded_data: EncodedData {
+                byte_idx: (byte_offset + if is_rle { 1 } else { i + 1 }) as u64,
+                encoded_len: last_row.encoded_data.encoded_len,
+                value_byte,
+                value_rlc,
+                reverse: false,
+                ..Default::default()
+            },
+            decoded_data: DecodedData {
+                decoded_len: last_row.decoded_data.decoded_len,
+            },
+            bitstream_read_data: BitstreamReadRow::default(),
+            fse_data: FseDecodingRow::default(),
+            literals_data: LiteralsDecodingRow {
+                is_literal: true,
+                literal_idx: (i + 1) as u64,
+                literal: value_byte as u64,
+                is_last_literal: i + 1 == block_size,
+                ..Default::default()
+            },
+        })
+        .collect::<Vec<_>>();
+
+    // The FSE tables are laid out per block, so a block without sequences still assigns the
+    // predefined tables.
+    let fse_aux_tables = [FseTableKind::LLT, FseTableKind::MOT, FseTableKind::MLT].map(|kind| {
+        FseAuxiliaryTableData::reconstruct(src, block_idx, kind, byte_offset, true)
+            .expect("Reconstructing predefined FSE table should not fail.")
+            .2
+    });
+
+    decoded_bytes.extend_from_slice(&literals);
+
+    Ok(BlockProcessingResult {
+        offset: end_offset,
+        witness_rows,
+        literals: literals.iter().map(|&b| b as u64).collect(),
+        sequence_info: SequenceInfo {
+            block_idx: block_idx as usize,
+            num_sequences: 0,
+            ..Default::default()
+        },
+        fse_aux_tables,
+        address_table_rows: vec![],
+        sequence_exec_result: SequenceExecResult {
+            exec_trace: vec![SequenceExec(
+                0,
+                SequenceExecInfo::LiteralCopy(0..block_size),
+            )],
+            recovered_bytes: literals,
+        },
+        repeated_offset,
+        repeated_fse_tables: repeated_fse_tables.clone(),
+        regen_size: block_size as u64,
+        huffman_codes: None,
+        literals_info: LiteralsInfo {
+            huffman_block_idx: last_huffman_codes.map_or(0, |codes| codes.block_idx),
+            ..Default::default()
+        },
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -439,6 +1188,7 @@ pub struct SequencesProcessingResult<F> {
     pub sequence_info: SequenceInfo,
     pub sequence_exec: Vec<SequenceExec>,
     pub repeated_offset: [usize; 3],
+    pub repeated_fse_tables: [Option<FseAuxiliaryTableData>; 3],
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -453,7 +1203,8 @@ fn process_sequences<F: Field>(
     last_block: bool,
     randomness: Value<F>,
     mut repeated_offset: [usize; 3],
-) -> SequencesProcessingResult<F> {
+    repeated_fse_tables: &[Option<FseAuxiliaryTableData>; 3],
+) -> Result<SequencesProcessingResult<F>, DecodeError> {
     // Initialize witness values
     let mut witness_rows: Vec<ZstdWitnessRow<F>> = vec![];
     let encoded_len = last_row.encoded_data.encoded_len;
@@ -466,71 +1217,63 @@ fn process_sequences<F: Field>(
         ..Default::default()
     };
 
-    let byte0 = *src
-        .get(byte_offset)
-        .expect("First byte of sequence header must exist.");
-    assert!(byte0 > 0u8, "Sequences can't be of 0 length");
+    let sequence_header_byte = |offset: usize| {
+        if offset < end_offset {
+            read_bytes(src, offset, 1).map(|bytes| bytes[0])
+        } else {
+            Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "sequences header exceeds the block",
+            })
+        }
+    };
+    let byte0 = sequence_header_byte(byte_offset)?;
+    if byte0 == 0 {
+        return Err(DecodeError::EmptySequences { block_idx });
+    }
 
     let (num_of_sequences, num_sequence_header_bytes) = if byte0 < 128 {
         (byte0 as u64, 2usize)
     } else {
-        let byte1 = *src
-            .get(byte_offset + 1)
-            .expect("Next byte of sequence header must exist.");
+        let byte1 = sequence_header_byte(byte_offset + 1)?;
         if byte0 < 255 {
             ((((byte0 - 128) as u64) << 8) + byte1 as u64, 3)
         } else {
-            let byte2 = *src
-                .get(byte_offset + 2)
-                .expect("Third byte of sequence header must exist.");
+            let byte2 = sequence_header_byte(byte_offset + 2)?;
             ((byte1 as u64) + ((byte2 as u64) << 8) + 0x7F00, 4)
         }
     };
     sequence_info.num_sequences = num_of_sequences as usize;
 
-    let compression_mode_byte = *src
-        .get(byte_offset + num_sequence_header_bytes - 1)
-        .expect("Compression mode byte must exist.");
+    let compression_mode_byte = sequence_header_byte(byte_offset + num_sequence_header_bytes - 1)?;
     let mode_bits = value_bits_le(compression_mode_byte);
 
-    let literal_lengths_mode = mode_bits[6] + mode_bits[7] * 2;
-    let offsets_mode = mode_bits[4] + mode_bits[5] * 2;
-    let match_lengths_mode = mode_bits[2] + mode_bits[3] * 2;
+    let literal_lengths_mode = SymbolCompressionMode::from(mode_bits[6] + mode_bits[7] * 2);
+    let offsets_mode = SymbolCompressionMode::from(mode_bits[4] + mode_bits[5] * 2);
+    let match_lengths_mode = SymbolCompressionMode::from(mode_bits[2] + mode_bits[3] * 2);
     let reserved = mode_bits[0] + mode_bits[1] * 2;
 
-    assert!(reserved == 0, "Reserved bits must be 0");
-
-    // Note: Only 2 modes of FSE encoding are accepted (instead of 4):
-    // 0 - Predefined.
-    // 2 - Variable bit packing.
-    assert!(
-        literal_lengths_mode == 2 || literal_lengths_mode == 0,
-        "Only FSE_Compressed_Mode or Predefined are allowed"
-    );
-    assert!(
-        offsets_mode == 2 || offsets_mode == 0,
-        "Only FSE_Compressed_Mode or Predefined are allowed"
-    );
-    assert!(
-        match_lengths_mode == 2 || match_lengths_mode == 0,
-        "Only FSE_Compressed_Mode or Predefined are allowed"
-    );
-    sequence_info.compression_mode = [
-        literal_lengths_mode > 0,
-        offsets_mode > 0,
-        match_lengths_mode > 0,
-    ];
+    if reserved != 0 {
+        return Err(DecodeError::SequencesReservedBits { block_idx });
+    }
+
+    sequence_info.compression_mode = [literal_lengths_mode, offsets_mode, match_lengths_mode];
 
     let multiplier =
         (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
     let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
-    let is_all_predefined_fse = literal_lengths_mode + offsets_mode + match_lengths_mode < 1;
+    let is_all_predefined_fse = !sequence_info
+        .compression_mode
+        .iter()
+        .any(|mode| mode.has_fse_code());
 
     // Add witness rows for the sequence header
     let sequence_header_start_offset = byte_offset;
     let sequence_header_end_offset = byte_offset + num_sequence_header_bytes;
 
-    let tag_rlc_iter = src[sequence_header_start_offset..sequence_header_end_offset]
+    let sequence_header_bytes =
+        read_bytes(src, sequence_header_start_offset, num_sequence_header_bytes)?;
+    let tag_rlc_iter = sequence_header_bytes
         .iter()
         .scan(Value::known(F::zero()), |acc, &byte| {
             *acc = *acc * randomness + Value::known(F::from(byte as u64));
@@ -538,7 +1281,7 @@ fn process_sequences<F: Field>(
         });
     let tag_rlc = tag_rlc_iter.clone().last().expect("Tag RLC must exist");
 
-    let header_rows = src[sequence_header_start_offset..sequence_header_end_offset]
+    let header_rows = sequence_header_bytes
         .iter()
         .zip(tag_rlc_iter)
         .enumerate()
@@ -571,6 +1314,7 @@ fn process_sequences<F: Field>(
             },
             bitstream_read_data: BitstreamReadRow::default(),
             fse_data: FseDecodingRow::default(),
+            literals_data: LiteralsDecodingRow::default(),
         })
         .collect::<Vec<_>>();
 
@@ -583,69 +1327,69 @@ fn process_sequences<F: Field>(
     let fse_starting_byte_offset = byte_offset;
 
     // Literal Length Table (LLT)
-    let (n_fse_bytes_llt, bit_boundaries_llt, table_llt) = FseAuxiliaryTableData::reconstruct(
+    let (n_fse_bytes_llt, bit_boundaries_llt, table_llt) = reconstruct_fse_table(
         src,
         block_idx,
         FseTableKind::LLT,
         byte_offset,
-        literal_lengths_mode < 2,
+        literal_lengths_mode,
+        repeated_fse_tables[0].as_ref(),
     )
-    .expect("Reconstructing FSE-packed Literl Length (LL) table should not fail.");
+    .map_err(|source| DecodeError::InvalidFseTable {
+        block_idx,
+        table_kind: FseTableKind::LLT,
+        source,
+    })?;
     let llt = table_llt.parse_state_table();
     // Determine the accuracy log of LLT
-    let al_llt = if literal_lengths_mode > 0 {
-        bit_boundaries_llt
-            .first()
-            .expect("Accuracy Log should exist")
-            .1
-            + 5
-    } else {
-        6
-    };
+    let al_llt = table_llt.accuracy_log();
 
     // Cooked Match Offset Table (CMOT)
     let byte_offset = byte_offset + n_fse_bytes_llt;
-    let (n_fse_bytes_cmot, bit_boundaries_cmot, table_cmot) = FseAuxiliaryTableData::reconstruct(
+    let (n_fse_bytes_cmot, bit_boundaries_cmot, table_cmot) = reconstruct_fse_table(
         src,
         block_idx,
         FseTableKind::MOT,
         byte_offset,
-        offsets_mode < 2,
+        offsets_mode,
+        repeated_fse_tables[1].as_ref(),
     )
-    .expect("Reconstructing FSE-packed Cooked Match Offset (CMO) table should not fail.");
+    .map_err(|source| DecodeError::InvalidFseTable {
+        block_idx,
+        table_kind: FseTableKind::MOT,
+        source,
+    })?;
     let cmot = table_cmot.parse_state_table();
     // Determine the accuracy log of CMOT
-    let al_cmot = if offsets_mode > 0 {
-        bit_boundaries_cmot
-            .first()
-            .expect("Accuracy Log should exist")
-            .1
-            + 5
-    } else {
-        5
-    };
+    let al_cmot = table_cmot.accuracy_log();
 
     // Match Length Table (MLT)
     let byte_offset = byte_offset + n_fse_bytes_cmot;
-    let (n_fse_bytes_mlt, bit_boundaries_mlt, table_mlt) = FseAuxiliaryTableData::reconstruct(
+    let (n_fse_bytes_mlt, bit_boundaries_mlt, table_mlt) = reconstruct_fse_table(
         src,
         block_idx,
         FseTableKind::MLT,
         byte_offset,
-        match_lengths_mode < 2,
+        match_lengths_mode,
+        repeated_fse_tables[2].as_ref(),
     )
-    .expect("Reconstructing FSE-packed Match Length (ML) table should not fail.");
+    .map_err(|source| DecodeError::InvalidFseTable {
+        block_idx,
+        table_kind: FseTableKind::MLT,
+        source,
+    })?;
     let mlt = table_mlt.parse_state_table();
     // Determine the accuracy log of MLT
-    let al_mlt = if match_lengths_mode > 0 {
-        bit_boundaries_mlt
-            .first()
-            .expect("Accuracy Log should exist")
-            .1
-            + 5
-    } else {
-        6
-    };
+    let al_mlt = table_mlt.accuracy_log();
+
+    // The FSE tables in use may have been described in a previous block (Repeat_Mode).
+    for (idx, table) in [&table_llt, &table_cmot, &table_mlt]
+        .into_iter()
+        .enumerate()
+    {
+        sequence_info.fse_table_block_idx[idx] = table.block_idx as usize;
+        sequence_info.is_predefined[idx] = table.is_predefined;
+    }
 
     // Add witness rows for the above three FSE tables
     let mut last_row = header_rows.last().cloned().unwrap();
@@ -656,7 +1400,7 @@ fn process_sequences<F: Field>(
             bit_boundaries_llt,
             n_fse_bytes_llt as u64,
             &table_llt,
-            offsets_mode + match_lengths_mode < 1,
+            !offsets_mode.has_fse_code() && !match_lengths_mode.has_fse_code(),
         ),
         (
             fse_starting_byte_offset + n_fse_bytes_llt,
@@ -664,7 +1408,7 @@ fn process_sequences<F: Field>(
             bit_boundaries_cmot,
             n_fse_bytes_cmot as u64,
             &table_cmot,
-            match_lengths_mode < 1,
+            !match_lengths_mode.has_fse_code(),
         ),
         (
             fse_starting_byte_offset + n_fse_bytes_llt + n_fse_bytes_cmot,
@@ -676,13 +1420,13 @@ fn process_sequences<F: Field>(
         ),
     ] {
         if end_offset > start_offset {
-            let mut tag_rlc_iter =
-                src[start_offset..end_offset]
-                    .iter()
-                    .scan(Value::known(F::zero()), |acc, &byte| {
-                        *acc = *acc * randomness + Value::known(F::from(byte as u64));
-                        Some(*acc)
-                    });
+            let fse_bytes = read_bytes(src, start_offset, end_offset - start_offset)?;
+            let mut tag_rlc_iter = fse_bytes
+                .iter()
+                .scan(Value::known(F::zero()), |acc, &byte| {
+                    *acc = *acc * randomness + Value::known(F::from(byte as u64));
+                    Some(*acc)
+                });
             let tag_rlc = tag_rlc_iter.clone().last().expect("Tag RLC must exist");
 
             let mut decoded: u64 = 0;
@@ -752,11 +1496,41 @@ fn process_sequences<F: Field>(
                     }
                     to_pos = ((to_byte_idx + 1) as i64, to_bit_idx as i64);
 
-                    if bit_boundary_idx < 1 {
+                    if bit_boundary_idx < 1 && table.table_size == 1 {
+                        // Read Scenarios 0: The only symbol of an FSE table in RLE_Mode, which is
+                        // allocated the only state of the table.
+                        if value_read != value_decoded {
+                            return Err(DecodeError::CorruptedData {
+                                block_idx,
+                                reason: "varbit packing for RLE symbol",
+                            });
+                        }
+                        Ok((
+                            *value_read,
+                            1,
+                            from_pos.0 as usize,
+                            from_pos.1 as usize,
+                            to_pos.0 as usize,
+                            to_pos.1 as usize,
+                            *value_read,
+                            *value_decoded,
+                            current_tag_rlc_acc,
+                            1,
+                            kind as u64,
+                            table.table_size,
+                            false,
+                            false,
+                        ))
+                    } else if bit_boundary_idx < 1 {
                         // Read Scenarios 1: Accuracy log bits (Always the First Read)
                         next_symbol += 1;
-                        assert_eq!(value_read, value_decoded, "no varbit packing for AL bits");
-                        (
+                        if value_read != value_decoded {
+                            return Err(DecodeError::CorruptedData {
+                                block_idx,
+                                reason: "varbit packing for accuracy log bits",
+                            });
+                        }
+                        Ok((
                             0,
                             n_emitted,
                             from_pos.0 as usize,
@@ -771,15 +1545,17 @@ fn process_sequences<F: Field>(
                             table.table_size,
                             false,
                             false,
-                        )
+                        ))
                     } else if !is_repeating_bit_boundary.contains_key(&bit_boundary_idx) {
                         if n_acc >= (table.table_size as usize) {
                             // Read Scenarios 2: Trailing Bits
-                            assert_eq!(
-                                value_read, value_decoded,
-                                "no varbit packing for trailing bits"
-                            );
-                            (
+                            if value_read != value_decoded {
+                                return Err(DecodeError::CorruptedData {
+                                    block_idx,
+                                    reason: "varbit packing for trailing bits",
+                                });
+                            }
+                            Ok((
                                 last_symbol as u64,
                                 n_emitted,
                                 from_pos.0 as usize,
@@ -794,10 +1570,15 @@ fn process_sequences<F: Field>(
                                 table.table_size,
                                 false,
                                 true,
-                            )
+                            ))
                         } else {
                             // Read Scenarios 3: Regular Decoding State
-                            assert!(next_symbol >= 0);
+                            if next_symbol < 0 {
+                                return Err(DecodeError::CorruptedData {
+                                    block_idx,
+                                    reason: "FSE symbol decoded before the accuracy log",
+                                });
+                            }
                             decoded = next_symbol as u64;
                             n_emitted += 1;
                             last_symbol = next_symbol;
@@ -815,8 +1596,14 @@ fn process_sequences<F: Field>(
                                 1 => {
                                     let mut repeating_bit_boundary_idx = bit_boundary_idx + 1;
                                     loop {
-                                        let repeating_bits =
-                                            bit_boundaries[repeating_bit_boundary_idx].1;
+                                        let Some(&(_, repeating_bits, _)) =
+                                            bit_boundaries.get(repeating_bit_boundary_idx)
+                                        else {
+                                            return Err(DecodeError::CorruptedData {
+                                                block_idx,
+                                                reason: "repeat bits missing in FSE table",
+                                            });
+                                        };
                                         next_symbol += repeating_bits as i32; // skip symbols
                                         is_repeating_bit_boundary
                                             .insert(repeating_bit_boundary_idx, true);
@@ -833,7 +1620,7 @@ fn process_sequences<F: Field>(
                                 }
                             }
 
-                            (
+                            Ok((
                                 decoded,
                                 n_emitted,
                                 from_pos.0 as usize,
@@ -848,17 +1635,19 @@ fn process_sequences<F: Field>(
                                 table.table_size,
                                 false,
                                 false,
-                            )
+                            ))
                         }
                     } else {
                         // Read Scenarios 3: Repeating Bits
                         let symbol = last_symbol as u64 + value_decoded;
                         last_symbol = symbol as i32;
-                        assert_eq!(
-                            value_read, value_decoded,
-                            "no varbit packing for repeat-bits flag"
-                        );
-                        (
+                        if value_read != value_decoded {
+                            return Err(DecodeError::CorruptedData {
+                                block_idx,
+                                reason: "varbit packing for repeat bits",
+                            });
+                        }
+                        Ok((
                             symbol,
                             n_emitted,
                             from_pos.0 as usize,
@@ -874,25 +1663,28 @@ fn process_sequences<F: Field>(
                             table.table_size,
                             true,
                             false,
-                        )
+                        ))
                     }
                 })
-                .collect::<Vec<(
-                    u64,
-                    usize,
-                    usize,
-                    usize,
-                    usize,
-                    usize,
-                    u64,
-                    u64,
-                    Value<F>,
-                    usize,
-                    u64,
-                    u64,
-                    bool,
-                    bool,
-                )>>();
+                .collect::<Result<
+                    Vec<(
+                        u64,
+                        usize,
+                        usize,
+                        usize,
+                        usize,
+                        usize,
+                        u64,
+                        u64,
+                        Value<F>,
+                        usize,
+                        u64,
+                        u64,
+                        bool,
+                        bool,
+                    )>,
+                    DecodeError,
+                >>()?;
 
             // Transform bitstream rows into witness rows
             for (j, row) in bitstream_rows.iter().enumerate() {
@@ -915,7 +1707,7 @@ fn process_sequences<F: Field>(
                     encoded_data: EncodedData {
                         byte_idx: (start_offset + row.2) as u64,
                         encoded_len,
-                        value_byte: src[start_offset + row.2 - 1],
+                        value_byte: fse_bytes[row.2 - 1],
                         value_rlc,
                         reverse: false,
                         ..Default::default()
@@ -940,6 +1732,7 @@ fn process_sequences<F: Field>(
                         is_repeat_bits_loop: row.12,
                         is_trailing_bits: row.13,
                     },
+                    literals_data: LiteralsDecodingRow::default(),
                 });
 
                 // The maximum allowed accuracy log for literals length and match length tables is
@@ -949,7 +1742,7 @@ fn process_sequences<F: Field>(
                 // the second byte entirely, resulting in a nil row.
                 if (row.5 - row.3 + 1) > 8 && row.5 >= 15 {
                     last_row = witness_rows.last().cloned().unwrap();
-                    let byte_value = src[start_offset + row.2];
+                    let byte_value = fse_bytes[row.2];
 
                     witness_rows.push(ZstdWitnessRow {
                         state: ZstdState {
@@ -1002,6 +1795,7 @@ fn process_sequences<F: Field>(
                             is_repeat_bits_loop: false,
                             is_trailing_bits: row.13,
                         },
+                        literals_data: LiteralsDecodingRow::default(),
                     })
                 }
 
@@ -1021,7 +1815,14 @@ fn process_sequences<F: Field>(
 
     // Decode sequence bitstream
     let byte_offset = byte_offset + n_fse_bytes_mlt;
-    let sequence_bitstream = &src[byte_offset..end_offset]
+    if byte_offset >= end_offset {
+        return Err(DecodeError::CorruptedData {
+            block_idx,
+            reason: "FSE tables exceed the block",
+        });
+    }
+    let sequence_data_bytes = read_bytes(src, byte_offset, end_offset - byte_offset)?;
+    let sequence_bitstream = sequence_data_bytes
         .iter()
         .rev()
         .clone()
@@ -1044,13 +1845,12 @@ fn process_sequences<F: Field>(
         (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
     let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
 
-    let tag_rlc_iter =
-        &src[byte_offset..end_offset]
-            .iter()
-            .scan(Value::known(F::zero()), |acc, &byte| {
-                *acc = *acc * randomness + Value::known(F::from(byte as u64));
-                Some(*acc)
-            });
+    let tag_rlc_iter = sequence_data_bytes
+        .iter()
+        .scan(Value::known(F::zero()), |acc, &byte| {
+            *acc = *acc * randomness + Value::known(F::from(byte as u64));
+            Some(*acc)
+        });
     let tag_rlc = tag_rlc_iter.clone().last().expect("Tag RLC must exist");
     let mut tag_rlc_iter = tag_rlc_iter
         .clone()
@@ -1060,10 +1860,14 @@ fn process_sequences<F: Field>(
 
     let mut next_tag_rlc_acc = tag_rlc_iter.next().unwrap();
 
-    let mut padding_end_idx = 0;
-    while sequence_bitstream[padding_end_idx] == 0 {
-        padding_end_idx += 1;
-    }
+    let padding_end_idx =
+        sequence_bitstream
+            .iter()
+            .position(|&bit| bit == 1)
+            .ok_or(DecodeError::CorruptedData {
+                block_idx,
+                reason: "sentinel bit missing in sequences bitstream",
+            })?;
 
     // Add a witness row for leading 0s and the sentinel 1-bit
     witness_rows.push(ZstdWitnessRow {
@@ -1085,7 +1889,7 @@ fn process_sequences<F: Field>(
         encoded_data: EncodedData {
             byte_idx: (byte_offset + current_byte_idx) as u64,
             encoded_len,
-            value_byte: src[end_offset - current_byte_idx],
+            value_byte: sequence_data_bytes[n_sequence_data_bytes - current_byte_idx],
             value_rlc,
             reverse: true,
             reverse_len: n_sequence_data_bytes as u64,
@@ -1100,6 +1904,7 @@ fn process_sequences<F: Field>(
         },
         decoded_data: last_row.decoded_data.clone(),
         fse_data: FseDecodingRow::default(),
+        literals_data: LiteralsDecodingRow::default(),
     });
 
     // Exclude the leading zero section
@@ -1202,9 +2007,13 @@ fn process_sequences<F: Field>(
             curr_baseline = state_baselines[order_idx];
             let new_state = (curr_baseline as u64) + bitstring_value;
             last_states[order_idx] = new_state;
-            let new_state_params = f_tables[order_idx]
-                .get(&new_state)
-                .expect("State should exist.");
+            let new_state_params =
+                f_tables[order_idx]
+                    .get(&new_state)
+                    .ok_or(DecodeError::CorruptedData {
+                        block_idx,
+                        reason: "FSE state out of range",
+                    })?;
             let state_symbol = new_state_params.0;
             last_symbols[order_idx] = state_symbol;
 
@@ -1324,6 +2133,7 @@ fn process_sequences<F: Field>(
                 table_size,
                 ..Default::default()
             },
+            literals_data: LiteralsDecodingRow::default(),
         });
 
         // When the range of a multi-byte read operation from the bitstream covers an entire byte,
@@ -1350,10 +2160,12 @@ fn process_sequences<F: Field>(
                 let wrap_by = match to_bit_idx {
                     15 => 8,
                     16..=23 => 16,
-                    v => unreachable!(
-                        "unexpected bit_index_end={:?} in (table={:?}, update_f?={:?}) (bit_index_start={:?}, bitstring_len={:?})",
-                        v, table_kind, (current_decoding_state >= 3), from_bit_idx, to_bit_idx - from_bit_idx + 1,
-                    ),
+                    _ => {
+                        return Err(DecodeError::CorruptedData {
+                            block_idx,
+                            reason: "read operation spans more than 3 bytes of sequences bitstream",
+                        })
+                    }
                 };
                 witness_rows.push(ZstdWitnessRow {
                     state: ZstdState {
@@ -1408,6 +2220,7 @@ fn process_sequences<F: Field>(
                         table_size,
                         ..Default::default()
                     },
+                    literals_data: LiteralsDecodingRow::default(),
                 })
             }
         }
@@ -1512,8 +2325,13 @@ fn process_sequences<F: Field>(
                 repeated_offset[1] = repeated_offset[0];
                 repeated_offset[0] = result;
             } else if repeat_idx == 4 {
+                if repeated_offset[0] <= 1 {
+                    return Err(DecodeError::CorruptedData {
+                        block_idx,
+                        reason: "repeated offset underflow",
+                    });
+                }
                 let result = repeated_offset[0] - 1;
-                assert!(result > 0, "corruptied data");
                 repeated_offset[2] = repeated_offset[1];
                 repeated_offset[1] = repeated_offset[0];
                 repeated_offset[0] = result;
@@ -1545,6 +2363,12 @@ fn process_sequences<F: Field>(
 
     for inst in address_table_rows.iter() {
         let new_literal_pos = current_literal_pos + (inst.literal_length as usize);
+        if new_literal_pos > literals.len() {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "literal length exceeds the literals",
+            });
+        }
         if new_literal_pos > current_literal_pos {
             let r = current_literal_pos..new_literal_pos;
             seq_exec_info.push(SequenceExec(
@@ -1556,6 +2380,12 @@ fn process_sequences<F: Field>(
             decoded_bytes.extend_from_slice(ext_slice.as_slice());
         }
 
+        if inst.actual_offset == 0 || inst.actual_offset as usize > decoded_bytes.len() {
+            return Err(DecodeError::CorruptedData {
+                block_idx,
+                reason: "match offset exceeds the decoded bytes",
+            });
+        }
         let match_pos = decoded_bytes.len() - (inst.actual_offset as usize);
         if inst.match_length > 0 {
             let r = match_pos..(inst.match_length as usize + match_pos);
@@ -1589,15 +2419,55 @@ fn process_sequences<F: Field>(
         decoded_bytes.extend_from_slice(ext_slice.as_slice());
     }
 
-    SequencesProcessingResult {
+    // The FSE tables are laid out per block, so the FSE tables re-used with the Repeat_Mode are
+    // also assigned for this block.
+    let tables = [table_llt, table_cmot, table_mlt];
+    let fse_aux_tables = tables
+        .clone()
+        .map(|table| FseAuxiliaryTableData { block_idx, ..table });
+
+    Ok(SequencesProcessingResult {
         offset: end_offset,
         witness_rows,
-        fse_aux_tables: [table_llt, table_cmot, table_mlt],
+        fse_aux_tables,
         address_table_rows,
         original_bytes: recovered_inputs,
         sequence_info,
         sequence_exec: seq_exec_info,
         repeated_offset,
+        repeated_fse_tables: tables.map(Some),
+    })
+}
+
+/// Reconstruct the FSE table of the given kind as per its Symbol_Compression_Mode. With the
+/// Repeat_Mode, no bytes are read and the FSE table used in the previous block with sequences is
+/// returned as is.
+fn reconstruct_fse_table(
+    src: &[u8],
+    block_idx: u64,
+    table_kind: FseTableKind,
+    byte_offset: usize,
+    mode: SymbolCompressionMode,
+    repeated_fse_table: Option<&FseAuxiliaryTableData>,
+) -> std::io::Result<(usize, Vec<(u32, u64, u64)>, FseAuxiliaryTableData)> {
+    match mode {
+        SymbolCompressionMode::Predefined => {
+            FseAuxiliaryTableData::reconstruct(src, block_idx, table_kind, byte_offset, true)
+        }
+        SymbolCompressionMode::Rle => {
+            FseAuxiliaryTableData::reconstruct_rle(src, block_idx, table_kind, byte_offset)
+        }
+        SymbolCompressionMode::FseCompressed => {
+            FseAuxiliaryTableData::reconstruct(src, block_idx, table_kind, byte_offset, false)
+        }
+        SymbolCompressionMode::Repeat => repeated_fse_table
+            .map(|table| (0, vec![], table.clone()))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Repeat_Mode requires an FSE table from a previous block",
+                )
+            }),
     }
 }
 
@@ -1607,6 +2477,8 @@ pub struct LiteralsHeaderProcessingResult<F> {
     pub witness_rows: Vec<ZstdWitnessRow<F>>,
     pub regen_size: usize,
     pub compressed_size: usize,
+    pub literals_block_type: BlockType,
+    pub n_streams: usize,
 }
 
 fn process_block_zstd_literals_header<F: Field>(
@@ -1615,27 +2487,44 @@ fn process_block_zstd_literals_header<F: Field>(
     byte_offset: usize,
     last_row: &ZstdWitnessRow<F>,
     randomness: Value<F>,
-) -> LiteralsHeaderProcessingResult<F> {
+) -> Result<LiteralsHeaderProcessingResult<F>, DecodeError> {
     let lh_bytes = src
         .iter()
         .skip(byte_offset)
         .take(N_MAX_LITERAL_HEADER_BYTES)
         .cloned()
         .collect::<Vec<u8>>();
+    if lh_bytes.is_empty() {
+        return Err(DecodeError::UnexpectedEof { byte_offset });
+    }
 
     let literals_block_type = BlockType::from(lh_bytes[0] & 0x3);
+    if literals_block_type == BlockType::RleBlock {
+        return Err(DecodeError::RleLiteralsBlock { block_idx });
+    }
     let size_format = (lh_bytes[0] >> 2) & 3;
 
-    let [n_bits_fmt, n_bits_regen, n_bits_compressed, _n_streams, n_bytes_header, _branch]: [usize;
-        6] = match literals_block_type {
-        BlockType::RawBlock => match size_format {
+    let [n_bits_fmt, n_bits_regen, n_bits_compressed, n_streams, n_bytes_header, _branch]: [usize;
+        6] = if literals_block_type == BlockType::RawBlock {
+        match size_format {
             0b00 | 0b10 => [1, 5, 0, 1, 1, 0],
             0b01 => [2, 12, 0, 1, 2, 1],
-            0b11 => [2, 20, 0, 1, 3, 2],
-            _ => unreachable!("size_format out of bound"),
-        },
-        _ => unreachable!("BlockType::* unexpected. Must be raw bytes for literals."),
+            _ => [2, 20, 0, 1, 3, 2],
+        }
+    } else {
+        // Compressed_Literals_Block and Treeless_Literals_Block share the same header layout.
+        match size_format {
+            0b00 => [2, 10, 10, 1, 3, 3],
+            0b01 => [2, 10, 10, 4, 3, 4],
+            0b10 => [2, 14, 14, 4, 4, 5],
+            _ => [2, 18, 18, 4, 5, 6],
+        }
     };
+    if lh_bytes.len() < n_bytes_header {
+        return Err(DecodeError::UnexpectedEof {
+            byte_offset: src.len(),
+        });
+    }
 
     // Bits for representing regenerated_size and compressed_size
     let sizing_bits = &lh_bytes.clone().into_iter().fold(vec![], |mut acc, b| {
@@ -1648,7 +2537,9 @@ fn process_block_zstd_literals_header<F: Field>(
         le_bits_to_value(&sizing_bits[n_bits_regen..(n_bits_regen + n_bits_compressed)]) as usize;
     let tag_next = match literals_block_type {
         BlockType::RawBlock => ZstdTag::ZstdBlockLiteralsRawBytes,
-        _ => unreachable!("BlockType::* unexpected. Must be raw bytes for literals."),
+        BlockType::ZstdCompressedBlock => ZstdTag::ZstdBlockHuffmanCode,
+        _ if n_streams == 4 => ZstdTag::ZstdBlockJumpTable,
+        _ => ZstdTag::ZstdBlockLstream,
     };
 
     let tag_rlc_iter =
@@ -1665,7 +2556,7 @@ fn process_block_zstd_literals_header<F: Field>(
         (0..last_row.state.tag_len).fold(Value::known(F::one()), |acc, _| acc * randomness);
     let value_rlc = last_row.encoded_data.value_rlc * multiplier + last_row.state.tag_rlc;
 
-    LiteralsHeaderProcessingResult {
+    Ok(LiteralsHeaderProcessingResult {
         offset: byte_offset + n_bytes_header,
         witness_rows: lh_bytes
             .iter()
@@ -1695,11 +2586,14 @@ fn process_block_zstd_literals_header<F: Field>(
                 bitstream_read_data: BitstreamReadRow::default(),
                 decoded_data: last_row.decoded_data.clone(),
                 fse_data: FseDecodingRow::default(),
+                literals_data: LiteralsDecodingRow::default(),
             })
             .collect::<Vec<_>>(),
         regen_size,
         compressed_size,
-    }
+        literals_block_type,
+        n_streams,
+    })
 }
 
 /// Result for processing multiple blocks from compressed data
@@ -1712,10 +2606,17 @@ pub struct MultiBlockProcessResult<F> {
     pub sequence_info_arr: Vec<SequenceInfo>,
     pub address_table_rows: Vec<Vec<AddressTableRow>>,
     pub sequence_exec_results: Vec<SequenceExecResult>,
+    pub huffman_codes: Vec<HuffmanCodesData>,
 }
 
 /// Process a slice of bytes into decompression circuit witness rows
-pub fn process<F: Field>(src: &[u8], randomness: Value<F>) -> MultiBlockProcessResult<F> {
+///
+/// Returns a [`DecodeError`] if the frame is malformed or uses a feature that is not supported
+/// by the decoder circuit.
+pub fn process<F: Field>(
+    src: &[u8],
+    randomness: Value<F>,
+) -> Result<MultiBlockProcessResult<F>, DecodeError> {
     let mut witness_rows = vec![];
     let mut decoded_bytes: Vec<u8> = vec![];
     let mut literals: Vec<Vec<u64>> = vec![];
@@ -1724,6 +2625,7 @@ pub fn process<F: Field>(src: &[u8], randomness: Value<F>) -> MultiBlockProcessR
     let mut sequence_info_arr: Vec<SequenceInfo> = vec![];
     let mut address_table_arr: Vec<Vec<AddressTableRow>> = vec![];
     let mut sequence_exec_info_arr: Vec<SequenceExecResult> = vec![];
+    let mut huffman_codes_arr: Vec<HuffmanCodesData> = vec![];
 
     // FrameHeaderDescriptor and FrameContentSize
     let (mut byte_offset, rows) = process_frame_header::<F>(
@@ -1731,11 +2633,12 @@ pub fn process<F: Field>(src: &[u8], randomness: Value<F>) -> MultiBlockProcessR
         0, // frame header starts at offset=0
         &ZstdWitnessRow::init(src.len()),
         randomness,
-    );
+    )?;
     witness_rows.extend_from_slice(&rows);
 
     let mut block_idx: u64 = 1;
     let mut repeated_offset = [1, 4, 8];
+    let mut repeated_fse_tables: [Option<FseAuxiliaryTableData>; 3] = [None, None, None];
     loop {
         let AggregateBlockResult {
             offset,
@@ -1747,15 +2650,22 @@ pub fn process<F: Field>(src: &[u8], randomness: Value<F>) -> MultiBlockProcessR
             address_table_rows,
             sequence_exec_result,
             repeated_offset: end_repeated_offset,
+            repeated_fse_tables: end_repeated_fse_tables,
+            huffman_codes,
         } = process_block::<F>(
             src,
             &mut decoded_bytes,
             block_idx,
             byte_offset,
             witness_rows.last().expect("last row expected to exist"),
+            block_info_arr
+                .last()
+                .map_or(BlockType::default(), |info| info.block_type),
             randomness,
             repeated_offset,
-        );
+            &repeated_fse_tables,
+            huffman_codes_arr.last(),
+        )?;
         log::debug!("processed block={:?}: offset={:?}", block_idx, offset);
 
         witness_rows.extend_from_slice(&rows);
@@ -1768,18 +2678,27 @@ pub fn process<F: Field>(src: &[u8], randomness: Value<F>) -> MultiBlockProcessR
         sequence_info_arr.push(sequence_info);
         address_table_arr.push(address_table_rows);
         sequence_exec_info_arr.push(sequence_exec_result);
+        if let Some(huffman_codes) = huffman_codes {
+            huffman_codes_arr.push(huffman_codes);
+        }
 
         if block_info.is_last_block {
-            assert!(offset >= src.len());
+            if offset < src.len() {
+                return Err(DecodeError::CorruptedData {
+                    block_idx,
+                    reason: "trailing bytes after the last block",
+                });
+            }
             break;
         } else {
             repeated_offset = end_repeated_offset;
+            repeated_fse_tables = end_repeated_fse_tables;
             block_idx += 1;
             byte_offset = offset;
         }
     }
 
-    MultiBlockProcessResult {
+    Ok(MultiBlockProcessResult {
         witness_rows,
         literal_bytes: literals,
         fse_aux_tables,
@@ -1787,7 +2706,8 @@ pub fn process<F: Field>(src: &[u8], randomness: Value<F>) -> MultiBlockProcessR
         sequence_info_arr,
         address_table_rows: address_table_arr,
         sequence_exec_results: sequence_exec_info_arr,
-    }
+        huffman_codes: huffman_codes_arr,
+    })
 }
 
 #[cfg(test)]
@@ -1893,7 +2813,9 @@ mod tests {
                 sequence_info_arr: _s,
                 address_table_rows: _a,
                 sequence_exec_results,
-            } = process::<Fr>(&compressed, Value::known(Fr::from(123456789)));
+                huffman_codes: _h,
+            } = process::<Fr>(&compressed, Value::known(Fr::from(123456789)))
+                .expect("witness generation should not fail");
 
             let decoded_bytes = sequence_exec_results
                 .into_iter()
@@ -1905,4 +2827,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_zstd_witness_processing_malformed_frame() -> Result<(), std::io::Error> {
+        use super::*;
+        use halo2_proofs::halo2curves::bn256::Fr;
+
+        let raw_input_bytes = b"malformed frames are reported as a decode error".repeat(8);
+        let compressed = {
+            let mut encoder = init_zstd_encoder(None);
+            encoder.set_pledged_src_size(Some(raw_input_bytes.len() as u64))?;
+            encoder.write_all(&raw_input_bytes)?;
+            encoder.finish()?
+        };
+        assert!(process::<Fr>(&compressed, Value::unknown()).is_ok());
+
+        // content checksum flag set in the frame header descriptor.
+        let mut with_checksum = compressed.clone();
+        with_checksum[0] |= 1 << 2;
+        assert!(matches!(
+            process::<Fr>(&with_checksum, Value::unknown()),
+            Err(DecodeError::ContentChecksumPresent)
+        ));
+
+        // reserved block type in the first block header.
+        let mut reserved_block = compressed.clone();
+        let fcs_len = [1, 2, 4, 8][(compressed[0] >> 6) as usize];
+        reserved_block[1 + fcs_len] |= 0b110;
+        assert!(matches!(
+            process::<Fr>(&reserved_block, Value::unknown()),
+            Err(DecodeError::ReservedBlockType { block_idx: 1 })
+        ));
+
+        // truncated frame, at every possible length.
+        for len in 0..compressed.len() {
+            assert!(process::<Fr>(&compressed[..len], Value::unknown()).is_err());
+        }
+
+        Ok(())
+    }
 }
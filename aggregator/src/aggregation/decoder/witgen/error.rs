@@ -0,0 +1,117 @@
+use std::fmt;
+
+use super::FseTableKind;
+
+/// Errors encountered while generating the witness for a zstd-compressed frame.
+///
+/// The zstd decoder circuit only supports a subset of the zstd format, so an error is returned
+/// for frames that are either malformed or make use of an unsupported feature.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The frame ends before the data expected at the given byte offset.
+    UnexpectedEof { byte_offset: usize },
+    /// The frame header declares a dictionary ID.
+    DictionaryIdPresent,
+    /// The frame header declares a content checksum.
+    ContentChecksumPresent,
+    /// The reserved bit of the frame header descriptor is set.
+    FrameHeaderReservedBit,
+    /// The frame is not a single segment.
+    NotSingleSegment,
+    /// The block header declares the reserved block type.
+    ReservedBlockType { block_idx: u64 },
+    /// A raw or RLE block declares a block size of 0.
+    EmptyBlock { block_idx: u64 },
+    /// The literals section is an RLE_Literals_Block.
+    RleLiteralsBlock { block_idx: u64 },
+    /// A Treeless_Literals_Block is not preceded by a Huffman tree description.
+    MissingHuffmanTree { block_idx: u64 },
+    /// The Huffman tree description could not be reconstructed.
+    InvalidHuffmanCodes {
+        block_idx: u64,
+        source: std::io::Error,
+    },
+    /// The sequences section declares 0 sequences.
+    EmptySequences { block_idx: u64 },
+    /// The reserved bits of the sequences section's compression modes are set.
+    SequencesReservedBits { block_idx: u64 },
+    /// The FSE table of the given kind could not be reconstructed.
+    InvalidFseTable {
+        block_idx: u64,
+        table_kind: FseTableKind,
+        source: std::io::Error,
+    },
+    /// The frame makes use of a zstd feature that the decoder circuit does not constrain.
+    Unsupported {
+        block_idx: u64,
+        feature: &'static str,
+    },
+    /// The encoded data is inconsistent with what was declared in the headers.
+    CorruptedData {
+        block_idx: u64,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof { byte_offset } => {
+                write!(f, "unexpected end of frame at byte offset {byte_offset}")
+            }
+            Self::DictionaryIdPresent => write!(f, "dictionary ID is not supported"),
+            Self::ContentChecksumPresent => write!(f, "content checksum is not supported"),
+            Self::FrameHeaderReservedBit => {
+                write!(f, "reserved bit of the frame header descriptor is set")
+            }
+            Self::NotSingleSegment => write!(f, "frame is expected to be a single segment"),
+            Self::ReservedBlockType { block_idx } => {
+                write!(f, "block {block_idx}: reserved block type")
+            }
+            Self::EmptyBlock { block_idx } => {
+                write!(f, "block {block_idx}: empty Raw_Block/RLE_Block is not supported")
+            }
+            Self::RleLiteralsBlock { block_idx } => {
+                write!(f, "block {block_idx}: RLE_Literals_Block is not supported")
+            }
+            Self::MissingHuffmanTree { block_idx } => write!(
+                f,
+                "block {block_idx}: Treeless_Literals_Block requires a previously described Huffman tree"
+            ),
+            Self::InvalidHuffmanCodes { block_idx, source } => {
+                write!(f, "block {block_idx}: invalid Huffman codes: {source}")
+            }
+            Self::EmptySequences { block_idx } => {
+                write!(f, "block {block_idx}: sequences section with 0 sequences is not supported")
+            }
+            Self::SequencesReservedBits { block_idx } => {
+                write!(f, "block {block_idx}: reserved bits of the compression modes are set")
+            }
+            Self::InvalidFseTable {
+                block_idx,
+                table_kind,
+                source,
+            } => write!(
+                f,
+                "block {block_idx}: invalid FSE table {table_kind:?}: {source}"
+            ),
+            Self::Unsupported { block_idx, feature } => {
+                write!(f, "block {block_idx}: {feature} is not supported")
+            }
+            Self::CorruptedData { block_idx, reason } => {
+                write!(f, "block {block_idx}: corrupted data: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidHuffmanCodes { source, .. } | Self::InvalidFseTable { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
@@ -5,7 +5,7 @@ pub const N_BITS_PER_BYTE: usize = 8;
 pub const N_BLOCK_HEADER_BYTES: usize = 3;
 
 /// Constants for zstd-compressed block
-pub const N_MAX_LITERAL_HEADER_BYTES: usize = 3;
+pub const N_MAX_LITERAL_HEADER_BYTES: usize = 5;
 
 /// Number of bits used to represent the tag in binary form.
 pub const N_BITS_ZSTD_TAG: usize = 4;
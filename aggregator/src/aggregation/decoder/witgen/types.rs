@@ -10,10 +10,11 @@ use strum_macros::EnumIter;
 
 use super::{
     params::N_BITS_PER_BYTE,
-    util::{read_variable_bit_packing, smaller_powers_of_two, value_bits_le},
+    util::{bit_length, read_variable_bit_packing, smaller_powers_of_two, value_bits_le},
+    DecodeError,
 };
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum BlockType {
     #[default]
     RawBlock = 0,
@@ -34,6 +35,37 @@ impl From<u8> for BlockType {
     }
 }
 
+/// The Symbol_Compression_Mode used for the literals lengths, offsets and match lengths in the
+/// sequences section.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolCompressionMode {
+    #[default]
+    Predefined = 0,
+    Rle,
+    FseCompressed,
+    Repeat,
+}
+
+impl From<u8> for SymbolCompressionMode {
+    fn from(src: u8) -> Self {
+        match src {
+            0 => Self::Predefined,
+            1 => Self::Rle,
+            2 => Self::FseCompressed,
+            3 => Self::Repeat,
+            _ => unreachable!("Symbol_Compression_Mode is 2 bits"),
+        }
+    }
+}
+
+impl SymbolCompressionMode {
+    /// Whether the FSE table is described in the sequences section, i.e. RLE_Mode or
+    /// FSE_Compressed_Mode.
+    pub fn has_fse_code(&self) -> bool {
+        matches!(self, Self::Rle | Self::FseCompressed)
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct BlockInfo {
     pub block_idx: usize,
@@ -41,13 +73,40 @@ pub struct BlockInfo {
     pub block_len: usize,
     pub is_last_block: bool,
     pub regen_size: u64,
+    pub literals_info: LiteralsInfo,
+}
+
+/// Information about the Huffman-coded literals section of a block. For Raw_Literals_Block (and
+/// for Raw_Block and RLE_Block) only the index of the block that last described a Huffman tree is
+/// carried forward.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LiteralsInfo {
+    /// Whether the literals are Huffman-coded, i.e. Compressed_Literals_Block or
+    /// Treeless_Literals_Block.
+    pub is_huffman: bool,
+    /// Whether the Huffman-coded literals are split over 4 streams.
+    pub is_4_streams: bool,
+    /// The size of the Huffman-coded literals section following the literals header.
+    pub compressed_size: u64,
+    /// The number of weights explicitly described in the Huffman tree description.
+    pub n_weights: u64,
+    /// The sizes of the first 3 literals streams as per the jump table.
+    pub stream_sizes: [u64; 3],
+    /// The index of the block in which the Huffman tree applicable to this block is described.
+    pub huffman_block_idx: u64,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct SequenceInfo {
     pub block_idx: usize,
     pub num_sequences: usize,
-    pub compression_mode: [bool; 3],
+    /// The compression modes for the LLT, MOT and MLT.
+    pub compression_mode: [SymbolCompressionMode; 3],
+    /// The index of the block in which the FSE tables (LLT, MOT, MLT) in use were described. This
+    /// differs from block_idx only for the Repeat_Mode.
+    pub fse_table_block_idx: [usize; 3],
+    /// Whether the FSE tables (LLT, MOT, MLT) in use are the predefined ones.
+    pub is_predefined: [bool; 3],
 }
 
 /// The type for indicate each range in output bytes by sequence execution
@@ -114,6 +173,12 @@ pub enum ZstdTag {
     ZstdBlockSequenceFseCode,
     /// sequence bitstream for recovering instructions
     ZstdBlockSequenceData,
+    /// Zstd block's Huffman tree description for compressed literals.
+    ZstdBlockHuffmanCode,
+    /// Jump table specifying the sizes of the first 3 Huffman-coded literals streams.
+    ZstdBlockJumpTable,
+    /// Huffman-coded literals bitstream.
+    ZstdBlockLstream,
 }
 
 impl ZstdTag {
@@ -129,6 +194,9 @@ impl ZstdTag {
             Self::ZstdBlockSequenceHeader => true,
             Self::ZstdBlockSequenceFseCode => true,
             Self::ZstdBlockSequenceData => true,
+            Self::ZstdBlockHuffmanCode => true,
+            Self::ZstdBlockJumpTable => true,
+            Self::ZstdBlockLstream => true,
         }
     }
 
@@ -144,6 +212,9 @@ impl ZstdTag {
             Self::ZstdBlockSequenceHeader => false,
             Self::ZstdBlockSequenceFseCode => false,
             Self::ZstdBlockSequenceData => true,
+            Self::ZstdBlockHuffmanCode => false,
+            Self::ZstdBlockJumpTable => false,
+            Self::ZstdBlockLstream => true,
         }
     }
 
@@ -154,13 +225,15 @@ impl ZstdTag {
             Self::FrameHeaderDescriptor => 1,
             Self::FrameContentSize => 8,
             Self::BlockHeader => 3,
-            // as per spec, should be 5. But given that our encoder does not compress literals, it
-            // is 3.
-            Self::ZstdBlockLiteralsHeader => 3,
+            Self::ZstdBlockLiteralsHeader => 5,
             Self::ZstdBlockLiteralsRawBytes => (1 << 17) - 1,
             Self::ZstdBlockSequenceHeader => 4,
             Self::ZstdBlockSequenceFseCode => 128,
             Self::ZstdBlockSequenceData => (1 << 17) - 1,
+            // 1 header byte followed by at most 128 bytes of 4-bit weights.
+            Self::ZstdBlockHuffmanCode => 129,
+            Self::ZstdBlockJumpTable => 6,
+            Self::ZstdBlockLstream => (1 << 17) - 1,
         }
     }
 }
@@ -199,6 +272,9 @@ impl ToString for ZstdTag {
             Self::ZstdBlockSequenceHeader => "ZstdBlockSequenceHeader",
             Self::ZstdBlockSequenceFseCode => "ZstdBlockSequenceFseCode",
             Self::ZstdBlockSequenceData => "ZstdBlockSequenceData",
+            Self::ZstdBlockHuffmanCode => "ZstdBlockHuffmanCode",
+            Self::ZstdBlockJumpTable => "ZstdBlockJumpTable",
+            Self::ZstdBlockLstream => "ZstdBlockLstream",
         })
     }
 }
@@ -346,6 +422,21 @@ pub struct BitstreamReadRow {
     pub is_update_state: u64,
 }
 
+/// Literals decoded at a row, either a raw literal byte or a Huffman-coded literal.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LiteralsDecodingRow {
+    /// The index of the Huffman-coded literals stream, in 0..4.
+    pub lstream_idx: u64,
+    /// Whether a literal is decoded at this row.
+    pub is_literal: bool,
+    /// The 1-indexed position of the literal within the block's literals.
+    pub literal_idx: u64,
+    /// The decoded literal.
+    pub literal: u64,
+    /// Whether this is the last literal of the block, i.e. literal_idx == regen_size.
+    pub is_last_literal: bool,
+}
+
 /// Sequence data is interleaved with 6 bitstreams. Each producing a different type of value.
 #[derive(Clone, Copy, Debug)]
 pub enum SequenceDataTag {
@@ -743,6 +834,39 @@ impl FseAuxiliaryTableData {
         ))
     }
 
+    /// Reconstruct the FSE table for the RLE_Mode, which is described by a single byte, i.e. the
+    /// only symbol. All states of the table (AL=0) are allocated to that symbol.
+    pub fn reconstruct_rle(
+        src: &[u8],
+        block_idx: u64,
+        table_kind: FseTableKind,
+        byte_offset: usize,
+    ) -> std::io::Result<ReconstructedFse> {
+        let symbol = *src.get(byte_offset).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "RLE_Mode symbol must exist",
+            )
+        })? as u64;
+        let normalised_probs = BTreeMap::from([(symbol, 1)]);
+        let (sym_to_states, sym_to_sorted_states) =
+            Self::transform_normalised_probs(&normalised_probs, 0);
+
+        Ok((
+            1,
+            vec![(N_BITS_PER_BYTE as u32, symbol, symbol)],
+            Self {
+                block_idx,
+                is_predefined: false,
+                table_kind,
+                table_size: 1,
+                normalised_probs,
+                sym_to_states,
+                sym_to_sorted_states,
+            },
+        ))
+    }
+
     #[allow(non_snake_case)]
     fn transform_normalised_probs(
         normalised_probs: &BTreeMap<u64, i32>,
@@ -865,6 +989,11 @@ impl FseAuxiliaryTableData {
         (sym_to_states, sym_to_sorted_states)
     }
 
+    /// The accuracy log of the FSE table, i.e. table_size == 1 << AL.
+    pub fn accuracy_log(&self) -> u64 {
+        self.table_size.trailing_zeros() as u64
+    }
+
     /// Convert an FseAuxiliaryTableData into a state-mapped representation.
     /// This makes it easier to lookup state-chaining during decoding.
     pub fn parse_state_table(&self) -> FseStateMapping {
@@ -885,6 +1014,127 @@ impl FseAuxiliaryTableData {
     }
 }
 
+/// Data for the Huffman code used to decode Huffman-coded literals.
+///
+/// The Huffman tree is described by the weights of the symbols 0..=last_symbol, where the weight
+/// of the last symbol is not explicitly described but implied, such that the sum of
+/// 2^(weight - 1) over all symbols with a non-zero weight is a power of 2, i.e. 2^max_bits. A
+/// symbol with weight w > 0 is assigned a prefix code of (max_bits + 1 - w) bits. Prefix codes
+/// are assigned in the increasing order of (weight, symbol).
+///
+/// For more details, refer the [Huffman tree description][doclink] section.
+///
+/// [doclink]: https://github.com/facebook/zstd/blob/dev/doc/zstd_compression_format.md#huffman-tree-description
+#[derive(Clone, Debug, Default)]
+pub struct HuffmanCodesData {
+    /// The block index in which the Huffman tree description appears.
+    pub block_idx: u64,
+    /// The byte offset in the frame at which the Huffman tree description begins.
+    pub byte_offset: u64,
+    /// The weights of symbols 0..=last_symbol, including the implied weight of the last symbol.
+    pub weights: Vec<u64>,
+}
+
+/// Map<symbol, (weight, code, num_bits)>.
+type HuffmanCodeMapping = BTreeMap<u64, (u64, u64, u64)>;
+
+impl HuffmanCodesData {
+    /// Reconstruct the Huffman code from the Huffman tree description beginning at byte_offset.
+    /// Returns the number of bytes in the tree description and the reconstructed data.
+    ///
+    /// Only directly represented weights (header >= 128) are constrained by the decoder circuit,
+    /// so FSE-compressed weights are reported as unsupported.
+    pub fn reconstruct(
+        src: &[u8],
+        block_idx: u64,
+        byte_offset: usize,
+    ) -> Result<(usize, Self), DecodeError> {
+        let invalid = |kind, error| DecodeError::InvalidHuffmanCodes {
+            block_idx,
+            source: std::io::Error::new(kind, error),
+        };
+        let eof = || {
+            invalid(
+                std::io::ErrorKind::UnexpectedEof,
+                "Huffman tree description exceeds the frame",
+            )
+        };
+        let header = *src.get(byte_offset).ok_or_else(eof)? as usize;
+        if header < 128 {
+            return Err(DecodeError::Unsupported {
+                block_idx,
+                feature: "FSE-compressed Huffman weights",
+            });
+        }
+
+        // The weights are directly represented, 2 weights per byte with the first weight in the
+        // high nibble. If the number of weights is odd, the last nibble is ignored.
+        let n_weights = header - 127;
+        let n_weight_bytes = (n_weights + 1) / 2;
+        let mut weights = src
+            .get((byte_offset + 1)..(byte_offset + 1 + n_weight_bytes))
+            .ok_or_else(eof)?
+            .iter()
+            .flat_map(|&byte| [(byte >> 4) as u64, (byte & 0xf) as u64])
+            .take(n_weights)
+            .collect::<Vec<u64>>();
+
+        // The weight of the last symbol is implied by the weights of the other symbols.
+        let sum = weights
+            .iter()
+            .filter(|&&weight| weight > 0)
+            .map(|&weight| 1u64 << (weight - 1))
+            .sum::<u64>();
+        let left_over = (1 << bit_length(sum)) - sum;
+        if !left_over.is_power_of_two() {
+            return Err(invalid(
+                std::io::ErrorKind::InvalidData,
+                "Huffman weights do not sum up to a power of 2",
+            ));
+        }
+        weights.push(bit_length(left_over));
+
+        Ok((
+            1 + n_weight_bytes,
+            Self {
+                block_idx,
+                byte_offset: byte_offset as u64,
+                weights,
+            },
+        ))
+    }
+
+    /// The maximum number of bits in a prefix code.
+    pub fn max_bits(&self) -> u64 {
+        let sum = self
+            .weights
+            .iter()
+            .filter(|&&weight| weight > 0)
+            .map(|&weight| 1u64 << (weight - 1))
+            .sum::<u64>();
+        bit_length(sum) - 1
+    }
+
+    /// Assign the canonical prefix codes to symbols with a non-zero weight.
+    pub fn parse_canonical(&self) -> HuffmanCodeMapping {
+        let max_bits = self.max_bits();
+        let mut start = 0u64;
+
+        self.weights
+            .iter()
+            .enumerate()
+            .filter(|(_symbol, &weight)| weight > 0)
+            .map(|(symbol, &weight)| (weight, symbol as u64))
+            .sorted()
+            .map(|(weight, symbol)| {
+                let code = start >> (weight - 1);
+                start += 1 << (weight - 1);
+                (symbol, (weight, code, max_bits + 1 - weight))
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Row witness value for decompression circuit
 pub struct ZstdWitnessRow<F> {
@@ -898,6 +1148,8 @@ pub struct ZstdWitnessRow<F> {
     pub fse_data: FseDecodingRow,
     /// Bitstream reader
     pub bitstream_read_data: BitstreamReadRow,
+    /// Decoded literals
+    pub literals_data: LiteralsDecodingRow,
 }
 
 impl<F: Field> ZstdWitnessRow<F> {
@@ -912,6 +1164,19 @@ impl<F: Field> ZstdWitnessRow<F> {
             decoded_data: DecodedData::default(),
             fse_data: FseDecodingRow::default(),
             bitstream_read_data: BitstreamReadRow::default(),
+            literals_data: LiteralsDecodingRow::default(),
+        }
+    }
+
+    /// The number of encoded bytes covered by the tag at this row, i.e. the power of randomness
+    /// by which the encoded RLC is shifted once the tag is processed. This is the tag length for
+    /// all tags except the contents of an RLE block, where a single encoded byte is repeated over
+    /// Block_Size rows.
+    pub fn encoded_tag_len(&self, block_type: BlockType) -> u64 {
+        if block_type == BlockType::RleBlock && self.state.tag.is_block() {
+            1
+        } else {
+            self.state.tag_len
         }
     }
 }
@@ -1116,4 +1381,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_huffman_canonical_codes() -> Result<(), DecodeError> {
+        // The example from the zstd specification: the weights of literals 0..=4 are 4, 3, 2, 0
+        // and 1, directly represented (header == 127 + 5). The last literal has the implied
+        // weight 1.
+        let src = vec![0xff, 0x84, 0x43, 0x20, 0x10, 0xff];
+
+        let (n_bytes, huffman_codes) = HuffmanCodesData::reconstruct(&src, 1, 1)?;
+
+        assert_eq!(n_bytes, 4);
+        assert_eq!(huffman_codes.weights, vec![4, 3, 2, 0, 1, 1]);
+        assert_eq!(huffman_codes.max_bits(), 4);
+        assert_eq!(
+            huffman_codes.parse_canonical(),
+            HuffmanCodeMapping::from([
+                (0, (4, 0b1, 1)),
+                (1, (3, 0b01, 2)),
+                (2, (2, 0b001, 3)),
+                (4, (1, 0b0000, 4)),
+                (5, (1, 0b0001, 4)),
+            ]),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_huffman_fse_compressed_weights() {
+        // A header < 128 gives the size of the FSE-compressed weights that follow.
+        let src = vec![0xff, 0x05, 0x30, 0x6f, 0x9b, 0x03, 0xff];
+
+        assert!(matches!(
+            HuffmanCodesData::reconstruct(&src, 1, 1),
+            Err(DecodeError::Unsupported { block_idx: 1, .. })
+        ));
+    }
 }
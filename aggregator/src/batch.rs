@@ -1,22 +1,86 @@
 //! This module implements related functions that aggregates public inputs of many chunks into a
 //! single one.
 
+use std::fmt;
+
 use eth_types::{Field, ToBigEndian, H256};
 use ethers_core::utils::keccak256;
+use itertools::Itertools;
 
 use crate::{
-    blob::{BatchData, PointEvaluationAssignments},
+    aggregation::DecodeError,
+    blob::{BatchData, BlobKzgData, PointEvaluationAssignments, MAX_BLOBS_PER_BATCH},
     chunk::ChunkHash,
+    constants::BATCH_BLOB_LEN,
+    pi_layout::{da_commitment_len, PiField, PiHashLayout, PiHashLayoutError},
 };
 
-#[derive(Default, Debug, Clone)]
+/// How the batch data is made available on L1, which determines the layout of the batch public
+/// input hash.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataAvailability {
+    /// The blob payload is published in EIP-4844 blobs, and committed to via the challenge point
+    /// z, the evaluation y and the versioned hash of each blob.
+    #[default]
+    Blob,
+    /// The blob payload is published as calldata, and committed to via its keccak hash.
+    Calldata,
+}
+
+/// Errors encountered while building a [`BatchHash`].
+#[derive(Debug)]
+pub enum BatchHashError {
+    /// The pi hash layouts do not match the chunks or the data availability.
+    PiHashLayout(PiHashLayoutError),
+    /// The zstd encoded batch data cannot be decoded by the zstd decoder circuit.
+    Decode(DecodeError),
+}
+
+impl fmt::Display for BatchHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PiHashLayout(e) => write!(f, "invalid pi hash layout: {e}"),
+            Self::Decode(e) => write!(f, "failed to decode zstd frame: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BatchHashError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::PiHashLayout(e) => Some(e),
+            Self::Decode(e) => Some(e),
+        }
+    }
+}
+
+impl From<PiHashLayoutError> for BatchHashError {
+    fn from(e: PiHashLayoutError) -> Self {
+        Self::PiHashLayout(e)
+    }
+}
+
+impl From<DecodeError> for BatchHashError {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+#[derive(Debug, Clone)]
 /// A batch is a set of N_SNARKS num of continuous chunks
 /// - the first k chunks are from real traces
 /// - the last (#N_SNARKS-k) chunks are from empty traces
 /// A BatchHash consists of 2 hashes.
 /// - batch_pi_hash   := keccak(chain_id || chunk_0.prev_state_root || chunk_k-1.post_state_root ||
-///   chunk_k-1.withdraw_root || batch_data_hash)
+///   chunk_k-1.withdraw_root || batch_data_hash || da_commitment)
 /// - batch_data_hash := keccak(chunk_0.data_hash || ... || chunk_k-1.data_hash)
+///
+/// where da_commitment depends on the [`DataAvailability`]:
+/// - Blob:     (z || y || versioned_hash) for each blob used by the batch
+/// - Calldata: keccak(calldata), the calldata being the blob payload
+///
+/// The above are the default layouts of the batch_pi_hash and of the chunk pi hashes, see
+/// [`PiHashLayout`] for other ones.
 pub struct BatchHash<const N_SNARKS: usize> {
     /// Chain ID of the network.
     pub(crate) chain_id: u64,
@@ -29,19 +93,64 @@ pub struct BatchHash<const N_SNARKS: usize> {
     pub(crate) data_hash: H256,
     /// The public input hash, as calculated on-chain:
     /// - keccak256( chain_id || prev_state_root || next_state_root || withdraw_trie_root ||
-    ///   batch_data_hash || z || y )
+    ///   batch_data_hash || z_0 || y_0 || versioned_hash_0 || ... || z_(n-1) || y_(n-1) ||
+    ///   versioned_hash_(n-1) ), where n = num_blobs, for blob DA
+    /// - keccak256( chain_id || prev_state_root || next_state_root || withdraw_trie_root ||
+    ///   batch_data_hash || calldata_hash ), for calldata DA
     pub(crate) public_input_hash: H256,
     /// The number of chunks that contain meaningful data, i.e. not padded chunks.
     pub(crate) number_of_valid_chunks: usize,
-    /// 4844 point evaluation check related assignments.
-    pub(crate) point_evaluation_assignments: PointEvaluationAssignments,
-    /// The 4844 versioned hash for the blob.
-    pub(crate) versioned_hash: H256,
+    /// Whether the batch data bytes are zstd encoded in the blob payload. This is decided once
+    /// when the batch is built, as it requires to encode and decode the batch data bytes.
+    pub(crate) is_compressed: bool,
+    /// The number of blobs used by the batch, i.e. required to hold the blob payload.
+    pub(crate) num_blobs: usize,
+    /// 4844 point evaluation check related assignments, for each of the MAX_BLOBS_PER_BATCH
+    /// blobs.
+    pub(crate) point_evaluation_assignments: Vec<PointEvaluationAssignments>,
+    /// The 4844 versioned hash for each of the MAX_BLOBS_PER_BATCH blobs, zero for the unused
+    /// ones.
+    pub(crate) versioned_hashes: Vec<H256>,
+    /// How the batch data is made available.
+    pub(crate) data_availability: DataAvailability,
+    /// The keccak hash of the calldata, i.e. the blob payload.
+    pub(crate) calldata_hash: H256,
+    /// The layout of the chunk public input hashes.
+    pub(crate) chunk_pi_layout: PiHashLayout,
+    /// The layout of the batch public input hash.
+    pub(crate) batch_pi_layout: PiHashLayout,
 }
 
 impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
-    /// Build Batch hash from an ordered list of #N_SNARKS of chunks.
+    /// Build Batch hash from an ordered list of #N_SNARKS of chunks, with the batch data made
+    /// available via blobs.
     pub fn construct(chunks_with_padding: &[ChunkHash]) -> Self {
+        Self::construct_with_data_availability(chunks_with_padding, DataAvailability::Blob)
+    }
+
+    /// Build Batch hash from an ordered list of #N_SNARKS of chunks, with the batch data made
+    /// available as specified.
+    pub fn construct_with_data_availability(
+        chunks_with_padding: &[ChunkHash],
+        data_availability: DataAvailability,
+    ) -> Self {
+        Self::construct_with_pi_layouts(
+            chunks_with_padding,
+            data_availability,
+            PiHashLayout::default_chunk(),
+            PiHashLayout::default_batch(data_availability),
+        )
+    }
+
+    /// Build Batch hash from an ordered list of #N_SNARKS of chunks, with the batch data made
+    /// available as specified, and the chunk and batch public input hashes arranged as per the
+    /// given layouts.
+    pub fn construct_with_pi_layouts(
+        chunks_with_padding: &[ChunkHash],
+        data_availability: DataAvailability,
+        chunk_pi_layout: PiHashLayout,
+        batch_pi_layout: PiHashLayout,
+    ) -> Self {
         assert_eq!(
             chunks_with_padding.len(),
             N_SNARKS,
@@ -72,6 +181,14 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
         // sanity checks
         // ========================
         // todo: return errors instead
+        if let Err(e) = check_pi_layouts(
+            chunks_with_padding,
+            data_availability,
+            &chunk_pi_layout,
+            &batch_pi_layout,
+        ) {
+            panic!("{e}");
+        }
         for i in 0..N_SNARKS - 1 {
             assert_eq!(
                 chunks_with_padding[i].chain_id,
@@ -98,6 +215,10 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
                     chunks_with_padding[i + 1].tx_bytes_hash(),
                     chunks_with_padding[i].tx_bytes_hash(),
                 );
+                assert_eq!(
+                    chunks_with_padding[i + 1].extra_public_inputs,
+                    chunks_with_padding[i].extra_public_inputs,
+                );
             } else {
                 assert_eq!(
                     chunks_with_padding[i].post_state_root,
@@ -117,47 +238,66 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
         let batch_data_hash = keccak256(preimage);
 
         let batch_data = BatchData::<N_SNARKS>::new(number_of_valid_chunks, chunks_with_padding);
-        let point_evaluation_assignments = PointEvaluationAssignments::from(&batch_data);
-        let versioned_hash = batch_data.get_versioned_hash();
+        let num_blobs = batch_data.num_blobs();
+        let point_evaluation_assignments = batch_data.get_point_evaluation_assignments();
+        let versioned_hashes = batch_data.get_versioned_hashes();
+        let calldata_hash: H256 = keccak256(batch_data.get_blob_payload()).into();
 
-        // public input hash is build as
+        // public input hash is build as, for the default layout,
         // keccak(
         //     chain_id ||
         //     chunk[0].prev_state_root ||
         //     chunk[k-1].post_state_root ||
         //     chunk[k-1].withdraw_root ||
         //     batch_data_hash ||
-        //     z ||
-        //     y ||
-        //     versioned_hash
+        //     z_0 || y_0 || versioned_hash_0 ||
+        //     ...
+        //     z_(n-1) || y_(n-1) || versioned_hash_(n-1)
         // )
-        let preimage = [
-            chunks_with_padding[0].chain_id.to_be_bytes().as_ref(),
-            chunks_with_padding[0].prev_state_root.as_bytes(),
-            chunks_with_padding[N_SNARKS - 1].post_state_root.as_bytes(),
-            chunks_with_padding[N_SNARKS - 1].withdraw_root.as_bytes(),
-            batch_data_hash.as_slice(),
-            point_evaluation_assignments
-                .challenge
-                .to_be_bytes()
-                .as_ref(),
-            point_evaluation_assignments
-                .evaluation
-                .to_be_bytes()
-                .as_ref(),
-            versioned_hash.as_bytes(),
-        ]
-        .concat();
+        //
+        // where n is the number of blobs used by the batch, and z, y and versioned_hash of each
+        // blob are replaced by calldata_hash for calldata DA
+        let padded_preimage = batch_pi_hash_preimage(
+            &batch_pi_layout,
+            chunks_with_padding[0].chain_id,
+            chunks_with_padding,
+            batch_data_hash.into(),
+            da_commitment_preimage(
+                data_availability,
+                &point_evaluation_assignments,
+                &versioned_hashes,
+                calldata_hash,
+            ),
+        );
+        let preimage = strip_unused_blobs(
+            &batch_pi_layout,
+            data_availability,
+            num_blobs,
+            padded_preimage,
+        );
         let public_input_hash: H256 = keccak256(preimage).into();
 
         log::info!(
-            "batch pi hash {:?}, datahash {}, z {}, y {}, versioned hash {:x}",
+            "batch pi hash {:?}, datahash {}, {:?} DA, {} blobs, calldata hash {:x}",
             public_input_hash,
             hex::encode(batch_data_hash),
-            hex::encode(point_evaluation_assignments.challenge.to_be_bytes()),
-            hex::encode(point_evaluation_assignments.evaluation.to_be_bytes()),
-            versioned_hash,
+            data_availability,
+            num_blobs,
+            calldata_hash,
         );
+        for (i, (point_evaluation_assignments, versioned_hash)) in point_evaluation_assignments
+            .iter()
+            .zip(versioned_hashes.iter())
+            .take(num_blobs)
+            .enumerate()
+        {
+            log::info!(
+                "blob {i}: z {}, y {}, versioned hash {:x}",
+                hex::encode(point_evaluation_assignments.challenge.to_be_bytes()),
+                hex::encode(point_evaluation_assignments.evaluation.to_be_bytes()),
+                versioned_hash,
+            );
+        }
 
         Self {
             chain_id: chunks_with_padding[0].chain_id,
@@ -165,18 +305,92 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
             data_hash: batch_data_hash.into(),
             public_input_hash,
             number_of_valid_chunks,
+            is_compressed: batch_data.is_compressed,
+            num_blobs,
             point_evaluation_assignments,
-            versioned_hash,
+            versioned_hashes,
+            data_availability,
+            calldata_hash,
+            chunk_pi_layout,
+            batch_pi_layout,
         }
     }
 
-    /// Return the blob polynomial and its evaluation at challenge
-    pub fn point_evaluation_assignments(&self) -> PointEvaluationAssignments {
+    /// Build Batch hash from an ordered list of #N_SNARKS of chunks, with the batch data made
+    /// available as specified and the chunk and batch public input hashes arranged as per the
+    /// given layouts, and check that the blob can be decoded by the zstd decoder circuit.
+    ///
+    /// Layouts that do not match the chunks or the data availability are reported as a
+    /// [`PiHashLayoutError`]. A zstd encoded blob that is malformed or makes use of an unsupported
+    /// zstd feature is reported as a [`DecodeError`], rather than failing later on during proof
+    /// generation. Note that the batch data is put in the blob uncompressed if it cannot be
+    /// decoded in the first place.
+    pub fn try_construct(
+        chunks_with_padding: &[ChunkHash],
+        data_availability: DataAvailability,
+        chunk_pi_layout: PiHashLayout,
+        batch_pi_layout: PiHashLayout,
+    ) -> Result<Self, BatchHashError> {
+        check_pi_layouts(
+            chunks_with_padding,
+            data_availability,
+            &chunk_pi_layout,
+            &batch_pi_layout,
+        )?;
+        let batch_hash = Self::construct_with_pi_layouts(
+            chunks_with_padding,
+            data_availability,
+            chunk_pi_layout,
+            batch_pi_layout,
+        );
+        let batch_data = BatchData::from(&batch_hash);
+        if batch_data.is_compressed {
+            batch_data.decode_encoded_batch_data_bytes()?;
+        }
+
+        Ok(batch_hash)
+    }
+
+    /// Return the blob polynomial and its evaluation at challenge, for each blob
+    pub fn point_evaluation_assignments(&self) -> Vec<PointEvaluationAssignments> {
         self.point_evaluation_assignments.clone()
     }
 
+    /// Return whether the batch data bytes are zstd encoded in the blob payload
+    pub fn is_compressed(&self) -> bool {
+        self.is_compressed
+    }
+
+    /// Return the number of blobs used by the batch
+    pub fn num_blobs(&self) -> usize {
+        self.num_blobs
+    }
+
+    /// Return how the batch data is made available
+    pub fn data_availability(&self) -> DataAvailability {
+        self.data_availability
+    }
+
+    /// Return the layout of the chunk public input hashes
+    pub fn chunk_pi_layout(&self) -> &PiHashLayout {
+        &self.chunk_pi_layout
+    }
+
+    /// Return the layout of the batch public input hash
+    pub fn batch_pi_layout(&self) -> &PiHashLayout {
+        &self.batch_pi_layout
+    }
+
+    /// Return the KZG commitment, versioned hash and KZG proof at challenge, for each blob used
+    /// by the batch
+    pub fn blob_kzg_data(
+        &self,
+        kzg_settings: &c_kzg::KzgSettings,
+    ) -> Result<Vec<BlobKzgData>, c_kzg::Error> {
+        BatchData::from(self).get_kzg_data(kzg_settings)
+    }
+
     /// Extract all the hash inputs that will ever be used.
-    /// There are N_SNARKS + 2 hashes.
     ///
     /// orders:
     /// - batch_public_input_hash
@@ -184,7 +398,8 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
     /// - batch_data_hash_preimage
     /// - preimage for blob metadata
     /// - chunk\[i\].flattened_l2_signed_data for i in \[0, N_SNARKS)
-    /// - preimage for challenge digest
+    /// - preimage for challenge digest, for each blob, for blob DA
+    /// - calldata, i.e. the blob payload, for calldata DA
     pub(crate) fn extract_hash_preimages(&self) -> Vec<Vec<u8>> {
         let mut res = vec![];
 
@@ -195,25 +410,20 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
         //      chunk[k-1].post_state_root ||
         //      chunk[k-1].withdraw_root ||
         //      batch_data_hash ||
-        //      z ||
-        //      y ||
-        //      blob_versioned_hash
+        //      z_0 || y_0 || blob_versioned_hash_0 ||
+        //      ...
+        //      z_(n-1) || y_(n-1) || blob_versioned_hash_(n-1)
         //  )
-        let batch_public_input_hash_preimage = [
-            self.chain_id.to_be_bytes().as_ref(),
-            self.chunks_with_padding[0].prev_state_root.as_bytes(),
-            self.chunks_with_padding[N_SNARKS - 1]
-                .post_state_root
-                .as_bytes(),
-            self.chunks_with_padding[N_SNARKS - 1]
-                .withdraw_root
-                .as_bytes(),
-            self.data_hash.as_bytes(),
-            &self.point_evaluation_assignments.challenge.to_be_bytes(),
-            &self.point_evaluation_assignments.evaluation.to_be_bytes(),
-            self.versioned_hash.as_bytes(),
-        ]
-        .concat();
+        //
+        // where n is the number of blobs used by the batch, z, y and blob_versioned_hash of each
+        // blob are replaced by calldata_hash for calldata DA, and the fields are arranged as per
+        // the batch pi hash layout
+        let batch_public_input_hash_preimage = strip_unused_blobs(
+            &self.batch_pi_layout,
+            self.data_availability,
+            self.num_blobs,
+            self.padded_batch_pi_hash_preimage(),
+        );
         res.push(batch_public_input_hash_preimage);
 
         // compute piHash for each chunk for i in [0..N_SNARKS)
@@ -226,8 +436,14 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
         //     chunk[i].datahash ||
         //     chunk[i].tx_data_hash
         // )
+        //
+        // arranged as per the chunk pi hash layout
         for chunk in self.chunks_with_padding.iter() {
-            res.push(chunk.extract_hash_preimage());
+            res.push(
+                chunk
+                    .extract_hash_preimage_with_layout(&self.chunk_pi_layout)
+                    .expect("chunk pi hash layout is checked when building the batch"),
+            );
         }
 
         // batchDataHash = keccak(chunk[0].dataHash || ... || chunk[k-1].dataHash)
@@ -246,16 +462,38 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
         // These include:
         // - preimage for batch metadata
         // - preimage for each chunk's flattened L2 signed tx data
-        // - preimage for the challenge digest
+        // - preimage for the challenge digest of each blob, for blob DA
+        // - calldata, for calldata DA
         let batch_data = BatchData::from(self);
-        let dynamic_preimages = batch_data.preimages();
+        let dynamic_preimages = batch_data.preimages(self.data_availability);
         for dynamic_preimage in dynamic_preimages {
             res.push(dynamic_preimage);
         }
+        if self.data_availability == DataAvailability::Calldata {
+            res.push(batch_data.get_blob_payload());
+        }
 
         res
     }
 
+    /// The batch public input hash preimage as laid out in the circuit, i.e. with the DA
+    /// commitment of all the MAX_BLOBS_PER_BATCH blobs for blob DA. The bytes committing to the
+    /// unused blobs are not part of the keccak preimage.
+    pub(crate) fn padded_batch_pi_hash_preimage(&self) -> Vec<u8> {
+        batch_pi_hash_preimage(
+            &self.batch_pi_layout,
+            self.chain_id,
+            &self.chunks_with_padding,
+            self.data_hash,
+            da_commitment_preimage(
+                self.data_availability,
+                &self.point_evaluation_assignments,
+                &self.versioned_hashes,
+                self.calldata_hash,
+            ),
+        )
+    }
+
     /// Compute the public inputs for this circuit, excluding the accumulator.
     /// Content: the public_input_hash
     pub(crate) fn instances_exclude_acc<F: Field>(&self) -> Vec<Vec<F>> {
@@ -267,3 +505,173 @@ impl<const N_SNARKS: usize> BatchHash<N_SNARKS> {
             .collect()]
     }
 }
+
+/// Check that the chunk and batch public input hash layouts match the chunks and the data
+/// availability:
+/// - the DA commitment of the batch has the length of the data availability
+/// - the extra fields of the batch are those of the chunks with the same indices
+/// - the extra public inputs of each chunk match the extra fields of the chunk layout
+fn check_pi_layouts(
+    chunks_with_padding: &[ChunkHash],
+    data_availability: DataAvailability,
+    chunk_pi_layout: &PiHashLayout,
+    batch_pi_layout: &PiHashLayout,
+) -> Result<(), PiHashLayoutError> {
+    let da_commitment = batch_pi_layout.range(PiField::DaCommitment)?;
+    if da_commitment.len() != da_commitment_len(data_availability) {
+        return Err(PiHashLayoutError::DaCommitmentMismatch {
+            data_availability,
+            len: da_commitment.len(),
+        });
+    }
+
+    if !chunk_pi_layout
+        .extra_lens()
+        .starts_with(batch_pi_layout.extra_lens().as_slice())
+    {
+        return Err(PiHashLayoutError::BatchExtraFieldsMismatch);
+    }
+
+    let chunk_extra_lens = chunk_pi_layout.extra_lens();
+    for chunk in chunks_with_padding {
+        let extra_values_lens = chunk
+            .extra_public_inputs
+            .iter()
+            .map(Vec::len)
+            .collect::<Vec<_>>();
+        if extra_values_lens != chunk_extra_lens {
+            return Err(PiHashLayoutError::ExtraValuesMismatch {
+                expected: chunk_extra_lens,
+                actual: extra_values_lens,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The batch public input hash preimage, arranged as per the layout. The prev state root is that
+/// of the first chunk, the post state root, the withdraw root and the extra fields are those of
+/// the last chunk.
+fn batch_pi_hash_preimage(
+    layout: &PiHashLayout,
+    chain_id: u64,
+    chunks_with_padding: &[ChunkHash],
+    batch_data_hash: H256,
+    da_commitment: Vec<u8>,
+) -> Vec<u8> {
+    let first_chunk = &chunks_with_padding[0];
+    let last_chunk = &chunks_with_padding[chunks_with_padding.len() - 1];
+    layout
+        .build_preimage(
+            |field| match field {
+                PiField::ChainId => Some(chain_id.to_be_bytes().to_vec()),
+                PiField::PrevStateRoot => {
+                    Some(first_chunk.prev_state_root.to_fixed_bytes().to_vec())
+                }
+                PiField::PostStateRoot => {
+                    Some(last_chunk.post_state_root.to_fixed_bytes().to_vec())
+                }
+                PiField::WithdrawRoot => Some(last_chunk.withdraw_root.to_fixed_bytes().to_vec()),
+                PiField::DataHash => Some(batch_data_hash.to_fixed_bytes().to_vec()),
+                PiField::DaCommitment => Some(da_commitment.clone()),
+                PiField::TxDataHash | PiField::Extra { .. } => None,
+            },
+            last_chunk
+                .extra_public_inputs
+                .get(..layout.extra_ranges().len())
+                .unwrap_or_default(),
+        )
+        .expect("batch pi hash layout is checked when building the batch")
+}
+
+/// The part of the padded batch public input hash preimage that commits to the batch data:
+/// - z || y || versioned_hash, for each of the MAX_BLOBS_PER_BATCH blobs, for blob DA
+/// - calldata_hash, for calldata DA
+fn da_commitment_preimage(
+    data_availability: DataAvailability,
+    point_evaluation_assignments: &[PointEvaluationAssignments],
+    versioned_hashes: &[H256],
+    calldata_hash: H256,
+) -> Vec<u8> {
+    match data_availability {
+        DataAvailability::Blob => blobs_preimage(point_evaluation_assignments, versioned_hashes),
+        DataAvailability::Calldata => calldata_hash.to_fixed_bytes().to_vec(),
+    }
+}
+
+/// The part of the padded batch public input hash preimage that commits to the blobs:
+/// - z || y || versioned_hash, for each of the MAX_BLOBS_PER_BATCH blobs
+fn blobs_preimage(
+    point_evaluation_assignments: &[PointEvaluationAssignments],
+    versioned_hashes: &[H256],
+) -> Vec<u8> {
+    point_evaluation_assignments
+        .iter()
+        .zip_eq(versioned_hashes.iter())
+        .flat_map(|(point_evaluation_assignments, versioned_hash)| {
+            [
+                point_evaluation_assignments.challenge.to_be_bytes(),
+                point_evaluation_assignments.evaluation.to_be_bytes(),
+                versioned_hash.to_fixed_bytes(),
+            ]
+            .concat()
+        })
+        .collect()
+}
+
+/// Remove the bytes committing to the blobs that are not used by the batch from the padded batch
+/// public input hash preimage, for blob DA.
+fn strip_unused_blobs(
+    layout: &PiHashLayout,
+    data_availability: DataAvailability,
+    num_blobs: usize,
+    mut padded_preimage: Vec<u8>,
+) -> Vec<u8> {
+    if data_availability == DataAvailability::Blob {
+        let unused_blobs_start = layout
+            .range(PiField::DaCommitment)
+            .expect("batch pi hash layout contains the DA commitment")
+            .start
+            + num_blobs * BATCH_BLOB_LEN;
+        padded_preimage.drain(
+            unused_blobs_start
+                ..unused_blobs_start + (MAX_BLOBS_PER_BATCH - num_blobs) * BATCH_BLOB_LEN,
+        );
+    }
+    padded_preimage
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::blob::N_BLOB_BYTES;
+
+    #[test]
+    fn batch_pi_hash_commits_to_used_blobs() {
+        let mut rng = test_rng();
+        let mut chunk = ChunkHash::mock_random_chunk_hash_for_testing(&mut rng);
+        // incompressible tx bytes that do not fit in a single blob.
+        chunk.tx_bytes = (0..N_BLOB_BYTES + N_BLOB_BYTES / 2)
+            .map(|_| rng.gen())
+            .collect();
+        let padded_chunk = ChunkHash::mock_padded_chunk_hash_for_testing(&chunk);
+        let batch_hash = BatchHash::<2>::construct(&[chunk, padded_chunk]);
+        assert_eq!(batch_hash.num_blobs(), 2);
+
+        let preimage = &batch_hash.extract_hash_preimages()[0];
+        assert_eq!(preimage.len(), 136 + BATCH_BLOB_LEN * 2);
+        assert_eq!(H256(keccak256(preimage)), batch_hash.public_input_hash);
+
+        // the padded preimage only has the extra bytes committing to the unused blobs.
+        let padded_preimage = batch_hash.padded_batch_pi_hash_preimage();
+        assert_eq!(
+            padded_preimage.len(),
+            136 + BATCH_BLOB_LEN * MAX_BLOBS_PER_BATCH
+        );
+        assert_eq!(padded_preimage[..preimage.len()], preimage[..]);
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{
-    aggregation::{interpolate, witgen::init_zstd_encoder, BLS_MODULUS},
-    BatchHash, ChunkHash,
+    aggregation::{
+        interpolate,
+        witgen::{init_zstd_encoder, process, DecodeError},
+        BLS_MODULUS,
+    },
+    BatchHash, ChunkHash, DataAvailability,
 };
 
 use eth_types::{ToBigEndian, H256, U256};
@@ -22,6 +26,13 @@ use std::{
 };
 use zkevm_circuits::util::Challenges;
 
+/// Decoding of blobs back into the batch data.
+mod decode;
+/// KZG commitments and proofs for blobs.
+mod kzg;
+pub use decode::BlobDecodeError;
+pub use kzg::{load_kzg_settings, BlobKzgData};
+
 /// The number of coefficients (BLS12-381 scalars) to represent the blob polynomial in evaluation
 /// form.
 pub const BLOB_WIDTH: usize = 4096;
@@ -41,8 +52,18 @@ pub const N_ROWS_NUM_CHUNKS: usize = 2;
 /// we explicitly set the most-significant byte to 0, effectively utilising only 31 bytes.
 pub const N_BLOB_BYTES: usize = BLOB_WIDTH * N_DATA_BYTES_PER_COEFFICIENT;
 
-/// Allow up to 5x compression via zstd encoding of the batch data.
-pub const N_BATCH_BYTES: usize = N_BLOB_BYTES * 5;
+/// The maximum number of blobs a batch may span. The zstd encoded batch data is packed into the
+/// blobs in order, and only the blobs required to hold the encoded data are committed to.
+pub const MAX_BLOBS_PER_BATCH: usize = 6;
+
+/// The maximum number of batch data bytes. The blob payload, i.e. the is_compressed flag followed
+/// by at most as many bytes as the batch data, hence always fits in MAX_BLOBS_PER_BATCH blobs.
+pub const N_BATCH_BYTES: usize = N_BLOB_BYTES * (MAX_BLOBS_PER_BATCH - 1);
+
+const _: () = assert!(
+    1 + N_BATCH_BYTES <= MAX_BLOBS_PER_BATCH * N_BLOB_BYTES,
+    "the blob payload must fit in MAX_BLOBS_PER_BATCH blobs"
+);
 
 /// KZG trusted setup
 pub static KZG_TRUSTED_SETUP: Lazy<Arc<c_kzg::KzgSettings>> = Lazy::new(|| {
@@ -56,7 +77,7 @@ pub static KZG_TRUSTED_SETUP: Lazy<Arc<c_kzg::KzgSettings>> = Lazy::new(|| {
 });
 
 /// Helper struct to generate witness for the Batch Data Config.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BatchData<const N_SNARKS: usize> {
     /// The number of valid chunks in the batch. This could be any number between:
     /// [1, N_SNARKS]
@@ -72,13 +93,18 @@ pub struct BatchData<const N_SNARKS: usize> {
     /// copied over for the padded chunks. The `chunk_data_digest` for padded chunks is the
     /// `chunk_data_digest` of the last valid chunk (from Aggregation Circuit's perspective).
     pub chunk_data: [Vec<u8>; N_SNARKS],
+    /// Whether the batch data bytes are zstd encoded in the blob payload. The batch data bytes
+    /// are put in the blob as-is if zstd encoding does not make them any smaller, or if the
+    /// encoded bytes cannot be decoded by the zstd decoder circuit.
+    pub is_compressed: bool,
 }
 
 impl<const N_SNARKS: usize> From<&BatchHash<N_SNARKS>> for BatchData<N_SNARKS> {
     fn from(batch_hash: &BatchHash<N_SNARKS>) -> Self {
-        Self::new(
+        Self::with_compression(
             batch_hash.number_of_valid_chunks,
             &batch_hash.chunks_with_padding,
+            batch_hash.is_compressed,
         )
     }
 }
@@ -111,11 +137,15 @@ impl<const N_SNARKS: usize> From<&Vec<Vec<u8>>> for BatchData<N_SNARKS> {
             .try_into()
             .expect("we have N_SNARKS chunks");
 
-        Self {
+        let mut batch_data = Self {
             num_valid_chunks: num_valid_chunks.try_into().unwrap(),
             chunk_sizes,
             chunk_data,
-        }
+            is_compressed: false,
+        };
+        batch_data.is_compressed = should_compress(&batch_data.get_batch_data_bytes());
+
+        batch_data
     }
 }
 
@@ -126,6 +156,33 @@ impl<const N_SNARKS: usize> Default for BatchData<N_SNARKS> {
     }
 }
 
+/// Encode the batch data bytes with zstd, as expected by the zstd decoder circuit.
+fn encode_batch_data_bytes(batch_data_bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = init_zstd_encoder(None);
+    encoder
+        .set_pledged_src_size(Some(batch_data_bytes.len() as u64))
+        .expect("infallible");
+    encoder.write_all(batch_data_bytes).expect("infallible");
+    encoder.finish().expect("infallible")
+}
+
+/// Whether the batch data bytes should be zstd encoded in the blob payload, i.e. zstd encoding
+/// makes them smaller and the encoded bytes can be decoded by the zstd decoder circuit.
+fn should_compress(batch_data_bytes: &[u8]) -> bool {
+    let encoded_bytes = encode_batch_data_bytes(batch_data_bytes);
+    if encoded_bytes.len() >= batch_data_bytes.len() {
+        return false;
+    }
+
+    match process::<Fr>(&encoded_bytes, Value::unknown()) {
+        Ok(_) => true,
+        Err(e) => {
+            log::warn!("batch data bytes not compressed, zstd decoding failed: {e}");
+            false
+        }
+    }
+}
+
 fn kzg_to_versioned_hash(commitment: &c_kzg::KzgCommitment) -> H256 {
     let mut res = Sha256::digest(commitment.as_slice());
     res[0] = VERSIONED_HASH_VERSION_KZG;
@@ -136,10 +193,10 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
     /// The number of rows in Blob Data config's layout to represent the "digest rlc" section.
     /// - metadata digest RLC (1 row)
     /// - chunk_digests RLC for each chunk (MAX_AGG_SNARKS rows)
-    /// - blob versioned hash RLC (1 row)
-    /// - challenge digest RLC (1 row)
+    /// - blob versioned hash RLC for each blob (MAX_BLOBS_PER_BATCH rows)
+    /// - challenge digest RLC for each blob (MAX_BLOBS_PER_BATCH rows)
     pub const fn n_rows_digest_rlc() -> usize {
-        1 + N_SNARKS + 1 + 1
+        1 + N_SNARKS + MAX_BLOBS_PER_BATCH + MAX_BLOBS_PER_BATCH
     }
 
     /// The number of rows in Blob Data config's layout to represent the "digest bytes" section.
@@ -174,6 +231,19 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
     }
 
     pub(crate) fn new(num_valid_chunks: usize, chunks_with_padding: &[ChunkHash]) -> Self {
+        let mut batch_data = Self::with_compression(num_valid_chunks, chunks_with_padding, false);
+        batch_data.is_compressed = should_compress(&batch_data.get_batch_data_bytes());
+
+        batch_data
+    }
+
+    /// Build the batch data with the given is_compressed flag, i.e. as already decided for the
+    /// batch, rather than encoding and decoding the batch data bytes again to decide it.
+    pub(crate) fn with_compression(
+        num_valid_chunks: usize,
+        chunks_with_padding: &[ChunkHash],
+        is_compressed: bool,
+    ) -> Self {
         assert!(num_valid_chunks > 0);
         assert!(num_valid_chunks <= N_SNARKS);
 
@@ -207,30 +277,57 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
             num_valid_chunks: num_valid_chunks as u16,
             chunk_sizes,
             chunk_data,
+            is_compressed,
         }
     }
 
-    /// Get the versioned hash as per EIP-4844.
-    pub(crate) fn get_versioned_hash(&self) -> H256 {
-        let coefficients = self.get_coefficients();
-        let blob = c_kzg::Blob::from_bytes(
-            &coefficients
-                .iter()
-                .cloned()
-                .flat_map(|coeff| coeff.to_be_bytes())
-                .collect::<Vec<_>>(),
-        )
-        .expect("blob-coefficients to 4844 blob should succeed");
-        let c = c_kzg::KzgCommitment::blob_to_kzg_commitment(&blob, &KZG_TRUSTED_SETUP)
-            .expect("blob to kzg commitment should succeed");
-        kzg_to_versioned_hash(&c)
+    /// The number of blobs required to hold the blob payload, between 1 and MAX_BLOBS_PER_BATCH.
+    pub fn num_blobs(&self) -> usize {
+        self.get_blob_payload().len().div_ceil(N_BLOB_BYTES)
+    }
+
+    /// Get the versioned hash of each blob as per EIP-4844. The versioned hash of a blob that is
+    /// not used by the batch is zero, as BLOBHASH returns for a blob index beyond those of the
+    /// transaction.
+    pub(crate) fn get_versioned_hashes(&self) -> Vec<H256> {
+        let num_blobs = self.num_blobs();
+        self.get_blob_bytes()
+            .iter()
+            .enumerate()
+            .map(|(i, blob_bytes)| {
+                if i >= num_blobs {
+                    return H256::zero();
+                }
+                let blob = c_kzg::Blob::from_bytes(blob_bytes)
+                    .expect("blob-coefficients to 4844 blob should succeed");
+                let c = c_kzg::KzgCommitment::blob_to_kzg_commitment(&blob, &KZG_TRUSTED_SETUP)
+                    .expect("blob to kzg commitment should succeed");
+                kzg_to_versioned_hash(&c)
+            })
+            .collect()
+    }
+
+    /// Get the preimage of the challenge digest of each blob.
+    pub(crate) fn get_challenge_digest_preimages(&self) -> Vec<Vec<u8>> {
+        self.challenge_digest_preimages(&self.get_versioned_hashes())
+    }
+
+    /// Compute the challenge digest of each blob from blob bytes.
+    pub(crate) fn get_challenge_digests(&self) -> Vec<U256> {
+        self.get_challenge_digest_preimages()
+            .iter()
+            .map(|preimage| U256::from_big_endian(&keccak256(preimage)))
+            .collect()
     }
 
-    /// Get the preimage of the challenge digest.
-    pub(crate) fn get_challenge_digest_preimage(&self) -> Vec<u8> {
+    /// Get the preimage of the challenge digest of each blob, given the blobs' versioned hashes.
+    fn challenge_digest_preimages(&self, versioned_hashes: &[H256]) -> Vec<Vec<u8>> {
         let metadata_digest = keccak256(self.to_metadata_bytes());
-        let chunk_digests = self.chunk_data.iter().map(keccak256);
-        let blob_versioned_hash = self.get_versioned_hash();
+        let chunk_digests = self
+            .chunk_data
+            .iter()
+            .flat_map(keccak256)
+            .collect::<Vec<_>>();
 
         // preimage =
         //     metadata_digest ||
@@ -239,18 +336,18 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
         //     blob_versioned_hash
         //
         // where chunk_data_digest for a padded chunk is set equal to the "last valid chunk"'s
-        // chunk_data_digest.
-        metadata_digest
-            .into_iter()
-            .chain(chunk_digests.flatten())
-            .chain(blob_versioned_hash.to_fixed_bytes())
-            .collect::<Vec<_>>()
-    }
-
-    /// Compute the challenge digest from blob bytes.
-    pub(crate) fn get_challenge_digest(&self) -> U256 {
-        let challenge_digest = keccak256(self.get_challenge_digest_preimage());
-        U256::from_big_endian(&challenge_digest)
+        // chunk_data_digest, and blob_versioned_hash is the versioned hash of the blob the
+        // challenge is computed for.
+        versioned_hashes
+            .iter()
+            .map(|blob_versioned_hash| {
+                metadata_digest
+                    .into_iter()
+                    .chain(chunk_digests.iter().cloned())
+                    .chain(blob_versioned_hash.to_fixed_bytes())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
     }
 
     /// Get the batch data bytes that will be populated in BatchDataConfig.
@@ -270,38 +367,92 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
 
     /// Get the zstd encoded batch data bytes.
     pub(crate) fn get_encoded_batch_data_bytes(&self) -> Vec<u8> {
-        let batch_data_bytes = self.get_batch_data_bytes();
-        let mut encoder = init_zstd_encoder(None);
-        encoder
-            .set_pledged_src_size(Some(batch_data_bytes.len() as u64))
-            .expect("infallible");
-        encoder.write_all(&batch_data_bytes).expect("infallible");
-        encoder.finish().expect("infallible")
+        encode_batch_data_bytes(&self.get_batch_data_bytes())
+    }
+
+    /// Get the blob payload, i.e. the bytes packed into the blobs:
+    ///
+    /// is_compressed || zstd_encode(batch_data_bytes), if the batch data is compressed
+    /// is_compressed || batch_data_bytes, otherwise
+    ///
+    /// where is_compressed is a single byte, either 1 or 0. This is also the calldata if the batch
+    /// data is made available via calldata instead of blobs.
+    pub fn get_blob_payload(&self) -> Vec<u8> {
+        let payload = if self.is_compressed {
+            self.get_encoded_batch_data_bytes()
+        } else {
+            self.get_batch_data_bytes()
+        };
+
+        once(self.is_compressed as u8).chain(payload).collect()
+    }
+
+    /// Decode the zstd encoded batch data bytes as the zstd decoder circuit would, returning the
+    /// recovered batch data bytes.
+    pub(crate) fn decode_encoded_batch_data_bytes(&self) -> Result<Vec<u8>, DecodeError> {
+        let encoded_batch_data_bytes = self.get_encoded_batch_data_bytes();
+        let result = process::<Fr>(&encoded_batch_data_bytes, Value::unknown())?;
+
+        Ok(result
+            .sequence_exec_results
+            .into_iter()
+            .flat_map(|res| res.recovered_bytes)
+            .collect())
     }
 
-    /// Get the BLOB_WIDTH number of scalar field elements, as 32-bytes unsigned integers.
-    pub(crate) fn get_coefficients(&self) -> [U256; BLOB_WIDTH] {
-        let mut coefficients = [[0u8; N_BYTES_U256]; BLOB_WIDTH];
+    /// Get the BLOB_WIDTH number of scalar field elements, as 32-bytes unsigned integers, for each
+    /// of the MAX_BLOBS_PER_BATCH blobs.
+    pub(crate) fn get_coefficients(&self) -> Vec<[U256; BLOB_WIDTH]> {
+        let mut coefficients = vec![[[0u8; N_BYTES_U256]; BLOB_WIDTH]; MAX_BLOBS_PER_BATCH];
 
         // We only consider the data from `valid` chunks and ignore the padded chunks.
-        let blob_bytes = self.get_encoded_batch_data_bytes();
+        let blob_bytes = self.get_blob_payload();
         assert!(
-            blob_bytes.len() < N_BLOB_BYTES,
+            blob_bytes.len() < MAX_BLOBS_PER_BATCH * N_BLOB_BYTES,
             "too many bytes in batch data"
         );
 
         for (i, &byte) in blob_bytes.iter().enumerate() {
-            coefficients[i / 31][1 + (i % 31)] = byte;
+            let (blob_idx, j) = (i / N_BLOB_BYTES, i % N_BLOB_BYTES);
+            coefficients[blob_idx][j / 31][1 + (j % 31)] = byte;
         }
 
-        coefficients.map(|coeff| U256::from_big_endian(&coeff))
+        coefficients
+            .into_iter()
+            .map(|blob| blob.map(|coeff| U256::from_big_endian(&coeff)))
+            .collect()
+    }
+
+    /// Get the raw bytes of each of the MAX_BLOBS_PER_BATCH blobs, i.e. the big-endian bytes of
+    /// the blob's BLOB_WIDTH coefficients.
+    pub fn get_blob_bytes(&self) -> Vec<Vec<u8>> {
+        self.get_coefficients()
+            .iter()
+            .map(|coefficients| {
+                coefficients
+                    .iter()
+                    .flat_map(|coeff| coeff.to_be_bytes())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Get the point evaluation assignments for each of the MAX_BLOBS_PER_BATCH blobs.
+    pub(crate) fn get_point_evaluation_assignments(&self) -> Vec<PointEvaluationAssignments> {
+        self.get_coefficients()
+            .into_iter()
+            .zip_eq(self.get_challenge_digests())
+            .map(|(coefficients, challenge_digest)| {
+                PointEvaluationAssignments::new(coefficients, challenge_digest)
+            })
+            .collect()
     }
 
     /// Get the list of preimages that need to go through the keccak hashing function, and
     /// eventually required to be checked for the consistency of blob's metadata, its chunks' bytes
-    /// and the final blob preimage.
-    pub fn preimages(&self) -> Vec<Vec<u8>> {
-        let mut preimages = Vec::with_capacity(2 + N_SNARKS);
+    /// and the final blob preimage. The challenge digests are only hashed for blob DA.
+    pub fn preimages(&self, data_availability: DataAvailability) -> Vec<Vec<u8>> {
+        let mut preimages = Vec::with_capacity(1 + N_SNARKS + MAX_BLOBS_PER_BATCH);
 
         // metadata
         preimages.push(self.to_metadata_bytes());
@@ -311,21 +462,27 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
             preimages.push(chunk.to_vec());
         }
 
-        // preimage for challenge digest
-        preimages.push(self.get_challenge_digest_preimage());
+        // preimage for each blob's challenge digest
+        if data_availability == DataAvailability::Blob {
+            preimages.extend(self.get_challenge_digest_preimages());
+        }
 
         preimages
     }
 
     /// Get the witness rows for assignment to the BlobDataConfig.
-    pub(crate) fn to_rows(&self, challenge: Challenges<Value<Fr>>) -> Vec<BatchDataRow<Fr>> {
+    pub(crate) fn to_rows(
+        &self,
+        challenge: Challenges<Value<Fr>>,
+        data_availability: DataAvailability,
+    ) -> Vec<BatchDataRow<Fr>> {
         let metadata_rows = self.to_metadata_rows(challenge);
         assert_eq!(metadata_rows.len(), Self::n_rows_metadata());
 
         let data_rows = self.to_data_rows(challenge);
         assert_eq!(data_rows.len(), Self::n_rows_data());
 
-        let digest_rows = self.to_digest_rows(challenge);
+        let digest_rows = self.to_digest_rows(challenge, data_availability);
         assert_eq!(digest_rows.len(), Self::n_rows_digest());
 
         metadata_rows
@@ -462,7 +619,11 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
     }
 
     /// Get the witness rows for both "digest rlc" and "digest bytes" sections of Blob data config.
-    fn to_digest_rows(&self, challenge: Challenges<Value<Fr>>) -> Vec<BatchDataRow<Fr>> {
+    fn to_digest_rows(
+        &self,
+        challenge: Challenges<Value<Fr>>,
+        data_availability: DataAvailability,
+    ) -> Vec<BatchDataRow<Fr>> {
         let zero = Value::known(Fr::zero());
 
         // metadata
@@ -488,31 +649,50 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
             })
             .unzip();
 
-        // challenge digest
-        let challenge_digest_preimage = self.get_challenge_digest_preimage();
-        let challenge_digest_preimage_rlc =
-            challenge_digest_preimage.iter().fold(zero, |acc, &byte| {
-                acc * challenge.keccak_input() + Value::known(Fr::from(byte as u64))
-            });
-        let challenge_digest = keccak256(&challenge_digest_preimage);
-        let challenge_digest_rlc = challenge_digest.iter().fold(zero, |acc, &byte| {
-            acc * challenge.evm_word() + Value::known(Fr::from(byte as u64))
-        });
+        // blob versioned hashes
+        let versioned_hashes = self.get_versioned_hashes();
+        let versioned_hash_rlcs = versioned_hashes
+            .iter()
+            .map(|versioned_hash| {
+                versioned_hash.as_bytes().iter().fold(zero, |acc, &byte| {
+                    acc * challenge.evm_word() + Value::known(Fr::from(byte as u64))
+                })
+            })
+            .collect::<Vec<_>>();
 
-        // blob versioned hash
-        let versioned_hash = self.get_versioned_hash();
-        let versioned_hash_rlc = versioned_hash.as_bytes().iter().fold(zero, |acc, &byte| {
-            acc * challenge.evm_word() + Value::known(Fr::from(byte as u64))
-        });
+        // challenge digests
+        let (challenge_digests, challenge_digest_rows): (Vec<[u8; 32]>, Vec<BatchDataRow<Fr>>) =
+            self.challenge_digest_preimages(&versioned_hashes)
+                .iter()
+                .map(|preimage| {
+                    let preimage_rlc = preimage.iter().fold(zero, |acc, &byte| {
+                        acc * challenge.keccak_input() + Value::known(Fr::from(byte as u64))
+                    });
+                    let digest = keccak256(preimage);
+                    let digest_rlc = digest.iter().fold(zero, |acc, &byte| {
+                        acc * challenge.evm_word() + Value::known(Fr::from(byte as u64))
+                    });
+                    let row = BatchDataRow {
+                        preimage_rlc,
+                        digest_rlc,
+                        accumulator: 32 * (N_SNARKS + 1 + 1) as u64,
+                        // the challenge digests are only looked up in the keccak table for
+                        // blob DA.
+                        is_boundary: data_availability == DataAvailability::Blob,
+                        ..Default::default()
+                    };
+                    (digest, row)
+                })
+                .unzip();
 
         // - metadata digest rlc
         // - chunks[i].chunk_data_digest rlc for each chunk
-        // - versioned hash rlc
-        // - challenge digest rlc
+        // - versioned hash rlc for each blob
+        // - challenge digest rlc for each blob
         // - metadata digest bytes
         // - chunks[i].chunk_data_digest bytes for each chunk
-        // - versioned hash bytes
-        // - challenge digest bytes
+        // - versioned hash bytes for each blob
+        // - challenge digest bytes for each blob
         once(BatchDataRow {
             preimage_rlc: Value::known(Fr::zero()),
             digest_rlc: metadata_digest_rlc,
@@ -535,19 +715,17 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
                     ..Default::default()
                 }),
         )
-        // versioned hash RLC
-        .chain(once(BatchDataRow {
-            preimage_rlc: Value::known(Fr::zero()),
-            digest_rlc: versioned_hash_rlc,
-            ..Default::default()
-        }))
-        .chain(once(BatchDataRow {
-            preimage_rlc: challenge_digest_preimage_rlc,
-            digest_rlc: challenge_digest_rlc,
-            accumulator: 32 * (N_SNARKS + 1 + 1) as u64,
-            is_boundary: true,
-            ..Default::default()
-        }))
+        // versioned hash RLCs
+        .chain(
+            versioned_hash_rlcs
+                .into_iter()
+                .map(|digest_rlc| BatchDataRow {
+                    preimage_rlc: Value::known(Fr::zero()),
+                    digest_rlc,
+                    ..Default::default()
+                }),
+        )
+        .chain(challenge_digest_rows)
         .chain(metadata_digest.iter().map(|&byte| BatchDataRow {
             preimage_rlc: Value::known(Fr::zero()),
             digest_rlc: Value::known(Fr::zero()),
@@ -562,23 +740,28 @@ impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
                 ..Default::default()
             })
         }))
-        // bytes of versioned hash
-        .chain(versioned_hash.as_bytes().iter().map(|&byte| BatchDataRow {
-            preimage_rlc: Value::known(Fr::zero()),
-            digest_rlc: Value::known(Fr::zero()),
-            byte,
-            ..Default::default()
+        // bytes of versioned hashes
+        .chain(versioned_hashes.iter().flat_map(|versioned_hash| {
+            versioned_hash.as_bytes().iter().map(|&byte| BatchDataRow {
+                preimage_rlc: Value::known(Fr::zero()),
+                digest_rlc: Value::known(Fr::zero()),
+                byte,
+                ..Default::default()
+            })
         }))
-        .chain(challenge_digest.iter().map(|&byte| BatchDataRow {
-            preimage_rlc: Value::known(Fr::zero()),
-            digest_rlc: Value::known(Fr::zero()),
-            byte,
-            ..Default::default()
+        .chain(challenge_digests.iter().flat_map(|digest| {
+            digest.iter().map(|&byte| BatchDataRow {
+                preimage_rlc: Value::known(Fr::zero()),
+                digest_rlc: Value::known(Fr::zero()),
+                byte,
+                ..Default::default()
+            })
         }))
         .collect()
     }
 }
 
+/// The point evaluation of a single blob.
 #[derive(Clone, Debug)]
 pub struct PointEvaluationAssignments {
     /// The random challenge scalar z.
@@ -603,18 +786,18 @@ impl Default for PointEvaluationAssignments {
     }
 }
 
-impl<const N_SNARKS: usize> From<&BatchData<N_SNARKS>> for PointEvaluationAssignments {
-    fn from(batch_data: &BatchData<N_SNARKS>) -> Self {
+impl PointEvaluationAssignments {
+    /// Evaluate the blob polynomial, given in evaluation form by its coefficients, at the
+    /// challenge derived from the challenge digest.
+    pub fn new(coefficients: [U256; BLOB_WIDTH], challenge_digest: U256) -> Self {
         // blob polynomial in evaluation form.
         //
         // also termed P(x)
-        let coefficients = batch_data.get_coefficients();
         let coefficients_as_scalars = coefficients.map(|coeff| Scalar::from_raw(coeff.0));
 
         // challenge := challenge_digest % BLS_MODULUS
         //
         // also termed z
-        let challenge_digest = batch_data.get_challenge_digest();
         let (_, challenge) = challenge_digest.div_mod(*BLS_MODULUS);
 
         // y = P(z)
@@ -727,15 +910,21 @@ mod tests {
         .iter()
         {
             let batch_data: BatchData<MAX_AGG_SNARKS> = tcase.into();
-            let point_evaluation_assignments = PointEvaluationAssignments::from(&batch_data);
-            let versioned_hash = batch_data.get_versioned_hash();
-            println!(
-                "[[ {:60} ]]\nchallenge (z) = {:0>64x}, evaluation (y) = {:0>64x}, versioned hash = {:0>64x}\n\n",
-                annotation,
-                point_evaluation_assignments.challenge,
-                point_evaluation_assignments.evaluation,
-                versioned_hash,
-            );
+            let point_evaluation_assignments = batch_data.get_point_evaluation_assignments();
+            let versioned_hashes = batch_data.get_versioned_hashes();
+            println!("[[ {:60} ]]", annotation);
+            for (point_evaluation_assignments, versioned_hash) in point_evaluation_assignments
+                .iter()
+                .zip_eq(versioned_hashes.iter())
+            {
+                println!(
+                    "challenge (z) = {:0>64x}, evaluation (y) = {:0>64x}, versioned hash = {:0>64x}",
+                    point_evaluation_assignments.challenge,
+                    point_evaluation_assignments.evaluation,
+                    versioned_hash,
+                );
+            }
+            println!("\n");
         }
     }
 
@@ -747,16 +936,95 @@ mod tests {
         let default_chunk_digests = [keccak256([]); MAX_AGG_SNARKS];
 
         let default_batch = BatchData::<MAX_AGG_SNARKS>::default();
-        let versioned_hash = default_batch.get_versioned_hash();
+        let versioned_hashes = default_batch.get_versioned_hashes();
+        assert_eq!(versioned_hashes.len(), MAX_BLOBS_PER_BATCH);
         assert_eq!(
-            default_batch.get_challenge_digest(),
-            U256::from(keccak256(
-                default_metadata_digest
-                    .into_iter()
-                    .chain(default_chunk_digests.into_iter().flatten())
-                    .chain(versioned_hash.to_fixed_bytes())
-                    .collect::<Vec<u8>>()
-            )),
+            default_batch.get_challenge_digests(),
+            versioned_hashes
+                .iter()
+                .map(|versioned_hash| U256::from(keccak256(
+                    default_metadata_digest
+                        .into_iter()
+                        .chain(default_chunk_digests.into_iter().flatten())
+                        .chain(versioned_hash.to_fixed_bytes())
+                        .collect::<Vec<u8>>()
+                )))
+                .collect::<Vec<_>>(),
         )
     }
+
+    #[test]
+    fn batch_data_spanning_multiple_blobs() {
+        // incompressible bytes that do not fit in a single blob.
+        let chunk = (0..N_BLOB_BYTES + N_BLOB_BYTES / 2)
+            .scan(0x9e3779b9u32, |state, _| {
+                *state ^= *state << 13;
+                *state ^= *state >> 17;
+                *state ^= *state << 5;
+                Some(*state as u8)
+            })
+            .collect::<Vec<u8>>();
+        let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&vec![chunk]);
+        let payload_len = batch_data.get_blob_payload().len();
+        assert!(payload_len > N_BLOB_BYTES);
+        assert!(payload_len <= 2 * N_BLOB_BYTES);
+        assert_eq!(batch_data.num_blobs(), 2);
+
+        // only the first two blobs hold data.
+        let coefficients = batch_data.get_coefficients();
+        assert_eq!(coefficients.len(), MAX_BLOBS_PER_BATCH);
+        assert!(coefficients[1].iter().any(|coeff| !coeff.is_zero()));
+        assert!(coefficients[2..]
+            .iter()
+            .all(|blob| blob.iter().all(U256::is_zero)));
+
+        // every blob has its own challenge, which depends on its own versioned hash. the unused
+        // blobs have a zero versioned hash.
+        let versioned_hashes = batch_data.get_versioned_hashes();
+        assert_ne!(versioned_hashes[0], versioned_hashes[1]);
+        assert!(versioned_hashes[..2].iter().all(|hash| !hash.is_zero()));
+        assert!(versioned_hashes[2..].iter().all(H256::is_zero));
+        let challenge_digests = batch_data.get_challenge_digests();
+        assert_ne!(challenge_digests[0], challenge_digests[1]);
+        assert_eq!(
+            batch_data.preimages(DataAvailability::Blob).len(),
+            2 + MAX_BLOBS_PER_BATCH
+        );
+        assert_eq!(batch_data.preimages(DataAvailability::Calldata).len(), 2);
+    }
+
+    #[test]
+    fn uncompressed_fallback() {
+        // compressible batch data is zstd encoded.
+        let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&vec![vec![0xff; 1024]]);
+        assert!(batch_data.is_compressed);
+        assert_eq!(
+            batch_data.get_blob_payload(),
+            once(1)
+                .chain(batch_data.get_encoded_batch_data_bytes())
+                .collect::<Vec<_>>()
+        );
+
+        // zstd encoding makes incompressible batch data larger, so it is put in the blob as-is.
+        let chunk = (0..1024)
+            .scan(0x9e3779b9u32, |state, _| {
+                *state ^= *state << 13;
+                *state ^= *state >> 17;
+                *state ^= *state << 5;
+                Some(*state as u8)
+            })
+            .collect::<Vec<u8>>();
+        let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&vec![chunk]);
+        assert!(!batch_data.is_compressed);
+        assert!(
+            batch_data.get_encoded_batch_data_bytes().len()
+                >= batch_data.get_batch_data_bytes().len()
+        );
+        assert_eq!(
+            batch_data.get_blob_payload(),
+            once(0)
+                .chain(batch_data.get_batch_data_bytes())
+                .collect::<Vec<_>>()
+        );
+    }
 }
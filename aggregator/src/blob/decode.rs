@@ -0,0 +1,493 @@
+use std::fmt;
+
+use ethers_core::utils::rlp::Rlp;
+use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+use zstd_encoder::zstd::zstd_safe::{find_frame_compressed_size, get_error_name};
+
+use crate::aggregation::witgen::{process, DecodeError};
+
+use super::{
+    BatchData, BLOB_WIDTH, MAX_BLOBS_PER_BATCH, N_BATCH_BYTES, N_BYTES_U256,
+    N_DATA_BYTES_PER_COEFFICIENT, N_ROWS_NUM_CHUNKS,
+};
+
+/// Errors encountered while reconstructing the batch data from a set of blobs.
+#[derive(Debug)]
+pub enum BlobDecodeError {
+    /// No blob was provided.
+    NoBlobs,
+    /// More than MAX_BLOBS_PER_BATCH blobs were provided.
+    TooManyBlobs { num_blobs: usize },
+    /// The blob is not BLOB_WIDTH 32-bytes coefficients long.
+    InvalidBlobLength { blob_idx: usize, len: usize },
+    /// The most-significant byte of a coefficient is not 0.
+    NonZeroMostSignificantByte { blob_idx: usize, coeff_idx: usize },
+    /// The is_compressed flag of the blob payload is neither 1 nor 0.
+    InvalidCompressionFlag { flag: u8 },
+    /// The blob payload does not start with a valid zstd frame.
+    InvalidFrame { reason: &'static str },
+    /// The zstd frame could not be decoded by the zstd decoder.
+    Decode(DecodeError),
+    /// The blob bytes following the zstd frame or the uncompressed batch data are not all 0.
+    NonZeroPadding,
+    /// The decoded batch data does not fit in N_BATCH_BYTES.
+    BatchDataTooLong { len: usize },
+    /// The decoded batch data is shorter than the batch metadata.
+    TruncatedMetadata { len: usize },
+    /// The number of valid chunks is not in [1, N_SNARKS].
+    InvalidNumValidChunks { num_valid_chunks: u16 },
+    /// A padded chunk declares a non-zero chunk size.
+    NonEmptyPaddedChunk { chunk_idx: usize },
+    /// The sum of the chunk sizes does not match the number of chunk data bytes.
+    ChunkSizeMismatch { expected: usize, actual: usize },
+    /// The chunk data does not hold a valid RLP-encoded signed transaction at the given byte
+    /// offset.
+    InvalidTransaction {
+        chunk_idx: usize,
+        byte_offset: usize,
+    },
+}
+
+impl fmt::Display for BlobDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoBlobs => write!(f, "no blob provided"),
+            Self::TooManyBlobs { num_blobs } => write!(
+                f,
+                "{num_blobs} blobs provided, at most {MAX_BLOBS_PER_BATCH} are supported"
+            ),
+            Self::InvalidBlobLength { blob_idx, len } => write!(
+                f,
+                "blob {blob_idx}: expected {} bytes, got {len}",
+                BLOB_WIDTH * N_BYTES_U256
+            ),
+            Self::NonZeroMostSignificantByte {
+                blob_idx,
+                coeff_idx,
+            } => write!(
+                f,
+                "blob {blob_idx}: most-significant byte of coefficient {coeff_idx} is not 0"
+            ),
+            Self::InvalidCompressionFlag { flag } => {
+                write!(f, "invalid is_compressed flag: {flag}")
+            }
+            Self::InvalidFrame { reason } => write!(f, "invalid zstd frame: {reason}"),
+            Self::Decode(e) => write!(f, "failed to decode zstd frame: {e}"),
+            Self::NonZeroPadding => write!(f, "non-zero bytes following the blob payload"),
+            Self::BatchDataTooLong { len } => write!(
+                f,
+                "batch data of {len} bytes exceeds the maximum of {N_BATCH_BYTES} bytes"
+            ),
+            Self::TruncatedMetadata { len } => {
+                write!(f, "batch data of {len} bytes is too short to hold the metadata")
+            }
+            Self::InvalidNumValidChunks { num_valid_chunks } => {
+                write!(f, "invalid number of valid chunks: {num_valid_chunks}")
+            }
+            Self::NonEmptyPaddedChunk { chunk_idx } => {
+                write!(f, "padded chunk {chunk_idx} has a non-zero chunk size")
+            }
+            Self::ChunkSizeMismatch { expected, actual } => write!(
+                f,
+                "chunk sizes add up to {expected} bytes, but the batch holds {actual} bytes of chunk data"
+            ),
+            Self::InvalidTransaction {
+                chunk_idx,
+                byte_offset,
+            } => write!(
+                f,
+                "chunk {chunk_idx}: invalid L2 transaction at byte offset {byte_offset}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlobDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for BlobDecodeError {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
+    /// Reconstruct the batch data from the raw bytes of the blobs it was published in, i.e. the
+    /// inverse of [`BatchData::get_blob_bytes`].
+    ///
+    /// Each blob is BLOB_WIDTH big-endian 32-bytes coefficients. The trailing all-zero blobs of a
+    /// batch may be omitted. The blob payload is either the zstd encoded or the raw batch data
+    /// bytes, as per its is_compressed flag, and a zstd frame is decoded exactly as the zstd
+    /// decoder circuit would.
+    pub fn from_blob_bytes<B: AsRef<[u8]>>(blobs: &[B]) -> Result<Self, BlobDecodeError> {
+        if blobs.is_empty() {
+            return Err(BlobDecodeError::NoBlobs);
+        }
+        if blobs.len() > MAX_BLOBS_PER_BATCH {
+            return Err(BlobDecodeError::TooManyBlobs {
+                num_blobs: blobs.len(),
+            });
+        }
+
+        // strip the most-significant byte of every coefficient.
+        let mut encoded_bytes = Vec::with_capacity(blobs.len() * BLOB_WIDTH * N_BYTES_U256);
+        for (blob_idx, blob) in blobs.iter().enumerate() {
+            let blob = blob.as_ref();
+            if blob.len() != BLOB_WIDTH * N_BYTES_U256 {
+                return Err(BlobDecodeError::InvalidBlobLength {
+                    blob_idx,
+                    len: blob.len(),
+                });
+            }
+            for (coeff_idx, coeff) in blob.chunks_exact(N_BYTES_U256).enumerate() {
+                if coeff[0] != 0 {
+                    return Err(BlobDecodeError::NonZeroMostSignificantByte {
+                        blob_idx,
+                        coeff_idx,
+                    });
+                }
+                encoded_bytes
+                    .extend_from_slice(&coeff[N_BYTES_U256 - N_DATA_BYTES_PER_COEFFICIENT..]);
+            }
+        }
+
+        let (&is_compressed, payload) = encoded_bytes
+            .split_first()
+            .expect("at least 1 blob is provided");
+        let payload_len = match is_compressed {
+            1 => find_frame_compressed_size(payload).map_err(|code| {
+                BlobDecodeError::InvalidFrame {
+                    reason: get_error_name(code),
+                }
+            })?,
+            0 => Self::batch_data_len(payload)?,
+            flag => return Err(BlobDecodeError::InvalidCompressionFlag { flag }),
+        };
+
+        // the payload is followed by zero padding.
+        let (payload, padding) = payload.split_at(payload_len);
+        if padding.iter().any(|&byte| byte != 0) {
+            return Err(BlobDecodeError::NonZeroPadding);
+        }
+
+        let mut batch_data = if is_compressed == 1 {
+            let batch_data_bytes = process::<Fr>(payload, Value::unknown())?
+                .sequence_exec_results
+                .into_iter()
+                .flat_map(|res| res.recovered_bytes)
+                .collect::<Vec<u8>>();
+            Self::from_batch_data_bytes(&batch_data_bytes)?
+        } else {
+            Self::from_batch_data_bytes(payload)?
+        };
+        batch_data.is_compressed = is_compressed == 1;
+
+        Ok(batch_data)
+    }
+
+    /// The number of batch data bytes, as per the batch metadata the bytes start with.
+    fn batch_data_len(bytes: &[u8]) -> Result<usize, BlobDecodeError> {
+        if bytes.len() < Self::n_rows_metadata() {
+            return Err(BlobDecodeError::TruncatedMetadata { len: bytes.len() });
+        }
+        let chunk_data_len = bytes[N_ROWS_NUM_CHUNKS..Self::n_rows_metadata()]
+            .chunks_exact(4)
+            .map(|bytes| u32::from_be_bytes(bytes.try_into().expect("4 bytes")) as usize)
+            .sum::<usize>();
+        if Self::n_rows_metadata() + chunk_data_len > bytes.len() {
+            return Err(BlobDecodeError::ChunkSizeMismatch {
+                expected: chunk_data_len,
+                actual: bytes.len() - Self::n_rows_metadata(),
+            });
+        }
+
+        Ok(Self::n_rows_metadata() + chunk_data_len)
+    }
+
+    /// Reconstruct the batch data from the batch data bytes, i.e. the inverse of
+    /// [`BatchData::get_batch_data_bytes`].
+    fn from_batch_data_bytes(batch_data_bytes: &[u8]) -> Result<Self, BlobDecodeError> {
+        if batch_data_bytes.len() > N_BATCH_BYTES {
+            return Err(BlobDecodeError::BatchDataTooLong {
+                len: batch_data_bytes.len(),
+            });
+        }
+        if batch_data_bytes.len() < Self::n_rows_metadata() {
+            return Err(BlobDecodeError::TruncatedMetadata {
+                len: batch_data_bytes.len(),
+            });
+        }
+        let (metadata_bytes, chunk_data_bytes) = batch_data_bytes.split_at(Self::n_rows_metadata());
+
+        let num_valid_chunks = u16::from_be_bytes(
+            metadata_bytes[..N_ROWS_NUM_CHUNKS]
+                .try_into()
+                .expect("2 bytes"),
+        );
+        if num_valid_chunks == 0 || num_valid_chunks as usize > N_SNARKS {
+            return Err(BlobDecodeError::InvalidNumValidChunks { num_valid_chunks });
+        }
+        let num_valid_chunks = num_valid_chunks as usize;
+
+        let chunk_sizes = metadata_bytes[N_ROWS_NUM_CHUNKS..]
+            .chunks_exact(4)
+            .map(|bytes| u32::from_be_bytes(bytes.try_into().expect("4 bytes")) as usize)
+            .collect::<Vec<_>>();
+        if let Some(chunk_idx) = (num_valid_chunks..N_SNARKS).find(|&i| chunk_sizes[i] != 0) {
+            return Err(BlobDecodeError::NonEmptyPaddedChunk { chunk_idx });
+        }
+        let expected = chunk_sizes.iter().sum::<usize>();
+        if expected != chunk_data_bytes.len() {
+            return Err(BlobDecodeError::ChunkSizeMismatch {
+                expected,
+                actual: chunk_data_bytes.len(),
+            });
+        }
+
+        let chunks = chunk_sizes
+            .iter()
+            .take(num_valid_chunks)
+            .scan(0, |offset, &chunk_size| {
+                let chunk = chunk_data_bytes[*offset..*offset + chunk_size].to_vec();
+                *offset += chunk_size;
+                Some(chunk)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self::from(&chunks))
+    }
+
+    /// Split the flattened L2 signed transaction data of each valid chunk into the RLP-encoded
+    /// signed transactions, where a typed transaction is prefixed with its transaction type.
+    pub fn l2_transactions(&self) -> Result<Vec<Vec<Vec<u8>>>, BlobDecodeError> {
+        self.chunk_data
+            .iter()
+            .take(self.num_valid_chunks as usize)
+            .enumerate()
+            .map(|(chunk_idx, chunk)| {
+                let mut txs = vec![];
+                let mut byte_offset = 0;
+                while byte_offset < chunk.len() {
+                    let tx_len = signed_tx_len(&chunk[byte_offset..]).ok_or(
+                        BlobDecodeError::InvalidTransaction {
+                            chunk_idx,
+                            byte_offset,
+                        },
+                    )?;
+                    txs.push(chunk[byte_offset..byte_offset + tx_len].to_vec());
+                    byte_offset += tx_len;
+                }
+                Ok(txs)
+            })
+            .collect()
+    }
+}
+
+/// The length of the signed transaction the bytes start with, if any.
+///
+/// A typed transaction (EIP-2718) is its transaction type in [0x00, 0x7f], followed by an RLP
+/// list, whereas a legacy transaction is an RLP list.
+fn signed_tx_len(bytes: &[u8]) -> Option<usize> {
+    let type_len = match bytes.first()? {
+        0x00..=0x7f => 1,
+        _ => 0,
+    };
+    let rlp = Rlp::new(&bytes[type_len..]);
+    if !rlp.is_list() {
+        return None;
+    }
+    let tx_len = type_len + rlp.payload_info().ok()?.total();
+
+    (tx_len <= bytes.len()).then_some(tx_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{blob::N_BLOB_BYTES, MAX_AGG_SNARKS};
+
+    /// Random chunks, each of which is either incompressible or highly compressible.
+    fn random_chunks(rng: &mut StdRng) -> Vec<Vec<u8>> {
+        let num_valid_chunks = rng.gen_range(1..=MAX_AGG_SNARKS);
+        (0..num_valid_chunks)
+            .map(|_| {
+                let chunk_size = rng.gen_range(0..2048);
+                if rng.gen_bool(0.5) {
+                    (0..chunk_size).map(|_| rng.gen()).collect()
+                } else {
+                    (0..chunk_size).map(|_| rng.gen_range(0..4)).collect()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn blob_bytes_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x4844);
+        let mut num_compressed = 0;
+        for _ in 0..32 {
+            let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&random_chunks(&mut rng));
+            num_compressed += batch_data.is_compressed as usize;
+            let blobs = batch_data.get_blob_bytes();
+            assert_eq!(blobs.len(), MAX_BLOBS_PER_BATCH);
+
+            let decoded = BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs).unwrap();
+            assert_eq!(decoded, batch_data);
+
+            // the trailing all-zero blobs may be omitted.
+            let decoded = BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs[..1]).unwrap();
+            assert_eq!(decoded, batch_data);
+        }
+        // both compressed and uncompressed blob payloads are covered.
+        assert!(num_compressed > 0 && num_compressed < 32);
+    }
+
+    #[test]
+    fn blob_bytes_round_trip_multiple_blobs() {
+        let mut rng = StdRng::seed_from_u64(0x4844);
+        let chunks = vec![
+            (0..N_BLOB_BYTES).map(|_| rng.gen()).collect(),
+            (0..N_BLOB_BYTES / 2).map(|_| rng.gen()).collect(),
+        ];
+        let mut batch_data = BatchData::<MAX_AGG_SNARKS>::from(&chunks);
+        assert!(!batch_data.is_compressed);
+        let blobs = batch_data.get_blob_bytes();
+
+        let decoded = BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs[..2]).unwrap();
+        assert_eq!(decoded, batch_data);
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs[..1]),
+            Err(BlobDecodeError::ChunkSizeMismatch { .. })
+        ));
+
+        // the zstd encoded batch data, albeit larger, spans multiple blobs as well.
+        batch_data.is_compressed = true;
+        let blobs = batch_data.get_blob_bytes();
+
+        let decoded = BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs[..2]).unwrap();
+        assert_eq!(decoded, batch_data);
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs[..1]),
+            Err(BlobDecodeError::InvalidFrame { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_blob_bytes() {
+        let mut blobs = BatchData::<MAX_AGG_SNARKS>::from(&vec![vec![1, 2, 3]]).get_blob_bytes();
+
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes::<Vec<u8>>(&[]),
+            Err(BlobDecodeError::NoBlobs)
+        ));
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&[&blobs[0][1..]]),
+            Err(BlobDecodeError::InvalidBlobLength {
+                blob_idx: 0,
+                len: 131071
+            })
+        ));
+
+        // non-zero padding following the zstd frame.
+        blobs[1][N_BYTES_U256 - 1] = 1;
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs),
+            Err(BlobDecodeError::NonZeroPadding)
+        ));
+
+        // is_compressed flag that is neither 1 nor 0.
+        blobs[0][1] = 2;
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs),
+            Err(BlobDecodeError::InvalidCompressionFlag { flag: 2 })
+        ));
+
+        // coefficient not in the canonical form.
+        blobs[1][N_BYTES_U256] = 1;
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_blob_bytes(&blobs),
+            Err(BlobDecodeError::NonZeroMostSignificantByte {
+                blob_idx: 1,
+                coeff_idx: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_batch_data_bytes() {
+        let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&vec![vec![1, 2, 3], vec![4]]);
+        let batch_data_bytes = batch_data.get_batch_data_bytes();
+        assert_eq!(
+            BatchData::<MAX_AGG_SNARKS>::from_batch_data_bytes(&batch_data_bytes).unwrap(),
+            batch_data
+        );
+
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_batch_data_bytes(&batch_data_bytes[..3]),
+            Err(BlobDecodeError::TruncatedMetadata { len: 3 })
+        ));
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_batch_data_bytes(
+                &batch_data_bytes[..batch_data_bytes.len() - 1]
+            ),
+            Err(BlobDecodeError::ChunkSizeMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+
+        let mut bytes = batch_data_bytes.clone();
+        bytes[..N_ROWS_NUM_CHUNKS].copy_from_slice(&0u16.to_be_bytes());
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_batch_data_bytes(&bytes),
+            Err(BlobDecodeError::InvalidNumValidChunks {
+                num_valid_chunks: 0
+            })
+        ));
+
+        // chunk size declared for the first padded chunk.
+        let mut bytes = batch_data_bytes;
+        bytes[N_ROWS_NUM_CHUNKS + 4 * 2 + 3] = 1;
+        assert!(matches!(
+            BatchData::<MAX_AGG_SNARKS>::from_batch_data_bytes(&bytes),
+            Err(BlobDecodeError::NonEmptyPaddedChunk { chunk_idx: 2 })
+        ));
+    }
+
+    #[test]
+    fn split_l2_transactions() {
+        // legacy tx, typed tx (EIP-1559) and a legacy tx with a long list payload.
+        let legacy_tx = vec![0xc3, 0x01, 0x02, 0x03];
+        let typed_tx = vec![0x02, 0xc2, 0x80, 0x80];
+        let long_tx = [vec![0xf8, 0x38], vec![0x80; 0x38]].concat();
+
+        let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&vec![
+            [legacy_tx.clone(), typed_tx.clone()].concat(),
+            vec![],
+            long_tx.clone(),
+        ]);
+        assert_eq!(
+            batch_data.l2_transactions().unwrap(),
+            vec![vec![legacy_tx, typed_tx.clone()], vec![], vec![long_tx]]
+        );
+
+        // truncated transaction.
+        let batch_data =
+            BatchData::<MAX_AGG_SNARKS>::from(&vec![[typed_tx.as_slice(), &[0x02, 0xc2]].concat()]);
+        assert!(matches!(
+            batch_data.l2_transactions(),
+            Err(BlobDecodeError::InvalidTransaction {
+                chunk_idx: 0,
+                byte_offset: 4
+            })
+        ));
+    }
+}
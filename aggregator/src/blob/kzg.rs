@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use c_kzg::{Blob, KzgCommitment, KzgProof, KzgSettings};
+use eth_types::{ToBigEndian, H256, U256};
+
+use super::{kzg_to_versioned_hash, BatchData};
+
+/// Everything the L1 commit transaction needs for a blob, i.e. the blob's KZG commitment, its
+/// versioned hash as per EIP-4844, and the KZG proof of the blob polynomial's evaluation at the
+/// challenge point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlobKzgData {
+    /// The KZG commitment to the blob polynomial.
+    pub commitment: [u8; 48],
+    /// The versioned hash of the KZG commitment.
+    pub versioned_hash: H256,
+    /// The challenge point z.
+    pub challenge: U256,
+    /// The evaluation y of the blob polynomial at z.
+    pub evaluation: U256,
+    /// The KZG proof for p(z) == y.
+    pub proof: [u8; 48],
+}
+
+/// Load the KZG trusted setup from a file, in the format of the EIP-4844 trusted setup, i.e. the
+/// number of G1 and G2 points followed by the hex encoded G1 and G2 points, one per line.
+pub fn load_kzg_settings(path: impl AsRef<Path>) -> Result<KzgSettings, c_kzg::Error> {
+    KzgSettings::load_trusted_setup_file(path.as_ref())
+}
+
+impl<const N_SNARKS: usize> BatchData<N_SNARKS> {
+    /// Get the KZG commitment, versioned hash and KZG proof at the challenge point, for each of
+    /// the blobs used by the batch.
+    pub fn get_kzg_data(
+        &self,
+        kzg_settings: &KzgSettings,
+    ) -> Result<Vec<BlobKzgData>, c_kzg::Error> {
+        self.get_blob_bytes()
+            .iter()
+            .zip(self.get_point_evaluation_assignments())
+            .take(self.num_blobs())
+            .map(|(blob_bytes, point_evaluation_assignments)| {
+                let blob = Blob::from_bytes(blob_bytes)?;
+                let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, kzg_settings)?;
+
+                let challenge = point_evaluation_assignments.challenge;
+                let (proof, evaluation) = KzgProof::compute_kzg_proof(
+                    &blob,
+                    &challenge.to_be_bytes().into(),
+                    kzg_settings,
+                )?;
+                let evaluation = U256::from_big_endian(evaluation.as_slice());
+                assert_eq!(
+                    evaluation, point_evaluation_assignments.evaluation,
+                    "evaluation at challenge mismatch"
+                );
+
+                Ok(BlobKzgData {
+                    commitment: *commitment.to_bytes(),
+                    versioned_hash: kzg_to_versioned_hash(&commitment),
+                    challenge,
+                    evaluation,
+                    proof: *proof.to_bytes(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use c_kzg::{Bytes32, Bytes48};
+
+    use super::*;
+    use crate::MAX_AGG_SNARKS;
+
+    /// Write the trusted setup shipped with revm to the given file, in the format of the EIP-4844
+    /// trusted setup.
+    fn write_trusted_setup_file(path: &Path) {
+        let g1_points = &revm_primitives::kzg::G1_POINTS.0;
+        let g2_points = &revm_primitives::kzg::G2_POINTS.0;
+        let mut contents = format!("{}\n{}\n", g1_points.len(), g2_points.len());
+        for point in g1_points.iter() {
+            contents.push_str(&hex::encode(point));
+            contents.push('\n');
+        }
+        for point in g2_points.iter() {
+            contents.push_str(&hex::encode(point));
+            contents.push('\n');
+        }
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn kzg_proofs_verify() {
+        let path = env::temp_dir().join(format!("trusted_setup_{}.txt", process::id()));
+        write_trusted_setup_file(&path);
+        let kzg_settings = load_kzg_settings(&path);
+        fs::remove_file(&path).unwrap();
+        let kzg_settings = kzg_settings.unwrap();
+
+        let batch_data = BatchData::<MAX_AGG_SNARKS>::from(&vec![
+            vec![30; 56],
+            vec![200; 100],
+            vec![0; 340],
+            vec![10; 23],
+        ]);
+        let kzg_data = batch_data.get_kzg_data(&kzg_settings).unwrap();
+
+        // only the blobs used by the batch.
+        let num_blobs = batch_data.num_blobs();
+        assert_eq!(kzg_data.len(), num_blobs);
+        assert_eq!(
+            kzg_data
+                .iter()
+                .map(|kzg_data| kzg_data.versioned_hash)
+                .collect::<Vec<_>>(),
+            batch_data.get_versioned_hashes()[..num_blobs]
+        );
+        for kzg_data in kzg_data {
+            assert!(KzgProof::verify_kzg_proof(
+                &Bytes48::from(kzg_data.commitment),
+                &Bytes32::from(kzg_data.challenge.to_be_bytes()),
+                &Bytes32::from(kzg_data.evaluation.to_be_bytes()),
+                &Bytes48::from(kzg_data.proof),
+                &kzg_settings,
+            )
+            .unwrap());
+        }
+    }
+
+    #[test]
+    fn missing_trusted_setup_file() {
+        assert!(load_kzg_settings("./data/missing_trusted_setup.txt").is_err());
+    }
+}
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use std::iter;
 use zkevm_circuits::witness::Block;
 
+use crate::pi_layout::{PiField, PiHashLayout, PiHashLayoutError};
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
 /// A chunk is a set of continuous blocks.
 /// A ChunkHash consists of 5 hashes, representing the changes incurred by this chunk of blocks:
@@ -15,6 +17,7 @@ use zkevm_circuits::witness::Block;
 /// - the data hash of this chunk
 /// - the tx data hash of this chunk
 /// - if the chunk is padded (en empty but valid chunk that is padded for aggregation)
+/// - the values of the fork specific fields of the public input hash, if any
 pub struct ChunkHash {
     /// Chain identifier
     pub chain_id: u64,
@@ -31,6 +34,9 @@ pub struct ChunkHash {
     pub tx_bytes: Vec<u8>,
     /// if the chunk is a padded chunk
     pub is_padding: bool,
+    /// Values of the extra fields of the public input hash, see [`PiField::Extra`]
+    #[serde(default)]
+    pub extra_public_inputs: Vec<Vec<u8>>,
 }
 
 impl ChunkHash {
@@ -68,6 +74,13 @@ impl ChunkHash {
                     num_txs,
                 );
 
+                // keep in sync with the PI circuit's data bytes
+                let prevrandao = if cfg!(feature = "prevrandao") {
+                    b_ctx.prevrandao.to_be_bytes().to_vec()
+                } else {
+                    vec![]
+                };
+
                 iter::empty()
                     // Block Values
                     .chain(b_ctx.number.as_u64().to_be_bytes())
@@ -75,6 +88,7 @@ impl ChunkHash {
                     .chain(b_ctx.base_fee.to_be_bytes())
                     .chain(b_ctx.gas_limit.to_be_bytes())
                     .chain(num_txs.to_be_bytes())
+                    .chain(prevrandao)
             }))
             // Tx Hashes (excluding L2 txs)
             .chain(block.txs
@@ -112,6 +126,7 @@ impl ChunkHash {
             data_hash,
             tx_bytes: tx_bytes.to_vec(),
             is_padding,
+            extra_public_inputs: vec![],
         }
     }
 
@@ -189,6 +204,7 @@ impl ChunkHash {
             data_hash: data_hash.into(),
             tx_bytes: txs.iter().flat_map(|tx| tx.rlp_unsigned()).collect(),
             is_padding: false,
+            extra_public_inputs: vec![],
         }
     }
 
@@ -207,6 +223,7 @@ impl ChunkHash {
             data_hash: previous_chunk.data_hash,
             tx_bytes: previous_chunk.tx_bytes.clone(),
             is_padding: true,
+            extra_public_inputs: previous_chunk.extra_public_inputs.clone(),
         }
     }
 
@@ -219,9 +236,20 @@ impl ChunkHash {
     ///     chunk data hash ||
     ///     chunk txdata hash
     /// )
+    ///
+    /// Panics if the chunk has extra public inputs, see [`Self::public_input_hash_with_layout`].
     pub fn public_input_hash(&self) -> H256 {
-        let preimage = self.extract_hash_preimage();
-        keccak256::<&[u8]>(preimage.as_ref()).into()
+        self.public_input_hash_with_layout(&PiHashLayout::default_chunk())
+            .expect("chunk with extra public inputs requires a custom pi hash layout")
+    }
+
+    /// Public input hash for a given chunk, with the preimage arranged as per the layout
+    pub fn public_input_hash_with_layout(
+        &self,
+        layout: &PiHashLayout,
+    ) -> Result<H256, PiHashLayoutError> {
+        let preimage = self.extract_hash_preimage_with_layout(layout)?;
+        Ok(keccak256::<&[u8]>(preimage.as_ref()).into())
     }
 
     /// Extract the preimage for the hash
@@ -234,15 +262,30 @@ impl ChunkHash {
     ///     chunk data hash ||
     ///     chunk txdata hash
     /// ]
+    ///
+    /// Panics if the chunk has extra public inputs, see
+    /// [`Self::extract_hash_preimage_with_layout`].
     pub fn extract_hash_preimage(&self) -> Vec<u8> {
-        [
-            self.chain_id.to_be_bytes().as_ref(),
-            self.prev_state_root.as_bytes(),
-            self.post_state_root.as_bytes(),
-            self.withdraw_root.as_bytes(),
-            self.data_hash.as_bytes(),
-            self.tx_bytes_hash().as_bytes(),
-        ]
-        .concat()
+        self.extract_hash_preimage_with_layout(&PiHashLayout::default_chunk())
+            .expect("chunk with extra public inputs requires a custom pi hash layout")
+    }
+
+    /// Extract the preimage for the hash, arranged as per the layout
+    pub fn extract_hash_preimage_with_layout(
+        &self,
+        layout: &PiHashLayout,
+    ) -> Result<Vec<u8>, PiHashLayoutError> {
+        layout.build_preimage(
+            |field| match field {
+                PiField::ChainId => Some(self.chain_id.to_be_bytes().to_vec()),
+                PiField::PrevStateRoot => Some(self.prev_state_root.to_fixed_bytes().to_vec()),
+                PiField::PostStateRoot => Some(self.post_state_root.to_fixed_bytes().to_vec()),
+                PiField::WithdrawRoot => Some(self.withdraw_root.to_fixed_bytes().to_vec()),
+                PiField::DataHash => Some(self.data_hash.to_fixed_bytes().to_vec()),
+                PiField::TxDataHash => Some(self.tx_bytes_hash().to_fixed_bytes().to_vec()),
+                PiField::DaCommitment | PiField::Extra { .. } => None,
+            },
+            &self.extra_public_inputs,
+        )
     }
 }
@@ -8,44 +8,29 @@ pub(crate) const CHAIN_ID_LEN: usize = 8;
 /// Digest length
 pub(crate) const DIGEST_LEN: usize = 32;
 
+/// Number of input bytes absorbed by each keccak_f permutation
+pub(crate) const INPUT_LEN_PER_ROUND: usize = 136;
+
 // TODO(ZZ): update to the right degree
 pub(crate) const LOG_DEGREE: u32 = 19;
 
 // ================================
-// indices for chunk pi hash table
+// batch pi hash DA commitment
 // ================================
 //
-// the preimages are arranged as
-// - chain_id:          8 bytes
-// - prev_state_root    32 bytes
-// - post_state_root    32 bytes
-// - withdraw_root      32 bytes
-// - chunk_data_hash    32 bytes
-// - chunk_tx_data_hash 32 bytes
-
-pub(crate) const PREV_STATE_ROOT_INDEX: usize = 8;
-pub(crate) const POST_STATE_ROOT_INDEX: usize = 40;
-pub(crate) const WITHDRAW_ROOT_INDEX: usize = 72;
-pub(crate) const CHUNK_DATA_HASH_INDEX: usize = 104;
-pub(crate) const CHUNK_TX_DATA_HASH_INDEX: usize = 136;
-
-// ================================
-// indices for batch pi hash table
-// ================================
+// the layouts of the chunk and batch pi hash preimages are given by `PiHashLayout`. for blob DA,
+// the DA commitment of the batch pi hash consists of, for each of the MAX_BLOBS_PER_BATCH blobs:
+// - z                32 bytes
+// - y                32 bytes
+// - versioned_hash   32 bytes
 //
-// the preimages are arranged as
-// - chain_id:          8 bytes
-// - prev_state_root    32 bytes
-// - post_state_root    32 bytes
-// - withdraw_root      32 bytes
-// - chunk_data_hash    32 bytes
-// - z                  32 bytes
-// - y                  32 bytes
-// - versioned_hash     32 bytes
+// the offsets below are relative to the commitment of a blob. the commitment of the i-th blob is
+// at offset i * BATCH_BLOB_LEN of the DA commitment.
 
-pub(crate) const BATCH_Z_OFFSET: usize = 136;
-pub(crate) const BATCH_Y_OFFSET: usize = 168;
-pub(crate) const BATCH_VH_OFFSET: usize = 200;
+pub(crate) const BLOB_Z_OFFSET: usize = 0;
+pub(crate) const BLOB_Y_OFFSET: usize = 32;
+pub(crate) const BLOB_VH_OFFSET: usize = 64;
+pub(crate) const BATCH_BLOB_LEN: usize = 96;
 
 // ================================
 // aggregator parameters
@@ -32,12 +32,15 @@ use zkevm_circuits::{
 };
 
 use crate::{
+    batch::DataAvailability,
+    blob::MAX_BLOBS_PER_BATCH,
     constants::{
-        BATCH_VH_OFFSET, BATCH_Y_OFFSET, BATCH_Z_OFFSET, CHAIN_ID_LEN, DIGEST_LEN, LOG_DEGREE,
+        BATCH_BLOB_LEN, BLOB_VH_OFFSET, BLOB_Y_OFFSET, BLOB_Z_OFFSET, DIGEST_LEN,
+        INPUT_LEN_PER_ROUND, LOG_DEGREE,
     },
+    pi_layout::{PiField, PiHashLayout},
     util::{assert_conditional_equal, assert_equal, parse_hash_preimage_cells},
-    RlcConfig, BITS, CHUNK_DATA_HASH_INDEX, CHUNK_TX_DATA_HASH_INDEX, LIMBS, POST_STATE_ROOT_INDEX,
-    PREV_STATE_ROOT_INDEX, WITHDRAW_ROOT_INDEX,
+    RlcConfig, BITS, LIMBS,
 };
 
 /// Subroutine for the witness generations.
@@ -153,6 +156,7 @@ pub(crate) struct ExtractedHashCells<const N_SNARKS: usize> {
     data_lens: Vec<AssignedCell<Fr, Fr>>,
     num_valid_snarks: AssignedCell<Fr, Fr>,
     chunks_are_padding: Vec<AssignedCell<Fr, Fr>>,
+    blobs_are_used: Vec<AssignedCell<Fr, Fr>>,
 }
 
 impl<const N_SNARKS: usize> ExtractedHashCells<N_SNARKS> {
@@ -160,6 +164,9 @@ impl<const N_SNARKS: usize> ExtractedHashCells<N_SNARKS> {
     /// Padded the number of hashes to N_SNARKS
     /// DOES NOT CONSTRAIN THE CORRECTNESS.
     /// Call `check_against_lookup_table` function to constrain the hash is correct.
+    ///
+    /// The batch pi hash preimage is assigned padded to MAX_BLOBS_PER_BATCH blobs, and its bytes
+    /// are only part of the RLC and of the data length if their flag is set.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn assign_hash_cells(
         plonk_config: &RlcConfig,
@@ -169,9 +176,12 @@ impl<const N_SNARKS: usize> ExtractedHashCells<N_SNARKS> {
         evm_word_challenge: &AssignedCell<Fr, Fr>,
         num_valid_chunks: usize,
         preimages: &[Vec<u8>],
+        padded_batch_pi_preimage: &[u8],
+        batch_pi_byte_flags: &[AssignedCell<Fr, Fr>],
         chunk_is_valid_cell32s: &[AssignedCell<Fr, Fr>],
         num_valid_snarks: AssignedCell<Fr, Fr>,
         chunks_are_padding: Vec<AssignedCell<Fr, Fr>>,
+        blobs_are_used: Vec<AssignedCell<Fr, Fr>>,
     ) -> Result<Self, halo2_proofs::plonk::Error> {
         let mut inputs = vec![];
         let mut input_rlcs = vec![];
@@ -180,14 +190,51 @@ impl<const N_SNARKS: usize> ExtractedHashCells<N_SNARKS> {
         let mut data_lens = vec![];
 
         // preimages are padded as follows
-        // - the first hash is batch_public_input_hash
+        // - the first hash is batch_public_input_hash, its input is padded to MAX_BLOBS_PER_BATCH
+        //   blobs
         // - the next hashes are chunk\[i\].piHash, we padded it to N_SNARKS by repeating the last
         //   chunk
         // - the last hash is batch_data_hash, its input is padded to 32*N_SNARKS
         log::trace!("preimage len: {}", preimages.len());
+        {
+            let mut preimage_cells = vec![];
+            for input in padded_batch_pi_preimage.iter() {
+                let v = Fr::from(*input as u64);
+                let cell = plonk_config.load_private(region, &v, offset)?;
+                preimage_cells.push(cell);
+            }
+            let input_rlc = plonk_config.rlc_with_flag(
+                region,
+                &preimage_cells,
+                keccak_input_challenge,
+                batch_pi_byte_flags,
+                offset,
+            )?;
+            inputs.push(preimage_cells);
+            input_rlcs.push(input_rlc);
+
+            let mut digest_cells = vec![];
+            let digest = keccak256(&preimages[0]);
+            for output in digest.iter() {
+                let v = Fr::from(*output as u64);
+                let cell = plonk_config.load_private(region, &v, offset)?;
+                digest_cells.push(cell);
+            }
+            let output_rlc = plonk_config.rlc(region, &digest_cells, evm_word_challenge, offset)?;
+            outputs.push(digest_cells);
+            output_rlcs.push(output_rlc);
+
+            // the data length is the number of flagged bytes
+            let mut data_len = batch_pi_byte_flags[0].clone();
+            for flag in batch_pi_byte_flags.iter().skip(1) {
+                data_len = plonk_config.add(region, &data_len, flag, offset)?;
+            }
+            data_lens.push(data_len);
+        }
         for preimage in preimages
             .iter()
-            .take(num_valid_chunks + 1)
+            .skip(1)
+            .take(num_valid_chunks)
             .chain(repeat(&preimages[num_valid_chunks]).take(N_SNARKS - num_valid_chunks))
         {
             {
@@ -277,6 +324,7 @@ impl<const N_SNARKS: usize> ExtractedHashCells<N_SNARKS> {
             data_lens,
             num_valid_snarks,
             chunks_are_padding,
+            blobs_are_used,
         })
     }
 
@@ -312,9 +360,17 @@ impl<const N_SNARKS: usize> ExtractedHashCells<N_SNARKS> {
 
 #[derive(Default)]
 pub(crate) struct ExpectedBlobCells {
-    pub(crate) z: Vec<AssignedCell<Fr, Fr>>,
-    pub(crate) y: Vec<AssignedCell<Fr, Fr>>,
-    pub(crate) versioned_hash: Vec<AssignedCell<Fr, Fr>>,
+    /// The big-endian bytes of z, for each blob. Empty for calldata DA.
+    pub(crate) z: Vec<Vec<AssignedCell<Fr, Fr>>>,
+    /// The big-endian bytes of y, for each blob. Empty for calldata DA.
+    pub(crate) y: Vec<Vec<AssignedCell<Fr, Fr>>>,
+    /// The versioned hash of each blob. Empty for calldata DA.
+    pub(crate) versioned_hashes: Vec<Vec<AssignedCell<Fr, Fr>>>,
+    /// Whether each blob is used by the batch, i.e. committed to in the batch public input hash.
+    /// Empty for calldata DA.
+    pub(crate) blobs_are_used: Vec<AssignedCell<Fr, Fr>>,
+    /// The keccak hash of the calldata. Empty for blob DA.
+    pub(crate) calldata_hash: Vec<AssignedCell<Fr, Fr>>,
     pub(crate) chunk_tx_data_digests: Vec<Vec<AssignedCell<Fr, Fr>>>,
 }
 
@@ -343,11 +399,15 @@ pub(crate) struct AssignedBatchHash {
 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
 // padded
 // 7. the hash input length are correct
-// - hashes[0] has 200 bytes
-// - hashes[1..N_SNARKS+1] has 168 bytes input
+// - hashes[0] has the length of the batch pi hash layout, i.e. 136 + 96 * num_blobs bytes for blob
+//   DA and 168 bytes for calldata DA by default
+// - hashes[1..N_SNARKS+1] have the length of the chunk pi hash layout, 168 bytes by default
 // - batch's data_hash length is 32 * number_of_valid_snarks
 // 8. batch data hash is correct w.r.t. its RLCs
 // 9. is_final_cells are set correctly
+// 10. the blobs used by the batch are the first num_blobs ones, and only those are committed to in
+// the batch pi hash
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn assign_batch_hashes<const N_SNARKS: usize>(
     keccak_config: &KeccakCircuitConfig<Fr>,
     rlc_config: &RlcConfig,
@@ -355,6 +415,11 @@ pub(crate) fn assign_batch_hashes<const N_SNARKS: usize>(
     challenges: Challenges<Value<Fr>>,
     chunks_are_valid: &[bool],
     num_valid_chunks: usize,
+    data_availability: DataAvailability,
+    num_blobs: usize,
+    chunk_pi_layout: &PiHashLayout,
+    batch_pi_layout: &PiHashLayout,
+    padded_batch_pi_preimage: &[u8],
     preimages: &[Vec<u8>],
 ) -> Result<AssignedBatchHash, Error> {
     // assign the hash table
@@ -367,12 +432,19 @@ pub(crate) fn assign_batch_hashes<const N_SNARKS: usize>(
     // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
     // padded
     // 7. batch data hash is correct w.r.t. its RLCs
+    // 10. the blobs used by the batch are the first num_blobs ones, and only those are committed
+    // to in the batch pi hash
     let extracted_hash_cells = conditional_constraints::<N_SNARKS>(
         rlc_config,
         layouter,
         challenges,
         chunks_are_valid,
         num_valid_chunks,
+        data_availability,
+        num_blobs,
+        chunk_pi_layout,
+        batch_pi_layout,
+        padded_batch_pi_preimage,
         preimages,
     )?;
 
@@ -380,21 +452,46 @@ pub(crate) fn assign_batch_hashes<const N_SNARKS: usize>(
     // 2.1. batch_pi_hash and chunk[0] use a same prev_state_root
     // 2.2. batch_pi_hash and chunk[N_SNARKS-1] use a same post_state_root
     // 2.3. batch_pi_hash and chunk[N_SNARKS-1] use a same withdraw_root
+    // 2.4. batch_pi_hash and chunk[N_SNARKS-1] use same extra fields
     // 5. batch and all its chunks use a same chain id
-    copy_constraints::<N_SNARKS>(layouter, &extracted_hash_cells.inputs)?;
+    copy_constraints::<N_SNARKS>(
+        layouter,
+        chunk_pi_layout,
+        batch_pi_layout,
+        &extracted_hash_cells.inputs,
+    )?;
 
     let batch_pi_input = &extracted_hash_cells.inputs[0]; //[0..INPUT_LEN_PER_ROUND * 2];
-    let expected_blob_cells = ExpectedBlobCells {
-        z: batch_pi_input[BATCH_Z_OFFSET..BATCH_Z_OFFSET + DIGEST_LEN].to_vec(),
-        y: batch_pi_input[BATCH_Y_OFFSET..BATCH_Y_OFFSET + DIGEST_LEN].to_vec(),
-        versioned_hash: batch_pi_input[BATCH_VH_OFFSET..BATCH_VH_OFFSET + DIGEST_LEN].to_vec(),
-        chunk_tx_data_digests: (0..N_SNARKS)
+    let da_commitment_offset = batch_pi_layout.range(PiField::DaCommitment)?.start;
+    let blob_cells = |offset: usize| -> Vec<Vec<AssignedCell<Fr, Fr>>> {
+        (0..MAX_BLOBS_PER_BATCH)
             .map(|i| {
-                extracted_hash_cells.inputs[i + 1]
-                    [CHUNK_TX_DATA_HASH_INDEX..CHUNK_TX_DATA_HASH_INDEX + DIGEST_LEN]
-                    .to_vec()
+                let offset = da_commitment_offset + offset + i * BATCH_BLOB_LEN;
+                batch_pi_input[offset..offset + DIGEST_LEN].to_vec()
             })
-            .collect(),
+            .collect()
+    };
+    let chunk_tx_data_hash = chunk_pi_layout.range(PiField::TxDataHash)?;
+    let chunk_tx_data_digests = (0..N_SNARKS)
+        .map(|i| extracted_hash_cells.inputs[i + 1][chunk_tx_data_hash.clone()].to_vec())
+        .collect();
+    let expected_blob_cells = match data_availability {
+        DataAvailability::Blob => ExpectedBlobCells {
+            z: blob_cells(BLOB_Z_OFFSET),
+            y: blob_cells(BLOB_Y_OFFSET),
+            versioned_hashes: blob_cells(BLOB_VH_OFFSET),
+            blobs_are_used: extracted_hash_cells.blobs_are_used,
+            calldata_hash: vec![],
+            chunk_tx_data_digests,
+        },
+        DataAvailability::Calldata => ExpectedBlobCells {
+            z: vec![],
+            y: vec![],
+            versioned_hashes: vec![],
+            blobs_are_used: vec![],
+            calldata_hash: batch_pi_input[batch_pi_layout.range(PiField::DaCommitment)?].to_vec(),
+            chunk_tx_data_digests,
+        },
     };
 
     Ok(AssignedBatchHash {
@@ -405,6 +502,25 @@ pub(crate) fn assign_batch_hashes<const N_SNARKS: usize>(
     })
 }
 
+/// Check that the keccak table fits the keccak_f permutations required to hash the preimages.
+/// Each preimage is padded to a multiple of INPUT_LEN_PER_ROUND bytes with at least one byte, and
+/// each INPUT_LEN_PER_ROUND bytes take one keccak_f permutation.
+pub(crate) fn check_keccak_capacity(preimages: &[Vec<u8>]) -> Result<(), Error> {
+    let keccak_capacity =
+        KeccakCircuit::<Fr>::capacity_for_row(1 << LOG_DEGREE).expect("the keccak table has rows");
+    let num_keccak_f = preimages
+        .iter()
+        .map(|preimage| preimage.len() / INPUT_LEN_PER_ROUND + 1)
+        .sum::<usize>();
+    if num_keccak_f > keccak_capacity {
+        return Err(Error::AssertionFailure(format!(
+            "{num_keccak_f} keccak_f permutations exceed the keccak table capacity of \
+             {keccak_capacity} at k = {LOG_DEGREE}"
+        )));
+    }
+    Ok(())
+}
+
 /// assign hash table
 pub(crate) fn assign_keccak_table(
     config: &KeccakCircuitConfig<Fr>,
@@ -412,6 +528,7 @@ pub(crate) fn assign_keccak_table(
     challenges: Challenges<Value<Fr>>,
     preimages: &[Vec<u8>],
 ) -> Result<(), Error> {
+    check_keccak_capacity(preimages)?;
     let keccak_capacity = KeccakCircuit::<Fr>::capacity_for_row(1 << LOG_DEGREE);
 
     let timer = start_timer!(|| ("multi keccak").to_string());
@@ -458,10 +575,47 @@ pub(crate) fn assign_keccak_table(
 // 5. batch and all its chunks use a same chain id
 fn copy_constraints<const N_SNARKS: usize>(
     layouter: &mut impl Layouter<Fr>,
+    chunk_pi_layout: &PiHashLayout,
+    batch_pi_layout: &PiHashLayout,
     hash_input_cells: &[Vec<AssignedCell<Fr, Fr>>],
 ) -> Result<(), Error> {
     let mut is_first_time = true;
 
+    // the fields shared by the batch_pi_hash and a chunk_pi_hash, as
+    // (name, byte range in batch_pi_hash, chunk index, byte range in chunk_pi_hash)
+    let shared_fields = [
+        (PiField::PrevStateRoot, 0),
+        (PiField::PostStateRoot, N_SNARKS - 1),
+        (PiField::WithdrawRoot, N_SNARKS - 1),
+    ]
+    .into_iter()
+    .map(|(field, chunk_index)| -> Result<_, Error> {
+        Ok((
+            format!("{field:?}"),
+            batch_pi_layout.range(field)?,
+            chunk_index,
+            chunk_pi_layout.range(field)?,
+        ))
+    })
+    .chain(
+        batch_pi_layout
+            .extra_ranges()
+            .into_iter()
+            .zip(chunk_pi_layout.extra_ranges())
+            .enumerate()
+            .map(|(i, (batch_range, chunk_range))| {
+                Ok((
+                    format!("extra field {i}"),
+                    batch_range,
+                    N_SNARKS - 1,
+                    chunk_range,
+                ))
+            }),
+    )
+    .collect::<Result<Vec<_>, Error>>()?;
+    let batch_chain_id = batch_pi_layout.range(PiField::ChainId)?;
+    let chunk_chain_id = chunk_pi_layout.range(PiField::ChainId)?;
+
     layouter
         .assign_region(
             || "copy constraints",
@@ -496,8 +650,7 @@ fn copy_constraints<const N_SNARKS: usize>(
                 //      chunk[k-1].post_state_root ||
                 //      chunk[k-1].withdraw_root ||
                 //      batch_data_hash ||
-                //      z ||
-                //      y
+                //      da_commitment
                 //   )
                 //
                 // chunk[i].piHash =
@@ -510,66 +663,33 @@ fn copy_constraints<const N_SNARKS: usize>(
                 //        chunk[i].tx_data_hash
                 //   )
                 //
-                // PREV_STATE_ROOT_INDEX, POST_STATE_ROOT_INDEX, WITHDRAW_ROOT_INDEX
-                // used below are byte positions for
-                // prev_state_root, post_state_root, withdraw_root
-                for i in 0..DIGEST_LEN {
-                    // 2.1 chunk[0].prev_state_root
-                    // sanity check
-                    assert_equal(
-                        &batch_pi_hash_preimage[i + PREV_STATE_ROOT_INDEX],
-                        &chunk_pi_hash_preimages[0][i + PREV_STATE_ROOT_INDEX],
-                        format!(
-                            "chunk and batch's prev_state_root do not match: {:?} {:?}",
-                            &batch_pi_hash_preimage[i + PREV_STATE_ROOT_INDEX].value(),
-                            &chunk_pi_hash_preimages[0][i + PREV_STATE_ROOT_INDEX].value(),
-                        )
-                        .as_str(),
-                    )?;
-                    region.constrain_equal(
-                        batch_pi_hash_preimage[i + PREV_STATE_ROOT_INDEX].cell(),
-                        chunk_pi_hash_preimages[0][i + PREV_STATE_ROOT_INDEX].cell(),
-                    )?;
-                    // 2.2 chunk[k-1].post_state_root
-                    // sanity check
-                    assert_equal(
-                        &batch_pi_hash_preimage[i + POST_STATE_ROOT_INDEX],
-                        &chunk_pi_hash_preimages[N_SNARKS - 1][i + POST_STATE_ROOT_INDEX],
-                        format!(
-                            "chunk and batch's post_state_root do not match: {:?} {:?}",
-                            &batch_pi_hash_preimage[i + POST_STATE_ROOT_INDEX].value(),
-                            &chunk_pi_hash_preimages[N_SNARKS - 1][i + POST_STATE_ROOT_INDEX]
-                                .value(),
-                        )
-                        .as_str(),
-                    )?;
-                    region.constrain_equal(
-                        batch_pi_hash_preimage[i + POST_STATE_ROOT_INDEX].cell(),
-                        chunk_pi_hash_preimages[N_SNARKS - 1][i + POST_STATE_ROOT_INDEX].cell(),
-                    )?;
-                    // 2.3 chunk[k-1].withdraw_root
-                    assert_equal(
-                        &batch_pi_hash_preimage[i + WITHDRAW_ROOT_INDEX],
-                        &chunk_pi_hash_preimages[N_SNARKS - 1][i + WITHDRAW_ROOT_INDEX],
-                        format!(
-                            "chunk and batch's withdraw_root do not match: {:?} {:?}",
-                            &batch_pi_hash_preimage[i + WITHDRAW_ROOT_INDEX].value(),
-                            &chunk_pi_hash_preimages[N_SNARKS - 1][i + WITHDRAW_ROOT_INDEX].value(),
-                        )
-                        .as_str(),
-                    )?;
-                    region.constrain_equal(
-                        batch_pi_hash_preimage[i + WITHDRAW_ROOT_INDEX].cell(),
-                        chunk_pi_hash_preimages[N_SNARKS - 1][i + WITHDRAW_ROOT_INDEX].cell(),
-                    )?;
+                // for the default layouts. the byte positions of the fields, including the extra
+                // fields that the batch copies from chunk[k-1], are given by the pi hash layouts.
+                for (name, batch_range, chunk_index, chunk_range) in shared_fields.iter() {
+                    for (lhs, rhs) in batch_pi_hash_preimage[batch_range.clone()]
+                        .iter()
+                        .zip_eq(chunk_pi_hash_preimages[*chunk_index][chunk_range.clone()].iter())
+                    {
+                        // sanity check
+                        assert_equal(
+                            lhs,
+                            rhs,
+                            format!(
+                                "chunk_{chunk_index} and batch's {name} do not match: {:?} {:?}",
+                                &lhs.value(),
+                                &rhs.value(),
+                            )
+                            .as_str(),
+                        )?;
+                        region.constrain_equal(lhs.cell(), rhs.cell())?;
+                    }
                 }
 
                 // 5 assert hashes use a same chain id
                 for (i, chunk_pi_hash_preimage) in chunk_pi_hash_preimages.iter().enumerate() {
-                    for (lhs, rhs) in batch_pi_hash_preimage
+                    for (lhs, rhs) in batch_pi_hash_preimage[batch_chain_id.clone()]
                         .iter()
-                        .take(CHAIN_ID_LEN)
-                        .zip(chunk_pi_hash_preimage.iter().take(CHAIN_ID_LEN))
+                        .zip_eq(chunk_pi_hash_preimage[chunk_chain_id.clone()].iter())
                     {
                         // sanity check
                         assert_equal(
@@ -600,20 +720,39 @@ fn copy_constraints<const N_SNARKS: usize>(
 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
 // padded
 // 7. the hash input length are correct
-// - hashes[0] has 200 bytes
-// - hashes[1..N_SNARKS+1] has 168 bytes input
+// - hashes[0] has the length of the batch pi hash layout, i.e. 136 + 96 * num_blobs bytes for blob
+//   DA and 168 bytes for calldata DA by default
+// - hashes[1..N_SNARKS+1] have the length of the chunk pi hash layout, 168 bytes by default
 // - batch's data_hash length is 32 * number_of_valid_snarks
 // 8. batch data hash is correct w.r.t. its RLCs
 // 9. is_final_cells are set correctly
-#[allow(clippy::type_complexity)]
+// 10. the blobs used by the batch are the first num_blobs ones, and only those are committed to in
+// the batch pi hash
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub(crate) fn conditional_constraints<const N_SNARKS: usize>(
     rlc_config: &RlcConfig,
     layouter: &mut impl Layouter<Fr>,
     challenges: Challenges<Value<Fr>>,
     chunks_are_valid: &[bool],
     num_valid_chunks: usize,
+    data_availability: DataAvailability,
+    num_blobs: usize,
+    chunk_pi_layout: &PiHashLayout,
+    batch_pi_layout: &PiHashLayout,
+    padded_batch_pi_preimage: &[u8],
     preimages: &[Vec<u8>],
 ) -> Result<ExtractedHashCells<N_SNARKS>, Error> {
+    assert_eq!(
+        padded_batch_pi_preimage.len(),
+        batch_pi_layout.preimage_len(),
+        "batch pi hash preimage does not match its layout"
+    );
+    let batch_da_commitment = batch_pi_layout.range(PiField::DaCommitment)?;
+    let batch_data_hash = batch_pi_layout.range(PiField::DataHash)?;
+    let chunk_data_hash = chunk_pi_layout.range(PiField::DataHash)?;
+    let chunk_prev_state_root = chunk_pi_layout.range(PiField::PrevStateRoot)?.start;
+    let chunk_post_state_root = chunk_pi_layout.range(PiField::PostStateRoot)?.start;
+
     layouter
         .assign_region(
             || "rlc conditional constraints",
@@ -656,6 +795,46 @@ pub(crate) fn conditional_constraints<const N_SNARKS: usize>(
 
                 log::trace!("number of valid chunks: {:?}", num_valid_snarks.value());
 
+                // ====================================================
+                // build the flags to indicate the blobs are used or not, and those of the batch
+                // pi hash preimage bytes: the bytes committing to an unused blob are not hashed
+                // ====================================================
+                let one = {
+                    let one = rlc_config.load_private(&mut region, &Fr::one(), &mut offset)?;
+                    let one_cell = rlc_config.one_cell(one.cell().region_index);
+                    region.constrain_equal(one.cell(), one_cell)?;
+                    one
+                };
+
+                let blobs_are_used = match data_availability {
+                    DataAvailability::Blob => {
+                        let blobs_are_used = (0..MAX_BLOBS_PER_BATCH)
+                            .map(|i| -> Result<_, halo2_proofs::plonk::Error> {
+                                rlc_config.load_private(
+                                    &mut region,
+                                    &Fr::from((i < num_blobs) as u64),
+                                    &mut offset,
+                                )
+                            })
+                            .collect::<Result<Vec<_>, halo2_proofs::plonk::Error>>()?;
+                        let num_blobs =
+                            constrain_flags(rlc_config, &mut region, &blobs_are_used, &mut offset)?;
+                        log::trace!("number of blobs: {:?}", num_blobs.value());
+                        blobs_are_used
+                    }
+                    DataAvailability::Calldata => vec![],
+                };
+
+                let batch_pi_byte_flags = (0..batch_pi_layout.preimage_len())
+                    .map(|i| {
+                        if blobs_are_used.is_empty() || !batch_da_commitment.contains(&i) {
+                            one.clone()
+                        } else {
+                            blobs_are_used[(i - batch_da_commitment.start) / BATCH_BLOB_LEN].clone()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
                 // ====================================================
                 // extract the hash cells from the witnesses and check against the lookup table
                 // ====================================================
@@ -667,9 +846,12 @@ pub(crate) fn conditional_constraints<const N_SNARKS: usize>(
                     &evm_word_challenge,
                     num_valid_chunks,
                     preimages,
+                    padded_batch_pi_preimage,
+                    &batch_pi_byte_flags,
                     &chunk_is_valid_cell32s,
                     num_valid_snarks,
                     chunks_are_padding.clone(),
+                    blobs_are_used,
                 )?;
                 assigned_hash_cells.check_against_lookup_table(
                     rlc_config,
@@ -707,9 +889,7 @@ pub(crate) fn conditional_constraints<const N_SNARKS: usize>(
                 // compare it with batchDataHash's input RLC
                 let batch_data_hash_rlc = rlc_config.rlc(
                     &mut region,
-                    batch_pi_hash_preimage
-                        [CHUNK_DATA_HASH_INDEX..CHUNK_DATA_HASH_INDEX + DIGEST_LEN]
-                        .as_ref(),
+                    batch_pi_hash_preimage[batch_data_hash.clone()].as_ref(),
                     &evm_word_challenge,
                     &mut offset,
                 )?;
@@ -749,9 +929,7 @@ pub(crate) fn conditional_constraints<const N_SNARKS: usize>(
                     let batch_data_hash_reconstructed = chunk_pi_hash_preimages
                         .iter()
                         .flat_map(|&chunk_pi_hash_preimage| {
-                            chunk_pi_hash_preimage
-                                [CHUNK_DATA_HASH_INDEX..CHUNK_DATA_HASH_INDEX + DIGEST_LEN]
-                                .iter()
+                            chunk_pi_hash_preimage[chunk_data_hash.clone()].iter()
                         })
                         .cloned()
                         .collect::<Vec<_>>();
@@ -790,21 +968,21 @@ pub(crate) fn conditional_constraints<const N_SNARKS: usize>(
                     for j in 0..DIGEST_LEN {
                         // sanity check
                         assert_conditional_equal(
-                            &chunk_pi_hash_preimages[i + 1][PREV_STATE_ROOT_INDEX + j],
-                            &chunk_pi_hash_preimages[i][POST_STATE_ROOT_INDEX + j],
+                            &chunk_pi_hash_preimages[i + 1][chunk_prev_state_root + j],
+                            &chunk_pi_hash_preimages[i][chunk_post_state_root + j],
                             &chunk_is_valid_cells[i + 1],
                             format!(
                                 "chunk_{i} is not continuous: {:?} {:?} {:?}",
-                                &chunk_pi_hash_preimages[i + 1][PREV_STATE_ROOT_INDEX + j].value(),
-                                &chunk_pi_hash_preimages[i][POST_STATE_ROOT_INDEX + j].value(),
+                                &chunk_pi_hash_preimages[i + 1][chunk_prev_state_root + j].value(),
+                                &chunk_pi_hash_preimages[i][chunk_post_state_root + j].value(),
                                 &chunk_is_valid_cells[i + 1].value(),
                             )
                             .as_str(),
                         )?;
                         rlc_config.conditional_enforce_equal(
                             &mut region,
-                            &chunk_pi_hash_preimages[i + 1][PREV_STATE_ROOT_INDEX + j],
-                            &chunk_pi_hash_preimages[i][POST_STATE_ROOT_INDEX + j],
+                            &chunk_pi_hash_preimages[i + 1][chunk_prev_state_root + j],
+                            &chunk_pi_hash_preimages[i][chunk_post_state_root + j],
                             &chunk_is_valid_cells[i + 1],
                             &mut offset,
                         )?;
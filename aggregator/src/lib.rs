@@ -18,6 +18,8 @@ mod constants;
 mod core;
 /// Parameters for compression circuit
 mod param;
+/// Layouts of the chunk and batch public input hashes
+mod pi_layout;
 /// utilities
 mod util;
 
@@ -26,9 +28,13 @@ mod tests;
 
 pub use self::core::extract_proof_and_instances_with_pairing_check;
 pub use aggregation::*;
-pub use batch::BatchHash;
+pub use batch::{BatchHash, BatchHashError, DataAvailability};
+pub use blob::{
+    load_kzg_settings, BatchData, BlobDecodeError, BlobKzgData, BLOB_WIDTH, MAX_BLOBS_PER_BATCH,
+};
 pub use chunk::ChunkHash;
 pub use compression::*;
 pub use constants::MAX_AGG_SNARKS;
 pub(crate) use constants::*;
 pub use param::*;
+pub use pi_layout::{PiField, PiHashLayout, PiHashLayoutError};
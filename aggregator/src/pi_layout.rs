@@ -0,0 +1,483 @@
+//! This module implements the declarative layouts of the chunk and batch public input hash
+//! preimages, so that forks of the rollup contract committing to other fields can be aggregated.
+
+use std::{fmt, ops::Range};
+
+use itertools::Itertools;
+
+use crate::{
+    batch::DataAvailability,
+    blob::MAX_BLOBS_PER_BATCH,
+    constants::{BATCH_BLOB_LEN, CHAIN_ID_LEN, DIGEST_LEN},
+};
+
+/// A field of a chunk or batch public input hash preimage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PiField {
+    /// The chain id, 8 bytes.
+    ChainId,
+    /// The state root before the chunk or batch.
+    PrevStateRoot,
+    /// The state root after the chunk or batch.
+    PostStateRoot,
+    /// The withdraw root after the chunk or batch.
+    WithdrawRoot,
+    /// The chunk data hash for a chunk, the batch data hash for a batch.
+    DataHash,
+    /// The hash of the chunk's L2 tx bytes. Chunk layouts only.
+    TxDataHash,
+    /// The commitment to the batch data as per its [`DataAvailability`]. Batch layouts only.
+    DaCommitment,
+    /// A fork specific field of len bytes, e.g. an L1 block hash or the sequencer address.
+    ///
+    /// The extra field of a chunk with the given index takes the value at that index of the
+    /// chunk's `extra_public_inputs`. The extra field of a batch with the given index is copied
+    /// from the extra field of its last chunk with the same index, like the post state root and
+    /// the withdraw root. The extra fields of a layout are indexed from 0, in any order.
+    Extra { index: usize, len: usize },
+}
+
+/// Errors encountered while building a public input hash layout, or a preimage as per a layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PiHashLayoutError {
+    /// The layout does not contain the field.
+    MissingField { field: PiField },
+    /// The layout contains the field more than once.
+    DuplicateField { field: PiField },
+    /// The field is not allowed in the layout, e.g. a DA commitment in a chunk layout.
+    UnexpectedField { field: PiField },
+    /// The extra field has a length of 0.
+    EmptyExtraField { index: usize },
+    /// The indices of the extra fields are not 0, 1, ... in some order.
+    InvalidExtraIndices { indices: Vec<usize> },
+    /// The DA commitment does not have the length of the data availability.
+    DaCommitmentMismatch {
+        data_availability: DataAvailability,
+        len: usize,
+    },
+    /// The extra fields of the batch are not those of its chunks with the same indices.
+    BatchExtraFieldsMismatch,
+    /// The lengths of the extra field values do not match those of the layout's extra fields.
+    ExtraValuesMismatch {
+        expected: Vec<usize>,
+        actual: Vec<usize>,
+    },
+    /// The value of the field does not have the length of the field.
+    FieldLengthMismatch {
+        field: PiField,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for PiHashLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField { field } => write!(f, "pi hash does not contain {field:?}"),
+            Self::DuplicateField { field } => {
+                write!(f, "pi hash contains {field:?} more than once")
+            }
+            Self::UnexpectedField { field } => write!(f, "pi hash cannot contain {field:?}"),
+            Self::EmptyExtraField { index } => write!(f, "extra pi hash field {index} is empty"),
+            Self::InvalidExtraIndices { indices } => {
+                write!(
+                    f,
+                    "extra pi hash fields are not indexed from 0: {indices:?}"
+                )
+            }
+            Self::DaCommitmentMismatch {
+                data_availability,
+                len,
+            } => write!(
+                f,
+                "DA commitment of {len} bytes does not match {data_availability:?} DA"
+            ),
+            Self::BatchExtraFieldsMismatch => {
+                write!(
+                    f,
+                    "batch pi hash extra fields do not match those of the chunks"
+                )
+            }
+            Self::ExtraValuesMismatch { expected, actual } => write!(
+                f,
+                "extra pi hash values of {actual:?} bytes, expected {expected:?} bytes"
+            ),
+            Self::FieldLengthMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "value of {field:?} has {actual} bytes, expected {expected} bytes"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PiHashLayoutError {}
+
+impl From<PiHashLayoutError> for snark_verifier::Error {
+    fn from(e: PiHashLayoutError) -> Self {
+        Self::AssertionFailure(e.to_string())
+    }
+}
+
+/// The layout of a chunk or batch public input hash preimage, i.e. the fields it is the
+/// concatenation of, in order.
+///
+/// The default layouts are those of the L1 rollup contract:
+/// - chunk: chain_id || prev_state_root || post_state_root || withdraw_root || chunk_data_hash ||
+///   chunk_tx_data_hash
+/// - batch: chain_id || prev_state_root || post_state_root || withdraw_root || batch_data_hash ||
+///   da_commitment
+///
+/// Note that a custom chunk layout also requires the chunk circuit to expose the matching public
+/// input hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PiHashLayout {
+    /// The fields, along with their length in bytes.
+    fields: Vec<(PiField, usize)>,
+}
+
+impl PiHashLayout {
+    /// Build a chunk public input hash layout. It must contain each of the chain id, the state
+    /// roots, the withdraw root, the data hash and the tx data hash exactly once.
+    pub fn chunk(fields: &[PiField]) -> Result<Self, PiHashLayoutError> {
+        if fields.contains(&PiField::DaCommitment) {
+            return Err(PiHashLayoutError::UnexpectedField {
+                field: PiField::DaCommitment,
+            });
+        }
+        Self::new(fields, PiField::TxDataHash, 0)
+    }
+
+    /// Build a batch public input hash layout for the given data availability. It must contain
+    /// each of the chain id, the state roots, the withdraw root, the data hash and the DA
+    /// commitment exactly once.
+    pub fn batch(
+        fields: &[PiField],
+        data_availability: DataAvailability,
+    ) -> Result<Self, PiHashLayoutError> {
+        if fields.contains(&PiField::TxDataHash) {
+            return Err(PiHashLayoutError::UnexpectedField {
+                field: PiField::TxDataHash,
+            });
+        }
+        Self::new(
+            fields,
+            PiField::DaCommitment,
+            da_commitment_len(data_availability),
+        )
+    }
+
+    /// The chunk public input hash layout of the L1 rollup contract.
+    pub fn default_chunk() -> Self {
+        Self::chunk(&[
+            PiField::ChainId,
+            PiField::PrevStateRoot,
+            PiField::PostStateRoot,
+            PiField::WithdrawRoot,
+            PiField::DataHash,
+            PiField::TxDataHash,
+        ])
+        .expect("default chunk pi hash layout is valid")
+    }
+
+    /// The batch public input hash layout of the L1 rollup contract.
+    pub fn default_batch(data_availability: DataAvailability) -> Self {
+        Self::batch(
+            &[
+                PiField::ChainId,
+                PiField::PrevStateRoot,
+                PiField::PostStateRoot,
+                PiField::WithdrawRoot,
+                PiField::DataHash,
+                PiField::DaCommitment,
+            ],
+            data_availability,
+        )
+        .expect("default batch pi hash layout is valid")
+    }
+
+    fn new(
+        fields: &[PiField],
+        required: PiField,
+        da_commitment_len: usize,
+    ) -> Result<Self, PiHashLayoutError> {
+        for field in [
+            PiField::ChainId,
+            PiField::PrevStateRoot,
+            PiField::PostStateRoot,
+            PiField::WithdrawRoot,
+            PiField::DataHash,
+            required,
+        ] {
+            match fields.iter().filter(|&&f| f == field).count() {
+                0 => return Err(PiHashLayoutError::MissingField { field }),
+                1 => {}
+                _ => return Err(PiHashLayoutError::DuplicateField { field }),
+            }
+        }
+
+        let mut extra_indices = Vec::new();
+        for &field in fields {
+            if let PiField::Extra { index, len } = field {
+                if len == 0 {
+                    return Err(PiHashLayoutError::EmptyExtraField { index });
+                }
+                extra_indices.push(index);
+            }
+        }
+        let mut sorted_indices = extra_indices.clone();
+        sorted_indices.sort_unstable();
+        if !sorted_indices.into_iter().eq(0..extra_indices.len()) {
+            return Err(PiHashLayoutError::InvalidExtraIndices {
+                indices: extra_indices,
+            });
+        }
+
+        Ok(Self {
+            fields: fields
+                .iter()
+                .map(|&field| {
+                    let len = match field {
+                        PiField::ChainId => CHAIN_ID_LEN,
+                        PiField::DaCommitment => da_commitment_len,
+                        PiField::Extra { len, .. } => len,
+                        _ => DIGEST_LEN,
+                    };
+                    (field, len)
+                })
+                .collect(),
+        })
+    }
+
+    /// The length of the preimage in bytes.
+    pub fn preimage_len(&self) -> usize {
+        self.fields.iter().map(|(_, len)| len).sum()
+    }
+
+    /// The byte offset of the given field in the preimage, if the layout contains it.
+    pub fn offset(&self, field: PiField) -> Option<usize> {
+        self.range(field).ok().map(|range| range.start)
+    }
+
+    /// The byte range of the given field in the preimage.
+    pub(crate) fn range(&self, field: PiField) -> Result<Range<usize>, PiHashLayoutError> {
+        self.ranges()
+            .find(|(f, _)| *f == field)
+            .map(|(_, range)| range)
+            .ok_or(PiHashLayoutError::MissingField { field })
+    }
+
+    /// The byte ranges of the extra fields in the preimage, by index.
+    pub(crate) fn extra_ranges(&self) -> Vec<Range<usize>> {
+        self.ranges()
+            .filter_map(|(field, range)| match field {
+                PiField::Extra { index, .. } => Some((index, range)),
+                _ => None,
+            })
+            .sorted_by_key(|(index, _)| *index)
+            .map(|(_, range)| range)
+            .collect()
+    }
+
+    /// The lengths of the extra fields, by index.
+    pub(crate) fn extra_lens(&self) -> Vec<usize> {
+        self.extra_ranges().iter().map(Range::len).collect()
+    }
+
+    /// Build the preimage, taking the value of the extra field with index i from
+    /// `extra_values[i]` and those of the other fields from `field_value`, which returns None for
+    /// the fields not allowed in the preimage.
+    pub(crate) fn build_preimage(
+        &self,
+        field_value: impl Fn(PiField) -> Option<Vec<u8>>,
+        extra_values: &[Vec<u8>],
+    ) -> Result<Vec<u8>, PiHashLayoutError> {
+        let extra_lens = self.extra_lens();
+        let extra_values_lens = extra_values.iter().map(Vec::len).collect::<Vec<_>>();
+        if extra_values_lens != extra_lens {
+            return Err(PiHashLayoutError::ExtraValuesMismatch {
+                expected: extra_lens,
+                actual: extra_values_lens,
+            });
+        }
+
+        let mut preimage = Vec::with_capacity(self.preimage_len());
+        for &(field, len) in self.fields.iter() {
+            let value = match field {
+                PiField::Extra { index, .. } => extra_values[index].clone(),
+                _ => field_value(field).ok_or(PiHashLayoutError::UnexpectedField { field })?,
+            };
+            if value.len() != len {
+                return Err(PiHashLayoutError::FieldLengthMismatch {
+                    field,
+                    expected: len,
+                    actual: value.len(),
+                });
+            }
+            preimage.extend(value);
+        }
+
+        Ok(preimage)
+    }
+
+    fn ranges(&self) -> impl Iterator<Item = (PiField, Range<usize>)> + '_ {
+        self.fields.iter().scan(0, |offset, &(field, len)| {
+            let range = *offset..*offset + len;
+            *offset += len;
+            Some((field, range))
+        })
+    }
+}
+
+/// The length of the DA commitment in the batch public input hash preimage, as laid out in the
+/// circuit:
+/// - z || y || versioned_hash, for each of the MAX_BLOBS_PER_BATCH blobs, for blob DA. Only the
+///   first num_blobs of them, i.e. the blobs used by the batch, are hashed.
+/// - calldata_hash, for calldata DA
+pub(crate) fn da_commitment_len(data_availability: DataAvailability) -> usize {
+    match data_availability {
+        DataAvailability::Blob => BATCH_BLOB_LEN * MAX_BLOBS_PER_BATCH,
+        DataAvailability::Calldata => DIGEST_LEN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layouts() {
+        let chunk = PiHashLayout::default_chunk();
+        assert_eq!(chunk.offset(PiField::PrevStateRoot), Some(8));
+        assert_eq!(chunk.offset(PiField::PostStateRoot), Some(40));
+        assert_eq!(chunk.offset(PiField::WithdrawRoot), Some(72));
+        assert_eq!(chunk.offset(PiField::DataHash), Some(104));
+        assert_eq!(chunk.offset(PiField::TxDataHash), Some(136));
+        assert_eq!(chunk.preimage_len(), 168);
+
+        let batch = PiHashLayout::default_batch(DataAvailability::Blob);
+        assert_eq!(batch.offset(PiField::DataHash), Some(104));
+        assert_eq!(batch.offset(PiField::DaCommitment), Some(136));
+        assert_eq!(batch.preimage_len(), 136 + 96 * MAX_BLOBS_PER_BATCH);
+        assert_eq!(
+            PiHashLayout::default_batch(DataAvailability::Calldata).preimage_len(),
+            168
+        );
+    }
+
+    #[test]
+    fn extra_fields() {
+        let layout = PiHashLayout::chunk(&[
+            PiField::ChainId,
+            PiField::PrevStateRoot,
+            PiField::Extra { index: 1, len: 20 },
+            PiField::PostStateRoot,
+            PiField::WithdrawRoot,
+            PiField::DataHash,
+            PiField::TxDataHash,
+            PiField::Extra { index: 0, len: 32 },
+            PiField::Extra { index: 2, len: 32 },
+        ])
+        .unwrap();
+        assert_eq!(layout.offset(PiField::PostStateRoot), Some(60));
+        assert_eq!(
+            layout.offset(PiField::Extra { index: 0, len: 32 }),
+            Some(188)
+        );
+        assert_eq!(
+            layout.offset(PiField::Extra { index: 2, len: 32 }),
+            Some(220)
+        );
+        assert_eq!(layout.extra_ranges(), vec![188..220, 40..60, 220..252]);
+        assert_eq!(layout.extra_lens(), vec![32, 20, 32]);
+
+        let preimage = layout
+            .build_preimage(
+                |field| Some(vec![1; layout.range(field).unwrap().len()]),
+                &[vec![0xaa; 32], vec![0xbb; 20], vec![0xcc; 32]],
+            )
+            .unwrap();
+        assert_eq!(preimage.len(), layout.preimage_len());
+        assert_eq!(preimage[40..60], [0xbb; 20]);
+        assert_eq!(preimage[188..220], [0xaa; 32]);
+        assert_eq!(preimage[220..], [0xcc; 32]);
+
+        assert_eq!(
+            layout.build_preimage(
+                |field| Some(vec![1; layout.range(field).unwrap().len()]),
+                &[]
+            ),
+            Err(PiHashLayoutError::ExtraValuesMismatch {
+                expected: vec![32, 20, 32],
+                actual: vec![],
+            })
+        );
+        assert_eq!(
+            layout.range(PiField::DaCommitment),
+            Err(PiHashLayoutError::MissingField {
+                field: PiField::DaCommitment
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_layouts() {
+        let fields = [
+            PiField::ChainId,
+            PiField::PrevStateRoot,
+            PiField::PostStateRoot,
+            PiField::WithdrawRoot,
+            PiField::DataHash,
+        ];
+        assert_eq!(
+            PiHashLayout::chunk(&fields),
+            Err(PiHashLayoutError::MissingField {
+                field: PiField::TxDataHash
+            })
+        );
+        assert_eq!(
+            PiHashLayout::chunk(&[&fields[..], &[PiField::TxDataHash, PiField::DataHash]].concat()),
+            Err(PiHashLayoutError::DuplicateField {
+                field: PiField::DataHash
+            })
+        );
+        assert_eq!(
+            PiHashLayout::batch(
+                &[&fields[..], &[PiField::DaCommitment, PiField::TxDataHash]].concat(),
+                DataAvailability::Blob
+            ),
+            Err(PiHashLayoutError::UnexpectedField {
+                field: PiField::TxDataHash
+            })
+        );
+        assert_eq!(
+            PiHashLayout::chunk(
+                &[
+                    &fields[..],
+                    &[PiField::TxDataHash, PiField::Extra { index: 0, len: 0 }]
+                ]
+                .concat()
+            ),
+            Err(PiHashLayoutError::EmptyExtraField { index: 0 })
+        );
+        assert_eq!(
+            PiHashLayout::chunk(
+                &[
+                    &fields[..],
+                    &[
+                        PiField::TxDataHash,
+                        PiField::Extra { index: 1, len: 32 },
+                        PiField::Extra { index: 1, len: 20 }
+                    ]
+                ]
+                .concat()
+            ),
+            Err(PiHashLayoutError::InvalidExtraIndices {
+                indices: vec![1, 1]
+            })
+        );
+    }
+}
@@ -3,12 +3,17 @@ use std::{fs, path::Path, process};
 use ark_std::{end_timer, start_timer, test_rng};
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr, poly::commitment::Params};
 use itertools::Itertools;
+use rand::Rng;
 use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
 use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, verify_snark_shplonk, CircuitExt};
 
 use crate::{
-    aggregation::AggregationCircuit, batch::BatchHash, constants::MAX_AGG_SNARKS, layer_0,
-    tests::mock_chunk::MockChunkCircuit, ChunkHash,
+    aggregation::AggregationCircuit,
+    batch::{BatchHash, DataAvailability},
+    constants::MAX_AGG_SNARKS,
+    layer_0,
+    tests::mock_chunk::MockChunkCircuit,
+    ChunkHash, PiField, PiHashLayout,
 };
 
 #[test]
@@ -16,7 +21,8 @@ fn test_max_agg_snarks_aggregation_circuit() {
     let k = 20;
 
     // This set up requires one round of keccak for chunk's data hash
-    let circuit: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit(2, k);
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit(2, k, DataAvailability::Blob);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
@@ -26,7 +32,8 @@ fn test_max_agg_snarks_aggregation_circuit() {
 fn test_2_snark_aggregation_circuit() {
     let k = 20;
 
-    let circuit: AggregationCircuit<2> = build_new_aggregation_circuit(1, k);
+    let circuit: AggregationCircuit<2> =
+        build_new_aggregation_circuit(1, k, DataAvailability::Blob);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
@@ -36,12 +43,142 @@ fn test_2_snark_aggregation_circuit() {
 fn test_14_snark_aggregation_circuit() {
     let k = 20;
 
-    let circuit: AggregationCircuit<14> = build_new_aggregation_circuit(12, k);
+    let circuit: AggregationCircuit<14> =
+        build_new_aggregation_circuit(12, k, DataAvailability::Blob);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
 }
 
+#[test]
+fn test_calldata_da_aggregation_circuit() {
+    let k = 20;
+
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit(2, k, DataAvailability::Calldata);
+    let instance = circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_extra_pi_fields_aggregation_circuit() {
+    let k = 20;
+
+    // a fork of the rollup contract that also commits to e.g. the L1 block hash and the
+    // sequencer address, the batch taking the L1 block hash of its last chunk
+    let chunk_pi_layout = PiHashLayout::chunk(&[
+        PiField::ChainId,
+        PiField::PrevStateRoot,
+        PiField::PostStateRoot,
+        PiField::WithdrawRoot,
+        PiField::Extra { index: 0, len: 32 },
+        PiField::DataHash,
+        PiField::TxDataHash,
+        PiField::Extra { index: 1, len: 20 },
+    ])
+    .unwrap();
+    let batch_pi_layout = PiHashLayout::batch(
+        &[
+            PiField::ChainId,
+            PiField::PrevStateRoot,
+            PiField::PostStateRoot,
+            PiField::WithdrawRoot,
+            PiField::DataHash,
+            PiField::Extra { index: 0, len: 32 },
+            PiField::DaCommitment,
+        ],
+        DataAvailability::Blob,
+    )
+    .unwrap();
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit_with_pi_layouts(
+        2,
+        k,
+        DataAvailability::Blob,
+        chunk_pi_layout,
+        batch_pi_layout,
+    );
+    let instance = circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_compressed_batch_aggregation_circuit() {
+    let k = 20;
+
+    // compressible batch data is zstd encoded in the blob
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit_with_tx_bytes(vec![vec![0xff; 1024]; 2], k);
+    assert!(circuit.batch_hash.is_compressed());
+    let instance = circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_uncompressed_batch_aggregation_circuit() {
+    let k = 20;
+
+    // incompressible batch data is put in the blob as-is
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit_with_tx_bytes(incompressible_tx_bytes(2, 1024), k);
+    assert!(!circuit.batch_hash.is_compressed());
+    let instance = circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_flipped_is_compressed_aggregation_circuit() {
+    let k = 20;
+
+    for chunks_tx_bytes in [vec![vec![0xff; 1024]; 2], incompressible_tx_bytes(2, 1024)] {
+        let mut circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+            build_new_aggregation_circuit_with_tx_bytes(chunks_tx_bytes, k);
+        let instance = circuit.instances();
+
+        // the blob payload no longer matches the blobs committed to in the public input
+        circuit.batch_hash.is_compressed = !circuit.batch_hash.is_compressed;
+        assert!(MockProver::<Fr>::run(k, &circuit, instance)
+            .map_or(true, |mock_prover| mock_prover.verify_par().is_err()));
+    }
+}
+
+#[test]
+fn test_mismatched_da_aggregation_circuit() {
+    // the batch pi hash commits to the calldata, the circuit to the blobs
+    let result = try_build_aggregation_circuit_with_tx_bytes::<MAX_AGG_SNARKS>(
+        vec![vec![0xff; 1024]; 2],
+        DataAvailability::Calldata,
+        DataAvailability::Blob,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_calldata_da_exceeding_keccak_capacity() {
+    // 128 KiB of tx bytes fit the keccak table for blob DA, but not once the calldata is also
+    // hashed for calldata DA
+    let chunks_tx_bytes = incompressible_tx_bytes(1, 1 << 17);
+    assert!(
+        try_build_aggregation_circuit_with_tx_bytes::<MAX_AGG_SNARKS>(
+            chunks_tx_bytes.clone(),
+            DataAvailability::Blob,
+            DataAvailability::Blob,
+        )
+        .is_ok()
+    );
+    assert!(
+        try_build_aggregation_circuit_with_tx_bytes::<MAX_AGG_SNARKS>(
+            chunks_tx_bytes,
+            DataAvailability::Calldata,
+            DataAvailability::Calldata,
+        )
+        .is_err()
+    );
+}
+
 #[ignore = "it takes too much time"]
 #[test]
 fn test_aggregation_circuit_all_possible_num_snarks() {
@@ -52,7 +189,8 @@ fn test_aggregation_circuit_all_possible_num_snarks() {
     for i in 1..=MAX_AGG_SNARKS {
         println!("{i} real chunks and {} padded chunks", MAX_AGG_SNARKS - i);
         // This set up requires one round of keccak for chunk's data hash
-        let circuit: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit(i, k);
+        let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+            build_new_aggregation_circuit(i, k, DataAvailability::Blob);
         let instance = circuit.instances();
         let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
         mock_prover.assert_satisfied_par();
@@ -73,7 +211,8 @@ fn test_aggregation_circuit_full() {
     fs::create_dir(path).unwrap();
 
     // This set up requires one round of keccak for chunk's data hash
-    let circuit: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit(2, k);
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit(2, k, DataAvailability::Blob);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
@@ -97,7 +236,8 @@ fn test_aggregation_circuit_full() {
     log::trace!("finished verification for circuit");
 
     // This set up requires two rounds of keccak for chunk's data hash
-    let circuit: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit(5, k);
+    let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit(5, k, DataAvailability::Blob);
     let snark = gen_snark_shplonk(&param, &pk, circuit, &mut rng, None::<String>);
     log::trace!("finished snark generation for circuit");
 
@@ -114,11 +254,13 @@ fn test_aggregation_circuit_full() {
 fn test_aggregation_circuit_variadic() {
     let k = 20;
 
-    let circuit1: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit(5, k);
+    let circuit1: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit(5, k, DataAvailability::Blob);
     let instance1 = circuit1.instances();
     let prover1 = MockProver::<Fr>::run(k, &circuit1, instance1).unwrap();
 
-    let circuit2: AggregationCircuit<MAX_AGG_SNARKS> = build_new_aggregation_circuit(10, k);
+    let circuit2: AggregationCircuit<MAX_AGG_SNARKS> =
+        build_new_aggregation_circuit(10, k, DataAvailability::Blob);
     let instance2 = circuit2.instances();
     let prover2 = MockProver::<Fr>::run(k, &circuit2, instance2).unwrap();
 
@@ -127,18 +269,115 @@ fn test_aggregation_circuit_variadic() {
 }
 
 fn build_new_aggregation_circuit<const N_SNARKS: usize>(
+    num_real_chunks: usize,
+    k: u32,
+    data_availability: DataAvailability,
+) -> AggregationCircuit<N_SNARKS> {
+    build_new_aggregation_circuit_with_pi_layouts(
+        num_real_chunks,
+        k,
+        data_availability,
+        PiHashLayout::default_chunk(),
+        PiHashLayout::default_batch(data_availability),
+    )
+}
+
+fn build_new_aggregation_circuit_with_pi_layouts<const N_SNARKS: usize>(
     num_real_chunks: usize,
     _k: u32,
+    data_availability: DataAvailability,
+    chunk_pi_layout: PiHashLayout,
+    batch_pi_layout: PiHashLayout,
+) -> AggregationCircuit<N_SNARKS> {
+    let mut rng = test_rng();
+    let chunks_without_padding = (0..num_real_chunks)
+        .map(|_| {
+            let mut chunk = ChunkHash::mock_random_chunk_hash_for_testing(&mut rng);
+            chunk.extra_public_inputs = chunk_pi_layout
+                .extra_lens()
+                .into_iter()
+                .map(|len| (0..len).map(|_| rng.gen()).collect())
+                .collect();
+            chunk
+        })
+        .collect_vec();
+
+    build_aggregation_circuit_for_chunks(
+        chunks_without_padding,
+        data_availability,
+        chunk_pi_layout,
+        batch_pi_layout,
+    )
+}
+
+fn build_new_aggregation_circuit_with_tx_bytes<const N_SNARKS: usize>(
+    chunks_tx_bytes: Vec<Vec<u8>>,
+    _k: u32,
+) -> AggregationCircuit<N_SNARKS> {
+    try_build_aggregation_circuit_with_tx_bytes(
+        chunks_tx_bytes,
+        DataAvailability::Blob,
+        DataAvailability::Blob,
+    )
+    .unwrap()
+}
+
+fn try_build_aggregation_circuit_with_tx_bytes<const N_SNARKS: usize>(
+    chunks_tx_bytes: Vec<Vec<u8>>,
+    batch_data_availability: DataAvailability,
+    circuit_data_availability: DataAvailability,
+) -> Result<AggregationCircuit<N_SNARKS>, snark_verifier::Error> {
+    let mut rng = test_rng();
+    let chunks_without_padding = chunks_tx_bytes
+        .into_iter()
+        .map(|tx_bytes| {
+            let mut chunk = ChunkHash::mock_random_chunk_hash_for_testing(&mut rng);
+            chunk.tx_bytes = tx_bytes;
+            chunk
+        })
+        .collect_vec();
+
+    try_build_aggregation_circuit_for_chunks(
+        chunks_without_padding,
+        batch_data_availability,
+        circuit_data_availability,
+        PiHashLayout::default_chunk(),
+        PiHashLayout::default_batch(batch_data_availability),
+    )
+}
+
+fn build_aggregation_circuit_for_chunks<const N_SNARKS: usize>(
+    chunks_without_padding: Vec<ChunkHash>,
+    data_availability: DataAvailability,
+    chunk_pi_layout: PiHashLayout,
+    batch_pi_layout: PiHashLayout,
 ) -> AggregationCircuit<N_SNARKS> {
+    try_build_aggregation_circuit_for_chunks(
+        chunks_without_padding,
+        data_availability,
+        data_availability,
+        chunk_pi_layout,
+        batch_pi_layout,
+    )
+    .unwrap()
+}
+
+/// Build the aggregation circuit for a batch made available as per batch_data_availability, the
+/// circuit being configured for circuit_data_availability.
+fn try_build_aggregation_circuit_for_chunks<const N_SNARKS: usize>(
+    mut chunks_without_padding: Vec<ChunkHash>,
+    batch_data_availability: DataAvailability,
+    circuit_data_availability: DataAvailability,
+    chunk_pi_layout: PiHashLayout,
+    batch_pi_layout: PiHashLayout,
+) -> Result<AggregationCircuit<N_SNARKS>, snark_verifier::Error> {
     // inner circuit: Mock circuit
     let k0 = 8;
 
-    let mut rng = test_rng();
+    let rng = test_rng();
     let params = gen_srs(k0);
 
-    let mut chunks_without_padding = (0..num_real_chunks)
-        .map(|_| ChunkHash::mock_random_chunk_hash_for_testing(&mut rng))
-        .collect_vec();
+    let num_real_chunks = chunks_without_padding.len();
     for i in 0..num_real_chunks - 1 {
         chunks_without_padding[i + 1].prev_state_root = chunks_without_padding[i].post_state_root;
     }
@@ -157,7 +396,9 @@ fn build_new_aggregation_circuit<const N_SNARKS: usize>(
         let circuits = chunks_with_padding
             .iter()
             .take(num_real_chunks)
-            .map(|chunk| MockChunkCircuit::new(true, chunk.clone()))
+            .map(|chunk| {
+                MockChunkCircuit::new_with_pi_layout(true, chunk.clone(), chunk_pi_layout.clone())
+            })
             .collect_vec();
         circuits
             .iter()
@@ -176,13 +417,36 @@ fn build_new_aggregation_circuit<const N_SNARKS: usize>(
     // ==========================
     // batch
     // ==========================
-    let batch_hash = BatchHash::construct(&chunks_with_padding);
+    let batch_hash = BatchHash::construct_with_pi_layouts(
+        &chunks_with_padding,
+        batch_data_availability,
+        chunk_pi_layout.clone(),
+        batch_pi_layout.clone(),
+    );
 
     AggregationCircuit::new(
         &params,
         [real_snarks, padded_snarks].concat().as_ref(),
         rng,
         batch_hash,
+        circuit_data_availability,
+        chunk_pi_layout,
+        batch_pi_layout,
     )
-    .unwrap()
+}
+
+/// The tx bytes of the given number of chunks, that zstd encoding does not make any smaller.
+fn incompressible_tx_bytes(num_chunks: usize, chunk_len: usize) -> Vec<Vec<u8>> {
+    (0..num_chunks)
+        .map(|i| {
+            (0..chunk_len)
+                .scan(0x9e3779b9u32 + i as u32, |state, _| {
+                    *state ^= *state << 13;
+                    *state ^= *state >> 17;
+                    *state ^= *state << 5;
+                    Some(*state as u8)
+                })
+                .collect()
+        })
+        .collect()
 }
@@ -2,9 +2,9 @@ use crate::{
     aggregation::{
         AssignedBarycentricEvaluationConfig, BarycentricEvaluationConfig, BlobDataConfig, RlcConfig,
     },
-    blob::{BatchData, PointEvaluationAssignments, N_BYTES_U256},
+    blob::{BatchData, N_BLOB_BYTES, N_BYTES_U256},
     param::ConfigParams,
-    BatchDataConfig, MAX_AGG_SNARKS,
+    BatchDataConfig, DataAvailability, MAX_AGG_SNARKS,
 };
 use halo2_base::{
     gates::range::{RangeConfig, RangeStrategy},
@@ -105,17 +105,19 @@ impl Circuit<Fr> for BlobCircuit {
     ) -> Result<(), Error> {
         let challenge_values = config.challenges.values(&layouter);
 
-        config
-            .keccak_table
-            .dev_load(&mut layouter, &self.data.preimages(), &challenge_values)?;
+        config.keccak_table.dev_load(
+            &mut layouter,
+            &self.data.preimages(DataAvailability::Blob),
+            &challenge_values,
+        )?;
 
         let mut first_pass = halo2_base::SKIP_FIRST_PASS;
         let barycentric_assignments = layouter.assign_region(
             || "barycentric config",
-            |region| -> Result<AssignedBarycentricEvaluationConfig, Error> {
+            |region| -> Result<Vec<AssignedBarycentricEvaluationConfig>, Error> {
                 if first_pass {
                     first_pass = false;
-                    return Ok(AssignedBarycentricEvaluationConfig::default());
+                    return Ok(vec![]);
                 }
 
                 let gate = &config.barycentric.scalar.range.gate;
@@ -128,13 +130,8 @@ impl Circuit<Fr> for BlobCircuit {
                     },
                 );
 
-                let point_eval = PointEvaluationAssignments::from(&self.data);
-                Ok(config.barycentric.assign(
-                    &mut ctx,
-                    &point_eval.coefficients,
-                    point_eval.challenge_digest,
-                    point_eval.evaluation,
-                ))
+                let point_evaluations = self.data.get_point_evaluation_assignments();
+                Ok(config.barycentric.assign(&mut ctx, &point_evaluations))
             },
         )?;
 
@@ -166,7 +163,8 @@ impl Circuit<Fr> for BlobCircuit {
             challenge_values,
             &config.rlc,
             &self.data,
-            &barycentric_assignments.barycentric_assignments,
+            DataAvailability::Blob,
+            &barycentric_assignments,
         )?;
 
         layouter.assign_region(
@@ -176,13 +174,15 @@ impl Circuit<Fr> for BlobCircuit {
                     &mut region,
                     challenge_values,
                     &self.data,
+                    DataAvailability::Blob,
                 )?;
                 let assigned_batch_data_export = config.batch_data_config.assign_internal_checks(
                     &mut region,
                     challenge_values,
                     &config.rlc,
                     &chunks_are_padding,
-                    &barycentric_assignments.barycentric_assignments,
+                    DataAvailability::Blob,
+                    &barycentric_assignments,
                     &assigned_rows,
                 )?;
 
@@ -266,6 +266,15 @@ fn blob_circuit_completeness() {
         .collect();
     let empty_chunk_followed_by_nonempty_chunk = vec![vec![], vec![3, 100, 24, 30]];
     let nonempty_chunk_followed_by_empty_chunk = vec![vec![3, 100, 24, 30], vec![]];
+    // pseudo-random bytes that do not compress, so that the uncompressed bytes span two blobs.
+    let two_blobs = vec![(0..N_BLOB_BYTES + 1000)
+        .scan(0x9e3779b9u32, |state, _| {
+            *state ^= *state << 13;
+            *state ^= *state >> 17;
+            *state ^= *state << 5;
+            Some(*state as u8)
+        })
+        .collect()];
     let empty_and_nonempty_chunks = vec![
         vec![3, 100, 24, 30],
         vec![],
@@ -288,6 +297,7 @@ fn blob_circuit_completeness() {
         nonempty_chunk_followed_by_empty_chunk,
         empty_and_nonempty_chunks,
         all_empty_except_last,
+        two_blobs,
     ] {
         assert_eq!(check_data(BatchData::from(&blob)), Ok(()), "{:?}", blob);
     }
@@ -310,6 +320,14 @@ fn generic_batch_data_is_valid() {
     assert_eq!(check_data(generic_batch_data()), Ok(()));
 }
 
+#[test]
+fn uncompressed_batch_data_is_valid() {
+    let mut batch_data = generic_batch_data();
+    assert!(batch_data.is_compressed);
+    batch_data.is_compressed = false;
+    assert_eq!(check_data(batch_data), Ok(()));
+}
+
 #[test]
 fn inconsistent_chunk_size() {
     let mut blob_data = generic_batch_data();
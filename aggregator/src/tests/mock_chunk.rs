@@ -13,7 +13,7 @@ use zkevm_circuits::{table::KeccakTable, util::Challenges};
 
 use crate::{
     constants::{ACC_LEN, DIGEST_LEN},
-    ChunkHash, RlcConfig, LOG_DEGREE,
+    ChunkHash, PiHashLayout, RlcConfig, LOG_DEGREE,
 };
 
 /// This config is used to compute RLCs for bytes.
@@ -27,7 +27,7 @@ pub struct MockConfig {
     pub(crate) instance: Column<Instance>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 /// A mock chunk circuit
 ///
 /// This mock chunk circuit simulates a zkEVM circuit.
@@ -37,13 +37,30 @@ pub(crate) struct MockChunkCircuit {
     // This circuit has an accumulator if it has already gone through compression
     pub(crate) has_accumulator: bool,
     pub(crate) chunk: ChunkHash,
+    // the layout of the public input hash
+    pub(crate) chunk_pi_layout: PiHashLayout,
+}
+
+impl Default for MockChunkCircuit {
+    fn default() -> Self {
+        Self::new(false, ChunkHash::default())
+    }
 }
 
 impl MockChunkCircuit {
     pub(crate) fn new(has_accumulator: bool, chunk: ChunkHash) -> Self {
+        Self::new_with_pi_layout(has_accumulator, chunk, PiHashLayout::default_chunk())
+    }
+
+    pub(crate) fn new_with_pi_layout(
+        has_accumulator: bool,
+        chunk: ChunkHash,
+        chunk_pi_layout: PiHashLayout,
+    ) -> Self {
         MockChunkCircuit {
             has_accumulator,
             chunk,
+            chunk_pi_layout,
         }
     }
 }
@@ -55,14 +72,14 @@ impl MockChunkCircuit {
         is_padding: bool,
     ) -> Self {
         let chunk = ChunkHash::mock_random_chunk_hash_for_testing(r);
-        Self {
+        Self::new(
             has_accumulator,
-            chunk: if is_padding {
+            if is_padding {
                 ChunkHash::mock_padded_chunk_hash_for_testing(&chunk)
             } else {
                 chunk
             },
-        }
+        )
     }
 }
 
@@ -110,10 +127,14 @@ impl Circuit<Fr> for MockChunkCircuit {
                 let mut index = 0;
                 let acc_len = if self.has_accumulator { ACC_LEN } else { 0 };
 
-                for byte in iter::repeat(0)
-                    .take(acc_len)
-                    .chain(self.chunk.public_input_hash().as_bytes().iter().copied())
-                {
+                for byte in iter::repeat(0).take(acc_len).chain(
+                    self.chunk
+                        .public_input_hash_with_layout(&self.chunk_pi_layout)
+                        .unwrap()
+                        .as_bytes()
+                        .iter()
+                        .copied(),
+                ) {
                     let cell = config
                         .rlc_config
                         .load_private(&mut region, &Fr::from(byte as u64), &mut index)
@@ -144,7 +165,14 @@ impl CircuitExt<Fr> for MockChunkCircuit {
         let acc_len = if self.has_accumulator { ACC_LEN } else { 0 };
         vec![iter::repeat(0)
             .take(acc_len)
-            .chain(self.chunk.public_input_hash().as_bytes().iter().copied())
+            .chain(
+                self.chunk
+                    .public_input_hash_with_layout(&self.chunk_pi_layout)
+                    .unwrap()
+                    .as_bytes()
+                    .iter()
+                    .copied(),
+            )
             .map(|x| Fr::from(x as u64))
             .collect()]
     }
@@ -51,6 +51,7 @@ impl Circuit<Fr> for DynamicHashCircuit {
             let keccak_circuit_config_args = KeccakCircuitConfigArgs {
                 keccak_table,
                 challenges: challenges_exprs,
+                num_rows_per_round: keccak_packed_multi::get_num_rows_per_round(),
             };
 
             KeccakCircuitConfig::new(meta, keccak_circuit_config_args)
@@ -20,9 +20,11 @@ use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
     operation::{self, CallContextField, Operation, RWCounter, StartOp, StorageOp, RW},
+    precompile::PrecompileCalls,
+    trace_compat::TracerFlavor,
 };
 pub use access::{Access, AccessSet, AccessValue, CodeSource};
-pub use block::{Block, BlockContext};
+pub use block::{Block, BlockContext, BLOCK_DUMP_VERSION};
 pub use builder_client::{build_state_code_db, BuilderClient};
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
@@ -37,19 +39,21 @@ use ethers_core::utils::keccak256;
 pub use execution::{
     BigModExp, CopyAccessList, CopyBytes, CopyDataType, CopyEvent, CopyEventStepsBuilder, CopyStep,
     EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, ExecState, ExecStep, ExpEvent, ExpStep,
-    NumberOrHash, PrecompileEvent, PrecompileEvents, N_BYTES_PER_PAIR, N_PAIRING_PER_OP, SHA256,
+    NumberOrHash, PrecompileEvent, PrecompileEvents, MAX_N_BYTES_EC_PAIRING_INPUT,
+    N_BYTES_PER_PAIR, N_PAIRING_PER_OP, SHA256,
 };
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 #[cfg(feature = "scroll")]
 use mpt_zktrie::state::ZktrieState;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 pub use transaction::{
     Transaction, TransactionContext, TxL1Fee, TX_L1_COMMIT_EXTRA_COST, TX_L1_FEE_PRECISION,
 };
 
 /// Setup parameters for ECC-related precompile calls.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PrecompileEcParams {
     /// Maximum number of EcAdd ops supported in one block.
     pub ec_add: usize,
@@ -57,6 +61,9 @@ pub struct PrecompileEcParams {
     pub ec_mul: usize,
     /// Maximum number of EcPairing ops supported in one block.
     pub ec_pairing: usize,
+    /// Maximum number of chunks of `N_PAIRING_PER_OP` pairs, over all the EcPairing ops of one
+    /// block.
+    pub ec_pairing_chunks: usize,
 }
 
 impl Default for PrecompileEcParams {
@@ -65,12 +72,69 @@ impl Default for PrecompileEcParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
+        }
+    }
+}
+
+/// Optional sub circuits of the Super Circuit. Chains that don't support the corresponding
+/// precompiles can leave them out, in which case calls to those precompiles are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubCircuitSelection {
+    /// SHA256 circuit, for the SHA256 precompile.
+    pub sha256: bool,
+    /// ModExp circuit, for the MODEXP precompile.
+    pub modexp: bool,
+    /// ECC circuit, for the BN254 ECADD, ECMUL and ECPAIRING precompiles.
+    pub ecc: bool,
+}
+
+impl Default for SubCircuitSelection {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl SubCircuitSelection {
+    /// All the sub circuits enabled.
+    pub const fn all() -> Self {
+        Self {
+            sha256: true,
+            modexp: true,
+            ecc: true,
+        }
+    }
+
+    /// Enable or disable the SHA256 circuit.
+    pub const fn with_sha256(self, sha256: bool) -> Self {
+        Self { sha256, ..self }
+    }
+
+    /// Enable or disable the ModExp circuit.
+    pub const fn with_modexp(self, modexp: bool) -> Self {
+        Self { modexp, ..self }
+    }
+
+    /// Enable or disable the ECC circuit.
+    pub const fn with_ecc(self, ecc: bool) -> Self {
+        Self { ecc, ..self }
+    }
+
+    /// Whether the sub circuit proving the precompile is enabled.
+    pub fn is_precompile_enabled(&self, precompile: PrecompileCalls) -> bool {
+        match precompile {
+            PrecompileCalls::Sha256 => self.sha256,
+            PrecompileCalls::Modexp => self.modexp,
+            PrecompileCalls::Bn128Add
+            | PrecompileCalls::Bn128Mul
+            | PrecompileCalls::Bn128Pairing => self.ecc,
+            _ => true,
         }
     }
 }
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CircuitsParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// number of rows). This must be at least the number of rw operations
@@ -110,11 +174,20 @@ pub struct CircuitsParams {
     pub max_poseidon_rows: usize,
     /// Max number of ECC-related ops supported in the ECC circuit.
     pub max_ec_ops: PrecompileEcParams,
+    /// Max number of ECDSA verifications (from L2 txs and ecrecover calls)
+    /// supported in the Sig circuit. The number of advice columns of the
+    /// ECDSA chip grows with it. When 0, the Sig circuit default of 128 is
+    /// used.
+    #[serde(default)]
+    pub max_sig_verifs: usize,
     /// This number indicate what 100% usage means, for example if we can support up to 2
     /// ecPairing inside circuit, and max_vertical_circuit_rows is set to 1_000_000,
     /// then if there is 1 ecPairing in the input, we will return 500_000 as the "row usage"
     /// for the ec circuit.
     pub max_vertical_circuit_rows: usize,
+    /// Optional sub circuits of the Super Circuit.
+    #[serde(default)]
+    pub sub_circuits: SubCircuitSelection,
 }
 
 impl Default for CircuitsParams {
@@ -137,6 +210,8 @@ impl Default for CircuitsParams {
             max_vertical_circuit_rows: 0,
             max_rlp_rows: 1000,
             max_ec_ops: PrecompileEcParams::default(),
+            max_sig_verifs: 0,
+            sub_circuits: SubCircuitSelection::all(),
         }
     }
 }
@@ -187,6 +262,11 @@ impl<'a> CircuitInputBuilder {
             mpt_init_state: Default::default(),
         }
     }
+    /// Set the client implementation which produced the traces handled by this builder.
+    pub fn set_tracer_flavor(&mut self, flavor: TracerFlavor) {
+        self.block_ctx.tracer_flavor = flavor;
+    }
+
     /// Create a new CircuitInputBuilder from the given `eth_block` and
     /// `constants`.
     pub fn new_from_headers(
@@ -1,5 +1,6 @@
 use crate::operation::RW;
 use eth_types::{geth_types::GethData, Address, GethPrestateTrace, Word};
+use serde::{Deserialize, Serialize};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 /// State and Code Access with "keys/index" used in the access operation.
@@ -115,7 +116,7 @@ impl AccessSet {
 }
 
 /// Source of the code in the EVM execution.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum CodeSource {
     /// Code comes from a deployed contract at `Address`.
     Address(Address),
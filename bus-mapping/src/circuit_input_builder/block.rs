@@ -7,10 +7,16 @@ use super::{
 };
 use crate::{
     operation::{OperationContainer, RWCounter},
+    trace_compat::TracerFlavor,
+    util::{dump_versioned, load_versioned},
     Error,
 };
 use eth_types::{Address, Hash, ToWord, Word};
-use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
 #[derive(Debug)]
@@ -24,6 +30,8 @@ pub struct BlockContext {
     pub(crate) call_map: HashMap<usize, (usize, usize)>,
     /// Total gas used by previous transactions in this block.
     pub(crate) cumulative_gas_used: u64,
+    /// Client implementation which produced the traces of the block.
+    pub(crate) tracer_flavor: TracerFlavor,
 }
 
 impl Default for BlockContext {
@@ -39,12 +47,13 @@ impl BlockContext {
             rwc: RWCounter::new(),
             call_map: HashMap::new(),
             cumulative_gas_used: 0,
+            tracer_flavor: TracerFlavor::default(),
         }
     }
 }
 
 /// Block-wise execution steps that don't belong to any Transaction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockSteps {
     /// Padding step that is repeated after the last transaction and before
     /// reaching the last EVM row.
@@ -69,7 +78,7 @@ impl Default for BlockSteps {
 }
 
 /// Circuit Input related to a block.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockHead {
     /// chain id
     pub chain_id: u64,
@@ -86,6 +95,8 @@ pub struct BlockHead {
     pub timestamp: Word,
     /// gas limit
     pub difficulty: Word,
+    /// prevrandao, i.e. the mix hash of a post-Merge block
+    pub prevrandao: Word,
     /// base fee
     pub base_fee: Word,
     /// start l1 queue index
@@ -130,6 +141,11 @@ impl BlockHead {
             } else {
                 eth_block.difficulty
             },
+            // same fallback as the tracer when no mix hash is set
+            prevrandao: match eth_block.mix_hash {
+                Some(mix_hash) if !mix_hash.is_zero() => mix_hash.to_fixed_bytes().into(),
+                _ => eth_block.difficulty,
+            },
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
             eth_block: eth_block.clone(),
         })
@@ -172,6 +188,11 @@ impl BlockHead {
             } else {
                 eth_block.difficulty
             },
+            // same fallback as the tracer when no mix hash is set
+            prevrandao: match eth_block.mix_hash {
+                Some(mix_hash) if !mix_hash.is_zero() => mix_hash.to_fixed_bytes().into(),
+                _ => eth_block.difficulty,
+            },
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
             eth_block: eth_block.clone(),
         })
@@ -179,7 +200,7 @@ impl BlockHead {
 }
 
 /// Circuit Input related to a block.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Block {
     /// The `Block` struct is in fact "chunk" for l2
     /// while "headers" are "Blocks" insides a chunk
@@ -314,6 +335,23 @@ impl Block {
     }
 }
 
+/// Format version of blocks written by [`Block::dump`]. Bump it whenever a change to the
+/// serialized types makes older dumps unreadable.
+pub const BLOCK_DUMP_VERSION: u32 = 3;
+
+impl Block {
+    /// Dump the block to `path`, so that it can be reloaded with [`Block::load`] without
+    /// re-tracing.
+    pub fn dump(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        dump_versioned(path, BLOCK_DUMP_VERSION, self)
+    }
+
+    /// Load a block written by [`Block::dump`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        load_versioned(path, BLOCK_DUMP_VERSION)
+    }
+}
+
 impl Block {
     /// Push a copy event to the block.
     pub fn add_copy_event(&mut self, event: CopyEvent) {
@@ -9,7 +9,7 @@ use external_tracer::TraceConfig;
 use hex::decode_to_slice;
 
 use super::{AccessSet, Block, BlockHead, CircuitInputBuilder, CircuitsParams};
-use crate::{error::Error, rpc::GethClient};
+use crate::{error::Error, rpc::GethClient, trace_compat::TracerFlavor};
 
 use std::{collections::HashMap, iter};
 
@@ -20,6 +20,7 @@ pub struct BuilderClient<P: JsonRpcClient> {
     cli: GethClient<P>,
     chain_id: u64,
     circuits_params: CircuitsParams,
+    tracer_flavor: TracerFlavor,
 }
 
 /// Build a partial StateDB from step 3
@@ -60,14 +61,33 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         circuits_params: CircuitsParams,
     ) -> Result<Self, Error> {
         let chain_id = client.get_chain_id().await?;
+        let tracer_flavor = match TracerFlavor::from_env() {
+            Some(flavor) => flavor,
+            None => client
+                .get_client_version()
+                .await
+                .map(|version| TracerFlavor::from_client_version(&version))
+                .unwrap_or_else(|e| {
+                    log::warn!("web3_clientVersion failed, assuming a geth node: {e:?}");
+                    TracerFlavor::Geth
+                }),
+        };
+        log::debug!("tracer flavor: {:?}", tracer_flavor);
 
         Ok(Self {
             cli: client,
             chain_id,
             circuits_params,
+            tracer_flavor,
         })
     }
 
+    /// Client implementation of the node, detected from its `web3_clientVersion` or set with
+    /// the `TRACER_FLAVOR` environment variable.
+    pub fn tracer_flavor(&self) -> TracerFlavor {
+        self.tracer_flavor
+    }
+
     /// Step 1. Query geth for Block, Txs, TxExecTraces, history block hashes
     /// and previous state root.
     pub async fn get_block(
@@ -75,7 +95,13 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         block_num: u64,
     ) -> Result<(EthBlock, Vec<eth_types::GethExecTrace>, Vec<Word>, Word), Error> {
         let eth_block = self.cli.get_block_by_number(block_num.into()).await?;
-        let geth_traces = self.cli.trace_block_by_number(block_num.into()).await?;
+        let geth_traces = if self.tracer_flavor.supports_mux_tracer() {
+            self.cli.trace_block_by_number(block_num.into()).await?
+        } else {
+            self.cli
+                .trace_block_by_number_legacy(block_num.into())
+                .await?
+        };
 
         // fetch up to 256 blocks
         let mut n_blocks = 0; // std::cmp::min(256, block_num as usize);
@@ -308,6 +334,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             self.circuits_params,
         )?;
         let mut builder = CircuitInputBuilder::new(sdb, code_db, &block);
+        builder.set_tracer_flavor(self.tracer_flavor);
         builder.handle_block(eth_block, geth_traces)?;
         Ok(builder)
     }
@@ -326,6 +353,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             code_db,
             Default::default(),
         );
+        builder.set_tracer_flavor(self.tracer_flavor);
         for (idx, (eth_block, geth_traces)) in blocks_and_traces.iter().enumerate() {
             let is_last = idx == blocks_and_traces.len() - 1;
             let header = BlockHead::new(self.chain_id, Default::default(), eth_block)?;
@@ -416,11 +444,12 @@ impl<P: JsonRpcClient> BuilderClient<P> {
 
         let mut tx: eth_types::Transaction = self.cli.get_tx_by_hash(tx_hash).await?;
         tx.transaction_index = Some(0.into());
-        let geth_trace = if cfg!(feature = "rpc-legacy-tracer") {
-            self.cli.trace_tx_by_hash_legacy(tx_hash).await
-        } else {
-            self.cli.trace_tx_by_hash(tx_hash).await
-        }?;
+        let geth_trace =
+            if cfg!(feature = "rpc-legacy-tracer") || !self.tracer_flavor.supports_mux_tracer() {
+                self.cli.trace_tx_by_hash_legacy(tx_hash).await
+            } else {
+                self.cli.trace_tx_by_hash(tx_hash).await
+            }?;
         let mut eth_block = self
             .cli
             .get_block_by_number(tx.block_number.unwrap().into())
@@ -471,6 +500,7 @@ impl<P: JsonRpcClient> BuilderClient<P> {
                 timestamp: eth_block.timestamp,
                 number: eth_block.number.unwrap(),
                 difficulty: eth_block.difficulty,
+                mix_hash: eth_block.mix_hash.unwrap_or_default(),
                 gas_limit: eth_block.gas_limit,
                 base_fee: eth_block.base_fee_per_gas.unwrap(),
             },
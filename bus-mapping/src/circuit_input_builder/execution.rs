@@ -27,9 +27,10 @@ use halo2_proofs::{
     },
     plonk::Expression,
 };
+use serde::{Deserialize, Serialize};
 
 /// An execution step of the EVM.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecStep {
     /// Execution state
     pub exec_state: ExecState,
@@ -141,10 +142,10 @@ impl Default for ExecStep {
 }
 
 /// Execution state
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ExecState {
     /// EVM Opcode ID
-    Op(OpcodeId),
+    Op(#[serde(with = "eth_types::evm_types::opcode_ids::serde_opcode_byte")] OpcodeId),
     /// Precompile call
     Precompile(PrecompileCalls),
     /// Virtual step Begin Tx
@@ -206,7 +207,7 @@ impl ExecState {
 }
 
 /// Defines the various source/destination types for a copy event.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CopyDataType {
     /// When we need to pad the Copy rows of the circuit up to a certain maximum
     /// with rows that are not "useful".
@@ -349,7 +350,7 @@ impl_expr!(CopyDataType, u64::from);
 
 /// Defines a single copy step in a copy event. This type is unified over the
 /// source/destination row in the copy table.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CopyStep {
     /// Byte value copied in this step.
     pub value: u8,
@@ -360,7 +361,7 @@ pub struct CopyStep {
 }
 
 /// Defines an enum type that can hold either a number or a hash value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NumberOrHash {
     /// Variant to indicate a number value.
     Number(usize),
@@ -379,7 +380,7 @@ pub enum NumberOrHash {
 ///
 /// Additionally, when the destination is memory, `bytes_write_prev` holds the memory content
 /// *before* the write.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CopyBytes {
     /// Represents the list of (bytes, is_code, mask) copied during this copy event
     pub bytes: Vec<(u8, bool, bool)>,
@@ -410,7 +411,7 @@ impl CopyBytes {
 /// Save address, storage_key, storage_key_index and is_warm_prev
 /// to column value_word_rlc, value_word_rlc_prev, value and
 /// value_prev in copy circuit.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyAccessList {
     /// Access list address
     pub address: Address,
@@ -446,7 +447,7 @@ impl CopyAccessList {
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyEvent {
     /// Represents the start address at the source of the copy event.
     pub src_addr: u64,
@@ -848,7 +849,7 @@ where
 }
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExpStep {
     /// First multiplicand.
     pub a: Word,
@@ -869,7 +870,7 @@ impl From<(Word, Word, Word)> for ExpStep {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod 2^256)`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExpEvent {
     /// Base `a` for the exponentiation.
     pub base: Word,
@@ -897,7 +898,7 @@ impl Default for ExpEvent {
 }
 
 /// I/Os from all precompiled contract calls in a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PrecompileEvents {
     /// All events.
     pub events: Vec<PrecompileEvent>,
@@ -991,7 +992,7 @@ impl PrecompileEvents {
 }
 
 /// I/O from a precompiled contract call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PrecompileEvent {
     /// Represents the I/O from Ecrecover call.
     Ecrecover(SignData),
@@ -1014,7 +1015,7 @@ impl Default for PrecompileEvent {
 }
 
 /// EcAdd operation: P + Q = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcAddOp {
     /// EVM input for first operand to EcAdd.
     pub p: (U256, U256),
@@ -1116,7 +1117,7 @@ impl EcAddOp {
 }
 
 /// EcMul operation: s.P = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcMulOp {
     /// The EVM inputs to the G1 point.
     pub p: (U256, U256),
@@ -1224,15 +1225,25 @@ impl EcMulOp {
     }
 }
 
-/// The number of pairing inputs per pairing operation. If the inputs provided to the precompile
-/// call are < 4, we append (G1::infinity, G2::generator) until we have the required no. of inputs.
+/// The number of pairing inputs per chunk of a pairing operation. The ECC circuit accumulates the
+/// Miller loop of an operation chunk by chunk, so the pairs provided to the precompile call are
+/// padded with (G1::infinity, G2::infinity) to a multiple of 4.
 pub const N_PAIRING_PER_OP: usize = 4;
 
 /// The number of bytes taken to represent a pair (G1, G2).
 pub const N_BYTES_PER_PAIR: usize = 192;
 
+/// The maximum length of the input to an ecPairing call. l2geth rejects calls with more than 4
+/// pairs, whereas Ethereum only bounds the number of pairs with the gas cost.
+#[cfg(feature = "scroll")]
+pub const MAX_N_BYTES_EC_PAIRING_INPUT: usize = N_PAIRING_PER_OP * N_BYTES_PER_PAIR;
+/// The maximum length of the input to an ecPairing call. l2geth rejects calls with more than 4
+/// pairs, whereas Ethereum only bounds the number of pairs with the gas cost.
+#[cfg(not(feature = "scroll"))]
+pub const MAX_N_BYTES_EC_PAIRING_INPUT: usize = u32::MAX as usize;
+
 /// Pair of (G1, G2).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingPair {
     /// EVM inputs for the G1 point.
     pub g1_point: (U256, U256),
@@ -1318,9 +1329,9 @@ impl EcPairingPair {
             .collect()
     }
 
-    /// Padding pair for EcPairing operation. The pairing check is done with a constant number
-    /// `N_PAIRING_PER_OP` of (G1, G2) pairs. In case EVM inputs are less in number, we pad them
-    /// with `(G1::Infinity, G2::Infinity)` for simplicity.
+    /// Padding pair for EcPairing operation. The pairing check is done on chunks of
+    /// `N_PAIRING_PER_OP` (G1, G2) pairs. In case EVM inputs don't fill the last chunk, we pad
+    /// them with `(G1::Infinity, G2::Infinity)` for simplicity.
     pub fn padding_pair() -> Self {
         Self {
             g1_point: (U256::zero(), U256::zero()),
@@ -1334,10 +1345,11 @@ impl EcPairingPair {
 }
 
 /// EcPairing operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingOp {
-    /// tuples of G1 and G2 points supplied to the ECC circuit.
-    pub pairs: [EcPairingPair; N_PAIRING_PER_OP],
+    /// tuples of G1 and G2 points supplied to the ECC circuit, padded to a non-zero multiple of
+    /// `N_PAIRING_PER_OP`.
+    pub pairs: Vec<EcPairingPair>,
     /// Result from the pairing check.
     pub output: Word,
     /// Input bytes to the ecPairing call.
@@ -1359,23 +1371,12 @@ impl Default for EcPairingOp {
         let g2_y0 = U256::from_little_endian(&g2_point.y.c1.to_bytes());
         let g2_y1 = U256::from_little_endian(&g2_point.y.c0.to_bytes());
         Self {
-            pairs: [
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
+            pairs: vec![
                 EcPairingPair {
                     g1_point: (g1_x, g1_y),
                     g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
-                EcPairingPair {
-                    g1_point: (g1_x, g1_y),
-                    g2_point: (g2_x0, g2_x1, g2_y0, g2_y1),
-                },
+                };
+                N_PAIRING_PER_OP
             ],
             output: Word::zero(),
             // It does not matter what the input bytes and return bytes are in this case, as this
@@ -1403,6 +1404,16 @@ impl EcPairingOp {
         false
     }
 
+    /// The number of chunks of `N_PAIRING_PER_OP` pairs processed by the ECC Circuit for this op.
+    pub fn n_chunks(&self) -> usize {
+        self.pairs.len().div_ceil(N_PAIRING_PER_OP)
+    }
+
+    /// The chunks of `N_PAIRING_PER_OP` pairs processed by the ECC Circuit for this op.
+    pub fn chunks(&self) -> impl Iterator<Item = &[EcPairingPair]> {
+        self.pairs.chunks(N_PAIRING_PER_OP)
+    }
+
     /// Whether the EVM inputs are valid or not, i.e. does the precompile succeed or fail.
     pub fn is_valid(&self) -> bool {
         self.pairs.iter().all(|pair| pair.is_valid())
@@ -1416,7 +1427,7 @@ impl EcPairingOp {
         let other_g1 = G1Affine::from(G1Affine::generator() * Fr::from(6));
         let other_g2 = G2Affine::generator();
         Self {
-            pairs: [
+            pairs: vec![
                 EcPairingPair::new(g1_neg, g2),
                 EcPairingPair::new(other_g1, other_g2),
                 EcPairingPair::new(G1Affine::identity(), G2Affine::generator()),
@@ -1429,7 +1440,7 @@ impl EcPairingOp {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod m)` in precompile modexp.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BigModExp {
     /// Base `a` for the exponentiation.
     pub base: Word,
@@ -1453,7 +1464,7 @@ impl Default for BigModExp {
 }
 
 /// Event representating an SHA256 hash in precompile sha256.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SHA256 {
     /// input bytes
     pub input: Vec<u8>,
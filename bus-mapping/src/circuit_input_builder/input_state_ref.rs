@@ -65,10 +65,11 @@ impl<'a> CircuitInputStateRef<'a> {
             call_ctx.reversible_write_counter,
             self.tx_ctx.log_id,
         );
-        #[cfg(feature = "fix-refund")]
         let step = {
             let mut step = step;
-            if geth_step.refund.0 != self.sdb.refund() {
+            if self.block_ctx.tracer_flavor.rebuilds_refund()
+                && geth_step.refund.0 != self.sdb.refund()
+            {
                 log::trace!(
                     "correct op refund to {} trace: {}",
                     self.sdb.refund(),
@@ -10,6 +10,7 @@ use eth_types::{
     AccessList, Address, GethExecTrace, Signature, Word, H256,
 };
 use ethers_core::utils::get_contract_address;
+use serde::{Deserialize, Serialize};
 
 /// Precision of transaction L1 fee
 pub const TX_L1_FEE_PRECISION: u64 = 1_000_000_000;
@@ -169,7 +170,7 @@ impl TransactionContext {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Result of the parsing of an Ethereum Transaction.
 pub struct Transaction {
     /// ..
@@ -452,10 +453,10 @@ impl Transaction {
                 ExecState::Op(OpcodeId::SELFDESTRUCT)
                     | ExecState::Op(OpcodeId::INVALID(0xff))
                     | ExecState::Op(OpcodeId::INVALID(0x48)) // basefee
-                    | ExecState::Op(OpcodeId::DIFFICULTY)
                     | ExecState::Precompile(PrecompileCalls::Modexp)
                     | ExecState::Precompile(PrecompileCalls::Bn128Pairing)
-            )
+            ) || (!cfg!(feature = "prevrandao")
+                && step.exec_state == ExecState::Op(OpcodeId::DIFFICULTY))
         });
 
         let different_precompiles = self
@@ -468,7 +469,7 @@ impl Transaction {
 }
 
 /// Transaction L1 fee for L1GasPriceOracle contract
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TxL1Fee {
     /// L1 base fee
     pub base_fee: u64,
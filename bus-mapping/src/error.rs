@@ -1,8 +1,10 @@
 //! Error module for the bus-mapping crate
 
+use crate::precompile::PrecompileCalls;
 use core::fmt::{Display, Formatter, Result as FmtResult};
 use eth_types::{evm_types::OpcodeId, Address, GethExecError, GethExecStep, Word, H256};
 use ethers_providers::ProviderError;
+use serde::{Deserialize, Serialize};
 use std::error::Error as StdError;
 
 /// Error type for any BusMapping related failure.
@@ -39,6 +41,16 @@ pub enum Error {
     ExecutionError(ExecError),
     /// Internal Code error
     InternalError(&'static str),
+    /// Call to a precompile whose sub circuit is disabled in the circuits
+    /// params.
+    DisabledPrecompile(PrecompileCalls),
+    /// A dumped witness was written with another format version.
+    DumpVersionMismatch {
+        /// Version supported by this build
+        expected: u32,
+        /// Version found in the dump
+        found: u32,
+    },
 }
 
 impl From<eth_types::Error> for Error {
@@ -62,7 +74,7 @@ impl Display for Error {
 impl StdError for Error {}
 
 /// Out of Gas errors by opcode
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OogError {
     /// Out of Gas for opcodes which have non-zero constant gas cost
     Constant,
@@ -103,7 +115,7 @@ pub enum OogError {
 }
 
 /// Contract address collision errors by opcode/state.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ContractAddressCollisionError {
     /// Contract address collision during CREATE opcode.
     Create,
@@ -112,7 +124,7 @@ pub enum ContractAddressCollisionError {
 }
 
 /// Depth above limit errors by opcode/state.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DepthError {
     /// Depth above limit during CALL/CALLCODE/DELEGATECALL/STATICCALL opcode.
     Call,
@@ -123,7 +135,7 @@ pub enum DepthError {
 }
 
 /// Insufficient balance errors by opcode/state.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InsufficientBalanceError {
     /// Insufficient balance during CALL/CALLCODE opcode.
     Call,
@@ -134,7 +146,7 @@ pub enum InsufficientBalanceError {
 }
 
 /// Nonce uint overflow errors by opcode/state.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NonceUintOverflowError {
     /// Nonce uint overflow during CREATE opcode.
     Create,
@@ -143,7 +155,7 @@ pub enum NonceUintOverflowError {
 }
 
 /// EVM Execution Error
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExecError {
     /// Invalid Opcode
     InvalidOpcode,
@@ -216,7 +228,9 @@ pub(crate) fn get_step_reported_error(op: &OpcodeId, error: GethExecError) -> Ex
             ExecError::OutOfGas(oog_err)
         }
         GethExecError::StackOverflow { .. } => ExecError::StackOverflow,
-        GethExecError::StackUnderflow { .. } => ExecError::StackUnderflow,
+        GethExecError::StackUnderflow { .. } | GethExecError::StackUnderflowUnspecified => {
+            ExecError::StackUnderflow
+        }
         GethExecError::WriteProtection => ExecError::WriteProtection,
         _ => panic!("Unknown GethExecStep.error: {error}"),
     }
@@ -288,6 +288,14 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             (false, true, _) => {
                 let code_address = code_address.unwrap();
                 let precompile_call: PrecompileCalls = code_address.0[19].into();
+                if !state
+                    .block
+                    .circuits_params
+                    .sub_circuits
+                    .is_precompile_enabled(precompile_call)
+                {
+                    return Err(Error::DisabledPrecompile(precompile_call));
+                }
 
                 // get the result of the precompile call.
                 // For failed call, it will cost all gas provided.
@@ -1076,4 +1084,59 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_disabled_precompile_call() {
+        use crate::{
+            circuit_input_builder::{CircuitsParams, SubCircuitSelection},
+            mock::BlockData,
+            precompile::PrecompileCalls,
+            Error,
+        };
+        use eth_types::{bytecode, geth_types::GethData};
+        use mock::{
+            test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+            TestContext,
+        };
+
+        let code = PrecompileCallArgs {
+            name: "SHA2-256",
+            setup_code: bytecode! {
+                PUSH1(0xFF) // data
+                PUSH1(0)
+                MSTORE
+            },
+            ret_size: Word::from(0x20),
+            ret_offset: Word::from(0x20),
+            call_data_length: Word::from(0x1),
+            call_data_offset: Word::from(0x1F),
+            address: Word::from(0x2),
+            ..Default::default()
+        }
+        .with_call_op(OpcodeId::STATICCALL);
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                sub_circuits: SubCircuitSelection::all().with_sha256(false),
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        let err = builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DisabledPrecompile(PrecompileCalls::Sha256)
+        ));
+    }
 }
@@ -32,7 +32,12 @@ impl BlockHeaderToField for GetBlockHeaderField<{ OpcodeId::NUMBER }> {
 
 impl BlockHeaderToField for GetBlockHeaderField<{ OpcodeId::DIFFICULTY }> {
     fn handle(block_head: &BlockHead) -> Word {
-        block_head.difficulty
+        // EIP-4399: post-Merge, DIFFICULTY (0x44) returns PREVRANDAO.
+        if cfg!(feature = "prevrandao") {
+            block_head.prevrandao
+        } else {
+            block_head.difficulty
+        }
     }
 }
 
@@ -3,7 +3,8 @@ use itertools::Itertools;
 
 use crate::{
     circuit_input_builder::{
-        EcPairingOp, EcPairingPair, PrecompileEvent, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+        EcPairingOp, EcPairingPair, PrecompileEvent, MAX_N_BYTES_EC_PAIRING_INPUT,
+        N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
     },
     precompile::{EcPairingAuxData, EcPairingError, PrecompileAuxData},
 };
@@ -38,7 +39,7 @@ pub(crate) fn opt_data(
     }
 
     let op = if !input_bytes.is_empty() {
-        if (input_bytes.len() > N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+        if (input_bytes.len() > MAX_N_BYTES_EC_PAIRING_INPUT)
             || (input_bytes.len() % N_BYTES_PER_PAIR != 0)
         {
             return (
@@ -50,7 +51,7 @@ pub(crate) fn opt_data(
         }
         debug_assert!(
             input_bytes.len() % N_BYTES_PER_PAIR == 0
-                && input_bytes.len() <= N_PAIRING_PER_OP * N_BYTES_PER_PAIR
+                && input_bytes.len() <= MAX_N_BYTES_EC_PAIRING_INPUT
         );
         // process input bytes.
         let mut pairs = input_bytes
@@ -71,17 +72,18 @@ pub(crate) fn opt_data(
                 }
             })
             .collect_vec();
-        // pad the pairs to make them of fixed size: N_PAIRING_PER_OP.
-        pairs.resize(N_PAIRING_PER_OP, EcPairingPair::padding_pair());
+        // pad the pairs to fill the last chunk of N_PAIRING_PER_OP pairs.
+        let n_chunks = pairs.len().div_ceil(N_PAIRING_PER_OP);
+        pairs.resize(n_chunks * N_PAIRING_PER_OP, EcPairingPair::padding_pair());
         EcPairingOp {
-            pairs: <[_; N_PAIRING_PER_OP]>::try_from(pairs).unwrap(),
+            pairs,
             output: pairing_check,
             input_bytes: input_bytes.to_vec(),
             output_bytes: output_bytes.to_vec(),
             return_bytes: return_bytes.to_vec(),
         }
     } else {
-        let pairs = [EcPairingPair::padding_pair(); N_PAIRING_PER_OP];
+        let pairs = vec![EcPairingPair::padding_pair(); N_PAIRING_PER_OP];
         EcPairingOp {
             pairs,
             output: pairing_check,
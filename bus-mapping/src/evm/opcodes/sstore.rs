@@ -169,13 +169,9 @@ impl Opcode for Sstore {
         let refund_expected =
             calc_expected_tx_refund(state.sdb.refund(), value, value_prev, committed_value);
 
-        #[cfg(not(feature = "fix-refund"))]
-        assert_eq!(
-            refund, refund_expected,
-            "expected refund {refund_expected} is not equal to current {refund}"
-        );
-        #[cfg(feature = "fix-refund")]
-        let refund = if refund != refund_expected {
+        let refund = if refund == refund_expected {
+            refund
+        } else if state.block_ctx.tracer_flavor.rebuilds_refund() {
             exec_step.gas_refund.0 = refund_expected;
             log::debug!(
                 "correct sstore refund from {} -> {}, prev {}",
@@ -185,7 +181,7 @@ impl Opcode for Sstore {
             );
             refund_expected
         } else {
-            refund
+            panic!("expected refund {refund_expected} is not equal to current {refund}");
         };
 
         state.push_op_reversible(
@@ -233,6 +233,7 @@ pub mod mock;
 pub mod operation;
 pub mod precompile;
 pub mod rpc;
+pub mod trace_compat;
 pub mod util;
 
 pub use error::Error;
@@ -2,6 +2,7 @@
 
 use eth_types::{evm_types::GasCost, Address, ToBigEndian, Word};
 use revm_precompile::{Precompile, PrecompileError, Precompiles};
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 use crate::circuit_input_builder::{EcMulOp, EcPairingOp};
@@ -42,7 +43,7 @@ pub(crate) fn execute_precompiled(
 }
 
 /// Addresses of the precompiled contracts.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, Serialize, Deserialize)]
 pub enum PrecompileCalls {
     /// Elliptic Curve Recovery
     Ecrecover = 0x01,
@@ -140,7 +141,7 @@ impl PrecompileCalls {
 }
 
 /// Auxiliary data for Ecrecover
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcrecoverAuxData {
     /// Keccak hash of the message being signed.
     pub msg_hash: Word,
@@ -202,7 +203,7 @@ pub const MODEXP_SIZE_LIMIT: usize = 32;
 pub const MODEXP_INPUT_LIMIT: usize = 192;
 
 /// Auxiliary data for Modexp
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModExpAuxData {
     /// The specified len of inputs: [base, exp, modulus]
     pub input_lens: [Word; 3],
@@ -303,7 +304,7 @@ impl ModExpAuxData {
 }
 
 /// Auxiliary data for EcAdd, i.e. P + Q = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcAddAuxData {
     /// x co-ordinate of the first point.
     pub p_x: Word,
@@ -348,7 +349,7 @@ impl EcAddAuxData {
 }
 
 /// Auxiliary data for EcMul, i.e. s * P = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcMulAuxData {
     /// x co-ordinate of the point.
     pub p_x: Word,
@@ -395,20 +396,20 @@ impl EcMulAuxData {
 }
 
 /// Auxiliary data for EcPairing.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingAuxData(pub EcPairingOp);
 
 /// Erroneous bytes passed to the EcPairing precompile call.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EcPairingError {
     /// the calldatalength passed to EcPairing precompile call is expected to be:
-    /// 1. len(input) <= 768
+    /// 1. len(input) <= MAX_N_BYTES_EC_PAIRING_INPUT
     /// 2. len(input) % 192 == 0
     InvalidInputLen(Vec<u8>),
 }
 
 /// Auxiliary data attached to an internal state for precompile verification.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrecompileAuxData {
     /// Base precompile (used for SHA256, RIPEMD-160 and BLAKE2F).
     Base {
@@ -12,7 +12,10 @@ use serde::Serialize;
 use serde_json::json;
 use std::collections::HashMap;
 
-use crate::util::GETH_TRACE_CHECK_LEVEL;
+use crate::{
+    trace_compat::{normalize_exec_trace, normalize_exec_traces},
+    util::GETH_TRACE_CHECK_LEVEL,
+};
 
 /// Serialize a type.
 ///
@@ -93,6 +96,15 @@ impl<P: JsonRpcClient> GethClient<P> {
             .map_err(|e| Error::JSONRpcError(e.into()))
     }
 
+    /// Calls `web3_clientVersion` via JSON-RPC returning the client name and version of the
+    /// node.
+    pub async fn get_client_version(&self) -> Result<String, Error> {
+        self.0
+            .request("web3_clientVersion", ())
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))
+    }
+
     /// Calls `eth_chainId` via JSON-RPC returning the chain id of the network.
     pub async fn get_chain_id(&self) -> Result<u64, Error> {
         let net_id: U64 = self
@@ -149,11 +161,14 @@ impl<P: JsonRpcClient> GethClient<P> {
             timeout: Some("300s".to_string()),
             ..Default::default()
         });
-        let resp: ResultGethExecTraces = self
+        let mut traces: Vec<serde_json::Value> = self
             .0
             .request("debug_traceBlockByHash", [hash, cfg])
             .await
             .map_err(|e| Error::JSONRpcError(e.into()))?;
+        normalize_exec_traces(&mut traces);
+        let resp: ResultGethExecTraces = serde_json::from_value(serde_json::Value::Array(traces))
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
         Ok(resp.0.into_iter().map(|step| step.result).collect())
     }
 
@@ -174,6 +189,7 @@ impl<P: JsonRpcClient> GethClient<P> {
             .request("debug_traceBlockByNumber", [num.clone(), cfg])
             .await
             .map_err(|e| Error::JSONRpcError(e.into()))?;
+        normalize_exec_traces(&mut struct_logs);
         let mux_trace: Vec<serde_json::Value> = self
             .0
             .request(
@@ -211,6 +227,63 @@ impl<P: JsonRpcClient> GethClient<P> {
         Ok(resp.0.into_iter().map(|step| step.result).collect())
     }
 
+    /// Calls `debug_traceBlockByNumber` via JSON-RPC like
+    /// [`trace_block_by_number`](Self::trace_block_by_number), with separate `prestateTracer`
+    /// and `callTracer` requests for nodes which don't implement the `muxTracer`.
+    pub async fn trace_block_by_number_legacy(
+        &self,
+        block_num: BlockNumber,
+    ) -> Result<Vec<GethExecTrace>, Error> {
+        let num = serialize(&block_num);
+        let cfg = serialize(&GethLoggerConfig {
+            timeout: Some("300s".to_string()),
+            ..Default::default()
+        });
+        let mut struct_logs: Vec<serde_json::Value> = self
+            .0
+            .request("debug_traceBlockByNumber", [num.clone(), cfg])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
+        normalize_exec_traces(&mut struct_logs);
+        let prestates: Vec<serde_json::Value> = self
+            .0
+            .request(
+                "debug_traceBlockByNumber",
+                [
+                    num.clone(),
+                    json!({"tracer": "prestateTracer", "timeout": "300s"}),
+                ],
+            )
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
+        let calls: Vec<serde_json::Value> = self
+            .0
+            .request(
+                "debug_traceBlockByNumber",
+                [num, json!({"tracer": "callTracer", "timeout": "300s"})],
+            )
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))?;
+
+        for ((struct_log, prestate), call) in struct_logs.iter_mut().zip(prestates).zip(calls) {
+            merge_json_object(
+                struct_log,
+                json!({
+                    "result": {
+                        "prestate": prestate["result"],
+                        "callTrace": call["result"],
+                    }
+                }),
+            );
+        }
+
+        let resp: ResultGethExecTraces =
+            serde_json::from_value(serde_json::Value::Array(struct_logs))
+                .map_err(|e| Error::JSONRpcError(e.into()))?;
+
+        Ok(resp.0.into_iter().map(|step| step.result).collect())
+    }
+
     /// ...
     pub async fn trace_tx_by_hash_legacy(&self, hash: H256) -> Result<GethExecTrace, Error> {
         let hash = serialize(&hash);
@@ -224,6 +297,7 @@ impl<P: JsonRpcClient> GethClient<P> {
             .request("debug_traceTransaction", [hash.clone(), cfg])
             .await
             .map_err(|e| Error::JSONRpcError(e.into()))?;
+        normalize_exec_trace(&mut struct_logs);
 
         let cfg = serialize(&serde_json::json! ({
             "tracer": "prestateTracer",
@@ -268,6 +342,7 @@ impl<P: JsonRpcClient> GethClient<P> {
             .request("debug_traceTransaction", [hash.clone(), cfg])
             .await
             .map_err(|e| Error::JSONRpcError(e.into()))?;
+        normalize_exec_trace(&mut struct_logs);
         let mux_trace: serde_json::Value = self
             .0
             .request(
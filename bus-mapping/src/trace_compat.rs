@@ -0,0 +1,235 @@
+//! Compatibility layer for struct logger traces served by non-geth clients.
+//!
+//! Erigon and Reth implement `debug_trace*` with the geth struct logger format, with a few
+//! differences that the circuit input builder can't handle directly:
+//! - Erigon doesn't report the gas refund counter in its steps, so it must be rebuilt from the
+//!   state db while the trace is processed.
+//! - Reth reports step errors with the names of revm's `InstructionResult` instead of geth's error
+//!   strings, and prefixes the return value with `0x`.
+//! - Some clients return the captured memory as a single hex string rather than 32-byte chunks.
+//! - Neither of them implements geth's `muxTracer`.
+//!
+//! [`normalize_exec_trace`] rewrites a raw JSON trace into the geth format before it's
+//! deserialized into a [`GethExecTrace`](eth_types::GethExecTrace), and the
+//! [`TracerFlavor`] of the node tells the builder how much it can trust the trace.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Environment variable which overrides the detected tracer flavor.
+pub const TRACER_FLAVOR_ENV: &str = "TRACER_FLAVOR";
+
+/// Maximum EVM stack size, reported by geth in stack overflow errors.
+const STACK_LIMIT: usize = 1024;
+
+/// The client implementation which produced a struct logger trace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TracerFlavor {
+    /// go-ethereum (and l2geth)
+    #[default]
+    Geth,
+    /// Erigon
+    Erigon,
+    /// Reth
+    Reth,
+}
+
+impl FromStr for TracerFlavor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "geth" | "l2geth" => Ok(Self::Geth),
+            "erigon" => Ok(Self::Erigon),
+            "reth" => Ok(Self::Reth),
+            _ => Err(format!("unknown tracer flavor {s}")),
+        }
+    }
+}
+
+impl TracerFlavor {
+    /// Detect the flavor from the `web3_clientVersion` of the node, e.g. `Geth/v1.13.5-stable`,
+    /// `erigon/2.55.1/linux-amd64/go1.21.4` or `reth/v0.1.0-alpha.13`.
+    pub fn from_client_version(version: &str) -> Self {
+        let name = version.split('/').next().unwrap_or_default().to_lowercase();
+        if name.contains("erigon") {
+            Self::Erigon
+        } else if name.contains("reth") {
+            Self::Reth
+        } else {
+            Self::Geth
+        }
+    }
+
+    /// The flavor set with the `TRACER_FLAVOR` environment variable, if any.
+    pub fn from_env() -> Option<Self> {
+        let flavor = std::env::var(TRACER_FLAVOR_ENV).ok()?;
+        match flavor.parse() {
+            Ok(flavor) => Some(flavor),
+            Err(e) => {
+                log::warn!("ignoring {TRACER_FLAVOR_ENV}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Whether the refund counter of the trace steps can't be trusted and must be rebuilt from
+    /// the state db.
+    pub fn rebuilds_refund(&self) -> bool {
+        matches!(self, Self::Erigon)
+    }
+
+    /// Whether the node supports geth's `muxTracer`, which fetches the prestate and the call
+    /// trace in a single request.
+    pub fn supports_mux_tracer(&self) -> bool {
+        matches!(self, Self::Geth)
+    }
+}
+
+/// Geth error string of a step error reported by Reth, or `None` if the error is already a geth
+/// error string.
+fn geth_error(error: &str, step: &Value) -> Option<String> {
+    let stack_len = step["stack"].as_array().map(Vec::len);
+    let error = match error {
+        "OutOfGas" | "MemoryOOG" | "MemoryLimitOOG" | "PrecompileOOG" | "InvalidOperandOOG" => {
+            "out of gas".to_string()
+        }
+        "Revert" => "execution reverted".to_string(),
+        "InvalidJump" => "invalid jump destination".to_string(),
+        "StateChangeDuringStaticCall" | "CallNotAllowedInsideStatic" => {
+            "write protection".to_string()
+        }
+        "OutOfOffset" => "return data out of bounds".to_string(),
+        "CallTooDeep" => "max call depth exceeded".to_string(),
+        "OutOfFunds" => "insufficient balance for transfer".to_string(),
+        "CreateCollision" => "contract address collision".to_string(),
+        "CreateContractSizeLimit" => "max code size exceeded".to_string(),
+        "CreateInitCodeSizeLimit" => "max initcode size exceeded".to_string(),
+        "CreateContractStartingWithEF" => "invalid code: must not begin with 0xef".to_string(),
+        "NonceOverflow" => "nonce uint64 overflow".to_string(),
+        "OverflowPayment" => "gas uint64 overflow".to_string(),
+        "OpcodeNotFound" | "InvalidFEOpcode" => {
+            format!(
+                "invalid opcode: {}",
+                step["op"].as_str().unwrap_or("INVALID")
+            )
+        }
+        // Reth doesn't report the stack requirements, so the geth error can't be rebuilt.
+        "StackUnderflow" => "stack underflow".to_string(),
+        "StackOverflow" => format!(
+            "stack limit reached {} ({})",
+            stack_len.unwrap_or(STACK_LIMIT),
+            STACK_LIMIT
+        ),
+        _ => return None,
+    };
+    Some(error)
+}
+
+/// Split a memory captured as a single hex string into 32-byte chunks.
+fn memory_chunks(memory: &str) -> Value {
+    let memory = memory.trim_start_matches("0x");
+    Value::Array(
+        memory
+            .as_bytes()
+            .chunks(64)
+            .map(|chunk| {
+                let chunk = String::from_utf8_lossy(chunk);
+                Value::String(format!("{chunk:0<64}"))
+            })
+            .collect(),
+    )
+}
+
+/// Rewrite a struct logger trace returned by `debug_traceTransaction` into the geth format.
+pub fn normalize_exec_trace(trace: &mut Value) {
+    if let Some(return_value) = trace["returnValue"].as_str() {
+        if let Some(stripped) = return_value.strip_prefix("0x") {
+            trace["returnValue"] = Value::String(stripped.to_string());
+        }
+    }
+    let Some(steps) = trace["structLogs"].as_array_mut() else {
+        return;
+    };
+    for step in steps.iter_mut() {
+        if let Some(error) = step["error"].as_str() {
+            if let Some(error) = geth_error(error, step) {
+                step["error"] = Value::String(error);
+            }
+        }
+        if let Some(memory) = step["memory"].as_str() {
+            step["memory"] = memory_chunks(memory);
+        }
+    }
+}
+
+/// Rewrite the traces returned by `debug_traceBlockBy*`, where each trace is wrapped in a
+/// `result` field, into the geth format.
+pub fn normalize_exec_traces(traces: &mut [Value]) {
+    for trace in traces.iter_mut() {
+        normalize_exec_trace(&mut trace["result"]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::{GethExecError, GethExecStep};
+    use serde_json::json;
+
+    #[test]
+    fn detect_client_version() {
+        for (version, flavor) in [
+            (
+                "Geth/v1.13.5-stable-916d6a44/linux-amd64/go1.21.4",
+                TracerFlavor::Geth,
+            ),
+            ("erigon/2.55.1/linux-amd64/go1.21.4", TracerFlavor::Erigon),
+            (
+                "reth/v0.1.0-alpha.13-4a8b0e5/x86_64-unknown-linux-gnu",
+                TracerFlavor::Reth,
+            ),
+        ] {
+            assert_eq!(TracerFlavor::from_client_version(version), flavor);
+        }
+    }
+
+    #[test]
+    fn normalize_reth_trace() {
+        let mut trace = json!({
+            "gas": 26809,
+            "failed": true,
+            "returnValue": "0x",
+            "structLogs": [
+                {"pc": 0, "op": "PUSH1", "gas": 5000, "gasCost": 3, "depth": 1, "stack": []},
+                {"pc": 2, "op": "JUMP", "gas": 4997, "gasCost": 8, "depth": 1,
+                    "stack": ["0x1"], "error": "InvalidJump"},
+                {"pc": 3, "op": "POP", "gas": 4989, "gasCost": 2, "depth": 1,
+                    "stack": [], "error": "StackUnderflow"}
+            ]
+        });
+        normalize_exec_trace(&mut trace);
+        assert_eq!(trace["returnValue"], "");
+        let steps: Vec<GethExecStep> = serde_json::from_value(trace["structLogs"].clone()).unwrap();
+        assert_eq!(steps[0].error, None);
+        assert_eq!(steps[1].error, Some(GethExecError::InvalidJump));
+        assert_eq!(
+            steps[2].error,
+            Some(GethExecError::StackUnderflowUnspecified)
+        );
+    }
+
+    #[test]
+    fn normalize_memory_string() {
+        let mut trace = json!({
+            "structLogs": [{"memory": format!("0x{}01", "00".repeat(32))}]
+        });
+        normalize_exec_trace(&mut trace);
+        assert_eq!(
+            trace["structLogs"][0]["memory"],
+            json!(["00".repeat(32), format!("01{}", "0".repeat(62))])
+        );
+    }
+}
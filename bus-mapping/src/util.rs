@@ -1,6 +1,15 @@
 //! ..
+use crate::Error;
 pub use eth_types::{KECCAK_CODE_HASH_EMPTY, POSEIDON_CODE_HASH_EMPTY};
-use std::{convert::Infallible, str::FromStr, sync::LazyLock};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    convert::Infallible,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    sync::LazyLock,
+};
 
 /// ..
 pub fn read_env_var<T: Clone + FromStr>(var_name: &'static str, default: T) -> T {
@@ -46,3 +55,61 @@ impl FromStr for GethTraceSanityCheckLevel {
         }
     }
 }
+
+#[derive(Serialize)]
+struct VersionedRef<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: u32,
+}
+
+/// Write `data` as JSON to `path`, tagged with the format `version`.
+pub fn dump_versioned<T: Serialize>(
+    path: impl AsRef<Path>,
+    version: u32,
+    data: &T,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path).map_err(Error::IoError)?);
+    serde_json::to_writer(&mut writer, &VersionedRef { version, data })
+        .map_err(Error::SerdeError)?;
+    writer.flush().map_err(Error::IoError)
+}
+
+/// Read a value written by [`dump_versioned`]. Dumps tagged with another `version` are
+/// rejected.
+pub fn load_versioned<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    version: u32,
+) -> Result<T, Error> {
+    let path = path.as_ref();
+    let open = || File::open(path).map(BufReader::new).map_err(Error::IoError);
+    match serde_json::from_reader::<_, Versioned<T>>(open()?) {
+        Ok(versioned) if versioned.version == version => Ok(versioned.data),
+        Ok(versioned) => Err(Error::DumpVersionMismatch {
+            expected: version,
+            found: versioned.version,
+        }),
+        Err(err) => {
+            // a dump in an older layout usually fails to parse, report its version if possible
+            match serde_json::from_reader::<_, VersionOnly>(open()?) {
+                Ok(VersionOnly { version: found }) if found != version => {
+                    Err(Error::DumpVersionMismatch {
+                        expected: version,
+                        found,
+                    })
+                }
+                _ => Err(Error::SerdeError(err)),
+            }
+        }
+    }
+}
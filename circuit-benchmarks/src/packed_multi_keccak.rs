@@ -21,7 +21,10 @@ mod tests {
     use rand::SeedableRng;
     use rand_xorshift::XorShiftRng;
     use std::env::var;
-    use zkevm_circuits::{keccak_circuit::TestKeccakCircuit, util::SubCircuit};
+    use zkevm_circuits::{
+        keccak_circuit::{keccak_packed_multi::get_num_rows_per_round, TestKeccakCircuit},
+        util::SubCircuit,
+    };
 
     #[cfg_attr(not(feature = "benches"), ignore)]
     #[cfg_attr(not(feature = "print-trace"), allow(unused_variables))] // FIXME: remove this after ark-std upgrade
@@ -37,6 +40,8 @@ mod tests {
             .expect("No DEGREE env var was provided")
             .parse()
             .expect("Cannot parse DEGREE env var as u32");
+        // Rows per keccak_f inner round, set with the KECCAK_ROWS env var
+        let rows_per_round = get_num_rows_per_round();
 
         // Use the complete circuit
         let inputs = vec![(0u8..135).collect::<Vec<_>>(); 3];
@@ -46,6 +51,10 @@ mod tests {
             2usize.pow(degree) - TestKeccakCircuit::<Fr>::unusable_rows(),
             inputs,
         );
+        log::info!(
+            "{BENCHMARK_ID} with {rows_per_round} rows per round, capacity = {:?}",
+            circuit.capacity()
+        );
 
         // Initialize the polynomial commitment parameters
         let mut rng = XorShiftRng::from_seed([
@@ -54,7 +63,9 @@ mod tests {
         ]);
 
         // Bench setup generation
-        let setup_message = format!("{BENCHMARK_ID} {setup_prfx} with degree = {degree}");
+        let setup_message = format!(
+            "{BENCHMARK_ID} {setup_prfx} with degree = {degree}, rows per round = {rows_per_round}"
+        );
         let start1 = start_timer!(|| setup_message);
         let general_params = ParamsKZG::<Bn256>::setup(degree, &mut rng);
         let verifier_params: ParamsVerifierKZG<Bn256> = general_params.verifier_params().clone();
@@ -67,7 +78,9 @@ mod tests {
         let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
 
         // Bench proof generation time
-        let proof_message = format!("{BENCHMARK_ID} {proof_gen_prfx} with degree = {degree}");
+        let proof_message = format!(
+            "{BENCHMARK_ID} {proof_gen_prfx} with degree = {degree}, rows per round = {rows_per_round}"
+        );
         let start2 = start_timer!(|| proof_message);
         create_proof::<
             KZGCommitmentScheme<Bn256>,
@@ -1283,6 +1283,44 @@ impl<'de> Deserialize<'de> for OpcodeId {
     }
 }
 
+/// Serde helpers encoding an [`OpcodeId`] as its byte value. Unlike the default mnemonic
+/// encoding, this also round-trips `INVALID(_)` opcodes; use it with `#[serde(with = "...")]`.
+pub mod serde_opcode_byte {
+    use super::OpcodeId;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serialize an [`OpcodeId`] as a `u8`.
+    pub fn serialize<S: Serializer>(opcode: &OpcodeId, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(opcode.as_u8())
+    }
+
+    /// Deserialize an [`OpcodeId`] from a `u8`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OpcodeId, D::Error> {
+        u8::deserialize(deserializer).map(OpcodeId::from)
+    }
+
+    /// Same as the parent module, for an optional [`OpcodeId`].
+    pub mod option {
+        use super::OpcodeId;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        /// Serialize an optional [`OpcodeId`] as an optional `u8`.
+        pub fn serialize<S: Serializer>(
+            opcode: &Option<OpcodeId>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            opcode.map(|op| op.as_u8()).serialize(serializer)
+        }
+
+        /// Deserialize an optional [`OpcodeId`] from an optional `u8`.
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<OpcodeId>, D::Error> {
+            Option::<u8>::deserialize(deserializer).map(|op| op.map(OpcodeId::from))
+        }
+    }
+}
+
 impl fmt::Display for OpcodeId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{self:?}")
@@ -14,14 +14,14 @@ use ethers_core::types::{
 use halo2curves::{group::ff::PrimeField, secp256k1::Fq};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_with::serde_as;
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use strum_macros::EnumIter;
 
 /// Tx type
-#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TxType {
     /// EIP 155 tx
     #[default]
@@ -208,6 +208,8 @@ pub struct BlockConstants {
     pub number: U64,
     /// difficulty
     pub difficulty: Word,
+    /// mix hash, which holds PREVRANDAO for post-Merge blocks (EIP-4399)
+    pub mix_hash: Hash,
     /// gas limit
     pub gas_limit: Word,
     /// base fee
@@ -223,6 +225,7 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             timestamp: block.timestamp,
             number: block.number.ok_or(Error::IncompleteBlock)?,
             difficulty: block.difficulty,
+            mix_hash: block.mix_hash.unwrap_or_default(),
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
         })
@@ -236,6 +239,7 @@ impl BlockConstants {
         timestamp: Word,
         number: U64,
         difficulty: Word,
+        mix_hash: Hash,
         gas_limit: Word,
         base_fee: Word,
     ) -> BlockConstants {
@@ -244,6 +248,7 @@ impl BlockConstants {
             timestamp,
             number,
             difficulty,
+            mix_hash,
             gas_limit,
             base_fee,
         }
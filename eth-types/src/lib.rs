@@ -478,6 +478,8 @@ pub enum GethExecError {
         /// required length
         required: u64,
     },
+    /// stack underflow, reported without the stack length and requirements (e.g. by Reth)
+    StackUnderflowUnspecified,
     /// stack limit reached
     StackOverflow {
         /// stack length
@@ -507,7 +509,9 @@ impl GethExecError {
             GethExecError::GasUintOverflow => "gas uint64 overflow",
             GethExecError::InvalidCode => "invalid code: must not begin with 0xef",
             GethExecError::NonceUintOverflow => "nonce uint64 overflow",
-            GethExecError::StackUnderflow { .. } => "stack underflow",
+            GethExecError::StackUnderflow { .. } | GethExecError::StackUnderflowUnspecified => {
+                "stack underflow"
+            }
             GethExecError::StackOverflow { .. } => "stack limit reached",
             GethExecError::InvalidOpcode(_) => "invalid opcode",
         }
@@ -569,6 +573,7 @@ impl FromStr for GethExecError {
             "gas uint64 overflow" => GethExecError::GasUintOverflow,
             "invalid code: must not begin with 0xef" => GethExecError::InvalidCode,
             "nonce uint64 overflow" => GethExecError::NonceUintOverflow,
+            "stack underflow" => GethExecError::StackUnderflowUnspecified,
             _ if v.starts_with("stack underflow") => {
                 let caps = STACK_UNDERFLOW_RE.captures(v).unwrap();
                 let stack_len = caps.get(1).unwrap().as_str().parse::<u64>().unwrap();
@@ -160,6 +160,11 @@ impl StateDB {
         self.state.insert(*addr, acc);
     }
 
+    /// Remove the [`Account`] at `addr` from the StateDB.
+    pub fn remove_account(&mut self, addr: &Address) {
+        self.state.remove(addr);
+    }
+
     /// Get a reference to the [`Account`] at `addr`.  Returns false and a zero
     /// [`Account`] when the [`Account`] wasn't found in the state.
     pub fn get_account(&self, addr: &Address) -> (bool, &Account) {
@@ -98,6 +98,7 @@ type Block struct {
 	Timestamp  *hexutil.Big   `json:"timestamp"`
 	Number     *hexutil.Big   `json:"number"`
 	Difficulty *hexutil.Big   `json:"difficulty"`
+	MixHash    common.Hash    `json:"mix_hash"`
 	GasLimit   *hexutil.Big   `json:"gas_limit"`
 	BaseFee    *hexutil.Big   `json:"base_fee"`
 }
@@ -216,8 +217,11 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		return nil, fmt.Errorf("txs total gas: %d Exceeds block gas limit: %d", txsGasLimit, blockGasLimit)
 	}
 
-	// For opcode PREVRANDAO
-	randao := common.BigToHash(toBigInt(config.Block.Difficulty)) // TODO: fix
+	// For opcode PREVRANDAO, fall back to difficulty when no mix hash is given
+	randao := config.Block.MixHash
+	if randao == (common.Hash{}) {
+		randao = common.BigToHash(toBigInt(config.Block.Difficulty))
+	}
 
 	blockCtx := vm.BlockContext{
 		CanTransfer: core.CanTransfer,
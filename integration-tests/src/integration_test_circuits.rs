@@ -1,7 +1,7 @@
 use crate::{get_client, GenDataOutput};
 use bus_mapping::{
     circuit_input_builder::{
-        BuilderClient, CircuitInputBuilder, CircuitsParams, PrecompileEcParams,
+        BuilderClient, CircuitInputBuilder, CircuitsParams, PrecompileEcParams, SubCircuitSelection,
     },
     mock::BlockData,
 };
@@ -97,7 +97,10 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_add: MAX_EC_ADD,
         ec_mul: MAX_EC_MUL,
         ec_pairing: MAX_EC_PAIRING,
+        ec_pairing_chunks: MAX_EC_PAIRING,
     },
+    max_sig_verifs: 0,
+    sub_circuits: SubCircuitSelection::all(),
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
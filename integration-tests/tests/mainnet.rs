@@ -1,6 +1,8 @@
 #![allow(unused_mut)]
 use bus_mapping::{
-    circuit_input_builder::{BuilderClient, CircuitsParams, PrecompileEcParams},
+    circuit_input_builder::{
+        BuilderClient, CircuitsParams, PrecompileEcParams, SubCircuitSelection,
+    },
     util::read_env_var,
     Error::JSONRpcError,
 };
@@ -44,7 +46,10 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_add: 10,
         ec_mul: 10,
         ec_pairing: 4,
+        ec_pairing_chunks: 4,
     },
+    max_sig_verifs: 0,
+    sub_circuits: SubCircuitSelection::all(),
 };
 
 #[tokio::test]
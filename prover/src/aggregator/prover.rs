@@ -5,7 +5,7 @@ use crate::{
     io::{force_to_read, try_to_read},
     BatchProof, ChunkProof,
 };
-use aggregator::{ChunkHash, MAX_AGG_SNARKS};
+use aggregator::{ChunkHash, DataAvailability, PiHashLayout, MAX_AGG_SNARKS};
 use anyhow::{bail, Result};
 use sha2::{Digest, Sha256};
 use snark_verifier_sdk::Snark;
@@ -17,10 +17,37 @@ pub struct Prover {
     pub inner: common::Prover,
     pub chunk_protocol: Vec<u8>,
     raw_vk: Option<Vec<u8>>,
+    data_availability: DataAvailability,
+    chunk_pi_layout: PiHashLayout,
+    batch_pi_layout: PiHashLayout,
 }
 
 impl Prover {
     pub fn from_dirs(params_dir: &str, assets_dir: &str) -> Self {
+        Self::from_dirs_with_data_availability(params_dir, assets_dir, DataAvailability::Blob)
+    }
+
+    pub fn from_dirs_with_data_availability(
+        params_dir: &str,
+        assets_dir: &str,
+        data_availability: DataAvailability,
+    ) -> Self {
+        Self::from_dirs_with_pi_layouts(
+            params_dir,
+            assets_dir,
+            data_availability,
+            PiHashLayout::default_chunk(),
+            PiHashLayout::default_batch(data_availability),
+        )
+    }
+
+    pub fn from_dirs_with_pi_layouts(
+        params_dir: &str,
+        assets_dir: &str,
+        data_availability: DataAvailability,
+        chunk_pi_layout: PiHashLayout,
+        batch_pi_layout: PiHashLayout,
+    ) -> Self {
         env::set_var("KECCAK_ROW", AGG_KECCAK_ROW.to_string());
 
         let inner = common::Prover::from_params_dir(params_dir, &AGG_DEGREES);
@@ -39,6 +66,9 @@ impl Prover {
             inner,
             chunk_protocol,
             raw_vk,
+            data_availability,
+            chunk_pi_layout,
+            batch_pi_layout,
         }
     }
 
@@ -147,6 +177,9 @@ impl Prover {
             LayerId::Layer3.degree(),
             &chunk_hashes,
             &layer2_snarks,
+            self.data_availability,
+            &self.chunk_pi_layout,
+            &self.batch_pi_layout,
             output_dir,
         )?;
         log::info!("Got aggregation snark (layer-3): {name}");
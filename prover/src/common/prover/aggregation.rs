@@ -4,13 +4,16 @@ use crate::{
     io::{load_snark, write_snark},
     utils::gen_rng,
 };
-use aggregator::{AggregationCircuit, BatchHash, ChunkHash, MAX_AGG_SNARKS};
+use aggregator::{
+    AggregationCircuit, BatchHash, ChunkHash, DataAvailability, PiHashLayout, MAX_AGG_SNARKS,
+};
 use anyhow::{anyhow, Result};
 use rand::Rng;
 use snark_verifier_sdk::Snark;
 use std::env;
 
 impl Prover {
+    #[allow(clippy::too_many_arguments)]
     pub fn gen_agg_snark(
         &mut self,
         id: &str,
@@ -18,18 +21,35 @@ impl Prover {
         mut rng: impl Rng + Send,
         chunk_hashes: &[ChunkHash],
         previous_snarks: &[Snark],
+        data_availability: DataAvailability,
+        chunk_pi_layout: &PiHashLayout,
+        batch_pi_layout: &PiHashLayout,
     ) -> Result<Snark> {
         env::set_var("AGGREGATION_CONFIG", layer_config_path(id));
 
-        let batch_hash = BatchHash::construct(chunk_hashes);
+        let batch_hash = BatchHash::try_construct(
+            chunk_hashes,
+            data_availability,
+            chunk_pi_layout.clone(),
+            batch_pi_layout.clone(),
+        )
+        .map_err(|err| anyhow!("Failed to construct batch hash: {err}"))?;
 
-        let circuit: AggregationCircuit<MAX_AGG_SNARKS> =
-            AggregationCircuit::new(self.params(degree), previous_snarks, &mut rng, batch_hash)
-                .map_err(|err| anyhow!("Failed to construct aggregation circuit: {err:?}"))?;
+        let circuit: AggregationCircuit<MAX_AGG_SNARKS> = AggregationCircuit::new(
+            self.params(degree),
+            previous_snarks,
+            &mut rng,
+            batch_hash,
+            data_availability,
+            chunk_pi_layout.clone(),
+            batch_pi_layout.clone(),
+        )
+        .map_err(|err| anyhow!("Failed to construct aggregation circuit: {err:?}"))?;
 
         self.gen_snark(id, degree, &mut rng, circuit, "gen_agg_snark")
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn load_or_gen_agg_snark(
         &mut self,
         name: &str,
@@ -37,6 +57,9 @@ impl Prover {
         degree: u32,
         chunk_hashes: &[ChunkHash],
         previous_snarks: &[Snark],
+        data_availability: DataAvailability,
+        chunk_pi_layout: &PiHashLayout,
+        batch_pi_layout: &PiHashLayout,
         output_dir: Option<&str>,
     ) -> Result<Snark> {
         let file_path = format!(
@@ -50,7 +73,16 @@ impl Prover {
             Some(snark) => Ok(snark),
             None => {
                 let rng = gen_rng();
-                let result = self.gen_agg_snark(id, degree, rng, chunk_hashes, previous_snarks);
+                let result = self.gen_agg_snark(
+                    id,
+                    degree,
+                    rng,
+                    chunk_hashes,
+                    previous_snarks,
+                    data_availability,
+                    chunk_pi_layout,
+                    batch_pi_layout,
+                );
                 if let (Some(_), Ok(snark)) = (output_dir, &result) {
                     write_snark(&file_path, snark);
                 }
@@ -6,5 +6,7 @@ mod verifier;
 
 pub use self::prover::Prover;
 #[cfg(feature = "scroll")]
-pub use capacity_checker::{CircuitCapacityChecker, RowUsage, SubCircuitRowUsage};
+pub use capacity_checker::{
+    CallRowUsage, CircuitCapacityChecker, RowUsage, SubCircuitRowUsage, TxRowUsage,
+};
 pub use verifier::Verifier;
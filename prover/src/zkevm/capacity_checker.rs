@@ -1,17 +1,38 @@
 use super::circuit::{
     block_traces_to_witness_block_with_updated_state, calculate_row_usage_of_witness_block,
 };
-use bus_mapping::circuit_input_builder::{self, CircuitInputBuilder};
+use bus_mapping::{
+    circuit_input_builder::{self, CircuitInputBuilder, CopyDataType, CopyEvent},
+    operation::AccountField,
+    precompile::PrecompileAuxData,
+};
 use eth_types::{
     l2_types::BlockTrace,
-    state_db::{CodeDB, StateDB},
-    ToWord, H256,
+    state_db::{Account, CodeDB, StateDB},
+    Address, ToWord, H256,
 };
+use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 use itertools::Itertools;
-use mpt_zktrie::state::ZktrieState;
+use mpt_zktrie::state::{ZkTrieHash, ZktrieState};
 use serde_derive::{Deserialize, Serialize};
-use zkevm_circuits::super_circuit::params::{
-    get_sub_circuit_limit_and_confidence, get_super_circuit_params,
+use std::collections::{BTreeMap, BTreeSet};
+use zkevm_circuits::{
+    evm_circuit::ExecutionState,
+    exp_circuit::OFFSET_INCREMENT,
+    keccak_circuit::keccak_packed_multi::get_num_rows_per_update_with_rows_per_round,
+    modexp_circuit::MODEXPCONFIG_EACH_CHIP_ROWS,
+    sha256_circuit::SHA256Circuit,
+    sig_circuit::SigCircuit,
+    super_circuit::{
+        params::{
+            get_sub_circuit_limit_and_confidence, get_super_circuit_params,
+            DefaultSuperCircuitParams, SuperCircuitParams,
+        },
+        SuperCircuitEcc,
+    },
+    tx_circuit::CHUNK_TXBYTES_BLOB_LIMIT,
+    util::Challenges,
+    witness::{tx_convert, RlpFsmWitnessGen, Transaction},
 };
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -97,14 +118,276 @@ impl RowUsage {
     }
 }
 
+/// Rows attributed to an opcode (or a precompile) executed by a contract within a tx.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CallRowUsage {
+    /// Address of the executed code.
+    pub address: Address,
+    /// Opcode, or execution state for the steps without opcode, e.g. precompiles.
+    pub opcode: String,
+    pub copy_rows: usize,
+    pub keccak_rows: usize,
+    pub ecc_rows: usize,
+}
+
+impl CallRowUsage {
+    fn total_rows(&self) -> usize {
+        self.copy_rows + self.keccak_rows + self.ecc_rows
+    }
+}
+
+/// Row usage attributed to a single tx.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TxRowUsage {
+    pub tx_hash: H256,
+    pub evm_rows: usize,
+    pub rw_count: usize,
+    pub copy_rows: usize,
+    pub keccak_rows: usize,
+    pub ecc_rows: usize,
+    /// Rows attributed to the tx in each sub circuit, see `attribute_row_usage`.
+    pub row_usage: RowUsage,
+    /// Copy, keccak and ECC rows per contract and opcode, heaviest first.
+    pub calls: Vec<CallRowUsage>,
+}
+
+impl TxRowUsage {
+    /// Rows attributed to the tx in the sub circuit `name`.
+    pub fn sub_circuit_rows(&self, name: &str) -> usize {
+        self.row_usage
+            .row_usage_details
+            .iter()
+            .find(|x| x.name == name)
+            .map_or(0, |x| x.row_number)
+    }
+
+    /// Split the row usage of the trace among its txs. The evm, state, copy, keccak and ecc rows
+    /// are the ones attributed to the steps of each tx, the sig rows are split by the number of
+    /// signatures verified for each tx, the tx and rlp rows by the size of each tx, and the
+    /// rows of the other sub circuits evenly.
+    fn attribute_row_usage(tx_row_usages: &mut [Self], txs: &[Transaction], row_usage: &RowUsage) {
+        let split = |total: usize, weights: &[usize]| -> Vec<usize> {
+            let sum: usize = weights.iter().sum();
+            weights
+                .iter()
+                .map(|weight| match sum {
+                    0 => total / weights.len(),
+                    _ => (total as u128 * *weight as u128 / sum as u128) as usize,
+                })
+                .collect_vec()
+        };
+        let sig_verifs = txs
+            .iter()
+            .map(|tx| {
+                usize::from(!tx.tx_type.is_l1_msg())
+                    + tx.steps
+                        .iter()
+                        .filter(|step| step.execution_state == ExecutionState::PrecompileEcrecover)
+                        .count()
+            })
+            .collect_vec();
+        let tx_sizes = txs.iter().map(|tx| tx.rlp_signed.len()).collect_vec();
+        let evenly = vec![1; txs.len()];
+
+        let mut details = vec![vec![]; tx_row_usages.len()];
+        for detail in row_usage.row_usage_details.iter() {
+            let rows = match detail.name.as_str() {
+                "evm" => tx_row_usages.iter().map(|tx| tx.evm_rows).collect_vec(),
+                "state" => tx_row_usages.iter().map(|tx| tx.rw_count).collect_vec(),
+                "copy" => tx_row_usages.iter().map(|tx| tx.copy_rows).collect_vec(),
+                "keccak" => tx_row_usages.iter().map(|tx| tx.keccak_rows).collect_vec(),
+                "ecc" => tx_row_usages.iter().map(|tx| tx.ecc_rows).collect_vec(),
+                "sig" => split(detail.row_number, &sig_verifs),
+                "tx" | "rlp" => split(detail.row_number, &tx_sizes),
+                _ => split(detail.row_number, &evenly),
+            };
+            for (tx_details, row_number) in details.iter_mut().zip_eq(rows) {
+                tx_details.push(SubCircuitRowUsage {
+                    name: detail.name.clone(),
+                    row_number,
+                });
+            }
+        }
+        for (tx_row_usage, details) in tx_row_usages.iter_mut().zip_eq(details) {
+            tx_row_usage.row_usage = RowUsage::from_row_usage_details(details);
+        }
+    }
+
+    /// Attribute the rows of each tx to the steps responsible for them. The `ecc_rows` of the
+    /// txs are split among the precompile calls in proportion to the cells they use.
+    fn from_txs(txs: &[Transaction], copy_events: &[CopyEvent], ecc_rows: usize) -> Vec<Self> {
+        let mut tx_row_usages = txs
+            .iter()
+            .map(|tx| Self {
+                tx_hash: tx.hash,
+                evm_rows: tx
+                    .steps
+                    .iter()
+                    .map(|step| step.execution_state.get_step_height())
+                    .sum(),
+                rw_count: tx.steps.iter().map(|step| step.rw_indices.len()).sum(),
+                ..Default::default()
+            })
+            .collect_vec();
+        // (tx index, address, opcode) -> usage
+        let mut call_row_usages = BTreeMap::new();
+
+        // copy events are matched to the step whose rw counter range contains their start
+        let steps = txs
+            .iter()
+            .enumerate()
+            .flat_map(|(tx_idx, tx)| {
+                tx.steps
+                    .iter()
+                    .enumerate()
+                    .map(move |(step_idx, step)| (step.rw_counter, tx_idx, step_idx))
+            })
+            .collect_vec();
+        for copy_event in copy_events.iter() {
+            let pos = steps.partition_point(|(rwc, _, _)| *rwc <= copy_event.rw_counter_start.0);
+            let Some(&(_, tx_idx, step_idx)) = pos.checked_sub(1).map(|pos| &steps[pos]) else {
+                continue;
+            };
+            let copy_rows = copy_event.full_length() as usize * 2;
+            let keccak_rows = if copy_event.dst_type == CopyDataType::RlcAcc {
+                (copy_event.copy_bytes.bytes.len() as f64 / 136.0).ceil() as usize
+                    * get_num_rows_per_update_with_rows_per_round(
+                        DefaultSuperCircuitParams::KECCAK_ROWS_PER_ROUND,
+                    )
+            } else {
+                0
+            };
+            tx_row_usages[tx_idx].copy_rows += copy_rows;
+            tx_row_usages[tx_idx].keccak_rows += keccak_rows;
+            let usage = Self::call_row_usage(&mut call_row_usages, txs, tx_idx, step_idx);
+            usage.copy_rows += copy_rows;
+            usage.keccak_rows += keccak_rows;
+        }
+
+        let ecc_cells = txs
+            .iter()
+            .enumerate()
+            .flat_map(|(tx_idx, tx)| {
+                tx.steps
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(step_idx, step)| {
+                        let cells = match (&step.execution_state, &step.aux_data) {
+                            (ExecutionState::PrecompileBn256Add, _) => {
+                                SuperCircuitEcc::<Fr>::num_cells(1, 0, 0)
+                            }
+                            (ExecutionState::PrecompileBn256ScalarMul, _) => {
+                                SuperCircuitEcc::<Fr>::num_cells(0, 1, 0)
+                            }
+                            (
+                                ExecutionState::PrecompileBn256Pairing,
+                                Some(PrecompileAuxData::EcPairing(aux_data)),
+                            ) => match aux_data.as_ref() {
+                                Ok(aux_data) => {
+                                    SuperCircuitEcc::<Fr>::num_cells(0, 0, aux_data.0.n_chunks())
+                                }
+                                Err(_) => 0,
+                            },
+                            _ => 0,
+                        };
+                        (cells > 0).then_some((tx_idx, step_idx, cells))
+                    })
+            })
+            .collect_vec();
+        let total_ecc_cells: usize = ecc_cells.iter().map(|(_, _, cells)| cells).sum();
+        for (tx_idx, step_idx, cells) in ecc_cells {
+            let rows = (ecc_rows as u128 * cells as u128 / total_ecc_cells as u128) as usize;
+            tx_row_usages[tx_idx].ecc_rows += rows;
+            Self::call_row_usage(&mut call_row_usages, txs, tx_idx, step_idx).ecc_rows += rows;
+        }
+
+        for ((tx_idx, _, _), usage) in call_row_usages {
+            tx_row_usages[tx_idx].calls.push(usage);
+        }
+        for tx_row_usage in tx_row_usages.iter_mut() {
+            tx_row_usage
+                .calls
+                .sort_by_key(|usage| std::cmp::Reverse(usage.total_rows()));
+        }
+        tx_row_usages
+    }
+
+    fn call_row_usage<'a>(
+        call_row_usages: &'a mut BTreeMap<(usize, Address, String), CallRowUsage>,
+        txs: &[Transaction],
+        tx_idx: usize,
+        step_idx: usize,
+    ) -> &'a mut CallRowUsage {
+        let tx = &txs[tx_idx];
+        let step = &tx.steps[step_idx];
+        let call = &tx.calls[step.call_index];
+        let address = call.code_address.unwrap_or(call.callee_address);
+        let opcode = step
+            .opcode
+            .map(|op| op.to_string())
+            .unwrap_or_else(|| format!("{:?}", step.execution_state));
+        call_row_usages
+            .entry((tx_idx, address, opcode.clone()))
+            .or_insert_with(|| CallRowUsage {
+                address,
+                opcode,
+                ..Default::default()
+            })
+    }
+}
+
+type BuilderCtx = (CodeDB, StateDB, Option<ZktrieState>);
+
+/// Key of the MPT update of an account field, the MPT updates are deduplicated by key.
+type MptAccountKey = (Address, AccountField);
+
+/// What an estimated trace changed in the builder context, recorded so that it can be undone
+/// without cloning the whole context.
+#[derive(Debug)]
+struct Checkpoint {
+    acc_row_usage: RowUsage,
+    /// Whether there was a builder context before the trace.
+    has_builder_ctx: bool,
+    /// The accounts of the storage trace, which are all the accounts the trace can touch, with
+    /// their state before it. `None` for the accounts not in the state db yet.
+    accounts: Vec<(Address, Option<Account>)>,
+    /// The codes first seen in the trace.
+    new_codes: Vec<H256>,
+    /// The account MPT keys first updated in the trace.
+    new_mpt_keys: Vec<MptAccountKey>,
+    /// The trie root before the trace, when the zktrie state is kept.
+    mpt_root: Option<ZkTrieHash>,
+}
+
+/// Row usage of an estimated trace, with what it added to the builder context.
+struct TraceRowUsage {
+    row_usage: RowUsage,
+    tx_row_usages: Vec<TxRowUsage>,
+    /// The codes first seen in the trace.
+    new_codes: Vec<H256>,
+    /// The account MPT keys first updated in the trace, only in delta mode.
+    new_mpt_keys: Vec<MptAccountKey>,
+}
+
 #[derive(Debug)]
 pub struct CircuitCapacityChecker {
     /// When "light_mode" enabled, we skip zktrie subcircuit in row estimation to avoid the heavy
     /// poseidon cost.
     pub light_mode: bool,
+    /// When "delta_mode" enabled, no witness block is built: each trace is only executed and
+    /// contributes the rows of its own steps, copy events, hash inputs and MPT updates, see
+    /// `delta_row_usage`. The fixed overhead of the sub circuits isn't accounted, and the zktrie
+    /// state isn't kept, as in light mode. The last traces can be undone with `rollback_last_tx`.
+    pub delta_mode: bool,
     pub acc_row_usage: RowUsage,
     pub row_usages: Vec<RowUsage>,
-    pub builder_ctx: Option<(CodeDB, StateDB, Option<ZktrieState>)>,
+    /// Row usage attributed to each tx, grouped by estimated trace like `row_usages`.
+    pub tx_row_usages: Vec<Vec<TxRowUsage>>,
+    pub builder_ctx: Option<BuilderCtx>,
+    /// Account MPT keys updated by the estimated traces, only kept in delta mode.
+    mpt_account_keys: BTreeSet<MptAccountKey>,
+    /// Undo record of each estimated trace, only kept in delta mode.
+    checkpoints: Vec<Checkpoint>,
 }
 
 impl Default for CircuitCapacityChecker {
@@ -119,18 +402,29 @@ impl CircuitCapacityChecker {
         Self {
             acc_row_usage: RowUsage::new(),
             row_usages: Vec::new(),
+            tx_row_usages: Vec::new(),
             light_mode: true,
+            delta_mode: false,
             builder_ctx: None,
+            mpt_account_keys: BTreeSet::new(),
+            checkpoints: Vec::new(),
         }
     }
     pub fn reset(&mut self) {
         self.builder_ctx = None;
         self.acc_row_usage = RowUsage::new();
         self.row_usages = Vec::new();
+        self.tx_row_usages = Vec::new();
+        self.mpt_account_keys = BTreeSet::new();
+        self.checkpoints = Vec::new();
     }
     pub fn set_light_mode(&mut self, light_mode: bool) {
         self.light_mode = light_mode;
     }
+    /// Should be set before estimating the first tx, or right after a `reset`.
+    pub fn set_delta_mode(&mut self, delta_mode: bool) {
+        self.delta_mode = delta_mode;
+    }
     pub fn get_tx_num(&self) -> usize {
         self.row_usages.len()
     }
@@ -145,7 +439,119 @@ impl CircuitCapacityChecker {
         &mut self,
         trace: BlockTrace,
     ) -> Result<RowUsage, anyhow::Error> {
-        let (mut estimate_builder, codedb_prev) =
+        let checkpoint = self.delta_mode.then(|| self.checkpoint(&trace));
+
+        let trace_row_usage = match self.estimate_tx_row_usage(trace) {
+            Ok(trace_row_usage) => trace_row_usage,
+            Err(e) => {
+                if let Some(checkpoint) = checkpoint {
+                    self.restore(checkpoint);
+                }
+                return Err(e);
+            }
+        };
+        if let Some(mut checkpoint) = checkpoint {
+            checkpoint.new_codes = trace_row_usage.new_codes;
+            checkpoint.new_mpt_keys = trace_row_usage.new_mpt_keys;
+            self.checkpoints.push(checkpoint);
+        }
+        self.acc_row_usage.add(&trace_row_usage.row_usage);
+        self.row_usages.push(trace_row_usage.row_usage);
+        self.tx_row_usages.push(trace_row_usage.tx_row_usages);
+        Ok(self.acc_row_usage.normalize())
+    }
+    /// Undo the last tx estimated in delta mode, restoring the accumulated row usage and the
+    /// execution state from before it. Returns the row usage of the removed tx, or `None` when
+    /// there is nothing to undo.
+    pub fn rollback_last_tx(&mut self) -> Option<RowUsage> {
+        let checkpoint = self.checkpoints.pop()?;
+        self.restore(checkpoint);
+        self.tx_row_usages.pop();
+        self.row_usages.pop()
+    }
+    fn checkpoint(&self, trace: &BlockTrace) -> Checkpoint {
+        let (accounts, mpt_root) = match &self.builder_ctx {
+            Some((_, sdb, mpt_state)) => (
+                trace
+                    .storage_trace
+                    .proofs
+                    .iter()
+                    .flat_map(|proofs| proofs.keys())
+                    .chain(trace.storage_trace.storage_proofs.keys())
+                    .unique()
+                    .map(|addr| {
+                        let (existed, account) = sdb.get_account(addr);
+                        (*addr, existed.then(|| account.clone()))
+                    })
+                    .collect(),
+                mpt_state.as_ref().map(|state| *state.root()),
+            ),
+            None => (vec![], None),
+        };
+        Checkpoint {
+            acc_row_usage: self.acc_row_usage.clone(),
+            has_builder_ctx: self.builder_ctx.is_some(),
+            accounts,
+            new_codes: vec![],
+            new_mpt_keys: vec![],
+            mpt_root,
+        }
+    }
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        self.acc_row_usage = checkpoint.acc_row_usage;
+        for key in checkpoint.new_mpt_keys {
+            self.mpt_account_keys.remove(&key);
+        }
+        if !checkpoint.has_builder_ctx {
+            self.builder_ctx = None;
+            return;
+        }
+        let Some((code_db, sdb, mpt_state)) = &mut self.builder_ctx else {
+            return;
+        };
+        for (addr, account) in checkpoint.accounts {
+            match account {
+                Some(account) => sdb.set_account(&addr, account),
+                None => sdb.remove_account(&addr),
+            }
+        }
+        for hash in checkpoint.new_codes {
+            code_db.0.remove(&hash);
+        }
+        if let (Some(mpt_state), Some(root)) = (mpt_state, checkpoint.mpt_root) {
+            // the db still holds the nodes of the previous root
+            let switched = mpt_state.switch_to(root);
+            debug_assert!(switched, "previous trie root missing");
+        }
+    }
+    /// The sub circuit closest to its limit, with its normalized row usage.
+    pub fn limiting_sub_circuit(&self) -> Option<SubCircuitRowUsage> {
+        if self.acc_row_usage.row_usage_details.is_empty() {
+            return None;
+        }
+        self.acc_row_usage
+            .normalize()
+            .row_usage_details
+            .into_iter()
+            .max_by_key(|x| x.row_number)
+    }
+    /// The `n` txs using the most rows of the limiting sub circuit, heaviest first. These are
+    /// the candidates to evict when the accumulated row usage overflows.
+    pub fn heaviest_txs(&self, n: usize) -> Vec<&TxRowUsage> {
+        let Some(limiting) = self.limiting_sub_circuit() else {
+            return vec![];
+        };
+        self.tx_row_usages
+            .iter()
+            .flatten()
+            .sorted_by_key(|tx| std::cmp::Reverse(tx.sub_circuit_rows(&limiting.name)))
+            .take(n)
+            .collect()
+    }
+    /// Returns the row usage of the trace, attributed to its txs, with the codes and the account
+    /// MPT keys first seen in it.
+    fn estimate_tx_row_usage(&mut self, trace: BlockTrace) -> Result<TraceRowUsage, anyhow::Error> {
+        let (mut estimate_builder, codedb_prev, more_trace) =
             if let Some((code_db, sdb, mpt_state)) = self.builder_ctx.take() {
                 // here we create a new builder for another (sealed) witness block
                 // this builder inherit the current execution state (sdb) of
@@ -175,53 +581,489 @@ impl CircuitCapacityChecker {
                 } else {
                     CircuitInputBuilder::new(sdb, CodeDB::new(), &builder_block)
                 };
-                builder.add_more_l2_trace(trace, false)?;
-                (builder, Some(code_db))
+                (builder, Some(code_db), Some(trace))
             } else {
                 (
                     CircuitInputBuilder::new_from_l2_trace(
                         get_super_circuit_params(),
                         trace,
                         false,
-                        self.light_mode,
+                        // the zktrie state is only needed to build the witness block
+                        self.light_mode || self.delta_mode,
                     )?,
                     None,
+                    None,
                 )
             };
-        let witness_block =
-            block_traces_to_witness_block_with_updated_state(vec![], &mut estimate_builder)?;
-        let mut rows = calculate_row_usage_of_witness_block(&witness_block)?;
+        let delta_mode = self.delta_mode;
+        let estimated = (|| -> Result<_, anyhow::Error> {
+            if let Some(trace) = more_trace {
+                estimate_builder.add_more_l2_trace(trace, false)?;
+            }
+            if delta_mode {
+                return Ok(None);
+            }
+            let witness_block =
+                block_traces_to_witness_block_with_updated_state(vec![], &mut estimate_builder)?;
+            let rows = calculate_row_usage_of_witness_block(&witness_block)?;
+            Ok(Some((witness_block, rows)))
+        })();
+        let estimated = match estimated {
+            Ok(estimated) => estimated,
+            Err(e) => {
+                // hand the context back for the checkpoint to undo the partial changes
+                if let (true, Some(code_db)) = (self.delta_mode, codedb_prev) {
+                    self.builder_ctx.replace((
+                        code_db,
+                        estimate_builder.sdb,
+                        estimate_builder.mpt_init_state,
+                    ));
+                }
+                return Err(e);
+            }
+        };
 
         let mut code_db = codedb_prev.unwrap_or_else(CodeDB::new);
-        // merge current codes with previous , and dedup bytecode row usage
-        // for bytecode circuit / poseidon circuit
-        for (hash, bytes) in estimate_builder.code_db.0 {
+        let mut new_codes = vec![];
+        let mut new_code_lens = vec![];
+        let mut seen_code_lens = vec![];
+        // merge current codes with previous, the codes evaluated in a previous run are only
+        // accounted once in the bytecode circuit and the poseidon circuit
+        for (hash, bytes) in std::mem::take(&mut estimate_builder.code_db.0) {
             let bytes_len = bytes.len();
-            // code for current run has been evaluated in previous
             if code_db.0.insert(hash, bytes).is_some() {
-                assert_eq!(rows[2].name, "bytecode");
-                rows[2].row_num_real -= bytes_len + 1;
-                assert_eq!(rows[11].name, "poseidon");
-                rows[11].row_num_real -= bytes_len / (31 * 2) * 9;
+                seen_code_lens.push(bytes_len);
+            } else {
+                new_codes.push(hash);
+                new_code_lens.push(bytes_len);
             }
         }
 
-        let row_usage_details: Vec<SubCircuitRowUsage> = rows
-            .into_iter()
-            .map(|x| SubCircuitRowUsage {
-                name: x.name,
-                row_number: x.row_num_real,
-            })
-            .collect_vec();
-        let tx_row_usage = RowUsage::from_row_usage_details(row_usage_details);
-        self.row_usages.push(tx_row_usage.clone());
-        self.acc_row_usage.add(&tx_row_usage);
+        let (row_usage, tx_row_usages, new_mpt_keys) = match estimated {
+            Some((witness_block, mut rows)) => {
+                let bytecode = rows.iter().position(|x| x.name == "bytecode").unwrap();
+                let poseidon = rows.iter().position(|x| x.name == "poseidon").unwrap();
+                for bytes_len in seen_code_lens {
+                    rows[bytecode].row_num_real -= bytes_len + 1;
+                    rows[poseidon].row_num_real -= bytes_len / (31 * 2) * 9;
+                }
+                let ecc_rows = rows
+                    .iter()
+                    .find(|x| x.name == "ecc")
+                    .map_or(0, |x| x.row_num_real);
+                let mut tx_row_usages =
+                    TxRowUsage::from_txs(&witness_block.txs, &witness_block.copy_events, ecc_rows);
+                let row_usage = RowUsage::from_row_usage_details(
+                    rows.into_iter()
+                        .map(|x| SubCircuitRowUsage {
+                            name: x.name,
+                            row_number: x.row_num_real,
+                        })
+                        .collect_vec(),
+                );
+                TxRowUsage::attribute_row_usage(&mut tx_row_usages, &witness_block.txs, &row_usage);
+                (row_usage, tx_row_usages, vec![])
+            }
+            None => self.delta_row_usage(&estimate_builder.block, &new_code_lens),
+        };
 
         self.builder_ctx.replace((
             code_db,
             estimate_builder.sdb,
             estimate_builder.mpt_init_state,
         ));
-        Ok(self.acc_row_usage.normalize())
+        Ok(TraceRowUsage {
+            row_usage,
+            tx_row_usages,
+            new_codes,
+            new_mpt_keys,
+        })
+    }
+    /// Row usage of the txs of `block`, the executed block of a single trace, computed from
+    /// their own steps, copy events, hash inputs and MPT updates, with the codes of
+    /// `new_code_lens` first seen in the trace. Returns it with its attribution to the txs and
+    /// the account MPT keys first updated in the trace.
+    ///
+    /// The evm, state, copy, bytecode, rlp, exp, modexp and sha256 rows add up to the ones of
+    /// the witness block, without its fixed overhead. The tx, pi, sig and ecc circuits report
+    /// their usage as a share of a capacity, which is added up per trace: for the tx and ecc
+    /// circuits, which report the most used of several capacities, that's an upper bound.
+    fn delta_row_usage(
+        &mut self,
+        block: &circuit_input_builder::Block,
+        new_code_lens: &[usize],
+    ) -> (RowUsage, Vec<TxRowUsage>, Vec<MptAccountKey>) {
+        let params = &block.circuits_params;
+        let txs = block
+            .txs
+            .iter()
+            .enumerate()
+            .map(|(idx, tx)| {
+                let next_block_num = block
+                    .txs
+                    .get(idx + 1)
+                    .map_or(tx.block_num + 1, |next_tx| next_tx.block_num);
+                tx_convert(tx, idx + 1, block.chain_id, next_block_num)
+            })
+            .collect_vec();
+
+        let ecc_rows = Self::delta_ecc_rows(block);
+        let mut tx_row_usages = TxRowUsage::from_txs(&txs, &block.copy_events, ecc_rows);
+        let sum = |rows: fn(&TxRowUsage) -> usize| tx_row_usages.iter().map(rows).sum::<usize>();
+
+        let keccak_rows = |len: usize| {
+            (len as f64 / 136.0).ceil() as usize
+                * get_num_rows_per_update_with_rows_per_round(
+                    DefaultSuperCircuitParams::KECCAK_ROWS_PER_ROUND,
+                )
+        };
+        let num_sigs = txs.iter().filter(|tx| !tx.tx_type.is_l1_msg()).count()
+            + block.precompile_events.get_ecrecover_events().len();
+        let chunk_txbytes_len: usize = txs
+            .iter()
+            .filter(|tx| tx.is_chunk_l2_tx())
+            .map(|tx| tx.rlp_signed.len())
+            .sum();
+        // besides the SHA3 inputs, the tx circuit hashes the L1 msgs, the chunk txbytes and the
+        // public key and message of each signature
+        let keccak = sum(|tx| tx.keccak_rows)
+            + txs
+                .iter()
+                .filter(|tx| tx.tx_type.is_l1_msg())
+                .map(|tx| keccak_rows(tx.rlp_signed.len()))
+                .sum::<usize>()
+            + keccak_rows(chunk_txbytes_len)
+            + num_sigs * (keccak_rows(64) + keccak_rows(32));
+
+        let sha256 = block
+            .precompile_events
+            .get_sha256_events()
+            .iter()
+            .map(|event| SHA256Circuit::<Fr>::min_num_rows_of_input(event.input.len()))
+            .sum();
+
+        let dynamic_len: usize = txs
+            .iter()
+            .map(|tx| {
+                tx.call_data.len()
+                    + tx.access_list.as_ref().map_or(0, |access_list| {
+                        access_list.0.len()
+                            + access_list
+                                .0
+                                .iter()
+                                .map(|item| item.storage_keys.len())
+                                .sum::<usize>()
+                    })
+            })
+            .sum();
+        let max_dynamic_len = match params.max_calldata {
+            0 => dynamic_len,
+            max_calldata => max_calldata,
+        };
+        let tx_usage = f32::max(
+            chunk_txbytes_len as f32 / CHUNK_TXBYTES_BLOB_LIMIT as f32,
+            dynamic_len as f32 / max_dynamic_len.max(1) as f32,
+        );
+        let tx = (tx_usage * params.max_vertical_circuit_rows as f32).ceil() as usize;
+
+        let challenges: Challenges<Value<Fr>> =
+            Challenges::mock(Value::unknown(), Value::unknown(), Value::unknown());
+        let rlp = txs
+            .iter()
+            .map(|tx| RlpFsmWitnessGen::<Fr>::gen_sm_witness(tx, &challenges).len())
+            .sum();
+
+        let exp = block
+            .exp_events
+            .iter()
+            .map(|event| event.steps.len() * OFFSET_INCREMENT)
+            .sum();
+        let mod_exp =
+            block.precompile_events.get_modexp_events().len() * MODEXPCONFIG_EACH_CHIP_ROWS;
+
+        let pi = (txs.len() as f32 / params.max_txs as f32
+            * params.max_vertical_circuit_rows as f32)
+            .ceil() as usize;
+
+        let sig_rows = match params.max_vertical_circuit_rows {
+            0 => SigCircuit::<Fr>::min_num_rows(),
+            rows => rows,
+        };
+        let sig = sig_rows / (SigCircuit::<Fr>::max_verif(params) - 1) * num_sigs;
+
+        // the MPT updates are deduplicated by key, within the chunk for the account fields and
+        // within the tx for the storage slots
+        let new_mpt_keys = block
+            .container
+            .account
+            .iter()
+            .map(|op| (op.op().address, op.op().field))
+            .unique()
+            .filter(|key| !self.mpt_account_keys.contains(key))
+            .collect_vec();
+        self.mpt_account_keys.extend(new_mpt_keys.iter().copied());
+        let num_storage_keys = block
+            .container
+            .storage
+            .iter()
+            .map(|op| (op.op().tx_id, op.op().address, op.op().key))
+            .unique()
+            .count();
+        let mpt = 3 * 32 * (new_mpt_keys.len() + num_storage_keys);
+
+        let sub_circuits = DefaultSuperCircuitParams::SUB_CIRCUITS;
+        let row_usage = RowUsage::from_row_usage_details(
+            [
+                ("evm", true, sum(|tx| tx.evm_rows)),
+                ("state", true, sum(|tx| tx.rw_count)),
+                (
+                    "bytecode",
+                    true,
+                    new_code_lens.iter().map(|len| len + 1).sum(),
+                ),
+                ("copy", true, sum(|tx| tx.copy_rows)),
+                ("keccak", true, keccak),
+                ("sha256", sub_circuits.sha256, sha256),
+                ("tx", true, tx),
+                ("rlp", true, rlp),
+                ("exp", true, exp),
+                ("mod_exp", sub_circuits.modexp, mod_exp),
+                ("pi", true, pi),
+                // the zktrie state isn't kept, so only the codes are hashed, as in light mode
+                (
+                    "poseidon",
+                    true,
+                    new_code_lens
+                        .iter()
+                        .map(|len| (len / (31 * 2) + 1) * 9)
+                        .sum(),
+                ),
+                ("sig", true, sig),
+                ("ecc", sub_circuits.ecc, ecc_rows),
+                ("mpt", cfg!(feature = "scroll"), mpt),
+            ]
+            .into_iter()
+            .filter(|(_, enabled, _)| *enabled)
+            .map(|(name, _, row_number)| SubCircuitRowUsage {
+                name: name.to_string(),
+                row_number,
+            })
+            .collect_vec(),
+        );
+        TxRowUsage::attribute_row_usage(&mut tx_row_usages, &txs, &row_usage);
+        (row_usage, tx_row_usages, new_mpt_keys)
+    }
+    /// ECC rows of the precompile calls of `block`, computed like the ECC circuit does for a
+    /// witness block.
+    fn delta_ecc_rows(block: &circuit_input_builder::Block) -> usize {
+        let row_num = match block.circuits_params.max_vertical_circuit_rows {
+            0 => SuperCircuitEcc::<Fr>::min_num_rows(),
+            rows => rows,
+        };
+        let max_ec_ops = &block.circuits_params.max_ec_ops;
+        let pairing_ops = block.precompile_events.get_ec_pairing_events();
+        // a kind of op the circuit has no slot for overflows it.
+        let row_usage = |n_ops: usize, max_ops: usize| match row_num.checked_div(max_ops) {
+            Some(rows_per_op) => rows_per_op * n_ops,
+            None if n_ops == 0 => 0,
+            None => row_num + 1,
+        };
+        [
+            row_usage(
+                block.precompile_events.get_ec_add_events().len(),
+                max_ec_ops.ec_add,
+            ),
+            row_usage(
+                block.precompile_events.get_ec_mul_events().len(),
+                max_ec_ops.ec_mul,
+            ),
+            row_usage(pairing_ops.len(), max_ec_ops.ec_pairing),
+            row_usage(
+                SuperCircuitEcc::<Fr>::n_pairing_chunks(&pairing_ops),
+                max_ec_ops.ec_pairing_chunks,
+            ),
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+}
+
+#[cfg(all(test, feature = "scroll"))]
+mod tests {
+    use super::*;
+    use eth_types::bytecode;
+    use mock::{
+        test_ctx::helpers::account_0_code_wallet_0_no_code, TestContext, MOCK_ACCOUNTS,
+        MOCK_WALLETS,
+    };
+
+    fn block_trace() -> BlockTrace {
+        let code = bytecode! {
+            PUSH1(0x40)
+            PUSH1(0x00)
+            SHA3
+            POP
+            STOP
+        };
+        TestContext::<2, 2>::new(
+            None,
+            account_0_code_wallet_0_no_code(code),
+            |mut txs, accs| {
+                txs[0].from(MOCK_WALLETS[0].clone()).to(accs[0].address);
+                txs[1].from(MOCK_WALLETS[0].clone()).to(accs[0].address);
+            },
+            |block, _txs| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .l2_trace()
+        .clone()
+    }
+
+    fn witness_block_rows(trace: BlockTrace) -> Vec<(String, usize)> {
+        let mut builder =
+            CircuitInputBuilder::new_from_l2_trace(get_super_circuit_params(), trace, false, true)
+                .unwrap();
+        let witness_block =
+            block_traces_to_witness_block_with_updated_state(vec![], &mut builder).unwrap();
+        calculate_row_usage_of_witness_block(&witness_block)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.name, x.row_num_real))
+            .collect()
+    }
+
+    fn rows(row_usage: &RowUsage) -> Vec<(String, usize)> {
+        row_usage
+            .row_usage_details
+            .iter()
+            .map(|x| (x.name.clone(), x.row_number))
+            .collect()
+    }
+
+    #[test]
+    fn row_usage_matches_witness_block() {
+        let trace = block_trace();
+        let mut checker = CircuitCapacityChecker::new();
+        checker.estimate_circuit_capacity(trace.clone()).unwrap();
+
+        assert_eq!(
+            rows(&checker.get_acc_row_usage(false)),
+            witness_block_rows(trace)
+        );
+    }
+
+    #[test]
+    fn delta_mode_adds_up_tx_rows() {
+        let trace = block_trace();
+        let full_rows = witness_block_rows(trace.clone());
+        let mut checker = CircuitCapacityChecker::new();
+        checker.estimate_circuit_capacity(trace.clone()).unwrap();
+
+        let mut delta_checker = CircuitCapacityChecker::new();
+        delta_checker.set_delta_mode(true);
+        delta_checker.estimate_circuit_capacity(trace).unwrap();
+        let delta_rows = rows(&delta_checker.row_usages[0]);
+        assert_eq!(rows(&delta_checker.get_acc_row_usage(false)), delta_rows);
+
+        for ((name, delta), (full_name, full)) in delta_rows.iter().zip_eq(full_rows.iter()) {
+            assert_eq!(name, full_name);
+            if ["bytecode", "tx", "rlp", "pi"].contains(&name.as_str()) {
+                assert_eq!(delta, full, "{name}");
+            } else if ["state", "copy", "exp", "sig", "mpt"].contains(&name.as_str()) {
+                // without the fixed overhead of the block
+                assert!(delta <= full, "{name}: {delta} > {full}");
+            }
+        }
+        // the rows of the steps are the ones attributed to the txs of the witness block
+        let tx_rows = |checker: &CircuitCapacityChecker, rows: fn(&TxRowUsage) -> usize| {
+            checker.tx_row_usages[0].iter().map(rows).collect_vec()
+        };
+        let step_rows: [fn(&TxRowUsage) -> usize; 4] = [
+            |tx| tx.evm_rows,
+            |tx| tx.rw_count,
+            |tx| tx.copy_rows,
+            |tx| tx.keccak_rows,
+        ];
+        for rows in step_rows {
+            assert_eq!(tx_rows(&delta_checker, rows), tx_rows(&checker, rows));
+        }
+    }
+
+    #[test]
+    fn rollback_last_tx_restores_state() {
+        let trace = block_trace();
+        let mut checker = CircuitCapacityChecker::new();
+        checker.set_delta_mode(true);
+        checker.estimate_circuit_capacity(trace.clone()).unwrap();
+        let acc_rows = rows(&checker.get_acc_row_usage(false));
+        assert!(!checker.mpt_account_keys.is_empty());
+
+        assert!(checker.rollback_last_tx().is_some());
+        assert_eq!(checker.get_tx_num(), 0);
+        assert!(checker.builder_ctx.is_none());
+        assert!(checker.mpt_account_keys.is_empty());
+        assert!(checker
+            .get_acc_row_usage(false)
+            .row_usage_details
+            .is_empty());
+        assert!(checker.rollback_last_tx().is_none());
+
+        // the same trace can be estimated again on the restored state
+        checker.estimate_circuit_capacity(trace).unwrap();
+        assert_eq!(rows(&checker.get_acc_row_usage(false)), acc_rows);
+    }
+
+    #[test]
+    fn tx_row_usage_splits_trace_rows() {
+        let mut checker = CircuitCapacityChecker::new();
+        checker.estimate_circuit_capacity(block_trace()).unwrap();
+        let trace_rows = rows(&checker.row_usages[0]);
+        let tx_row_usages = &checker.tx_row_usages[0];
+        assert_eq!(tx_row_usages.len(), 2);
+
+        for (name, total) in trace_rows {
+            let attributed: usize = tx_row_usages
+                .iter()
+                .map(|tx| tx.sub_circuit_rows(&name))
+                .sum();
+            // the split rows are rounded down, by less than a row per tx
+            assert!(attributed <= total, "{name}: {attributed} > {total}");
+            if !["evm", "state", "copy", "keccak", "ecc"].contains(&name.as_str()) {
+                assert!(attributed + 2 > total, "{name}: {attributed} << {total}");
+            }
+        }
+        for tx in tx_row_usages {
+            assert!(tx.evm_rows > 0);
+            assert_eq!(tx.sub_circuit_rows("evm"), tx.evm_rows);
+            // the SHA3 of the called contract
+            let call = &tx.calls[0];
+            assert_eq!(call.address, MOCK_ACCOUNTS[0]);
+            assert!(call.keccak_rows > 0);
+            assert_eq!(tx.keccak_rows, call.keccak_rows);
+        }
+    }
+
+    #[test]
+    fn heaviest_txs_of_limiting_sub_circuit() {
+        let mut checker = CircuitCapacityChecker::new();
+        assert!(checker.limiting_sub_circuit().is_none());
+        assert!(checker.heaviest_txs(1).is_empty());
+        checker.estimate_circuit_capacity(block_trace()).unwrap();
+
+        let limiting = checker.limiting_sub_circuit().unwrap();
+        assert_eq!(
+            limiting.row_number,
+            checker.get_acc_row_usage(true).row_number
+        );
+        let heaviest = checker.heaviest_txs(1);
+        assert_eq!(heaviest.len(), 1);
+        let max_rows = checker.tx_row_usages[0]
+            .iter()
+            .map(|tx| tx.sub_circuit_rows(&limiting.name))
+            .max()
+            .unwrap();
+        assert_eq!(heaviest[0].sub_circuit_rows(&limiting.name), max_rows);
+        assert_eq!(checker.heaviest_txs(5).len(), 2);
     }
 }
@@ -26,8 +26,9 @@ pub fn calculate_row_usage_of_witness_block(
     );
     // Check whether we need to "estimate" poseidon sub circuit row usage
     if witness_block.mpt_updates.smt_traces.is_empty() {
-        assert_eq!(rows[11].name, "poseidon");
-        assert_eq!(rows[14].name, "mpt");
+        // the optional sub circuits shift the indices, look them up by name
+        let poseidon = rows.iter().position(|x| x.name == "poseidon").unwrap();
+        let mpt = rows.iter().position(|x| x.name == "mpt").unwrap();
         // We collected real metrics from Scroll mainnet, and here is the graph
         // https://ibb.co/gVfvW7h
         // 6 is already very very conservative. Besides, considering a chunk consists of many txs,
@@ -40,8 +41,8 @@ pub fn calculate_row_usage_of_witness_block(
             // even i think 6 is safe, here we still keep the old value
             12
         };
-        let mpt_poseidon_rows = rows[14].row_num_real * poseidon_estimate_ratio;
-        rows[11].row_num_real += mpt_poseidon_rows;
+        let mpt_poseidon_rows = rows[mpt].row_num_real * poseidon_estimate_ratio;
+        rows[poseidon].row_num_real += mpt_poseidon_rows;
         log::debug!("calculate_row_usage_of_witness_block light mode, adding {mpt_poseidon_rows} poseidon rows");
     } else {
         log::debug!("calculate_row_usage_of_witness_block normal mode, skip adding poseidon rows");
@@ -14,7 +14,8 @@ use compiler::Compiler;
 use config::Config;
 use log::info;
 use statetest::{
-    load_statetests_suite, run_statetests_suite, run_test, CircuitsConfig, Results, StateTest,
+    load_statetests_suite, run_statetests_suite, run_test, CircuitsConfig, Results, RunnerConfig,
+    StateTest,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -22,7 +23,7 @@ use std::{
     fs::File,
     io::{BufRead, BufReader, Write},
     path::PathBuf,
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 use strum_macros::EnumString;
 
@@ -88,20 +89,36 @@ struct Args {
     #[clap(long)]
     exclude_test_ids: Option<String>,
 
+    /// Number of tests to run concurrently (default: 1 for sc, 20 for basic)
+    #[clap(short, long)]
+    jobs: Option<usize>,
+
+    /// Per-test timeout in seconds, timed out tests are reported as failed
+    #[clap(long)]
+    timeout: Option<u64>,
+
+    /// Do not start new tests while the process uses more than this many MiB
+    #[clap(long)]
+    max_rss_mb: Option<u64>,
+
     /// Verbose
     #[clap(short, long)]
     v: bool,
 }
 
 fn read_test_ids(file_path: &str) -> Result<Vec<String>> {
+    // When running across several processes, each one only takes its share of the list.
     let worker_index = env::var("WORKER_INDEX")
         .ok()
         .and_then(|val| val.parse::<usize>().ok())
-        .expect("WORKER_INDEX not set");
+        .unwrap_or(0);
     let total_workers = env::var("TOTAL_WORKERS")
         .ok()
         .and_then(|val| val.parse::<usize>().ok())
-        .expect("TOTAL_WORKERS not set");
+        .unwrap_or(1);
+    if worker_index >= total_workers {
+        bail!("WORKER_INDEX {worker_index} out of range, TOTAL_WORKERS is {total_workers}");
+    }
     info!("total workers: {total_workers}, worker index: {worker_index}");
 
     info!("read_test_ids from {}", file_path);
@@ -159,6 +176,11 @@ fn go() -> Result<()> {
     if args.circuits == Some(Circuits::sc) {
         circuits_config.super_circuit = true;
     }
+    let runner_config = RunnerConfig {
+        jobs: args.jobs,
+        timeout: args.timeout.map(Duration::from_secs),
+        max_rss: args.max_rss_mb.map(|mb| mb << 20),
+    };
 
     if let Some(oneliner) = &args.oneliner {
         let test = StateTest::parse_oneline_spec(oneliner)?;
@@ -305,7 +327,13 @@ fn go() -> Result<()> {
 
         previous_results.set_cache(PathBuf::from(csv_filename));
         previous_results.write_cache()?;
-        run_statetests_suite(state_tests, &circuits_config, &suite, &mut previous_results)?;
+        run_statetests_suite(
+            state_tests,
+            &circuits_config,
+            &runner_config,
+            &suite,
+            &mut previous_results,
+        )?;
 
         // filter non-csv files and files from the same commit
         let mut files: Vec<_> = std::fs::read_dir(REPORT_FOLDER)
@@ -341,7 +369,13 @@ fn go() -> Result<()> {
         };
 
         log::info!("Executing...");
-        run_statetests_suite(state_tests, &circuits_config, &suite, &mut results)?;
+        run_statetests_suite(
+            state_tests,
+            &circuits_config,
+            &runner_config,
+            &suite,
+            &mut results,
+        )?;
         let success = results.success();
 
         log::info!("Generating report...");
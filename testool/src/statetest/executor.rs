@@ -1,6 +1,8 @@
 use super::{AccountMatch, StateTest, StateTestResult};
 use crate::{config::TestSuite, utils::ETH_CHAIN_ID};
-use bus_mapping::circuit_input_builder::{CircuitInputBuilder, CircuitsParams, PrecompileEcParams};
+use bus_mapping::circuit_input_builder::{
+    CircuitInputBuilder, CircuitsParams, PrecompileEcParams, SubCircuitSelection,
+};
 use eth_types::{
     geth_types, state_db::CodeDB, Address, Bytes, GethExecTrace, ToBigEndian, ToWord, H256, U256,
     U64,
@@ -59,7 +61,7 @@ pub enum StateTestError {
     #[error("SkipTestSelfDestruct")]
     SkipTestSelfDestruct,
     #[error("SkipTestDifficulty")]
-    // scroll evm always returns 0 for "difficulty" opcode
+    // scroll evm always returns 0 for "difficulty" opcode, unless built with `prevrandao`
     SkipTestDifficulty,
     #[error("SkipTestBalanceOverflow")]
     SkipTestBalanceOverflow,
@@ -182,6 +184,7 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 timestamp: U256::from(st.env.current_timestamp),
                 number: U64::from(st.env.current_number),
                 difficulty: st.env.current_difficulty,
+                mix_hash: st.env.current_random,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: st.env.current_base_fee,
             },
@@ -403,6 +406,7 @@ fn trace_config_to_witness_block_l1(
         timestamp: trace_config.block_constants.timestamp,
         number: Some(U64::from(trace_config.block_constants.number.as_u64())),
         difficulty: trace_config.block_constants.difficulty,
+        mix_hash: Some(trace_config.block_constants.mix_hash),
         gas_limit: trace_config.block_constants.gas_limit,
         base_fee_per_gas: Some(trace_config.block_constants.base_fee),
         transactions,
@@ -462,7 +466,10 @@ fn get_params_for_super_circuit_test() -> CircuitsParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
         },
+        max_sig_verifs: 0,
+        sub_circuits: SubCircuitSelection::all(),
     }
 }
 */
@@ -486,7 +493,10 @@ fn get_params_for_sub_circuit_test() -> CircuitsParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
         },
+        max_sig_verifs: 0,
+        sub_circuits: SubCircuitSelection::all(),
     }
 }
 
@@ -29,6 +29,8 @@ struct TestEnv {
     current_coinbase: String,
     #[serde(default)]
     current_difficulty: String,
+    #[serde(default)]
+    current_random: String,
     current_gas_limit: String,
     current_number: String,
     current_timestamp: String,
@@ -254,6 +256,7 @@ impl<'a> JsonStateTestBuilder<'a> {
                 .unwrap_or_else(|_| U256::from(DEFAULT_BASE_FEE)),
             current_coinbase: parse::parse_address(&env.current_coinbase)?,
             current_difficulty: parse::parse_u256(&env.current_difficulty).unwrap_or_default(),
+            current_random: parse::parse_hash(&env.current_random).unwrap_or_default(),
             current_gas_limit: parse::parse_u64(&env.current_gas_limit)?,
             current_number: parse::parse_u64(&env.current_number)?,
             current_timestamp: parse::parse_u64(&env.current_timestamp)?,
@@ -445,6 +448,7 @@ mod test {
                 current_base_fee: U256::from(DEFAULT_BASE_FEE),
                 current_coinbase: Address::from_str("0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba")?,
                 current_difficulty: U256::from(131072u64),
+                current_random: H256::default(),
                 current_gas_limit: 0xFF112233445566,
                 current_number: 1,
                 current_timestamp: 1000,
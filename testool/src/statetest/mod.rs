@@ -10,7 +10,7 @@ pub use executor::{run_test, CircuitsConfig};
 pub use json::JsonStateTestBuilder;
 pub use results::{ResultLevel, Results};
 pub use spec::{AccountMatch, StateTest, StateTestResult};
-pub use suite::{load_statetests_suite, run_statetests_suite};
+pub use suite::{load_statetests_suite, run_statetests_suite, RunnerConfig};
 pub use yaml::YamlStateTestBuilder;
 
 #[cfg(test)]
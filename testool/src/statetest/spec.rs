@@ -24,6 +24,7 @@ pub struct Env {
     pub current_base_fee: U256,
     pub current_coinbase: Address,
     pub current_difficulty: U256,
+    pub current_random: H256,
     pub current_gas_limit: u64,
     pub current_number: u64,
     pub current_timestamp: u64,
@@ -119,6 +120,7 @@ impl std::fmt::Display for StateTest {
             "difficulty",
             format!("{}", self.env.current_difficulty)
         ]);
+        table.add_row(row!["random", format!("{:?}", self.env.current_random)]);
         table.add_row(row!["number", format!("{}", self.env.current_number)]);
         table.add_row(row!["timestamp", format!("{}", self.env.current_timestamp)]);
         table.add_row(row!["prev_hash", format!("{:?}", self.env.previous_hash)]);
@@ -291,6 +293,7 @@ impl StateTest {
                 current_base_fee: U256::from(DEFAULT_BASE_FEE),
                 current_coinbase: Address::default(),
                 current_difficulty: U256::default(),
+                current_random: H256::default(),
                 current_gas_limit: 16000000,
                 current_number: 1,
                 current_timestamp: 1,
@@ -1,4 +1,7 @@
-use super::{executor::run_test, CircuitsConfig, JsonStateTestBuilder, Results, StateTest};
+use super::{
+    executor::{run_test, StateTestError},
+    CircuitsConfig, JsonStateTestBuilder, Results, StateTest,
+};
 use crate::{
     compiler::Compiler,
     config::{Config, TestSuite},
@@ -6,12 +9,19 @@ use crate::{
         results::{ResultInfo, ResultLevel},
         YamlStateTestBuilder,
     },
+    utils::current_rss_bytes,
 };
 use anyhow::{Context, Result};
 use rayon::prelude::*;
 use std::{
+    any::Any,
     panic::AssertUnwindSafe,
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, RwLock,
+    },
+    time::Duration,
 };
 
 pub fn load_statetests_suite(
@@ -73,9 +83,121 @@ pub fn load_statetests_suite(
     Ok(tcs)
 }
 
+/// Controls how `run_statetests_suite` schedules tests.
+#[derive(Debug, Clone, Default)]
+pub struct RunnerConfig {
+    /// Number of tests executed concurrently. Defaults to one for the super circuit (which is
+    /// memory heavy) and to `DEFAULT_JOBS` otherwise.
+    pub jobs: Option<usize>,
+    /// Wall-clock limit for a single test. A test exceeding it is reported as failed and its
+    /// thread is detached, so it keeps running in the background until it finishes. Detached
+    /// tests still count against `jobs` and `max_rss` until then.
+    pub timeout: Option<Duration>,
+    /// Process-wide resident set size limit in bytes. No new test is started while the process
+    /// is above it, unless nothing else is running.
+    pub max_rss: Option<u64>,
+}
+
+const DEFAULT_JOBS: usize = 20;
+
+/// Outcome of running one test in isolation.
+enum TestOutcome {
+    Finished(Result<(), StateTestError>),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+fn panic_message(err: Box<dyn Any + Send>) -> String {
+    if let Some(s) = err.downcast_ref::<String>() {
+        s.to_string()
+    } else if let Some(s) = err.downcast_ref::<&str>() {
+        s.to_string()
+    } else {
+        "unable to get panic info".into()
+    }
+}
+
+/// Run `test`, catching panics, and give up waiting for it after `timeout`.
+fn run_isolated<F>(name: String, test: F, timeout: Option<Duration>) -> TestOutcome
+where
+    F: FnOnce() -> Result<(), StateTestError> + Send + 'static,
+{
+    let run = move || match std::panic::catch_unwind(AssertUnwindSafe(test)) {
+        Ok(res) => TestOutcome::Finished(res),
+        Err(err) => TestOutcome::Panicked(panic_message(err)),
+    };
+
+    let Some(timeout) = timeout else {
+        return run();
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new().name(name).spawn(move || {
+        // the receiver is gone if the test timed out, nothing to report then
+        let _ = tx.send(run());
+    });
+    if let Err(err) = spawned {
+        return TestOutcome::Panicked(format!("unable to spawn test thread: {err}"));
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(outcome) => outcome,
+        Err(RecvTimeoutError::Timeout) => TestOutcome::TimedOut(timeout),
+        Err(RecvTimeoutError::Disconnected) => {
+            TestOutcome::Panicked("test thread exited without result".into())
+        }
+    }
+}
+
+/// A slot taken by a running test, released when the test actually finishes, i.e. when the
+/// closure running it is dropped, which for a timed out test is when its detached thread ends.
+struct RunningSlot(Arc<AtomicUsize>);
+
+impl RunningSlot {
+    /// Block until fewer than `jobs` tests are running, then take a slot.
+    fn acquire(running: &Arc<AtomicUsize>, jobs: usize) -> Self {
+        loop {
+            let current = running.load(Ordering::SeqCst);
+            if current < jobs
+                && running
+                    .compare_exchange(current, current + 1, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+            {
+                return Self(running.clone());
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for RunningSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Block until the process memory usage drops below `max_rss`, or no other test is running.
+fn wait_for_memory(max_rss: u64, running: &AtomicUsize) {
+    while running.load(Ordering::SeqCst) > 0 {
+        match current_rss_bytes() {
+            Some(rss) if rss > max_rss => {
+                log::debug!(
+                    target : "testool",
+                    "rss {} MiB above limit {} MiB, waiting...",
+                    rss >> 20,
+                    max_rss >> 20
+                );
+                std::thread::sleep(Duration::from_millis(500));
+            }
+            _ => return,
+        }
+    }
+}
+
 pub fn run_statetests_suite(
     tcs: Vec<StateTest>,
     circuits_config: &CircuitsConfig,
+    runner_config: &RunnerConfig,
     suite: &TestSuite,
     results: &mut Results,
 ) -> Result<()> {
@@ -93,6 +215,16 @@ pub fn run_statetests_suite(
     );
 
     let results = Arc::new(RwLock::from(results));
+    // tests still running, including the timed out ones
+    let running = Arc::new(AtomicUsize::new(0));
+    let jobs = runner_config
+        .jobs
+        .unwrap_or(if circuits_config.super_circuit {
+            1
+        } else {
+            DEFAULT_JOBS
+        })
+        .max(1);
 
     // for each test
     let test_count = tcs.len();
@@ -112,7 +244,9 @@ pub fn run_statetests_suite(
             return;
         }
 
-        std::panic::set_hook(Box::new(|_info| {}));
+        if let Some(max_rss) = runner_config.max_rss {
+            wait_for_memory(max_rss, &running);
+        }
 
         log::debug!(
             target : "testool",
@@ -122,22 +256,32 @@ pub fn run_statetests_suite(
             test_id,
             path,
         );
-        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-            run_test(tc.clone(), suite.clone(), circuits_config.clone())
-        }));
-
-        // handle panic
-        let result = match result {
-            Ok(res) => res,
-            Err(err) => {
-                let panic_err = if let Some(s) = err.downcast_ref::<String>() {
-                    s.to_string()
-                } else if let Some(s) = err.downcast_ref::<&str>() {
-                    s.to_string()
-                } else {
-                    "unable to get panic info".into()
-                };
+        let slot = RunningSlot::acquire(&running, jobs);
+        let outcome = {
+            let (tc, suite, circuits_config) = (tc.clone(), suite.clone(), circuits_config.clone());
+            run_isolated(
+                test_id.clone(),
+                move || {
+                    let _slot = slot;
+                    run_test(tc, suite, circuits_config)
+                },
+                runner_config.timeout,
+            )
+        };
 
+        let (level, details) = match outcome {
+            TestOutcome::Finished(Ok(())) => (ResultLevel::Success, String::default()),
+            // handle known error
+            TestOutcome::Finished(Err(err)) => (
+                if err.is_skip() {
+                    ResultLevel::Ignored
+                } else {
+                    ResultLevel::Fail
+                },
+                err.to_string(),
+            ),
+            // handle panic
+            TestOutcome::Panicked(panic_err) => {
                 let level = if panic_err.contains("circuit was not satisfied") {
                     ResultLevel::Fail
                 } else if panic_err.contains("evm_unimplemented") {
@@ -145,63 +289,86 @@ pub fn run_statetests_suite(
                 } else {
                     ResultLevel::Panic
                 };
-                results
-                    .write()
-                    .unwrap()
-                    .insert(ResultInfo {
-                        test_id,
-                        level,
-                        details: panic_err,
-                        path,
-                    })
-                    .unwrap();
-                return;
+                (level, panic_err)
             }
+            TestOutcome::TimedOut(timeout) => (
+                ResultLevel::Fail,
+                format!("Timeout({}s)", timeout.as_secs()),
+            ),
         };
 
-        // handle known error
-        if let Err(err) = result {
-            results
-                .write()
-                .unwrap()
-                .insert(ResultInfo {
-                    test_id,
-                    level: if err.is_skip() {
-                        ResultLevel::Ignored
-                    } else {
-                        ResultLevel::Fail
-                    },
-                    details: err.to_string(),
-                    path,
-                })
-                .unwrap();
-            return;
-        }
-
         results
             .write()
             .unwrap()
             .insert(ResultInfo {
                 test_id,
-                level: ResultLevel::Success,
-                details: String::default(),
+                level,
+                details,
                 path,
             })
             .unwrap();
     };
 
-    if circuits_config.super_circuit {
-        tcs.into_iter().for_each(|ref tc| run_state_test(tc));
+    log::info!("running {test_count} tests with {jobs} jobs");
+
+    if jobs <= 1 {
+        tcs.iter().for_each(run_state_test);
     } else {
-        const PARALLELISM: usize = 20;
-        let mut groups =
-            [(); PARALLELISM].map(|_| Vec::with_capacity((tcs.len() / PARALLELISM) + 1));
-        tcs.into_iter().enumerate().for_each(|(i, tc)| {
-            groups[i % PARALLELISM].push(tc);
-        });
-        groups
-            .into_par_iter()
-            .for_each(|chunk| chunk.into_iter().for_each(|ref tc| run_state_test(tc)));
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .thread_name(|i| format!("testool-{i}"))
+            .build()?;
+        // one test per task so that a slow test does not hold back a whole batch
+        pool.install(|| tcs.par_iter().with_max_len(1).for_each(run_state_test));
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_isolated_catches_panic() {
+        let outcome = run_isolated("panic".into(), || panic!("boom"), None);
+        assert!(matches!(outcome, TestOutcome::Panicked(msg) if msg == "boom"));
+    }
+
+    #[test]
+    fn run_isolated_times_out() {
+        let outcome = run_isolated(
+            "sleep".into(),
+            || {
+                std::thread::sleep(Duration::from_secs(5));
+                Ok(())
+            },
+            Some(Duration::from_millis(50)),
+        );
+        assert!(matches!(outcome, TestOutcome::TimedOut(_)));
+
+        let outcome = run_isolated("ok".into(), || Ok(()), Some(Duration::from_secs(5)));
+        assert!(matches!(outcome, TestOutcome::Finished(Ok(()))));
+    }
+
+    #[test]
+    fn timed_out_test_keeps_its_slot() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let slot = RunningSlot::acquire(&running, 1);
+        let outcome = run_isolated(
+            "sleep".into(),
+            move || {
+                let _slot = slot;
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(())
+            },
+            Some(Duration::from_millis(50)),
+        );
+        assert!(matches!(outcome, TestOutcome::TimedOut(_)));
+        // the detached test is still running
+        assert_eq!(running.load(Ordering::SeqCst), 1);
+
+        // the next test waits for it to finish
+        let _slot = RunningSlot::acquire(&running, 1);
+        assert_eq!(running.load(Ordering::SeqCst), 1);
+    }
+}
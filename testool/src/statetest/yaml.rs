@@ -250,6 +250,7 @@ impl<'a> YamlStateTestBuilder<'a> {
                 .unwrap_or_else(|_| U256::from(DEFAULT_BASE_FEE)),
             current_coinbase: Self::parse_address(&yaml["currentCoinbase"], None)?,
             current_difficulty: Self::parse_u256(&yaml["currentDifficulty"]).unwrap_or_default(),
+            current_random: Self::parse_hash(&yaml["currentRandom"]).unwrap_or_default(),
             current_gas_limit: Self::parse_u64(&yaml["currentGasLimit"])?,
             current_number: Self::parse_u64(&yaml["currentNumber"])?,
             current_timestamp: Self::parse_u64(&yaml["currentTimestamp"])?,
@@ -760,6 +761,7 @@ arith:
                 current_base_fee: U256::from(DEFAULT_BASE_FEE),
                 current_coinbase: address!("0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba"),
                 current_difficulty: U256::from(0x20000u64),
+                current_random: H256::default(),
                 current_number: 1,
                 current_timestamp: 1000,
                 current_gas_limit: 100000000,
@@ -204,6 +204,17 @@ pub fn current_submodule_git_commit() -> Result<String> {
     }
 }
 
+/// Resident set size of the current process in bytes, if the platform exposes it.
+pub fn current_rss_bytes() -> Option<u64> {
+    // `/proc/self/statm` reports sizes in pages: "size resident shared ..."
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf has no preconditions
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    let page_size = u64::try_from(page_size).ok().filter(|&size| size > 0)?;
+    Some(resident_pages * page_size)
+}
+
 pub fn bytecode_of(code: &str) -> anyhow::Result<Bytecode> {
     let bytecode = if let Ok(bytes) = hex::decode(code) {
         let bytecode = Bytecode::from(bytes.clone());
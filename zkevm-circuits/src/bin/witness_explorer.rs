@@ -0,0 +1,78 @@
+//! Render a witness block dumped with `Block::dump`.
+//!
+//! ```text
+//! witness_explorer <witness.json> [--step-rws] [--rws] [--copy-events] [--row <n>]...
+//! ```
+//!
+//! Without `--row`, the transactions, calls and steps of the block are printed. Every `--row`
+//! maps an EVM circuit row (e.g. the offset reported by `MockProver`) back to its step instead.
+
+use std::process::exit;
+
+use zkevm_circuits::witness::{explorer::TraceExplorer, Block};
+
+const USAGE: &str =
+    "usage: witness_explorer <witness.json> [--step-rws] [--rws] [--copy-events] [--row <n>]...";
+
+fn main() {
+    let mut path = None;
+    let mut step_rws = false;
+    let mut rws = false;
+    let mut copy_events = false;
+    let mut rows = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--step-rws" => step_rws = true,
+            "--rws" => rws = true,
+            "--copy-events" => copy_events = true,
+            "--row" => match args.next().and_then(|row| row.parse::<usize>().ok()) {
+                Some(row) => rows.push(row),
+                None => {
+                    eprintln!("--row expects a row number\n{USAGE}");
+                    exit(2);
+                }
+            },
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("unexpected argument {arg}\n{USAGE}");
+                exit(2);
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("{USAGE}");
+        exit(2);
+    };
+
+    let block = match Block::load(&path) {
+        Ok(block) => block,
+        Err(e) => {
+            eprintln!("failed to load {path}: {e:?}");
+            exit(1);
+        }
+    };
+    let explorer = TraceExplorer::new(&block);
+
+    if !rows.is_empty() {
+        for row in rows {
+            match explorer.locate_evm_row(row) {
+                Some(location) => println!("row {row}: {location}"),
+                None => println!("row {row}: outside of the EVM circuit steps"),
+            }
+        }
+        return;
+    }
+    print!("{}", explorer.render_txs(step_rws));
+    if rws {
+        print!("{}", explorer.render_rws());
+    }
+    if copy_events {
+        print!("{}", explorer.render_copy_events());
+    }
+}
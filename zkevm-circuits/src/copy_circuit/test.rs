@@ -376,6 +376,22 @@ fn copy_circuit_valid_calldatacopy() {
     assert_eq!(test_copy_circuit_from_block(block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_from_dumped_blocks() {
+    let builder = gen_sha3_data();
+    let dir = std::env::temp_dir();
+
+    let path = dir.join("copy_circuit_valid_from_dumped_blocks.input.json");
+    builder.block.dump(&path).unwrap();
+    let input_block = bus_mapping::circuit_input_builder::Block::load(&path).unwrap();
+    let block = block_convert(&input_block, &builder.code_db).unwrap();
+
+    let path = dir.join("copy_circuit_valid_from_dumped_blocks.witness.json");
+    block.dump(&path).unwrap();
+    let block = Block::load(&path).unwrap();
+    assert_eq!(test_copy_circuit_from_block(block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_valid_codecopy() {
     let builder = gen_codecopy_data();
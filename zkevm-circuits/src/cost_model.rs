@@ -0,0 +1,210 @@
+//! Static cost model of the circuit row usage per opcode.
+//!
+//! The costs are measured once by running every opcode through the circuit input builder (see
+//! [`generate_cost_model`]) and exported as JSON, so that the row usage of a transaction can be
+//! estimated from the opcodes it executes without running the full capacity checker.
+//!
+//! Copy-like opcodes (`*COPY`, `LOG*`, `SHA3`, `RETURN`, `REVERT`) are measured on a 4KiB memory
+//! range, so their costs are only indicative for other sizes.
+
+use crate::evm_circuit::step::ExecutionState;
+use eth_types::evm_types::OpcodeId;
+use serde::{Deserialize, Serialize};
+
+/// Rows used in the circuits affected by an opcode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cost {
+    /// Rows in the EVM circuit
+    pub evm_rows: usize,
+    /// Number of read/write operations, i.e. rows in the State circuit
+    pub rw_count: usize,
+    /// Rows in the Copy circuit
+    pub copy_rows: usize,
+    /// Rows in the Keccak circuit
+    pub keccak_rows: usize,
+}
+
+impl Cost {
+    /// Component-wise maximum of two costs.
+    pub fn max(&self, other: &Self) -> Self {
+        Self {
+            evm_rows: self.evm_rows.max(other.evm_rows),
+            rw_count: self.rw_count.max(other.rw_count),
+            copy_rows: self.copy_rows.max(other.copy_rows),
+            keccak_rows: self.keccak_rows.max(other.keccak_rows),
+        }
+    }
+
+    /// Accumulate another cost into this one.
+    pub fn add(&mut self, other: &Self) {
+        self.evm_rows += other.evm_rows;
+        self.rw_count += other.rw_count;
+        self.copy_rows += other.copy_rows;
+        self.keccak_rows += other.keccak_rows;
+    }
+}
+
+/// Cost of executing an opcode.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcodeCost {
+    /// Execution state responsible for the opcode
+    pub state: ExecutionState,
+    /// The opcode
+    pub opcode: OpcodeId,
+    /// Gas spent by the opcode
+    pub gas_cost: u64,
+    /// Rows used
+    #[serde(flatten)]
+    pub cost: Cost,
+}
+
+/// Worst cost among the responsible opcodes of an execution state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateCost {
+    /// The execution state
+    pub state: ExecutionState,
+    /// Rows used
+    #[serde(flatten)]
+    pub cost: Cost,
+}
+
+/// Per-state and per-opcode circuit costs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Costs per execution state
+    pub states: Vec<StateCost>,
+    /// Costs per opcode
+    pub opcodes: Vec<OpcodeCost>,
+}
+
+impl CostModel {
+    /// Create a cost model from the opcode costs, deriving the per-state costs.
+    pub fn new(opcodes: Vec<OpcodeCost>) -> Self {
+        let mut states: Vec<StateCost> = vec![];
+        for op in opcodes.iter() {
+            match states.iter_mut().find(|s| s.state == op.state) {
+                Some(state) => state.cost = state.cost.max(&op.cost),
+                None => states.push(StateCost {
+                    state: op.state,
+                    cost: op.cost,
+                }),
+            }
+        }
+        Self { states, opcodes }
+    }
+
+    /// Parse a cost model from its JSON representation.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Export the cost model as JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Cost of an opcode, if it's part of the model.
+    pub fn opcode_cost(&self, opcode: OpcodeId) -> Option<&Cost> {
+        self.opcodes
+            .iter()
+            .find(|op| op.opcode == opcode)
+            .map(|op| &op.cost)
+    }
+
+    /// Cost of an execution state, if it's part of the model.
+    pub fn state_cost(&self, state: ExecutionState) -> Option<&Cost> {
+        self.states
+            .iter()
+            .find(|s| s.state == state)
+            .map(|s| &s.cost)
+    }
+
+    /// Worst cost among all the opcodes of the model.
+    pub fn max_cost(&self) -> Cost {
+        self.opcodes
+            .iter()
+            .fold(Cost::default(), |acc, op| acc.max(&op.cost))
+    }
+
+    /// Estimate the cost of executing `opcodes`.  Opcodes missing in the model are accounted with
+    /// [`Self::max_cost`].
+    pub fn estimate(&self, opcodes: impl IntoIterator<Item = OpcodeId>) -> Cost {
+        let max_cost = self.max_cost();
+        let mut total = Cost::default();
+        for opcode in opcodes {
+            total.add(self.opcode_cost(opcode).unwrap_or(&max_cost));
+        }
+        total
+    }
+}
+
+/// Measure the cost of every opcode supported by the EVM circuit.  This runs each opcode through
+/// the circuit input builder, so it's slow and meant to generate a static table, see
+/// `gen_cost_model` test.
+#[cfg(any(feature = "test", test))]
+pub fn generate_cost_model() -> CostModel {
+    use crate::stats::{bytecode_prefix_op_big_rws, collect_opcode_costs};
+
+    CostModel::new(collect_opcode_costs(
+        |state| {
+            !matches!(
+                state,
+                ExecutionState::ErrorInvalidOpcode | ExecutionState::SELFDESTRUCT
+            )
+        },
+        bytecode_prefix_op_big_rws,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Generate the static cost table, written to `COST_MODEL_PATH` (default
+    /// `opcode_costs.json`).
+    ///
+    /// Run with:
+    /// `cargo test -p zkevm-circuits --release --all-features
+    /// gen_cost_model -- --nocapture --ignored`
+    #[ignore]
+    #[test]
+    fn gen_cost_model() {
+        let path = std::env::var("COST_MODEL_PATH").unwrap_or_else(|_| "opcode_costs.json".into());
+        let json = generate_cost_model().to_json().unwrap();
+        std::fs::write(&path, json).unwrap();
+        log::info!("cost model written to {path}");
+    }
+
+    #[test]
+    fn cost_model_estimate() {
+        let op_cost = |state, opcode, evm_rows, rw_count| OpcodeCost {
+            state,
+            opcode,
+            gas_cost: 3,
+            cost: Cost {
+                evm_rows,
+                rw_count,
+                ..Default::default()
+            },
+        };
+        let model = CostModel::new(vec![
+            op_cost(ExecutionState::ADD_SUB, OpcodeId::ADD, 10, 3),
+            op_cost(ExecutionState::ADD_SUB, OpcodeId::SUB, 12, 3),
+            op_cost(ExecutionState::POP, OpcodeId::POP, 5, 1),
+        ]);
+        assert_eq!(
+            model.state_cost(ExecutionState::ADD_SUB),
+            Some(&Cost {
+                evm_rows: 12,
+                rw_count: 3,
+                ..Default::default()
+            })
+        );
+
+        let model = CostModel::from_json(&model.to_json().unwrap()).unwrap();
+        // MUL is missing, hence accounted with the max cost
+        let cost = model.estimate([OpcodeId::ADD, OpcodeId::POP, OpcodeId::MUL]);
+        assert_eq!(cost.evm_rows, 10 + 5 + 12);
+        assert_eq!(cost.rw_count, 3 + 1 + 3);
+    }
+}
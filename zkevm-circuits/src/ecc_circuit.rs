@@ -5,10 +5,12 @@ use std::{iter, marker::PhantomData};
 
 use crate::util::Field;
 use bus_mapping::{
-    circuit_input_builder::{EcAddOp, EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::{
+        EcAddOp, EcMulOp, EcPairingOp, PrecompileEcParams, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+    },
     precompile::PrecompileCalls,
 };
-use eth_types::{ToLittleEndian, ToScalar, U256};
+use eth_types::{ToLittleEndian, ToScalar, Word, U256};
 use halo2_base::{
     gates::{GateInstructions, RangeInstructions},
     utils::{decompose_bigint_option, fe_to_biguint, modulus},
@@ -51,8 +53,9 @@ mod util;
 
 use util::{
     EcAddAssigned, EcAddDecomposed, EcMulAssigned, EcMulDecomposed, EcOpsAssigned,
-    EcPairingAssigned, EcPairingDecomposed, G1Assigned, G1Decomposed, G2Decomposed, ScalarAssigned,
-    LOG_TOTAL_NUM_ROWS,
+    EcPairingAssigned, EcPairingChunk, EcPairingDecomposed, EcPairingOpDecomposed, G1Assigned,
+    G1Decomposed, G2Decomposed, ScalarAssigned, EC_ADD_CELLS, EC_MUL_CELLS, EC_PAIRING_CELLS,
+    LOG_TOTAL_NUM_ROWS, NUM_ADVICE_PHASE1,
 };
 
 macro_rules! log_context_cursor {
@@ -99,9 +102,9 @@ impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
         let num_limbs = 3;
         let limb_bits = 88;
         #[cfg(feature = "onephase")]
-        let num_advice = [35];
+        let num_advice = [NUM_ADVICE_PHASE1];
         #[cfg(not(feature = "onephase"))]
-        let num_advice = [35, 1];
+        let num_advice = [NUM_ADVICE_PHASE1, 1];
 
         let fp_config = FpConfig::configure(
             meta,
@@ -140,6 +143,10 @@ impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
 /// We follow a strategy to pre-allocate maximum number of cells for each of the above ECC
 /// operations, which means a witness that exceeds the pre-allocated number of cells for any of the
 /// operations will be invalid.
+///
+/// The pairs of an EcPairing operation are processed in chunks of `N_PAIRING_PER_OP` pairs, whose
+/// Miller loops are accumulated over the chunks of the operation. The final exponentiation is then
+/// done once per operation, on the accumulated Miller loop of its last chunk.
 #[derive(Clone, Debug, Default)]
 pub struct EccCircuit<F: Field, const XI_0: i64> {
     /// Maximum number of EcAdd operations supported in one instance of the ECC Circuit.
@@ -149,6 +156,9 @@ pub struct EccCircuit<F: Field, const XI_0: i64> {
     pub max_mul_ops: usize,
     /// Maximum number of pairing operations supported in one instance of the ECC Circuit.
     pub max_pairing_ops: usize,
+    /// Maximum number of chunks of `N_PAIRING_PER_OP` pairs of the pairing operations supported
+    /// in one instance of the ECC Circuit.
+    pub max_pairing_chunks: usize,
 
     /// EcAdd operations provided as witness data to the ECC circuit.
     pub add_ops: Vec<EcAddOp>,
@@ -175,6 +185,49 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         (1 << LOG_TOTAL_NUM_ROWS) - (max_blinding_factor + 3)
     }
 
+    /// Create the circuit for the given ops, with a fixed number of slots per op kind.
+    ///
+    /// The slots can't be shared between op kinds: the gates and copy constraints of the
+    /// halo2-lib gadgets laid out for an op are fixed by the verifying key, so the capacity is
+    /// bounded per kind by `max_ec_ops` rather than by the cost of the ops.
+    pub fn new(
+        max_ec_ops: PrecompileEcParams,
+        add_ops: Vec<EcAddOp>,
+        mul_ops: Vec<EcMulOp>,
+        pairing_ops: Vec<EcPairingOp>,
+    ) -> Self {
+        Self {
+            max_add_ops: max_ec_ops.ec_add,
+            max_mul_ops: max_ec_ops.ec_mul,
+            max_pairing_ops: max_ec_ops.ec_pairing,
+            max_pairing_chunks: max_ec_ops.ec_pairing_chunks,
+            add_ops,
+            mul_ops,
+            pairing_ops,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Number of advice cells used to lay out the given number of ops, with at most as many
+    /// pairing ops as pairing chunks.
+    pub fn num_cells(n_add_ops: usize, n_mul_ops: usize, n_pairing_chunks: usize) -> usize {
+        n_add_ops * EC_ADD_CELLS + n_mul_ops * EC_MUL_CELLS + n_pairing_chunks * EC_PAIRING_CELLS
+    }
+
+    /// Number of advice cells available to lay out the ops.
+    pub fn max_num_cells() -> usize {
+        NUM_ADVICE_PHASE1 * Self::min_num_rows()
+    }
+
+    /// Number of pairing chunks processed by the circuit for the given pairing ops.
+    pub fn n_pairing_chunks(pairing_ops: &[EcPairingOp]) -> usize {
+        pairing_ops
+            .iter()
+            .filter(|op| !op.skip_by_ecc_circuit())
+            .map(EcPairingOp::n_chunks)
+            .sum()
+    }
+
     /// Assign witness from the ecXX ops to the circuit.
     pub(crate) fn assign(
         &self,
@@ -182,27 +235,50 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         config: &<Self as SubCircuit<F>>::Config,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
+        let n_pairing_ops = self
+            .pairing_ops
+            .iter()
+            .filter(|op| !op.skip_by_ecc_circuit())
+            .count();
+        let n_pairing_chunks = Self::n_pairing_chunks(&self.pairing_ops);
         if self.add_ops.len() > self.max_add_ops
             || self.mul_ops.len() > self.max_mul_ops
-            || self.pairing_ops.len() > self.max_pairing_ops
+            || n_pairing_ops > self.max_pairing_ops
+            || n_pairing_chunks > self.max_pairing_chunks
         {
             error!(
-                "add ops = {}, mul ops = {}, pairing ops = {} > max add ops = {}, max mul ops = {}, max pairing ops = {}",
+                "add ops = {}, mul ops = {}, pairing ops = {}, pairing chunks = {} > max add ops = {}, max mul ops = {}, max pairing ops = {}, max pairing chunks = {}",
                 self.add_ops.len(),
                 self.mul_ops.len(),
-                self.pairing_ops.len(),
+                n_pairing_ops,
+                n_pairing_chunks,
                 self.max_add_ops,
                 self.max_mul_ops,
                 self.max_pairing_ops,
+                self.max_pairing_chunks,
+            );
+            return Err(Error::Synthesis);
+        }
+        // the op slots without a chunk only do a final exponentiation.
+        let num_cells = Self::num_cells(
+            self.max_add_ops,
+            self.max_mul_ops,
+            self.max_pairing_chunks.max(self.max_pairing_ops),
+        );
+        if num_cells > Self::max_num_cells() {
+            error!(
+                "ecc ops use {} cells > max cells = {}",
+                num_cells,
+                Self::max_num_cells(),
             );
             return Err(Error::Synthesis);
         }
 
-        // keccak powers of randomness.
+        // keccak powers of randomness, up to the shift of the input RLC by a pairing chunk.
         let keccak_powers = std::iter::successors(Some(Value::known(F::one())), |coeff| {
             Some(challenges.keccak_input() * coeff)
         })
-        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR + 1)
         .map(|x| QuantumCell::Witness(x))
         .collect_vec();
 
@@ -274,13 +350,50 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                 let ec_muls_decomposed =
                     decompose_ec_op!(EcMulOp, self.mul_ops, self.max_mul_ops, decompose_ec_mul_op);
 
-                // e(G1 . G2) * ... * e(G1 . G2) -> Gt
-                let ec_pairings_decomposed = decompose_ec_op!(
-                    EcPairingOp,
-                    self.pairing_ops,
-                    self.max_pairing_ops,
-                    decompose_ec_pairing_op
-                );
+                // e(G1 . G2) * ... * e(G1 . G2) -> Gt, with the Miller loops accumulated over
+                // the chunks of an op.
+                let default_pairing_op = EcPairingOp::default();
+                let pairing_chunks = self
+                    .pairing_ops
+                    .iter()
+                    .filter(|op| !op.skip_by_ecc_circuit())
+                    .chain(std::iter::repeat(&default_pairing_op))
+                    .flat_map(EcPairingChunk::from_op)
+                    .take(self.max_pairing_chunks)
+                    .collect_vec();
+                let mut ec_pairing_chunks_decomposed: Vec<EcPairingDecomposed<F>> =
+                    Vec::with_capacity(self.max_pairing_chunks);
+                for chunk in pairing_chunks.iter() {
+                    let decomposed = self.decompose_ec_pairing_chunk(
+                        &mut ctx,
+                        &ecc_chip,
+                        &fr_chip,
+                        &pairing_chip,
+                        &fp12_chip,
+                        &powers_of_256,
+                        chunk,
+                        ec_pairing_chunks_decomposed.last(),
+                    );
+                    ec_pairing_chunks_decomposed.push(decomposed);
+                }
+                // final exponentiation of each op, on the Miller loop of its last chunk.
+                let pairing_outputs = pairing_chunks
+                    .iter()
+                    .filter_map(|chunk| chunk.output)
+                    .collect_vec();
+                let ec_pairings_decomposed = (0..self.max_pairing_ops)
+                    .map(|op_index| {
+                        self.decompose_ec_pairing_op(
+                            &mut ctx,
+                            &ecc_chip,
+                            &pairing_chip,
+                            &fp12_chip,
+                            &ec_pairing_chunks_decomposed,
+                            op_index,
+                            pairing_outputs.get(op_index).copied(),
+                        )
+                    })
+                    .collect_vec();
 
                 #[cfg(not(feature = "onephase"))]
                 {
@@ -291,7 +404,30 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
 
                 let ec_adds_assigned = assign_ec_op!(ec_adds_decomposed, assign_ec_add);
                 let ec_muls_assigned = assign_ec_op!(ec_muls_decomposed, assign_ec_mul);
-                let ec_pairings_assigned = assign_ec_op!(ec_pairings_decomposed, assign_ec_pairing);
+                let mut pairing_chunk_input_rlcs: Vec<AssignedValue<F>> =
+                    Vec::with_capacity(self.max_pairing_chunks);
+                for decomposed in ec_pairing_chunks_decomposed.iter() {
+                    let input_rlc = self.assign_ec_pairing_chunk(
+                        &mut ctx,
+                        decomposed,
+                        &ecc_chip,
+                        &keccak_powers,
+                        pairing_chunk_input_rlcs.last(),
+                    );
+                    pairing_chunk_input_rlcs.push(input_rlc);
+                }
+                let ec_pairings_assigned = ec_pairings_decomposed
+                    .iter()
+                    .enumerate()
+                    .map(|(op_index, decomposed)| {
+                        self.assign_ec_pairing(
+                            &mut ctx,
+                            decomposed,
+                            &ecc_chip,
+                            &pairing_chunk_input_rlcs[op_index.min(self.max_pairing_chunks)..],
+                        )
+                    })
+                    .collect_vec();
 
                 // Finalize the Fp config always at the end of assignment.
                 let lookup_cells = config.fp_config.finalize(&mut ctx);
@@ -439,6 +575,12 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                         config.ecc_table.is_valid,
                         idx,
                     );
+                    // number of chunks, which sets how the chunks are grouped in ops.
+                    ec_pairing_assigned.n_chunks.copy_advice(
+                        &mut region,
+                        config.ecc_table.arg1_rlc,
+                        idx,
+                    );
                     // RLC(input_bytes)
                     ec_pairing_assigned.input_rlc.copy_advice(
                         &mut region,
@@ -452,7 +594,6 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                         idx,
                     );
                     for &col in [
-                        config.ecc_table.arg1_rlc,
                         config.ecc_table.arg2_rlc,
                         config.ecc_table.arg3_rlc,
                         config.ecc_table.arg4_rlc,
@@ -674,10 +815,10 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
-    /// Decomposes an EcPairing operation and returns cells that represent the LE-bytes of all
-    /// (G1, G2) pairs. In phase2 they will be RLC'd with the keccak randomness.
+    /// Decomposes a chunk of an EcPairing operation and returns cells that represent the LE-bytes
+    /// of its (G1, G2) pairs. In phase2 they will be RLC'd with the keccak randomness.
     #[allow(clippy::too_many_arguments)]
-    fn decompose_ec_pairing_op(
+    fn decompose_ec_pairing_chunk(
         &self,
         ctx: &mut Context<F>,
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
@@ -685,7 +826,8 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         pairing_chip: &PairingChip<F>,
         fp12_chip: &Fp12Chip<F, FpConfig<F, Fq>, Fq12, XI_0>,
         powers_of_256: &[QuantumCell<F>],
-        op: &EcPairingOp,
+        chunk: &EcPairingChunk,
+        prev: Option<&EcPairingDecomposed<F>>,
     ) -> EcPairingDecomposed<F> {
         log::trace!("[ECC] ==> EcPairing Assignment START:");
         log_context_cursor!(ctx);
@@ -693,7 +835,7 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         let fp2_chip = Fp2Chip::<F, FpConfig<F, Fq>, Fq2>::construct(pairing_chip.fp_chip.clone());
         let ecc2_chip = EccChip::construct(fp2_chip.clone());
 
-        let decomposed_pairs = op
+        let decomposed_pairs = chunk
             .pairs
             .iter()
             .map(|pair| {
@@ -823,7 +965,7 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         log_context_cursor!(ctx);
 
         // EVM input for EcPairing in Big-Endian representation, padded by 0 bytes so that the
-        // total number of bytes are N_PAIRING_PER_OP * N_BYTES_PER_PAIR.
+        // total number of bytes of the chunk are N_PAIRING_PER_OP * N_BYTES_PER_PAIR.
         let input_cells = decomposed_pairs
             .iter()
             .flat_map(|(_, _, g1, g2)| {
@@ -842,7 +984,8 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         log::trace!("[ECC] EcPairing Inputs RLC Assigned:");
         log_context_cursor!(ctx);
 
-        // Whether all the pairs are (G1::identity, G2::valid) or (G1::valid, G2::identity) form.
+        // Whether all the pairs of the chunk are (G1::identity, G2::valid) or (G1::valid,
+        // G2::identity) form.
         let all_pairs_zero = ecc_chip.field_chip().range().gate().and_many(
             ctx,
             decomposed_pairs
@@ -951,7 +1094,7 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
             .collect_vec();
         let pairs = pairs.iter().map(|(g1, g2)| (g1, g2)).collect_vec();
 
-        // if the entire input to ecPairing is valid.
+        // if the entire input of the chunk is valid.
         let is_valid = ecc_chip.field_chip().range().gate().and_many(
             ctx,
             decomposed_pairs
@@ -959,19 +1102,171 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                 .map(|&(_, is_pair_valid, _, _)| QuantumCell::Existing(is_pair_valid))
                 .collect_vec(),
         );
+        let miller_loop = pairing_chip.multi_miller_loop(ctx, pairs);
+
+        // a chunk starts a new op if the previous chunk is the last one of its op, the first chunk
+        // starts the first op. The index of the chunk in its op then tells whether the chunk is the
+        // last one of the op, as per the number of chunks of the op which is exposed in the ECC
+        // table.
+        let gate = ecc_chip.field_chip().range().gate();
+        let n_chunks = gate.load_witness(ctx, Value::known(F::from(chunk.n_chunks as u64)));
+        let (is_first, op_index, chunk_index, n_chunks) = match prev {
+            Some(prev) => {
+                let is_first = prev.is_last;
+                let op_index = gate.add(
+                    ctx,
+                    QuantumCell::Existing(prev.op_index),
+                    QuantumCell::Existing(is_first),
+                );
+                let next_chunk_index = gate.add(
+                    ctx,
+                    QuantumCell::Existing(prev.chunk_index),
+                    QuantumCell::Constant(F::one()),
+                );
+                let chunk_index = gate.select(
+                    ctx,
+                    QuantumCell::Constant(F::zero()),
+                    QuantumCell::Existing(next_chunk_index),
+                    QuantumCell::Existing(is_first),
+                );
+                let n_chunks = gate.select(
+                    ctx,
+                    QuantumCell::Existing(n_chunks),
+                    QuantumCell::Existing(prev.n_chunks),
+                    QuantumCell::Existing(is_first),
+                );
+                (is_first, op_index, chunk_index, n_chunks)
+            }
+            None => (
+                gate.load_constant(ctx, F::one()),
+                gate.load_zero(ctx),
+                gate.load_zero(ctx),
+                n_chunks,
+            ),
+        };
+        let chunk_count = gate.add(
+            ctx,
+            QuantumCell::Existing(chunk_index),
+            QuantumCell::Constant(F::one()),
+        );
+        let is_last = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(chunk_count),
+            QuantumCell::Existing(n_chunks),
+        );
 
-        // multi-miller loop and final exponentiation to do pairing check.
-        let success = {
-            let gt = {
-                let gt = pairing_chip.multi_miller_loop(ctx, pairs);
-                pairing_chip.final_exp(ctx, &gt)
+        // accumulate the chunk with the previous chunks of the same op. Since the final
+        // exponentiation is a homomorphism, the pairing check of the op can be done on the product
+        // of the Miller loops of its chunks.
+        let (is_valid, all_pairs_zero, miller_loop) = match prev {
+            Some(prev) => {
+                let prev_is_valid = gate.or(
+                    ctx,
+                    QuantumCell::Existing(is_first),
+                    QuantumCell::Existing(prev.is_valid),
+                );
+                let is_valid = gate.and(
+                    ctx,
+                    QuantumCell::Existing(is_valid),
+                    QuantumCell::Existing(prev_is_valid),
+                );
+                let prev_all_pairs_zero = gate.or(
+                    ctx,
+                    QuantumCell::Existing(is_first),
+                    QuantumCell::Existing(prev.all_pairs_zero),
+                );
+                let all_pairs_zero = gate.and(
+                    ctx,
+                    QuantumCell::Existing(all_pairs_zero),
+                    QuantumCell::Existing(prev_all_pairs_zero),
+                );
+                let accumulated = fp12_chip.mul(ctx, &prev.miller_loop, &miller_loop);
+                let miller_loop = fp12_chip.select(ctx, &miller_loop, &accumulated, &is_first);
+                (is_valid, all_pairs_zero, miller_loop)
+            }
+            None => (is_valid, all_pairs_zero, miller_loop),
+        };
+
+        log::trace!("[ECC] EcPairingAssignment END:");
+        log_context_cursor!(ctx);
+
+        EcPairingDecomposed {
+            is_first,
+            is_last,
+            op_index,
+            chunk_index,
+            n_chunks,
+            is_valid,
+            all_pairs_zero,
+            miller_loop,
+            input_cells,
+        }
+    }
+
+    /// Decomposes the `op_index`-th EcPairing operation, i.e. does the pairing check on the
+    /// Miller loop accumulated up to the last chunk of the operation. Chunks can't belong to an
+    /// operation after their own index, so only the chunks from the `op_index`-th one are
+    /// considered. If none of them is the last chunk of the operation, the operation is blank: it
+    /// has no chunk and isn't valid.
+    #[allow(clippy::too_many_arguments)]
+    fn decompose_ec_pairing_op(
+        &self,
+        ctx: &mut Context<F>,
+        ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
+        pairing_chip: &PairingChip<F>,
+        fp12_chip: &Fp12Chip<F, FpConfig<F, Fq>, Fq12, XI_0>,
+        chunks: &[EcPairingDecomposed<F>],
+        op_index: usize,
+        output: Option<Word>,
+    ) -> EcPairingOpDecomposed<F> {
+        let gate = ecc_chip.field_chip().range().gate();
+        let chunks = &chunks[op_index.min(chunks.len())..];
+        let last_chunk_selectors = chunks
+            .iter()
+            .map(|chunk| {
+                let is_op = gate.is_equal(
+                    ctx,
+                    QuantumCell::Existing(chunk.op_index),
+                    QuantumCell::Constant(F::from(op_index as u64)),
+                );
+                gate.and(
+                    ctx,
+                    QuantumCell::Existing(is_op),
+                    QuantumCell::Existing(chunk.is_last),
+                )
+            })
+            .collect_vec();
+        let mut select_from_last_chunk =
+            |value: fn(&EcPairingDecomposed<F>) -> AssignedValue<F>| {
+                gate.inner_product(
+                    ctx,
+                    last_chunk_selectors
+                        .iter()
+                        .map(|selector| QuantumCell::Existing(*selector)),
+                    chunks
+                        .iter()
+                        .map(|chunk| QuantumCell::Existing(value(chunk))),
+                )
             };
+        let n_chunks = select_from_last_chunk(|chunk| chunk.n_chunks);
+        let is_valid = select_from_last_chunk(|chunk| chunk.is_valid);
+        let all_pairs_zero = select_from_last_chunk(|chunk| chunk.all_pairs_zero);
+        let miller_loop = chunks.iter().zip(last_chunk_selectors.iter()).fold(
+            fp12_chip.load_constant(ctx, Fq12::one()),
+            |miller_loop, (chunk, selector)| {
+                fp12_chip.select(ctx, &chunk.miller_loop, &miller_loop, selector)
+            },
+        );
+
+        // final exponentiation to do pairing check.
+        let success = {
+            let gt = pairing_chip.final_exp(ctx, &miller_loop);
             // whether pairing check was successful.
             let one = fp12_chip.load_constant(ctx, Fq12::one());
             fp12_chip.is_equal(ctx, &gt, &one)
         };
         // success == true only if pairing check and validity are both satisfied.
-        let success = ecc_chip.field_chip().range().gate().and(
+        let success = gate.and(
             ctx,
             QuantumCell::Existing(is_valid),
             QuantumCell::Existing(success),
@@ -981,33 +1276,26 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         // - G2 == (0, 0, 0, 0) and G1 == random valid point on G1
         //
         // then success == true, i.e. success - all_pairs_zero == boolean
-        let success_minus_all_pairs_zero = ecc_chip
-            .field_chip()
-            .range()
-            .gate()
-            .load_witness(ctx, success.value - all_pairs_zero.value);
-        ecc_chip
-            .field_chip()
-            .range()
-            .gate()
-            .assert_bit(ctx, success_minus_all_pairs_zero);
+        let success_minus_all_pairs_zero =
+            gate.load_witness(ctx, success.value - all_pairs_zero.value);
+        gate.assert_bit(ctx, success_minus_all_pairs_zero);
 
-        let op_output = ecc_chip.field_chip().range().gate().load_witness(
+        let op_output = gate.load_witness(
             ctx,
-            Value::known(op.output.to_scalar().expect("EcPairing output = {0, 1}")),
+            output.map_or(success.value, |output| {
+                Value::known(output.to_scalar().expect("EcPairing output = {0, 1}"))
+            }),
         );
-        ecc_chip.field_chip().range().gate().assert_equal(
+        gate.assert_equal(
             ctx,
             QuantumCell::Existing(success),
             QuantumCell::Existing(op_output),
         );
 
-        log::trace!("[ECC] EcPairingAssignment END:");
-        log_context_cursor!(ctx);
-
-        EcPairingDecomposed {
+        EcPairingOpDecomposed {
+            last_chunk_selectors,
+            n_chunks,
             is_valid,
-            input_cells,
             success,
         }
     }
@@ -1101,21 +1389,66 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
-    /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes.
-    fn assign_ec_pairing(
+    /// Handles Phase2 for a chunk of an EcPairing operation and returns the RLC'd input bytes of
+    /// the operation up to this chunk.
+    fn assign_ec_pairing_chunk(
         &self,
         ctx: &mut Context<F>,
         ec_pairing_decomposed: &EcPairingDecomposed<F>,
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
         keccak_powers: &[QuantumCell<F>],
+        prev_input_rlc: Option<&AssignedValue<F>>,
+    ) -> AssignedValue<F> {
+        let gate = ecc_chip.field_chip().range().gate();
+        let input_rlc = gate.inner_product(
+            ctx,
+            ec_pairing_decomposed.input_cells.clone().into_iter().rev(),
+            keccak_powers.iter().cloned(),
+        );
+        // input_rlc = prev_input_rlc * r^(N_PAIRING_PER_OP * N_BYTES_PER_PAIR) + input_rlc, unless
+        // the chunk starts a new op.
+        match prev_input_rlc {
+            Some(prev_input_rlc) => {
+                let accumulated = gate.mul_add(
+                    ctx,
+                    QuantumCell::Existing(*prev_input_rlc),
+                    keccak_powers[N_PAIRING_PER_OP * N_BYTES_PER_PAIR].clone(),
+                    QuantumCell::Existing(input_rlc),
+                );
+                gate.select(
+                    ctx,
+                    QuantumCell::Existing(input_rlc),
+                    QuantumCell::Existing(accumulated),
+                    QuantumCell::Existing(ec_pairing_decomposed.is_first),
+                )
+            }
+            None => input_rlc,
+        }
+    }
+
+    /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes, taken from the
+    /// last chunk of the operation among the given chunk input RLCs.
+    fn assign_ec_pairing(
+        &self,
+        ctx: &mut Context<F>,
+        ec_pairing_decomposed: &EcPairingOpDecomposed<F>,
+        ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
+        chunk_input_rlcs: &[AssignedValue<F>],
     ) -> EcPairingAssigned<F> {
+        let input_rlc = ecc_chip.field_chip().range().gate().inner_product(
+            ctx,
+            ec_pairing_decomposed
+                .last_chunk_selectors
+                .iter()
+                .map(|selector| QuantumCell::Existing(*selector)),
+            chunk_input_rlcs
+                .iter()
+                .map(|input_rlc| QuantumCell::Existing(*input_rlc)),
+        );
         EcPairingAssigned {
+            n_chunks: ec_pairing_decomposed.n_chunks,
             is_valid: ec_pairing_decomposed.is_valid,
-            input_rlc: ecc_chip.field_chip().range().gate().inner_product(
-                ctx,
-                ec_pairing_decomposed.input_cells.clone().into_iter().rev(),
-                keccak_powers.iter().cloned(),
-            ),
+            input_rlc,
             success: ec_pairing_decomposed.success,
         }
     }
@@ -1317,15 +1650,12 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
     type Config = EccCircuitConfig<F>;
 
     fn new_from_block(block: &Block) -> Self {
-        Self {
-            max_add_ops: block.circuits_params.max_ec_ops.ec_add,
-            max_mul_ops: block.circuits_params.max_ec_ops.ec_mul,
-            max_pairing_ops: block.circuits_params.max_ec_ops.ec_pairing,
-            add_ops: block.get_ec_add_ops(),
-            mul_ops: block.get_ec_mul_ops(),
-            pairing_ops: block.get_ec_pairing_ops(),
-            _marker: PhantomData,
-        }
+        Self::new(
+            block.circuits_params.max_ec_ops,
+            block.get_ec_add_ops(),
+            block.get_ec_mul_ops(),
+            block.get_ec_pairing_ops(),
+        )
     }
 
     /// Returns number of unusable rows of the SubCircuit, which should be
@@ -1361,18 +1691,27 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
 
         let ec_adds = block.get_ec_add_ops().len();
         let ec_muls = block.get_ec_mul_ops().len();
-        let ec_pairings = block.get_ec_pairing_ops().len();
+        let pairing_ops = block.get_ec_pairing_ops();
+        let ec_pairings = pairing_ops.len();
+        let ec_pairing_chunks = Self::n_pairing_chunks(&pairing_ops);
         let max_ec_ops = &block.circuits_params.max_ec_ops;
-        log::debug!("ecc circuit row usage: ecadd {ec_adds}/{}, ecmul {ec_muls}/{}, ecpairing {ec_pairings}/{}",
-        max_ec_ops.ec_add, max_ec_ops.ec_mul, max_ec_ops.ec_pairing);
+        log::debug!("ecc circuit row usage: ecadd {ec_adds}/{}, ecmul {ec_muls}/{}, ecpairing {ec_pairings}/{}, ecpairing chunks {ec_pairing_chunks}/{}",
+        max_ec_ops.ec_add, max_ec_ops.ec_mul, max_ec_ops.ec_pairing, max_ec_ops.ec_pairing_chunks);
 
         // Instead of showing actual minimum row usage,
         // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
         // This functionality allows l2geth to decide if additional ops can be added.
+        // a kind of op the circuit has no slot for overflows it.
+        let row_usage = |n_ops: usize, max_ops: usize| match row_num.checked_div(max_ops) {
+            Some(rows_per_op) => rows_per_op * n_ops,
+            None if n_ops == 0 => 0,
+            None => row_num + 1,
+        };
         let min_row_num = [
-            (row_num / max_ec_ops.ec_add) * ec_adds,
-            (row_num / max_ec_ops.ec_mul) * ec_muls,
-            (row_num / max_ec_ops.ec_pairing) * ec_pairings,
+            row_usage(ec_adds, max_ec_ops.ec_add),
+            row_usage(ec_muls, max_ec_ops.ec_mul),
+            row_usage(ec_pairings, max_ec_ops.ec_pairing),
+            row_usage(ec_pairing_chunks, max_ec_ops.ec_pairing_chunks),
         ]
         .into_iter()
         .max()
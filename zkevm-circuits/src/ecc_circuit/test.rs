@@ -28,6 +28,7 @@ fn run<F: Field, const MUST_FAIL: bool>(
         max_add_ops: max_ec_ops.ec_add,
         max_mul_ops: max_ec_ops.ec_mul,
         max_pairing_ops: max_ec_ops.ec_pairing,
+        max_pairing_chunks: max_ec_ops.ec_pairing_chunks,
         add_ops,
         mul_ops,
         pairing_ops,
@@ -116,7 +117,7 @@ impl GenRand for EcPairingOp {
         let point_c = G1Affine::from(G1Affine::generator() * alpha * beta);
         let point_d = G2Affine::generator();
 
-        let mut pairs = [
+        let mut pairs = vec![
             EcPairingPair::new(point_p_negated, point_q),
             EcPairingPair::new(point_s, point_t),
             EcPairingPair::new(point_a_negated, point_b),
@@ -251,7 +252,7 @@ mod valid_invalid_cases {
                 let point_q = G2Affine::from(G2Affine::generator() * beta);
                 let point_s = G1Affine::from(G1Affine::generator() * alpha * beta);
                 let point_t = G2Affine::generator();
-                let pairs = [
+                let pairs = vec![
                     EcPairingPair::new(point_p_negated, point_q),
                     EcPairingPair::new(point_s, point_t),
                     EcPairingPair::padding_pair(),
@@ -271,7 +272,7 @@ mod valid_invalid_cases {
                 let point_p_negated = point_p.neg();
                 let point_q = G2Affine::from(G2Affine::generator() * beta);
                 let point_t = G2Affine::from(G2Affine::generator() * alpha * beta);
-                let pairs = [
+                let pairs = vec![
                     EcPairingPair::new(point_p_negated, point_q),
                     EcPairingPair {
                         g1_point: (
@@ -308,7 +309,7 @@ mod valid_invalid_cases {
                 let point_q = G2Affine::from(G2Affine::generator() * beta);
                 let point_s = G1Affine::from(G1Affine::generator() * gamma);
                 let point_t = G2Affine::generator();
-                let pairs = [
+                let pairs = vec![
                     EcPairingPair::new(point_p_negated, point_q),
                     EcPairingPair::new(point_s, point_t),
                     EcPairingPair::padding_pair(),
@@ -322,7 +323,7 @@ mod valid_invalid_cases {
             },
             // 4. invalid: not on curve G1.
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair {
                         g1_point: (U256::from(3), U256::from(4)),
                         g2_point: (U256::zero(), U256::zero(), U256::zero(), U256::zero()),
@@ -340,7 +341,7 @@ mod valid_invalid_cases {
         vec![
             // 5. invalid: not on curve G2.
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair {
                         g1_point: (U256::zero(), U256::zero()),
                         g2_point: (U256::from(3), U256::from(4), U256::from(5), U256::from(6)),
@@ -354,7 +355,7 @@ mod valid_invalid_cases {
             },
             // 6. valid: all zero.
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair::padding_pair(),
                     EcPairingPair::padding_pair(),
                     EcPairingPair::padding_pair(),
@@ -369,7 +370,7 @@ mod valid_invalid_cases {
         vec![
             // 7. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen)]
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
                     EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator()),
                     EcPairingPair::padding_pair(),
@@ -380,7 +381,7 @@ mod valid_invalid_cases {
             },
             // 8. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen); 2]
             EcPairingOp {
-                pairs: [
+                pairs: vec![
                     EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
                     EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator()),
                     EcPairingPair::new(G1Affine::generator(), G2Affine::generator()),
@@ -391,6 +392,61 @@ mod valid_invalid_cases {
             },
         ]
     });
+    pub(crate) static EC_PAIRING_OPS5: LazyLock<Vec<EcPairingOp>> = LazyLock::new(|| {
+        let pair = EcPairingPair::new(G1Affine::generator(), G2Affine::generator());
+        let pair_neg = EcPairingPair::new(G1Affine::generator().neg(), G2Affine::generator());
+        vec![
+            // 9. valid: [(G1::gen, G2::gen), (-G1::gen, G2::gen); 3] over 2 chunks.
+            EcPairingOp {
+                pairs: vec![
+                    pair,
+                    pair_neg,
+                    pair,
+                    pair_neg,
+                    pair,
+                    pair_neg,
+                    EcPairingPair::padding_pair(),
+                    EcPairingPair::padding_pair(),
+                ],
+                output: 1.into(),
+                ..Default::default()
+            },
+            // 10. invalid: not on curve G1 in the second chunk.
+            EcPairingOp {
+                pairs: vec![
+                    pair,
+                    pair_neg,
+                    pair,
+                    pair_neg,
+                    EcPairingPair {
+                        g1_point: (U256::from(3), U256::from(4)),
+                        g2_point: (U256::zero(), U256::zero(), U256::zero(), U256::zero()),
+                    },
+                    EcPairingPair::padding_pair(),
+                    EcPairingPair::padding_pair(),
+                    EcPairingPair::padding_pair(),
+                ],
+                output: 0.into(),
+                ..Default::default()
+            },
+            // 11. valid: e(G1::gen, G2::gen)^2 in the first chunk, e(G1::gen, G2::gen)^-2 in the
+            // second chunk.
+            EcPairingOp {
+                pairs: vec![
+                    pair,
+                    pair,
+                    pair,
+                    pair_neg,
+                    pair_neg,
+                    pair_neg,
+                    EcPairingPair::padding_pair(),
+                    EcPairingPair::padding_pair(),
+                ],
+                output: 1.into(),
+                ..Default::default()
+            },
+        ]
+    });
 }
 
 #[test]
@@ -399,6 +455,7 @@ fn test_ecc_circuit_valid_invalid() {
     use halo2_proofs::halo2curves::bn256::Fr;
     use valid_invalid_cases::{
         EC_ADD_OPS, EC_MUL_OPS, EC_PAIRING_OPS1, EC_PAIRING_OPS2, EC_PAIRING_OPS3, EC_PAIRING_OPS4,
+        EC_PAIRING_OPS5,
     };
 
     run::<Fr, false>(
@@ -415,6 +472,7 @@ fn test_ecc_circuit_valid_invalid() {
             ec_add: 0,
             ec_mul: 0,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
         },
         vec![],
         vec![],
@@ -427,6 +485,7 @@ fn test_ecc_circuit_valid_invalid() {
             ec_add: 0,
             ec_mul: 0,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
         },
         vec![],
         vec![],
@@ -439,11 +498,42 @@ fn test_ecc_circuit_valid_invalid() {
             ec_add: 0,
             ec_mul: 0,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
         },
         vec![],
         vec![],
         EC_PAIRING_OPS4.clone(),
     );
+
+    run::<Fr, false>(
+        LOG_TOTAL_NUM_ROWS,
+        PrecompileEcParams {
+            ec_add: 0,
+            ec_mul: 0,
+            ec_pairing: 3,
+            ec_pairing_chunks: 6,
+        },
+        vec![],
+        vec![],
+        EC_PAIRING_OPS5.clone(),
+    );
+
+    // the pairing check of an op holds over all its chunks, not over its last chunk only.
+    run::<Fr, true>(
+        LOG_TOTAL_NUM_ROWS,
+        PrecompileEcParams {
+            ec_add: 0,
+            ec_mul: 0,
+            ec_pairing: 3,
+            ec_pairing_chunks: 6,
+        },
+        vec![],
+        vec![],
+        vec![EcPairingOp {
+            output: 0.into(),
+            ..EC_PAIRING_OPS5[2].clone()
+        }],
+    );
 }
 
 #[ignore = "generate a lot of random invalid inputs for bn254 add"]
@@ -494,6 +584,7 @@ fn test_invalid_ec_add() {
             ec_add: ec_adds.len(),
             ec_mul: 0,
             ec_pairing: 0,
+            ec_pairing_chunks: 0,
         },
         ec_adds,
         vec![],
@@ -537,6 +628,7 @@ fn test_invalid_ec_add() {
             ec_add: ec_adds.len(),
             ec_mul: 0,
             ec_pairing: 0,
+            ec_pairing_chunks: 0,
         },
         ec_adds,
         vec![],
@@ -576,6 +668,28 @@ fn test_ecc_circuit_negative() {
     );
 }
 
+#[test]
+fn test_ecc_circuit_over_capacity() {
+    use crate::ecc_circuit::util::LOG_TOTAL_NUM_ROWS;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    let mut rng = rand::thread_rng();
+
+    // a config without any slot rejects the ops instead of being sized after them.
+    let circuit = EccCircuit::<Fr, 9>::new(
+        PrecompileEcParams {
+            ec_add: 0,
+            ec_mul: 0,
+            ec_pairing: 0,
+            ec_pairing_chunks: 0,
+        },
+        vec![],
+        gen(&mut rng, 1, false),
+        vec![],
+    );
+    assert!(MockProver::<Fr>::run(LOG_TOTAL_NUM_ROWS, &circuit, vec![]).is_err());
+}
+
 #[test]
 fn variadic_size_check() {
     use crate::ecc_circuit::util::LOG_TOTAL_NUM_ROWS;
@@ -590,6 +704,7 @@ fn variadic_size_check() {
         max_add_ops: default_params.ec_add,
         max_mul_ops: default_params.ec_mul,
         max_pairing_ops: default_params.ec_pairing,
+        max_pairing_chunks: default_params.ec_pairing_chunks,
         add_ops: gen(&mut rng, 25, false),
         mul_ops: gen(&mut rng, 20, false),
         pairing_ops: EC_PAIRING_OPS1.clone(),
@@ -601,6 +716,7 @@ fn variadic_size_check() {
         max_add_ops: default_params.ec_add,
         max_mul_ops: default_params.ec_mul,
         max_pairing_ops: default_params.ec_pairing,
+        max_pairing_chunks: default_params.ec_pairing_chunks,
         add_ops: {
             let mut ops = gen(&mut rng, 30, false);
             ops.extend_from_slice(&EC_ADD_OPS);
@@ -1,11 +1,18 @@
 use crate::util::Field;
+use bus_mapping::circuit_input_builder::{EcPairingOp, EcPairingPair};
+use eth_types::Word;
 use halo2_base::{AssignedValue, QuantumCell};
 use halo2_ecc::{bigint::CRTInteger, ecc::EcPoint, fields::FieldExtPoint};
 
 // Total number of rows allowable for ECC circuit
 pub const LOG_TOTAL_NUM_ROWS: u32 = 20;
 
-// Cell usage accounting for EcAdd, EcMul and EcPairing
+// Number of advice columns in the first phase, where the ECC ops are laid out.
+pub(super) const NUM_ADVICE_PHASE1: usize = 35;
+
+// Cell usage accounting for EcAdd, EcMul and EcPairing (per chunk of N_PAIRING_PER_OP pairs,
+// including a final exponentiation, so it bounds the cells of the pairing chunks and ops since
+// there are at most as many ops as chunks)
 // Roud up to nearest 100
 pub(super) const EC_ADD_CELLS: usize = 6_900; // actual: 6_851
 pub(super) const EC_MUL_CELLS: usize = 405_500; // actual: 405_476
@@ -83,17 +90,60 @@ pub(super) struct EcMulAssigned<F: Field> {
     pub point_r: G1Assigned<F>,
 }
 
-/// State of EcPairing operation post first phase.
+/// A chunk of `N_PAIRING_PER_OP` pairs of an EcPairing operation.
+pub(super) struct EcPairingChunk<'a> {
+    pub pairs: &'a [EcPairingPair],
+    /// Number of chunks of the operation.
+    pub n_chunks: usize,
+    /// Result of the pairing check, only known for the last chunk of the operation.
+    pub output: Option<Word>,
+}
+
+impl<'a> EcPairingChunk<'a> {
+    /// Split an EcPairing operation in chunks.
+    pub fn from_op(op: &'a EcPairingOp) -> impl Iterator<Item = Self> + 'a {
+        let n_chunks = op.n_chunks();
+        op.chunks().enumerate().map(move |(index, pairs)| Self {
+            pairs,
+            n_chunks,
+            output: (index + 1 == n_chunks).then_some(op.output),
+        })
+    }
+}
+
+/// State of EcPairing chunk post first phase. The validity, zero pairs check and Miller loop
+/// are accumulated over the chunks of the same operation.
 pub(super) struct EcPairingDecomposed<F: Field> {
+    pub is_first: AssignedValue<F>,
+    pub is_last: AssignedValue<F>,
+    /// Index of the operation the chunk belongs to.
+    pub op_index: AssignedValue<F>,
+    /// Index of the chunk in the operation.
+    pub chunk_index: AssignedValue<F>,
+    /// Number of chunks of the operation.
+    pub n_chunks: AssignedValue<F>,
     pub is_valid: AssignedValue<F>,
+    pub all_pairs_zero: AssignedValue<F>,
+    pub miller_loop: FieldExtPoint<CRTInteger<F>>,
     pub input_cells: Vec<QuantumCell<F>>,
+}
+
+/// State of EcPairing operation post first phase, taken from the last chunk of the operation
+/// after the final exponentiation.
+pub(super) struct EcPairingOpDecomposed<F: Field> {
+    /// Whether each chunk, from the one of the same index as the operation, is the last chunk of
+    /// the operation.
+    pub last_chunk_selectors: Vec<AssignedValue<F>>,
+    pub n_chunks: AssignedValue<F>,
+    pub is_valid: AssignedValue<F>,
     pub success: AssignedValue<F>,
 }
 
 /// State of EcPairing operation post second phase.
 pub(super) struct EcPairingAssigned<F: Field> {
+    pub n_chunks: AssignedValue<F>,
     pub is_valid: AssignedValue<F>,
-    /// RLC of (G1, G2) pairs.
+    /// RLC of the (G1, G2) pairs of all the chunks of the operation.
     pub input_rlc: AssignedValue<F>,
     pub success: AssignedValue<F>,
 }
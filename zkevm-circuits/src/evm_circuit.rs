@@ -6,7 +6,7 @@ use halo2_proofs::{
     plonk::*,
 };
 
-mod execution;
+pub(crate) mod execution;
 pub mod param;
 pub(crate) mod step;
 pub use step::ExecutionState;
@@ -21,6 +21,7 @@ pub use self::EvmCircuit as TestEvmCircuit;
 pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
+    failure_report::EvmLookupLayout,
     table::{
         BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable, LookupTable,
         ModExpTable, PowOfRandTable, RwTable, SHA256Table, SigTable, TxTable,
@@ -48,11 +49,11 @@ pub struct EvmCircuitConfig<F> {
     block_table: BlockTable,
     copy_table: CopyTable,
     keccak_table: KeccakTable,
-    sha256_table: SHA256Table,
+    sha256_table: Option<SHA256Table>,
     exp_table: ExpTable,
     sig_table: SigTable,
-    modexp_table: ModExpTable,
-    ecc_table: EccTable,
+    modexp_table: Option<ModExpTable>,
+    ecc_table: Option<EccTable>,
     pub(crate) pow_of_rand_table: PowOfRandTable,
 }
 
@@ -72,16 +73,17 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub copy_table: CopyTable,
     /// KeccakTable
     pub keccak_table: KeccakTable,
-    /// SHA256Table
-    pub sha256_table: SHA256Table,
+    /// SHA256Table, `None` when the SHA256 circuit is left out, so that calls to the SHA256
+    /// precompile can't be proven
+    pub sha256_table: Option<SHA256Table>,
     /// ExpTable
     pub exp_table: ExpTable,
     /// SigTable
     pub sig_table: SigTable,
-    /// ModExpTable
-    pub modexp_table: ModExpTable,
-    /// Ecc Table.
-    pub ecc_table: EccTable,
+    /// ModExpTable, `None` when the ModExp circuit is left out
+    pub modexp_table: Option<ModExpTable>,
+    /// Ecc Table, `None` when the ECC circuit is left out
+    pub ecc_table: Option<EccTable>,
     // Power of Randomness Table.
     pub pow_of_rand_table: PowOfRandTable,
 }
@@ -129,11 +131,15 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &block_table,
             &copy_table,
             &keccak_table,
-            &sha256_table,
+            sha256_table
+                .as_ref()
+                .map(|table| table as &dyn LookupTable<F>),
             &exp_table,
             &sig_table,
-            &modexp_table,
-            &ecc_table,
+            modexp_table
+                .as_ref()
+                .map(|table| table as &dyn LookupTable<F>),
+            ecc_table.as_ref().map(|table| table as &dyn LookupTable<F>),
             &pow_of_rand_table,
         ));
 
@@ -149,8 +155,12 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         keccak_table.annotate_columns(meta);
         exp_table.annotate_columns(meta);
         sig_table.annotate_columns(meta);
-        modexp_table.annotate_columns(meta);
-        ecc_table.annotate_columns(meta);
+        if let Some(modexp_table) = &modexp_table {
+            modexp_table.annotate_columns(meta);
+        }
+        if let Some(ecc_table) = &ecc_table {
+            ecc_table.annotate_columns(meta);
+        }
         pow_of_rand_table.annotate_columns(meta);
 
         Self {
@@ -215,6 +225,12 @@ impl<F: Field> EvmCircuitConfig<F> {
             },
         )
     }
+
+    /// The cells the execution gadgets store their lookups in, to resolve failing lookups in
+    /// [`FailureReport`](crate::failure_report::FailureReport)s.
+    pub fn lookup_layout(&self) -> EvmLookupLayout {
+        self.execution.lookup_layout()
+    }
 }
 
 /// Tx Circuit for verifying transaction signatures
@@ -469,11 +485,11 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     block_table,
                     copy_table,
                     keccak_table,
-                    sha256_table,
+                    sha256_table: Some(sha256_table),
                     exp_table,
                     sig_table,
-                    modexp_table,
-                    ecc_table,
+                    modexp_table: Some(modexp_table),
+                    ecc_table: Some(ecc_table),
                     pow_of_rand_table,
                 },
             ),
@@ -518,29 +534,33 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         config
             .keccak_table
             .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
-        config.sha256_table.dev_load(
-            &mut layouter,
-            block
-                .get_sha256()
-                .iter()
-                .map(|evt| (&evt.input, &evt.digest)),
-            &challenges,
-        )?;
+        if let Some(sha256_table) = &config.sha256_table {
+            sha256_table.dev_load(
+                &mut layouter,
+                block
+                    .get_sha256()
+                    .iter()
+                    .map(|evt| (&evt.input, &evt.digest)),
+                &challenges,
+            )?;
+        }
         config.exp_table.dev_load(&mut layouter, block)?;
         config
             .sig_table
             .dev_load(&mut layouter, block, &challenges)?;
-        config
-            .modexp_table
-            .dev_load(&mut layouter, &block.get_big_modexp())?;
-        config.ecc_table.dev_load(
-            &mut layouter,
-            block.circuits_params.max_ec_ops,
-            &block.get_ec_add_ops(),
-            &block.get_ec_mul_ops(),
-            &block.get_ec_pairing_ops(),
-            &challenges,
-        )?;
+        if let Some(modexp_table) = &config.modexp_table {
+            modexp_table.dev_load(&mut layouter, &block.get_big_modexp())?;
+        }
+        if let Some(ecc_table) = &config.ecc_table {
+            ecc_table.dev_load(
+                &mut layouter,
+                block.circuits_params.max_ec_ops,
+                &block.get_ec_add_ops(),
+                &block.get_ec_mul_ops(),
+                &block.get_ec_pairing_ops(),
+                &challenges,
+            )?;
+        }
         config
             .pow_of_rand_table
             .assign(&mut layouter, &challenges, 2048)?;
@@ -21,6 +21,7 @@ use crate::{
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
+    failure_report::{EvmLookupLayout, EvmStoredLookup},
     table::{LookupTable, RwTableTag, TxReceiptFieldTag},
     util::{query_expression, Challenges, Expr, Field},
 };
@@ -148,7 +149,7 @@ use address::AddressGadget;
 use balance::BalanceGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
-#[cfg(feature = "scroll")]
+#[cfg(any(feature = "scroll", feature = "prevrandao"))]
 use block_ctx::DifficultyGadget;
 use block_ctx::{BlockCtxU160Gadget, BlockCtxU256Gadget, BlockCtxU64Gadget};
 use blockhash::BlockHashGadget;
@@ -251,6 +252,35 @@ pub(crate) trait ExecutionGadget<F: Field> {
     ) -> Result<(), Error>;
 }
 
+/// Minimum number of steps assigned in each of the parallel "Execution step region1" regions.
+pub(crate) const REGION1_MIN_CHUNK_SIZE: usize = 50;
+/// Minimum number of padding rows assigned in each of the parallel "Execution step region2"
+/// regions.
+pub(crate) const REGION2_MIN_CHUNK_SIZE: usize = 300;
+
+/// Calculate chunk_size and chunk_num used to split an assignment into parallel regions.
+/// Here a min_chunk_size is provided to reduce threading overhead
+pub(crate) fn chunking_fn(name: &str, task_len: usize, min_chunk_size: usize) -> (usize, usize) {
+    if task_len == 0 {
+        return (0, 0);
+    }
+    let num_threads = std::thread::available_parallelism()
+        .map(|e| e.get())
+        .unwrap_or(1);
+    //let num_threads = 1;
+    let chunk_size = ((task_len + num_threads - 1) / num_threads).max(min_chunk_size);
+    let chunk_num = (task_len + chunk_size - 1) / chunk_size;
+    log::debug!(
+        "{} chunking: len = {}, num_threads = {}, chunk_size = {}, chunk_num = {}",
+        name,
+        task_len,
+        num_threads,
+        chunk_size,
+        chunk_num
+    );
+    (chunk_size, chunk_num)
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct ExecutionConfig<F> {
     // EVM Circuit selector, which enables all usable rows.  The rows where this selector is
@@ -272,6 +302,9 @@ pub(crate) struct ExecutionConfig<F> {
     step: Step<F>,
     pub(crate) height_map: HashMap<ExecutionState, usize>,
     stored_expressions_map: HashMap<ExecutionState, Vec<StoredExpression<F>>>,
+    // Index in the constraint system of the lookup of each lookup column, to the index of the
+    // column in the cell manager.
+    lookup_columns: HashMap<usize, usize>,
     instrument: Instrument,
     // internal state gadgets
     begin_tx_gadget: Box<BeginTxGadget<F>>,
@@ -342,7 +375,7 @@ pub(crate) struct ExecutionConfig<F> {
     block_ctx_u64_gadget: Box<BlockCtxU64Gadget<F>>,
     block_ctx_u160_gadget: Box<BlockCtxU160Gadget<F>>,
     block_ctx_u256_gadget: Box<BlockCtxU256Gadget<F>>,
-    #[cfg(feature = "scroll")]
+    #[cfg(any(feature = "scroll", feature = "prevrandao"))]
     difficulty_gadget: Box<DifficultyGadget<F>>,
     // error gadgets
     error_oog_call: Box<ErrorOOGCallGadget<F>>,
@@ -394,11 +427,11 @@ impl<F: Field> ExecutionConfig<F> {
         block_table: &dyn LookupTable<F>,
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
-        sha256_table: &dyn LookupTable<F>,
+        sha256_table: Option<&dyn LookupTable<F>>,
         exp_table: &dyn LookupTable<F>,
         sig_table: &dyn LookupTable<F>,
-        modexp_table: &dyn LookupTable<F>,
-        ecc_table: &dyn LookupTable<F>,
+        modexp_table: Option<&dyn LookupTable<F>>,
+        ecc_table: Option<&dyn LookupTable<F>>,
         pow_of_rand_table: &dyn LookupTable<F>,
     ) -> Self {
         let mut instrument = Instrument::default();
@@ -560,7 +593,7 @@ impl<F: Field> ExecutionConfig<F> {
 
         let cell_manager = step_curr.cell_manager.clone();
 
-        let config = Self {
+        let mut config = Self {
             q_usable,
             q_step,
             constants,
@@ -638,7 +671,7 @@ impl<F: Field> ExecutionConfig<F> {
             block_ctx_u64_gadget: configure_gadget!(),
             block_ctx_u160_gadget: configure_gadget!(),
             block_ctx_u256_gadget: configure_gadget!(),
-            #[cfg(feature = "scroll")]
+            #[cfg(any(feature = "scroll", feature = "prevrandao"))]
             difficulty_gadget: configure_gadget!(),
             // error gadgets
             error_oog_constant: configure_gadget!(),
@@ -677,10 +710,11 @@ impl<F: Field> ExecutionConfig<F> {
             step: step_curr,
             height_map,
             stored_expressions_map,
+            lookup_columns: HashMap::new(),
             instrument,
         };
 
-        Self::configure_lookup(
+        config.lookup_columns = Self::configure_lookup(
             meta,
             fixed_table,
             byte_table,
@@ -706,6 +740,30 @@ impl<F: Field> ExecutionConfig<F> {
         &self.instrument
     }
 
+    /// The cells the gadgets store their lookups in, to resolve failing lookups.
+    pub(crate) fn lookup_layout(&self) -> EvmLookupLayout {
+        let stored = self
+            .stored_expressions_map
+            .iter()
+            .map(|(execution_state, stored_expressions)| {
+                let lookups = stored_expressions
+                    .iter()
+                    .filter_map(|stored_expression| {
+                        let (table, column, rotation) = stored_expression.lookup_cell()?;
+                        Some(EvmStoredLookup {
+                            name: stored_expression.name.clone(),
+                            table: format!("{table:?}"),
+                            column,
+                            rotation,
+                        })
+                    })
+                    .collect();
+                (*execution_state, lookups)
+            })
+            .collect();
+        EvmLookupLayout::new(self.lookup_columns.clone(), stored)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn configure_gadget<G: ExecutionGadget<F>>(
         meta: &mut ConstraintSystem<F>,
@@ -959,35 +1017,45 @@ impl<F: Field> ExecutionConfig<F> {
         block_table: &dyn LookupTable<F>,
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
-        sha256_table: &dyn LookupTable<F>,
+        sha256_table: Option<&dyn LookupTable<F>>,
         exp_table: &dyn LookupTable<F>,
         sig_table: &dyn LookupTable<F>,
-        modexp_table: &dyn LookupTable<F>,
-        ecc_table: &dyn LookupTable<F>,
+        modexp_table: Option<&dyn LookupTable<F>>,
+        ecc_table: Option<&dyn LookupTable<F>>,
         pow_of_rand_table: &dyn LookupTable<F>,
         challenges: &Challenges<Expression<F>>,
         cell_manager: &CellManager<F>,
-    ) {
+    ) -> HashMap<usize, usize> {
+        let mut lookup_columns = HashMap::new();
         for column in cell_manager.columns().iter() {
             if let CellType::Lookup(table) = column.cell_type {
+                let lookup_table = match table {
+                    Table::Fixed => Some(fixed_table),
+                    Table::Tx => Some(tx_table),
+                    Table::Rw => Some(rw_table),
+                    Table::Bytecode => Some(bytecode_table),
+                    Table::Block => Some(block_table),
+                    Table::Copy => Some(copy_table),
+                    Table::Keccak => Some(keccak_table),
+                    Table::Sha256 => sha256_table,
+                    Table::Exp => Some(exp_table),
+                    Table::Sig => Some(sig_table),
+                    Table::ModExp => modexp_table,
+                    Table::Ecc => ecc_table,
+                    Table::PowOfRand => Some(pow_of_rand_table),
+                };
                 let name = format!("{table:?}");
+                let Some(lookup_table) = lookup_table else {
+                    // The sub circuit of the table is left out, so that no lookup into it can be
+                    // satisfied, i.e. the steps calling the corresponding precompile can't be
+                    // proven.
+                    let name = format!("{name} table disabled");
+                    meta.create_gate(Box::leak(name.into_boxed_str()), |_| vec![column.expr()]);
+                    continue;
+                };
+                lookup_columns.insert(meta.lookups().len(), column.index);
                 meta.lookup_any(Box::leak(name.into_boxed_str()), |meta| {
-                    let table_expressions = match table {
-                        Table::Fixed => fixed_table,
-                        Table::Tx => tx_table,
-                        Table::Rw => rw_table,
-                        Table::Bytecode => bytecode_table,
-                        Table::Block => block_table,
-                        Table::Copy => copy_table,
-                        Table::Keccak => keccak_table,
-                        Table::Sha256 => sha256_table,
-                        Table::Exp => exp_table,
-                        Table::Sig => sig_table,
-                        Table::ModExp => modexp_table,
-                        Table::Ecc => ecc_table,
-                        Table::PowOfRand => pow_of_rand_table,
-                    }
-                    .table_exprs(meta);
+                    let table_expressions = lookup_table.table_exprs(meta);
                     vec![(
                         column.expr(),
                         rlc::expr(&table_expressions, challenges.lookup_input()),
@@ -1003,6 +1071,7 @@ impl<F: Field> ExecutionConfig<F> {
                 });
             }
         }
+        lookup_columns
     }
 
     pub fn get_num_rows_required_no_padding(&self, block: &Block) -> usize {
@@ -1194,32 +1263,9 @@ impl<F: Field> ExecutionConfig<F> {
             }
         };
 
-        // Calculate chunk_size and chunk_num
-        // Here a min_chunk_size is provided to reduce threading overhead
-        let chunking_fn = |name: &str, task_len: usize, min_chunk_size: usize| -> (usize, usize) {
-            if task_len == 0 {
-                return (0, 0);
-            }
-            let num_threads = std::thread::available_parallelism()
-                .map(|e| e.get())
-                .unwrap_or(1);
-            //let num_threads = 1;
-            let chunk_size = ((task_len + num_threads - 1) / num_threads).max(min_chunk_size);
-            let chunk_num = (task_len + chunk_size - 1) / chunk_size;
-            log::debug!(
-                "{} chunking: len = {}, num_threads = {}, chunk_size = {}, chunk_num = {}",
-                name,
-                task_len,
-                num_threads,
-                chunk_size,
-                chunk_num
-            );
-            (chunk_size, chunk_num)
-        };
-
         // Step1: assign real steps
         let (region1_chunk_size, region1_chunk_num) =
-            chunking_fn("region1", step_assignments.len(), 50);
+            chunking_fn("region1", step_assignments.len(), REGION1_MIN_CHUNK_SIZE);
         let mut region1_is_first_time: Vec<(usize, bool)> = (0..region1_chunk_num)
             .map(|chunk_idx| (chunk_idx, true))
             .collect();
@@ -1302,7 +1348,8 @@ impl<F: Field> ExecutionConfig<F> {
 
         // part2: assign paddings steps when padding needed
         assert!(region2_height >= 1);
-        let (region2_chunk_size, region2_chunk_num) = chunking_fn("region2", region2_height, 300);
+        let (region2_chunk_size, region2_chunk_num) =
+            chunking_fn("region2", region2_height, REGION2_MIN_CHUNK_SIZE);
         let mut region2_is_first_time: Vec<(usize, bool)> = (0..region2_chunk_num)
             .map(|chunk_idx| (chunk_idx, true))
             .collect();
@@ -1621,7 +1668,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::BLOCKCTXU64 => assign_exec_step!(self.block_ctx_u64_gadget),
             ExecutionState::BLOCKCTXU160 => assign_exec_step!(self.block_ctx_u160_gadget),
             ExecutionState::BLOCKCTXU256 => assign_exec_step!(self.block_ctx_u256_gadget),
-            #[cfg(feature = "scroll")]
+            #[cfg(any(feature = "scroll", feature = "prevrandao"))]
             ExecutionState::DIFFICULTY => assign_exec_step!(self.difficulty_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
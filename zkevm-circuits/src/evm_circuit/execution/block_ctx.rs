@@ -188,20 +188,35 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU256Gadget<F> {
     }
 }
 
-#[cfg(feature = "scroll")]
+#[cfg(any(feature = "scroll", feature = "prevrandao"))]
 #[derive(Clone, Debug)]
 pub(crate) struct DifficultyGadget<F> {
     same_context: SameContextGadget<F>,
+    #[cfg(feature = "prevrandao")]
+    prevrandao: RandomLinearCombination<F, N_BYTES_WORD>,
 }
 
-#[cfg(feature = "scroll")]
+#[cfg(any(feature = "scroll", feature = "prevrandao"))]
 impl<F: Field> ExecutionGadget<F> for DifficultyGadget<F> {
     const NAME: &'static str = "DIFFICULTY";
 
     const EXECUTION_STATE: ExecutionState = ExecutionState::DIFFICULTY;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // Returns the block's PREVRANDAO (EIP-4399).
+        #[cfg(feature = "prevrandao")]
+        let prevrandao = {
+            let prevrandao = cb.query_word_rlc();
+            cb.stack_push(prevrandao.expr());
+            cb.block_lookup(
+                BlockContextFieldTag::PrevRandao.expr(),
+                cb.curr.state.block_number.expr(),
+                prevrandao.expr(),
+            );
+            prevrandao
+        };
         // Always returns 0 for scroll.
+        #[cfg(not(feature = "prevrandao"))]
         cb.stack_push(0.expr());
         let opcode = cb.query_cell();
         // State transition
@@ -214,7 +229,11 @@ impl<F: Field> ExecutionGadget<F> for DifficultyGadget<F> {
         };
         let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
 
-        Self { same_context }
+        Self {
+            same_context,
+            #[cfg(feature = "prevrandao")]
+            prevrandao,
+        }
     }
 
     fn assign_exec_step(
@@ -226,6 +245,12 @@ impl<F: Field> ExecutionGadget<F> for DifficultyGadget<F> {
         _: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
+        #[cfg(feature = "prevrandao")]
+        {
+            let prevrandao = _block.rws[step.rw_indices[0]].stack_value();
+            self.prevrandao
+                .assign(region, offset, Some(prevrandao.to_le_bytes()))?;
+        }
         self.same_context.assign_exec_step(region, offset, step)
     }
 }
@@ -274,4 +299,23 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    #[cfg(feature = "prevrandao")]
+    #[test]
+    fn prevrandao_gadget_test() {
+        use eth_types::H256;
+        use mock::test_ctx::helpers::*;
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! {
+                DIFFICULTY
+                STOP
+            }),
+            tx_from_1_to_0,
+            |block, _tx| block.mix_hash(H256::repeat_byte(0xab)),
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run()
+    }
 }
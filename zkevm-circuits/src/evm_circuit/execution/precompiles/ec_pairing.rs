@@ -1,6 +1,6 @@
 use crate::util::Field;
 use bus_mapping::{
-    circuit_input_builder::{N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::{MAX_N_BYTES_EC_PAIRING_INPUT, N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
     precompile::{EcPairingError, PrecompileAuxData, PrecompileCalls},
 };
 use eth_types::{evm_types::GasCost, ToScalar};
@@ -14,7 +14,10 @@ use crate::{
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, IsZeroGadget, LtGadget},
+            math_gadget::{
+                BinaryNumberGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget,
+                RangeCheckGadget,
+            },
             rlc, CachedRegion, Cell,
         },
     },
@@ -22,10 +25,11 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 
-/// Note: input_len ∈ { 0, 192, 384, 576, 768 } if valid.
+/// Note: input_len % 192 == 0 and input_len <= MAX_N_BYTES_EC_PAIRING_INPUT if valid.
 ///
-/// Note: input bytes are padded to 768 bytes within our zkEVM implementation to standardise a
-/// pairing operation, such that each pairing op has 4 pairs: [(G1, G2); 4].
+/// Note: input bytes are padded to a multiple of 768 bytes within our zkEVM implementation to
+/// standardise a pairing operation, such that each pairing op is made of chunks of 4 pairs:
+/// [(G1, G2); 4].
 #[derive(Clone, Debug)]
 pub struct EcPairingGadget<F> {
     // Random linear combination of input bytes to the precompile ecPairing call.
@@ -40,21 +44,20 @@ pub struct EcPairingGadget<F> {
     output: Cell<F>,
 
     // Verify invalidity of input bytes. We basically check `or(1, 2)` where:
-    // 1. input_len > 4 * 192
+    // 1. input_len > MAX_N_BYTES_EC_PAIRING_INPUT
     // 2. input_len % 192 != 0
     input_is_zero: IsZeroGadget<F>,
 
     // call_data_len must less than 2^32.
-    input_lt_769: LtGadget<F, 4>,
-    input_mod_192: Cell<F>,
-    input_div_192: Cell<F>,
-    input_mod_192_lt: LtGadget<F, 1>,
+    input_lt_max: LtGadget<F, 4>,
+    input_div_192: ConstantDivisionGadget<F, 4>,
     input_mod_192_is_zero: IsZeroGadget<F>,
 
-    /// Number of pairs provided through EVM input. Since a maximum of 4 pairs can be supplied from
-    /// EVM, we need 3 binary bits for a max value of [1, 0, 0].
-    n_pairs: Cell<F>,
-    n_pairs_cmp: BinaryNumberGadget<F, 3>,
+    /// Number of pairs provided through EVM input, decomposed as `4 * q + r`. The ECC circuit
+    /// pads the last chunk with `(4 - r) % 4` zero pairs.
+    n_pairs_div_4: Cell<F>,
+    n_pairs_div_4_range: RangeCheckGadget<F, 4>,
+    n_pairs_mod_4: BinaryNumberGadget<F, 2>,
     rand_pow_64: Cell<F>,
 
     is_success: Cell<F>,
@@ -80,9 +83,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             cb.query_bool(),
         );
 
-        let n_pairs = cb.query_cell();
-        let n_pairs_cmp = BinaryNumberGadget::construct(cb, n_pairs.expr());
-
         let [is_success, callee_address, is_root, call_data_offset, call_data_length, return_data_offset, return_data_length] =
             [
                 CallContextFieldTag::IsSuccess,
@@ -101,67 +101,23 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             cb.execution_state().precompile_base_gas_cost().expr(),
         );
 
-        // all gas sent to this call will be consumed if `is_success == false`.
-        let gas_cost = select::expr(
-            is_success.expr(),
-            GasCost::PRECOMPILE_BN256PAIRING.expr()
-                + n_pairs.expr() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
-            cb.curr.state.gas_left.expr(),
-        );
-
-        // if the precompile call was unsuccessful, the output (pairing check) MUST BE 0.
-        // `is_success` and `output` both are booleans, so:
-        cb.require_boolean(
-            "if the precompile call was unsuccessful, pairing check == 0",
-            is_success.expr() - output.expr(),
-        );
-
         //////////////////////////////// INVALID BEGIN ////////////////////////////////
         let input_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
-        let input_lt_769 = LtGadget::construct(cb, call_data_length.expr(), 769.expr());
-        let (input_mod_192, input_div_192, input_mod_192_lt, input_mod_192_is_zero) = cb.condition(
-            and::expr([not::expr(input_is_zero.expr()), input_lt_769.expr()]),
-            |cb| {
-                // r == len(input) % 192
-                let input_mod_192 = cb.query_byte();
-                // r < 192
-                let input_mod_192_lt = LtGadget::construct(cb, input_mod_192.expr(), 192.expr());
-                cb.require_equal("len(input) % 192 < 192", input_mod_192_lt.expr(), 1.expr());
-                // q == len(input) // 192
-                let input_div_192 = cb.query_cell();
-                cb.require_in_set(
-                    "len(input) // 192 ∈ { 0, 1, 2, 3, 4 }",
-                    input_div_192.expr(),
-                    vec![0.expr(), 1.expr(), 2.expr(), 3.expr(), 4.expr()],
-                );
-                // q * 192 + r == call_data_length
-                cb.require_equal(
-                    "q * 192 + r == len(input)",
-                    input_div_192.expr() * 192.expr() + input_mod_192.expr(),
-                    call_data_length.expr(),
-                );
-                let input_mod_192_is_zero = IsZeroGadget::construct(cb, input_mod_192.expr());
-                (
-                    input_mod_192,
-                    input_div_192,
-                    input_mod_192_lt,
-                    input_mod_192_is_zero,
-                )
-            },
+        let input_lt_max = LtGadget::construct(
+            cb,
+            call_data_length.expr(),
+            (MAX_N_BYTES_EC_PAIRING_INPUT as u64 + 1).expr(),
         );
-
-        // when input is zero then input mod_192 is zero
-        cb.condition(input_is_zero.expr(), |cb| {
-            cb.require_true(
-                "when input is zero, then input mod192 is zero",
-                input_mod_192_is_zero.expr(),
-            );
-        });
+        // q == len(input) // 192, r == len(input) % 192
+        let input_div_192 =
+            ConstantDivisionGadget::construct(cb, call_data_length.expr(), N_BYTES_PER_PAIR as u64);
+        let input_mod_192_is_zero = IsZeroGadget::construct(cb, input_div_192.remainder());
+        let n_pairs = input_div_192.quotient();
 
         cb.condition(
-            // (len(input) > 768) || (len(input) % 192 != 0)
+            // (len(input) > MAX_N_BYTES_EC_PAIRING_INPUT) || (len(input) % 192 != 0)
             or::expr([
-                not::expr(input_lt_769.expr()),
+                not::expr(input_lt_max.expr()),
                 not::expr(input_mod_192_is_zero.expr()),
             ]),
             |cb| {
@@ -175,14 +131,34 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
         );
         //////////////////////////////// INVALID END //////////////////////////////////
 
+        // all gas sent to this call will be consumed if `is_success == false`.
+        let gas_cost = select::expr(
+            is_success.expr(),
+            GasCost::PRECOMPILE_BN256PAIRING.expr()
+                + n_pairs.expr() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        // if the precompile call was unsuccessful, the output (pairing check) MUST BE 0.
+        // `is_success` and `output` both are booleans, so:
+        cb.require_boolean(
+            "if the precompile call was unsuccessful, pairing check == 0",
+            is_success.expr() - output.expr(),
+        );
+
         ///////////////////////////////// VALID BEGIN /////////////////////////////////
-        let rand_pow_64 = cb.condition(
-            // (len(input) == 0) || ((len(input) <= 768) && (len(input) % 192 == 0))
-            or::expr([
-                input_is_zero.expr(),
-                and::expr([input_lt_769.expr(), input_mod_192_is_zero.expr()]),
-            ]),
+        let (n_pairs_div_4, n_pairs_div_4_range, n_pairs_mod_4, rand_pow_64) = cb.condition(
+            // (len(input) <= MAX_N_BYTES_EC_PAIRING_INPUT) && (len(input) % 192 == 0)
+            and::expr([input_lt_max.expr(), input_mod_192_is_zero.expr()]),
             |cb| {
+                // n_pairs == 4 * q + r, where q < 2^32 and r < 4.
+                let n_pairs_div_4 = cb.query_cell();
+                let n_pairs_div_4_range = RangeCheckGadget::construct(cb, n_pairs_div_4.expr());
+                let n_pairs_mod_4 = BinaryNumberGadget::construct(
+                    cb,
+                    n_pairs.expr() - n_pairs_div_4.expr() * N_PAIRING_PER_OP.expr(),
+                );
+
                 let rand_pow_64 = cb.query_cell_phase2();
                 let (rand_pow_192, rand_pow_384, rand_pow_576) = {
                     let rand_pow_128 = rand_pow_64.expr() * rand_pow_64.expr();
@@ -193,25 +169,26 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 };
                 cb.pow_of_rand_lookup(64.expr(), rand_pow_64.expr());
 
-                // RLC(inputs) that was processed in the ECC Circuit.
+                // RLC(inputs) that was processed in the ECC Circuit, where the last chunk of
+                // pairs is padded with zero bytes.
                 let ecc_circuit_input_rlc = select::expr(
-                    n_pairs_cmp.value_equals(0usize),
+                    input_is_zero.expr(),
                     0.expr(),
                     select::expr(
-                        n_pairs_cmp.value_equals(1usize),
+                        n_pairs_mod_4.value_equals(1usize),
                         input_bytes_rlc.expr() * rand_pow_576.expr(), /* 576 bytes padded */
                         select::expr(
-                            n_pairs_cmp.value_equals(2usize),
+                            n_pairs_mod_4.value_equals(2usize),
                             input_bytes_rlc.expr() * rand_pow_384.expr(), /* 384 bytes padded */
                             select::expr(
-                                n_pairs_cmp.value_equals(3usize),
+                                n_pairs_mod_4.value_equals(3usize),
                                 input_bytes_rlc.expr() * rand_pow_192.expr(), /* 192 bytes padded */
                                 input_bytes_rlc.expr(),                       /* 0 bytes padded */
                             ),
                         ),
                     ),
                 );
-                cb.condition(n_pairs_cmp.value_equals(0usize), |cb| {
+                cb.condition(input_is_zero.expr(), |cb| {
                     cb.require_zero(
                         "ecPairing: n_pairs == 0 => evm input == 0",
                         input_bytes_rlc.expr(),
@@ -227,10 +204,16 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 //     - G1 co-ord is not in canonical form
                 //     - G2 co-ord is not in canonical form
                 //     - G1, G2 both valid
+                //
+                // The ECC circuit processes the pairs in chunks of N_PAIRING_PER_OP, an empty
+                // input being processed as one chunk of padding pairs.
+                let n_chunks = n_pairs_div_4.expr()
+                    + not::expr(n_pairs_mod_4.value_equals(0usize))
+                    + input_is_zero.expr();
                 cb.ecc_table_lookup(
                     u64::from(PrecompileCalls::Bn128Pairing).expr(),
                     is_success.expr(),
-                    0.expr(),
+                    n_chunks,
                     0.expr(),
                     0.expr(),
                     0.expr(),
@@ -239,27 +222,12 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                     0.expr(),
                 );
 
-                // since len(input) was valid, we know that we are left with 3 scenarios:
-                // 1. pairing check == true
-                // 2. pairing check == false
-                // 3. invalid inputs:
-                //      - invalid field element
-                //      - point not on G1
-                //      - point not on G2
-                //
-                // In all the above, we know that len(input) % 192 == 0 and len(input) <= 768
-                cb.require_equal(
-                    "ecPairing: n_pairs * N_BYTES_PER_PAIR == call_data_length",
-                    n_pairs.expr() * N_BYTES_PER_PAIR.expr(),
-                    call_data_length.expr(),
-                );
-                cb.require_in_set(
-                    "ecPairing: input_len ∈ { 0, 192, 384, 576, 768 }",
-                    call_data_length.expr(),
-                    vec![0.expr(), 192.expr(), 384.expr(), 576.expr(), 768.expr()],
-                );
-
-                rand_pow_64
+                (
+                    n_pairs_div_4,
+                    n_pairs_div_4_range,
+                    n_pairs_mod_4,
+                    rand_pow_64,
+                )
             },
         );
         ///////////////////////////////// VALID END ///////////////////////////////////
@@ -280,14 +248,13 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             output,
 
             input_is_zero,
-            input_lt_769,
-            input_mod_192,
+            input_lt_max,
             input_div_192,
-            input_mod_192_lt,
             input_mod_192_is_zero,
 
-            n_pairs,
-            n_pairs_cmp,
+            n_pairs_div_4,
+            n_pairs_div_4_range,
+            n_pairs_mod_4,
             rand_pow_64,
 
             is_success,
@@ -320,30 +287,23 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 "assign ec pairing exec step: calldata_len = {}",
                 call.call_data_length
             );
-            self.input_lt_769.assign(
+            self.input_lt_max.assign(
                 region,
                 offset,
                 F::from(call.call_data_length),
-                F::from(769),
+                F::from(MAX_N_BYTES_EC_PAIRING_INPUT as u64 + 1),
             )?;
-            let (input_div_192, input_mod_192) = (
-                call.call_data_length / (N_BYTES_PER_PAIR as u64),
-                call.call_data_length % (N_BYTES_PER_PAIR as u64),
-            );
-            self.input_div_192
-                .assign(region, offset, Value::known(F::from(input_div_192)))?;
-            self.input_mod_192
-                .assign(region, offset, Value::known(F::from(input_mod_192)))?;
-            self.input_mod_192_lt
-                .assign(region, offset, F::from(input_mod_192), F::from(192))?;
+            let (_, input_mod_192) =
+                self.input_div_192
+                    .assign(region, offset, u128::from(call.call_data_length))?;
             self.input_mod_192_is_zero
-                .assign(region, offset, F::from(input_mod_192))?;
+                .assign(region, offset, F::from(input_mod_192 as u64))?;
 
             match *res_aux_data {
                 Ok(aux_data) => {
                     debug_assert!(
-                        call.call_data_length <= (N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64,
-                        "len(input) > 768"
+                        call.call_data_length <= MAX_N_BYTES_EC_PAIRING_INPUT as u64,
+                        "len(input) > MAX_N_BYTES_EC_PAIRING_INPUT"
                     );
                     debug_assert!(
                         call.call_data_length % (N_BYTES_PER_PAIR as u64) == 0,
@@ -389,9 +349,13 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                     )?;
                     // Number of pairs provided in the EVM call.
                     let n_pairs = (call.call_data_length as usize) / N_BYTES_PER_PAIR;
-                    self.n_pairs
-                        .assign(region, offset, Value::known(F::from(n_pairs as u64)))?;
-                    self.n_pairs_cmp.assign(region, offset, n_pairs)?;
+                    let n_pairs_div_4 = F::from((n_pairs / N_PAIRING_PER_OP) as u64);
+                    self.n_pairs_div_4
+                        .assign(region, offset, Value::known(n_pairs_div_4))?;
+                    self.n_pairs_div_4_range
+                        .assign(region, offset, n_pairs_div_4)?;
+                    self.n_pairs_mod_4
+                        .assign(region, offset, n_pairs % N_PAIRING_PER_OP)?;
                     self.rand_pow_64.assign(
                         region,
                         offset,
@@ -405,7 +369,7 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                         "len(input) != call_data_length"
                     );
                     debug_assert!(
-                        (call.call_data_length > (N_PAIRING_PER_OP * N_BYTES_PER_PAIR) as u64)
+                        (call.call_data_length > MAX_N_BYTES_EC_PAIRING_INPUT as u64)
                             || (call.call_data_length % (N_BYTES_PER_PAIR as u64) != 0),
                         "len(input) is expected to be invalid",
                     );
@@ -656,6 +620,89 @@ mod test {
                 address: PrecompileCalls::Bn128Pairing.address().to_word(),
                 ..Default::default()
             },
+            #[cfg(not(feature = "scroll"))]
+            PrecompileCallArgs {
+                name: "ecPairing (pairing true): 7 pairs with random G1s",
+                setup_code: {
+                    let mut setup_code = bytecode! {
+                        // G1_x1
+                        PUSH32(word!("0x2cf44499d5d27bb186308b7af7af02ac5bc9eeb6a3d147c186b21fb1b76e18da"))
+                        PUSH1(0x00)
+                        MSTORE
+                        // G1_y1
+                        PUSH32(word!("0x2c0f001f52110ccfe69108924926e45f0b0c868df0e7bde1fe16d3242dc715f6"))
+                        PUSH1(0x20)
+                        MSTORE
+                        // G2_x11
+                        PUSH32(word!("0x1fb19bb476f6b9e44e2a32234da8212f61cd63919354bc06aef31e3cfaff3ebc"))
+                        PUSH1(0x40)
+                        MSTORE
+                        // G2_x12
+                        PUSH32(word!("0x22606845ff186793914e03e21df544c34ffe2f2f3504de8a79d9159eca2d98d9"))
+                        PUSH1(0x60)
+                        MSTORE
+                        // G2_y11
+                        PUSH32(word!("0x2bd368e28381e8eccb5fa81fc26cf3f048eea9abfdd85d7ed3ab3698d63e4f90"))
+                        PUSH1(0x80)
+                        MSTORE
+                        // G2_y12
+                        PUSH32(word!("0x2fe02e47887507adf0ff1743cbac6ba291e66f59be6bd763950bb16041a0a85e"))
+                        PUSH1(0xA0)
+                        MSTORE
+                        // G1_x2
+                        PUSH32(word!("0x0000000000000000000000000000000000000000000000000000000000000001"))
+                        PUSH1(0xC0)
+                        MSTORE
+                        // G1_y2
+                        PUSH32(word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"))
+                        PUSH1(0xE0)
+                        MSTORE
+                        // G2_x21
+                        PUSH32(word!("0x1971ff0471b09fa93caaf13cbf443c1aede09cc4328f5a62aad45f40ec133eb4"))
+                        PUSH2(0x100)
+                        MSTORE
+                        // G2_x22
+                        PUSH32(word!("0x091058a3141822985733cbdddfed0fd8d6c104e9e9eff40bf5abfef9ab163bc7"))
+                        PUSH2(0x120)
+                        MSTORE
+                        // G2_y21
+                        PUSH32(word!("0x2a23af9a5ce2ba2796c1f4e453a370eb0af8c212d9dc9acd8fc02c2e907baea2"))
+                        PUSH2(0x140)
+                        MSTORE
+                        // G2_y22
+                        PUSH32(word!("0x23a8eb0b0996252cb548a4487da97b02422ebc0e834613f954de6c7e0afdc1fc"))
+                        PUSH2(0x160)
+                        MSTORE
+                    };
+                    let mut memory_addr = 0x180;
+                    for _ in 0..5 {
+                        // G1::random
+                        let g1 = G1Affine::random(&mut rng);
+                        setup_code.push(32, Word::from_little_endian(&g1.x.to_bytes()));
+                        setup_code.push(2, memory_addr);
+                        memory_addr += 0x20;
+                        setup_code.write_op(OpcodeId::MSTORE);
+                        setup_code.push(32, Word::from_little_endian(&g1.y.to_bytes()));
+                        setup_code.push(2, memory_addr);
+                        memory_addr += 0x20;
+                        setup_code.write_op(OpcodeId::MSTORE);
+                        // G2::identity
+                        for _ in 0..4 {
+                            setup_code.push(1, 0x00);
+                            setup_code.push(2, memory_addr);
+                            memory_addr += 0x20;
+                            setup_code.write_op(OpcodeId::MSTORE);
+                        }
+                    }
+                    setup_code
+                },
+                call_data_offset: 0x00.into(),
+                call_data_length: 0x540.into(),
+                ret_offset: 0x540.into(),
+                ret_size: 0x20.into(),
+                address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                ..Default::default()
+            },
             PrecompileCallArgs {
                 name: "ecPairing (pairing true): 4 pairs with random G2s",
                 setup_code: {
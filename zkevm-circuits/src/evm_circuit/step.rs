@@ -14,6 +14,7 @@ use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, iter, marker::ConstParamTy};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -35,7 +36,9 @@ impl From<PrecompileCalls> for ExecutionState {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(
+    ConstParamTy, Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize,
+)]
 pub enum ExecutionState {
     // Internal state
     BeginTx,
@@ -81,8 +84,8 @@ pub enum ExecutionState {
     BLOCKCTXU64,  // TIMESTAMP, NUMBER, GASLIMIT
     BLOCKCTXU160, // COINBASE
     BLOCKCTXU256, // BASEFEE, DIFFICULTY (for non-scroll)
-    #[cfg(feature = "scroll")]
-    DIFFICULTY, // DIFFICULTY
+    #[cfg(any(feature = "scroll", feature = "prevrandao"))]
+    DIFFICULTY, // DIFFICULTY (or PREVRANDAO)
     CHAINID,
     SELFBALANCE,
     POP,
@@ -276,13 +279,13 @@ impl ExecutionState {
             Self::BLOCKCTXU64 => vec![OpcodeId::TIMESTAMP, OpcodeId::NUMBER, OpcodeId::GASLIMIT],
             Self::BLOCKCTXU160 => vec![OpcodeId::COINBASE],
             Self::BLOCKCTXU256 => {
-                if cfg!(feature = "scroll") {
+                if cfg!(any(feature = "scroll", feature = "prevrandao")) {
                     vec![OpcodeId::BASEFEE]
                 } else {
                     vec![OpcodeId::DIFFICULTY, OpcodeId::BASEFEE]
                 }
             }
-            #[cfg(feature = "scroll")]
+            #[cfg(any(feature = "scroll", feature = "prevrandao"))]
             Self::DIFFICULTY => vec![OpcodeId::DIFFICULTY],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
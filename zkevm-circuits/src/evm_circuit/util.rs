@@ -270,6 +270,16 @@ impl<F> Hash for StoredExpression<F> {
 }
 
 impl<F: Field> StoredExpression<F> {
+    /// The looked up table, cell manager column and rotation of a stored lookup.
+    pub(crate) fn lookup_cell(&self) -> Option<(Table, usize, usize)> {
+        match self.cell_type {
+            CellType::Lookup(table) => {
+                Some((table, self.cell.cell_column_index, self.cell.rotation))
+            }
+            _ => None,
+        }
+    }
+
     pub fn assign(
         &self,
         region: &mut CachedRegion<'_, '_, F>,
@@ -7,6 +7,7 @@ pub(crate) mod param;
 mod test;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 pub use dev::ExpCircuit as TestExpCircuit;
+pub use param::OFFSET_INCREMENT;
 
 use crate::{
     evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
@@ -0,0 +1,489 @@
+//! Human-friendly reports of `MockProver` verification failures.
+//!
+//! Failures are grouped by the sub-circuit that assigned the failing region. When the witness
+//! [`Block`] is provided, failures in the EVM circuit regions are resolved to the step, opcode,
+//! program counter and call that assigned the failing row. With the [`EvmLookupLayout`] of the
+//! circuit as well, a failing EVM lookup is resolved to the gadget lookup stored in the failing
+//! cell and, for rw lookups, to the rw table row the lookup was expected to match.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use halo2_proofs::dev::{metadata, FailureLocation, VerifyFailure};
+
+use crate::{
+    evm_circuit::ExecutionState,
+    witness::{
+        explorer::{EvmRegion, EvmRowLocation, TraceExplorer},
+        Block,
+    },
+};
+
+/// The sub-circuit a verification failure belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SubCircuitKind {
+    /// EVM circuit and its fixed tables
+    Evm,
+    /// State circuit and rw table
+    State,
+    /// MPT table
+    Mpt,
+    /// Copy circuit
+    Copy,
+    /// RLP circuit
+    Rlp,
+    /// Tx circuit
+    Tx,
+    /// Keccak circuit
+    Keccak,
+    /// Bytecode circuit
+    Bytecode,
+    /// Exponentiation circuit
+    Exp,
+    /// PI circuit
+    Pi,
+    /// Sig circuit
+    Sig,
+    /// ECC circuit
+    Ecc,
+    /// ModExp circuit
+    ModExp,
+    /// SHA256 circuit
+    Sha256,
+    /// Poseidon circuit
+    Poseidon,
+    /// The failure could not be attributed to a sub-circuit.
+    Unknown,
+}
+
+impl SubCircuitKind {
+    /// The sub-circuit which assigns the region of the given name. Only exact names of regions
+    /// assigned by this crate are recognized.
+    fn from_region_name(name: &str) -> Self {
+        match name {
+            "Execution step region1"
+            | "Execution step region2"
+            | "Execution step region3"
+            | "fixed table"
+            | "byte table" => Self::Evm,
+            "state circuit"
+            | "state circuit (StateCircuitConfig)"
+            | "state circuit (synthesize_sub) part1"
+            | "state circuit (synthesize_sub) part2"
+            | "state circuit (synthesize_sub) part3"
+            | "rw table"
+            | "assign call_context_field_tags fixed column"
+            | "assign u8 fixed column"
+            | "assign u10 fixed column"
+            | "assign u16 fixed column" => Self::State,
+            "mpt table zkevm" => Self::Mpt,
+            "assign copy table" | "copy table" => Self::Copy,
+            "RLP ROM table" | "RLP data table region" | "RLP sm region" | "RLP dev table" => {
+                Self::Rlp
+            }
+            "Tx ROM table" | "tx table" | "tx table aux" => Self::Tx,
+            "assign keccak rows" | "keccak table" => Self::Keccak,
+            "assign bytecode"
+            | "assign bytecode with poseidon hash extension"
+            | "push table"
+            | "bytecode table" => Self::Bytecode,
+            "exponentiation circuit" | "exponentiation table" => Self::Exp,
+            "dev block table" | "block table" | "pi region" | "pi connecting region" => Self::Pi,
+            "ecdsa chip verification" | "expose sig table" | "sig table (dev load)" => Self::Sig,
+            "ecc circuit" | "expose ecc table" | "ecc table dev load" => Self::Ecc,
+            "modexp circuit" | "modexp table" | "modexp table blank region" => Self::ModExp,
+            "initialize hasher"
+            | "sha256 input"
+            | "sha256 digest"
+            | "sha256 state initialized bind"
+            | "sha256 state initialized by iv bind"
+            | "sha256 table dev" => Self::Sha256,
+            "poseidon table" | "poseidon codehash table" => Self::Poseidon,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A region as reported by `MockProver`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RegionRef {
+    index: usize,
+    name: String,
+}
+
+impl RegionRef {
+    /// Fields of `halo2_proofs::dev::metadata::Region` aren't public, so we parse its format
+    /// string `Region <index> ('<name>')`, and only accept the parsed region if it is equal to
+    /// the reported one.
+    fn parse(region: &metadata::Region) -> Option<Self> {
+        let formatted = format!("{region}");
+        let (index, name) = formatted.strip_prefix("Region ")?.split_once(' ')?;
+        let name = name.strip_prefix("('")?.strip_suffix("')")?;
+        let parsed = Self {
+            index: index.parse().ok()?,
+            name: name.to_string(),
+        };
+        (metadata::Region::from((parsed.index, parsed.name.clone())) == *region).then_some(parsed)
+    }
+}
+
+/// A lookup stored by an EVM execution gadget.
+#[derive(Clone, Debug)]
+pub(crate) struct EvmStoredLookup {
+    /// Name of the stored lookup, e.g. `rw lookup Stack pop a`
+    pub(crate) name: String,
+    /// Name of the looked up table, as used for the lookup argument names
+    pub(crate) table: String,
+    /// Column of the cell in the cell manager
+    pub(crate) column: usize,
+    /// Rotation of the cell from the first row of the step
+    pub(crate) rotation: usize,
+}
+
+/// The cells in which the EVM circuit execution gadgets store their lookups, used to resolve a
+/// failing EVM lookup to the gadget lookup that failed. See
+/// [`EvmCircuitConfig::lookup_layout`](crate::evm_circuit::EvmCircuitConfig::lookup_layout).
+#[derive(Clone, Debug, Default)]
+pub struct EvmLookupLayout {
+    /// Index in the constraint system of the lookup of each lookup column, to the column index
+    columns: HashMap<usize, usize>,
+    /// Stored lookups of each execution state, in the order they were added
+    stored: HashMap<ExecutionState, Vec<EvmStoredLookup>>,
+}
+
+impl EvmLookupLayout {
+    pub(crate) fn new(
+        columns: HashMap<usize, usize>,
+        stored: HashMap<ExecutionState, Vec<EvmStoredLookup>>,
+    ) -> Self {
+        Self { columns, stored }
+    }
+
+    /// Find the stored lookups of the step which the failing lookup may have checked, along with
+    /// their ordinal among the step lookups into the same table. The lookup index gives the
+    /// column when it matches the layout, otherwise all the lookups into the table of that name
+    /// at the failing row are returned.
+    fn resolve(
+        &self,
+        execution_state: ExecutionState,
+        offset_in_step: usize,
+        lookup_name: &str,
+        lookup_index: usize,
+    ) -> Vec<(&EvmStoredLookup, usize)> {
+        let lookups: Vec<_> = self
+            .stored
+            .get(&execution_state)
+            .into_iter()
+            .flatten()
+            .filter(|lookup| lookup.table == lookup_name)
+            .enumerate()
+            .filter(|(_, lookup)| lookup.rotation == offset_in_step)
+            .map(|(ordinal, lookup)| (lookup, ordinal))
+            .collect();
+        match self.columns.get(&lookup_index) {
+            Some(column) if lookups.iter().any(|(lookup, _)| lookup.column == *column) => lookups
+                .into_iter()
+                .filter(|(lookup, _)| lookup.column == *column)
+                .collect(),
+            _ => lookups,
+        }
+    }
+}
+
+/// A verification failure with the context needed to explain it.
+#[derive(Clone, Debug)]
+pub struct FailureEntry {
+    /// Sub-circuit the failure belongs to
+    pub sub_circuit: SubCircuitKind,
+    /// The failure as reported by `MockProver`
+    pub description: String,
+    /// The EVM step that assigned the failing row, if any
+    pub evm_step: Option<EvmRowLocation>,
+    /// Names of the gadget lookups stored in the failing cell. There's more than one when the
+    /// failing column couldn't be told apart from the other columns of the same table.
+    pub lookups: Vec<String>,
+    /// The rw table rows the failing rw lookups were expected to match, assuming the preceding
+    /// rw lookups of the step were all performed
+    pub expected_rws: Vec<String>,
+    /// Whether this is a lookup failure
+    pub is_lookup: bool,
+}
+
+/// Verification failures grouped by sub-circuit.
+#[derive(Clone, Debug, Default)]
+pub struct FailureReport {
+    /// Failures of each sub-circuit
+    pub groups: BTreeMap<SubCircuitKind, Vec<FailureEntry>>,
+    /// Set when the EVM circuit steps were split in several regions and none of the failures
+    /// allowed to find which region came first, in which case the first failing step region is
+    /// assumed to be the first one.
+    pub evm_region_assumed: bool,
+}
+
+/// Region and offset of the failure, if the failure happened inside a region.
+fn failure_location(failure: &VerifyFailure) -> Option<(&metadata::Region, usize)> {
+    let location = match failure {
+        VerifyFailure::CellNotAssigned { region, offset, .. } => {
+            return usize::try_from(*offset).ok().map(|offset| (region, offset))
+        }
+        VerifyFailure::ConstraintNotSatisfied { location, .. } => location,
+        VerifyFailure::Lookup { location, .. } => location,
+        VerifyFailure::Permutation { location, .. } => location,
+        _ => return None,
+    };
+    match location {
+        FailureLocation::InRegion { region, offset } => Some((region, *offset)),
+        FailureLocation::OutsideRegion { .. } => None,
+    }
+}
+
+/// Find the layouter index of the first EVM region, from the EVM regions that failed.
+fn first_evm_region_index(regions: &[EvmRegion], failed: &[RegionRef]) -> Option<(usize, bool)> {
+    let count = |name: &str| regions.iter().filter(|r| r.name == name).count();
+    let ordinal_of_first = |name: &str| regions.iter().position(|r| r.name == name);
+    // A region name which is only used once gives an exact anchor.
+    for region in failed {
+        if count(&region.name) == 1 {
+            let ordinal = ordinal_of_first(&region.name)?;
+            return region
+                .index
+                .checked_sub(ordinal)
+                .map(|index| (index, false));
+        }
+    }
+    failed
+        .iter()
+        .filter(|region| ordinal_of_first(&region.name) == Some(0))
+        .map(|region| region.index)
+        .min()
+        .map(|index| (index, true))
+}
+
+impl FailureReport {
+    /// Group the failures by sub-circuit. With a `block`, EVM circuit failures are resolved to
+    /// the execution steps of the block, and with the `layout` too, EVM lookup failures are
+    /// resolved to the failing gadget lookups.
+    pub fn new(
+        failures: &[VerifyFailure],
+        block: Option<&Block>,
+        layout: Option<&EvmLookupLayout>,
+    ) -> Self {
+        let located: Vec<_> = failures
+            .iter()
+            .map(|failure| {
+                failure_location(failure)
+                    .and_then(|(region, offset)| Some((RegionRef::parse(region)?, offset)))
+            })
+            .collect();
+
+        let explorer = block.map(TraceExplorer::new);
+        let evm_regions = explorer
+            .as_ref()
+            .map(TraceExplorer::evm_regions)
+            .unwrap_or_default();
+        let failed_evm_regions: Vec<_> = located
+            .iter()
+            .flatten()
+            .filter(|(region, _)| evm_regions.iter().any(|r| r.name == region.name))
+            .map(|(region, _)| region.clone())
+            .collect();
+        let first_evm_region = first_evm_region_index(&evm_regions, &failed_evm_regions);
+
+        let mut report = Self {
+            evm_region_assumed: first_evm_region.map_or(false, |(_, assumed)| assumed),
+            ..Default::default()
+        };
+        for (failure, location) in failures.iter().zip(located) {
+            let description = format!("{failure}");
+            let sub_circuit = location
+                .as_ref()
+                .map_or(SubCircuitKind::Unknown, |(region, _)| {
+                    SubCircuitKind::from_region_name(&region.name)
+                });
+            let evm_step = match (&explorer, location, first_evm_region) {
+                (Some(explorer), Some((region, offset)), Some((first, _)))
+                    if sub_circuit == SubCircuitKind::Evm =>
+                {
+                    region
+                        .index
+                        .checked_sub(first)
+                        .and_then(|ordinal| evm_regions.get(ordinal))
+                        .filter(|evm_region| evm_region.name == region.name)
+                        .and_then(|evm_region| {
+                            explorer.locate_evm_row(evm_region.start_row + offset)
+                        })
+                }
+                _ => None,
+            };
+            let is_lookup = matches!(failure, VerifyFailure::Lookup { .. });
+            let resolved = match (failure, layout, &evm_step) {
+                (
+                    VerifyFailure::Lookup {
+                        name, lookup_index, ..
+                    },
+                    Some(layout),
+                    Some(step),
+                ) => layout.resolve(
+                    step.execution_state,
+                    step.offset_in_step,
+                    name,
+                    *lookup_index,
+                ),
+                _ => vec![],
+            };
+            let lookups = resolved
+                .iter()
+                .map(|(lookup, _)| lookup.name.clone())
+                .collect();
+            let expected_rws = match (block, &evm_step) {
+                (Some(block), Some(step)) => step
+                    .tx_index
+                    .map(|tx_index| &block.txs[tx_index].steps[step.step_index].rw_indices)
+                    .into_iter()
+                    .flat_map(|rw_indices| {
+                        resolved
+                            .iter()
+                            .filter(|(lookup, _)| lookup.table == "Rw")
+                            .filter_map(|(_, ordinal)| rw_indices.get(*ordinal))
+                    })
+                    .map(|(tag, idx)| format!("{:?}", block.rws[(*tag, *idx)]))
+                    .collect(),
+                _ => vec![],
+            };
+            report
+                .groups
+                .entry(sub_circuit)
+                .or_default()
+                .push(FailureEntry {
+                    sub_circuit,
+                    description,
+                    evm_step,
+                    lookups,
+                    expected_rws,
+                    is_lookup,
+                });
+        }
+        report
+    }
+
+    /// Render the report.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let total: usize = self.groups.values().map(Vec::len).sum();
+        writeln!(out, "{total} verification failure(s)").unwrap();
+        if self.evm_region_assumed {
+            writeln!(
+                out,
+                "note: the first failing EVM step region is assumed to be the first one"
+            )
+            .unwrap();
+        }
+        for (sub_circuit, entries) in self.groups.iter() {
+            writeln!(out, "{:?}: {} failure(s)", sub_circuit, entries.len()).unwrap();
+            for entry in entries.iter() {
+                for (i, line) in entry.description.lines().enumerate() {
+                    let indent = if i == 0 { "  - " } else { "    " };
+                    writeln!(out, "{indent}{line}").unwrap();
+                }
+                if let Some(step) = &entry.evm_step {
+                    writeln!(out, "    at {step}").unwrap();
+                }
+                for lookup in entry.lookups.iter() {
+                    writeln!(out, "    in {lookup}").unwrap();
+                }
+                for rw in entry.expected_rws.iter() {
+                    writeln!(out, "    expected {rw}").unwrap();
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_region() {
+        let region = metadata::Region::from((3, "Execution step region1"));
+        assert_eq!(
+            RegionRef::parse(&region),
+            Some(RegionRef {
+                index: 3,
+                name: "Execution step region1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn group_by_sub_circuit() {
+        let lookup = |index, name: &str, offset| VerifyFailure::Lookup {
+            name: "lookup".to_string(),
+            lookup_index: 0,
+            location: FailureLocation::InRegion {
+                region: metadata::Region::from((index, name.to_string())),
+                offset,
+            },
+        };
+        let failures = vec![
+            lookup(5, "Execution step region1", 3),
+            lookup(9, "state circuit", 7),
+            lookup(10, "copy table", 1),
+            lookup(11, "assign copy table", 1),
+            lookup(12, "Tx ROM table", 0),
+            lookup(13, "external copy table", 0),
+        ];
+        let report = FailureReport::new(&failures, None, None);
+        let count = |kind| report.groups.get(&kind).map_or(0, Vec::len);
+        assert_eq!(count(SubCircuitKind::Evm), 1);
+        assert_eq!(count(SubCircuitKind::State), 1);
+        assert_eq!(count(SubCircuitKind::Copy), 2);
+        assert_eq!(count(SubCircuitKind::Tx), 1);
+        assert_eq!(count(SubCircuitKind::Unknown), 1);
+        assert!(report.render().starts_with("6 verification failure(s)"));
+    }
+
+    #[test]
+    fn resolve_evm_lookup() {
+        let stored = |name: &str, table: &str, column, rotation| EvmStoredLookup {
+            name: name.to_string(),
+            table: table.to_string(),
+            column,
+            rotation,
+        };
+        let layout = EvmLookupLayout::new(
+            HashMap::from([(7, 0), (8, 1), (9, 2)]),
+            HashMap::from([(
+                ExecutionState::ADD_SUB,
+                vec![
+                    stored("rw lookup Stack pop a", "Rw", 0, 0),
+                    stored("rw lookup Stack pop b", "Rw", 1, 0),
+                    stored("rw lookup Stack push c", "Rw", 0, 1),
+                    stored("Responsible opcode lookup", "Fixed", 2, 0),
+                ],
+            )]),
+        );
+        let resolve = |offset_in_step, name, lookup_index| {
+            layout
+                .resolve(ExecutionState::ADD_SUB, offset_in_step, name, lookup_index)
+                .into_iter()
+                .map(|(lookup, ordinal)| (lookup.name.as_str(), ordinal))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(resolve(0, "Rw", 8), vec![("rw lookup Stack pop b", 1)]);
+        assert_eq!(resolve(1, "Rw", 7), vec![("rw lookup Stack push c", 2)]);
+        assert_eq!(
+            resolve(0, "Fixed", 9),
+            vec![("Responsible opcode lookup", 0)]
+        );
+        // A lookup index that doesn't match the layout gives all the candidates of the row.
+        assert_eq!(
+            resolve(0, "Rw", 9),
+            vec![("rw lookup Stack pop a", 0), ("rw lookup Stack pop b", 1)]
+        );
+    }
+}
@@ -21,7 +21,7 @@ pub use KeccakCircuitConfig as KeccakConfig;
 
 use self::{
     cell_manager::*,
-    keccak_packed_multi::{keccak_unusable_rows, multi_keccak, KeccakRow},
+    keccak_packed_multi::{keccak_unusable_rows, multi_keccak_with_rows_per_round, KeccakRow},
     param::*,
     table::*,
     util::*,
@@ -66,6 +66,8 @@ pub struct KeccakCircuitConfig<F> {
     pack_table: [TableColumn; 2],
     /// The column for enabling copy constraints in aggregator
     pub preimage_column_index: usize,
+    /// Rows per inner round of keccak_f
+    pub num_rows_per_round: usize,
     _marker: PhantomData<F>,
 }
 
@@ -76,6 +78,10 @@ pub struct KeccakCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// Challenges randomness
     pub challenges: Challenges<Expression<F>>,
+    /// Rows per inner round of keccak_f. More rows per round lay out the round
+    /// cells over less advice columns, at the cost of less keccak_f's for a
+    /// given number of rows. The witness must be generated with the same value.
+    pub num_rows_per_round: usize,
 }
 
 impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
@@ -87,10 +93,11 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         Self::ConfigArgs {
             keccak_table,
             challenges,
+            num_rows_per_round,
         }: Self::ConfigArgs,
     ) -> Self {
         assert!(
-            get_num_rows_per_round() > NUM_BYTES_PER_WORD,
+            num_rows_per_round > NUM_BYTES_PER_WORD,
             "KeccakCircuit requires KECCAK_ROWS>=9"
         );
         let q_enable = keccak_table.q_enable;
@@ -114,7 +121,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let chi_base_table = array_init::array_init(|_| meta.lookup_table_column());
         let pack_table = array_init::array_init(|_| meta.lookup_table_column());
 
-        let mut cell_manager = CellManager::new(get_num_rows_per_round());
+        let mut cell_manager = CellManager::new(num_rows_per_round);
         let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
         let mut total_lookup_counter = 0;
 
@@ -136,7 +143,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             for j in 0..5 {
                 let cell = cell_manager.query_cell(meta);
                 s[i][j] = cell.expr();
-                s_next[i][j] = cell.at_offset(meta, get_num_rows_per_round() as i32).expr();
+                s_next[i][j] = cell.at_offset(meta, num_rows_per_round as i32).expr();
             }
         }
         log::debug!("- Post states:");
@@ -149,7 +156,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let mut absorb_data_next = vec![0u64.expr(); NUM_WORDS_TO_ABSORB];
         let mut absorb_result_next = vec![0u64.expr(); NUM_WORDS_TO_ABSORB];
         for i in 0..NUM_WORDS_TO_ABSORB {
-            let rot = ((i + 1) * get_num_rows_per_round()) as i32;
+            let rot = ((i + 1) * num_rows_per_round) as i32;
             absorb_from_next[i] = absorb_from.at_offset(meta, rot).expr();
             absorb_data_next[i] = absorb_data.at_offset(meta, rot).expr();
             absorb_result_next[i] = absorb_result.at_offset(meta, rot).expr();
@@ -330,7 +337,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
                     if row_idx == 0 {
                         num_columns += 1;
                     }
-                    row_idx = (((row_idx as usize) + 1) % get_num_rows_per_round()) as i32;
+                    row_idx = (((row_idx as usize) + 1) % num_rows_per_round) as i32;
                 }
             }
         }
@@ -466,7 +473,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let squeeze_from = cell_manager.query_cell(meta);
         let mut squeeze_from_prev = vec![0u64.expr(); NUM_WORDS_TO_SQUEEZE];
         for (idx, squeeze_from_prev) in squeeze_from_prev.iter_mut().enumerate() {
-            let rot = (-(idx as i32) - 1) * get_num_rows_per_round() as i32;
+            let rot = (-(idx as i32) - 1) * num_rows_per_round as i32;
             *squeeze_from_prev = squeeze_from.at_offset(meta, rot).expr();
         }
         // Squeeze
@@ -546,7 +553,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let mut hash_bytes = Vec::new();
         for i in 0..NUM_WORDS_TO_SQUEEZE {
             for byte in squeeze_bytes.iter() {
-                let rot = (-(i as i32) - 1) * get_num_rows_per_round() as i32;
+                let rot = (-(i as i32) - 1) * num_rows_per_round as i32;
                 hash_bytes.push(byte.cell.at_offset(meta, rot).expr());
             }
         }
@@ -607,7 +614,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         // Enforce logic for when this block is the last block for a hash
         let last_is_padding_in_block = is_paddings.last().unwrap().at_offset(
             meta,
-            -(((NUM_ROUNDS + 1 - NUM_WORDS_TO_ABSORB) * get_num_rows_per_round()) as i32),
+            -(((NUM_ROUNDS + 1 - NUM_WORDS_TO_ABSORB) * num_rows_per_round) as i32),
         );
         meta.create_gate("is final", |meta| {
             let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
@@ -625,7 +632,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             );
             // For all the rows of a round, only the first row can have `is_final == 1`.
             cb.condition(
-                (1..get_num_rows_per_round() as i32)
+                (1..num_rows_per_round as i32)
                     .map(|i| meta.query_fixed(q_enable, Rotation(-i)))
                     .fold(0.expr(), |acc, elem| acc + elem),
                 |cb| {
@@ -644,7 +651,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
         let prev_is_padding = is_paddings
             .last()
             .unwrap()
-            .at_offset(meta, -(get_num_rows_per_round() as i32));
+            .at_offset(meta, -(num_rows_per_round as i32));
         meta.create_gate("padding", |meta| {
             let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
             let q_padding = meta.query_fixed(q_padding, Rotation::cur());
@@ -734,13 +741,10 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
 
             let q_padding = meta.query_fixed(q_padding, Rotation::cur());
-            let start_new_hash_prev =
-                start_new_hash(meta, Rotation(-(get_num_rows_per_round() as i32)));
-            let length_prev =
-                meta.query_advice(length, Rotation(-(get_num_rows_per_round() as i32)));
+            let start_new_hash_prev = start_new_hash(meta, Rotation(-(num_rows_per_round as i32)));
+            let length_prev = meta.query_advice(length, Rotation(-(num_rows_per_round as i32)));
             let length = meta.query_advice(length, Rotation::cur());
-            let data_rlc_prev =
-                meta.query_advice(data_rlc, Rotation(-(get_num_rows_per_round() as i32)));
+            let data_rlc_prev = meta.query_advice(data_rlc, Rotation(-(num_rows_per_round as i32)));
             let data_rlcs: Vec<_> = (0..NUM_BYTES_PER_WORD + 1)
                 .map(|i| meta.query_advice(data_rlc, Rotation(i as i32)))
                 .collect();
@@ -868,6 +872,7 @@ impl<F: Field> SubCircuitConfig<F> for KeccakCircuitConfig<F> {
             chi_base_table,
             pack_table,
             preimage_column_index,
+            num_rows_per_round,
             _marker: PhantomData,
         }
     }
@@ -994,7 +999,7 @@ impl<F: Field> KeccakCircuitConfig<F> {
 }
 
 /// KeccakCircuit
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct KeccakCircuit<F: Field> {
     // The input is a two dimensional vector
     // Each input row is a pre-image of the hash
@@ -1003,47 +1008,34 @@ pub struct KeccakCircuit<F: Field> {
     // The maximum number of rows, for example, 2^20
     // This needs to be large enough for the circuit.
     num_rows: usize,
+    // Rows per inner round of keccak_f, see `KeccakCircuitConfigArgs`
+    num_rows_per_round: usize,
     _marker: PhantomData<F>,
 }
 
+impl<F: Field> Default for KeccakCircuit<F> {
+    fn default() -> Self {
+        Self::new(0, vec![])
+    }
+}
+
 impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
     type Config = KeccakCircuitConfig<F>;
 
     fn unusable_rows() -> usize {
-        keccak_unusable_rows()
+        keccak_unusable_rows(get_num_rows_per_round())
     }
 
     /// The `block.circuits_params.keccak_padding` parameter, when enabled, sets
     /// up the circuit to support a fixed number of permutations/keccak_f's,
     /// independently of the permutations required by `inputs`.
     fn new_from_block(block: &witness::Block) -> Self {
-        Self::new(
-            block.circuits_params.max_keccak_rows,
-            keccak_inputs(block).unwrap(),
-        )
+        Self::new_from_block_with_rows_per_round(block, get_num_rows_per_round())
     }
 
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block) -> (usize, usize) {
-        let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
-        let aux_tables_rows = [
-            normalize_table_size(6),
-            normalize_table_size(4),
-            normalize_table_size(3),
-            lookup_table_size(CHI_BASE_LOOKUP_TABLE.len()),
-        ];
-        (
-            keccak_inputs(block)
-                .unwrap()
-                .iter()
-                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
-                .sum::<usize>()
-                + get_num_rows_per_round(), // reserved for first 12 dummy rows
-            max(
-                block.circuits_params.max_keccak_rows,
-                *(aux_tables_rows.iter().max().unwrap()),
-            ),
-        )
+        Self::min_num_rows_block_with_rows_per_round(block, get_num_rows_per_round())
     }
 
     /// Make the assignments to the KeccakCircuit
@@ -1053,6 +1045,10 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
         challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
+        assert_eq!(
+            self.num_rows_per_round, config.num_rows_per_round,
+            "keccak witness and circuit rows per round differ"
+        );
         config.load_aux_tables(layouter)?;
         let witness = self.generate_witness(*challenges);
         config.assign(layouter, witness.as_slice())
@@ -1062,24 +1058,84 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
 impl<F: Field> KeccakCircuit<F> {
     /// Creates a new circuit instance
     pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Self::new_with_rows_per_round(num_rows, inputs, get_num_rows_per_round())
+    }
+
+    /// Creates a new circuit instance with `num_rows_per_round` rows per inner
+    /// round of keccak_f
+    pub fn new_with_rows_per_round(
+        num_rows: usize,
+        inputs: Vec<Vec<u8>>,
+        num_rows_per_round: usize,
+    ) -> Self {
         KeccakCircuit {
             inputs,
             num_rows,
+            num_rows_per_round,
             _marker: PhantomData,
         }
     }
 
+    /// [`SubCircuit::new_from_block`] with `num_rows_per_round` rows per inner
+    /// round of keccak_f
+    pub fn new_from_block_with_rows_per_round(
+        block: &witness::Block,
+        num_rows_per_round: usize,
+    ) -> Self {
+        Self::new_with_rows_per_round(
+            block.circuits_params.max_keccak_rows,
+            keccak_inputs(block).unwrap(),
+            num_rows_per_round,
+        )
+    }
+
+    /// [`SubCircuit::min_num_rows_block`] with `num_rows_per_round` rows per
+    /// inner round of keccak_f
+    pub fn min_num_rows_block_with_rows_per_round(
+        block: &witness::Block,
+        num_rows_per_round: usize,
+    ) -> (usize, usize) {
+        let rows_per_chunk = (NUM_ROUNDS + 1) * num_rows_per_round;
+        let aux_tables_rows = [
+            normalize_table_size(6),
+            normalize_table_size(4),
+            normalize_table_size(3),
+            lookup_table_size(CHI_BASE_LOOKUP_TABLE.len()),
+        ];
+        (
+            keccak_inputs(block)
+                .unwrap()
+                .iter()
+                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
+                .sum::<usize>()
+                + num_rows_per_round, // reserved for the first dummy round
+            max(
+                block.circuits_params.max_keccak_rows,
+                *(aux_tables_rows.iter().max().unwrap()),
+            ),
+        )
+    }
+
     /// The number of keccak_f's that can be done in this circuit
     pub fn capacity(&self) -> Option<usize> {
-        Self::capacity_for_row(self.num_rows)
+        Self::capacity_for_row_with_rows_per_round(self.num_rows, self.num_rows_per_round)
     }
 
     /// The number of keccak_f's that can be done for
     /// a particular row number depending on current Keccak params
     pub fn capacity_for_row(num_rows: usize) -> Option<usize> {
+        Self::capacity_for_row_with_rows_per_round(num_rows, get_num_rows_per_round())
+    }
+
+    /// The number of keccak_f's that can be done for a particular row number
+    /// with `num_rows_per_round` rows per inner round of keccak_f
+    pub fn capacity_for_row_with_rows_per_round(
+        num_rows: usize,
+        num_rows_per_round: usize,
+    ) -> Option<usize> {
         if num_rows > 0 {
             // Subtract two for unusable rows
-            Some(num_rows / ((NUM_ROUNDS + 1) * get_num_rows_per_round()) - 2)
+            Some(num_rows / ((NUM_ROUNDS + 1) * num_rows_per_round) - 2)
         } else {
             None
         }
@@ -1087,7 +1143,12 @@ impl<F: Field> KeccakCircuit<F> {
 
     /// Sets the witness using the data to be hashed
     pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
-        multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
-            .expect("Too many inputs for given capacity")
+        multi_keccak_with_rows_per_round(
+            self.inputs.as_slice(),
+            challenges,
+            self.capacity(),
+            self.num_rows_per_round,
+        )
+        .expect("Too many inputs for given capacity")
     }
 }
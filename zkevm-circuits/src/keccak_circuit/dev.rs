@@ -1,7 +1,9 @@
 pub use super::KeccakCircuit;
 
 use crate::{
-    keccak_circuit::{KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    keccak_circuit::{
+        keccak_packed_multi::get_num_rows_per_round, KeccakCircuitConfig, KeccakCircuitConfigArgs,
+    },
     table::KeccakTable,
     util::{Challenges, Field, SubCircuit, SubCircuitConfig},
 };
@@ -31,6 +33,7 @@ impl<F: Field> Circuit<F> for KeccakCircuit<F> {
                 KeccakCircuitConfigArgs {
                     keccak_table,
                     challenges,
+                    num_rows_per_round: get_num_rows_per_round(),
                 },
             )
         };
@@ -14,7 +14,8 @@ use std::{env::var, vec};
 const MAX_DEGREE: usize = 9;
 
 /// Obtain the rows required for 1 iteration of f-box's inner round
-/// function (consisting of 5 phases) within Keccak circuit
+/// function (consisting of 5 phases) within Keccak circuit, when the
+/// circuit is not given its rows per round.
 pub fn get_num_rows_per_round() -> usize {
     let r = var("KECCAK_ROWS")
         .unwrap_or_else(|_| format!("{DEFAULT_KECCAK_ROWS}"))
@@ -30,7 +31,12 @@ pub fn get_num_rows_per_round() -> usize {
 /// function (consisting of nr = 12 + 2*l inner rounds)
 /// within Keccak circuit
 pub fn get_num_rows_per_update() -> usize {
-    get_num_rows_per_round() * (NUM_ROUNDS + 1)
+    get_num_rows_per_update_with_rows_per_round(get_num_rows_per_round())
+}
+/// Obtain the rows required for 1 iteration of the f-box function with
+/// `num_rows_per_round` rows per inner round
+pub fn get_num_rows_per_update_with_rows_per_round(num_rows_per_round: usize) -> usize {
+    num_rows_per_round * (NUM_ROUNDS + 1)
 }
 /// Obtain the column position of the hash inputs
 /// within cell_manager for an inner round.
@@ -56,13 +62,13 @@ pub fn get_input_bytes_col_idx_in_cell_manager() -> usize {
     col + absorb_parts_col * 2 + 1
 }
 
-pub(crate) fn keccak_unusable_rows() -> usize {
+pub(crate) fn keccak_unusable_rows(num_rows_per_round: usize) -> usize {
     const UNUSABLE_ROWS_BY_KECCAK_ROWS: [usize; 24] = [
         53, 67, 63, 59, 45, 79, 77, 75, 73, 71, 69, 67, 65, 63, 61, 59, 57, 71, 89, 107, 107, 107,
         107, 107,
     ];
     UNUSABLE_ROWS_BY_KECCAK_ROWS
-        .get(get_num_rows_per_round() - NUM_BYTES_PER_WORD - 1)
+        .get(num_rows_per_round - NUM_BYTES_PER_WORD - 1)
         .cloned()
         .unwrap_or(107)
 }
@@ -534,9 +540,10 @@ pub(crate) mod transform_to {
 pub(crate) fn keccak_rows<F: Field>(
     bytes: &[u8],
     challenges: Challenges<Value<F>>,
+    num_rows_per_round: usize,
 ) -> Vec<KeccakRow<F>> {
     let mut rows = Vec::new();
-    keccak(&mut rows, bytes, challenges);
+    keccak(&mut rows, bytes, challenges, num_rows_per_round);
     rows
 }
 
@@ -544,6 +551,7 @@ pub(crate) fn keccak<F: Field>(
     rows: &mut Vec<KeccakRow<F>>,
     bytes: &[u8],
     challenges: Challenges<Value<F>>,
+    num_rows_per_round: usize,
 ) {
     let mut bits = into_bits(bytes);
     let mut s = [[F::zero(); 5]; 5];
@@ -587,7 +595,7 @@ pub(crate) fn keccak<F: Field>(
         let mut round_lengths = Vec::new();
         let mut round_data_rlcs = Vec::new();
         for round in 0..NUM_ROUNDS + 1 {
-            let mut cell_manager = CellManager::new(get_num_rows_per_round());
+            let mut cell_manager = CellManager::new(num_rows_per_round);
             let mut region = KeccakRegion::new();
 
             let mut absorb_row = AbsorbData::default();
@@ -612,8 +620,8 @@ pub(crate) fn keccak<F: Field>(
             absorb_result.assign(&mut region, 0, absorb_row.result);
 
             // Column padding
-            if get_num_rows_per_round() > 28 {
-                for _ in 28..get_num_rows_per_round() {
+            if num_rows_per_round > 28 {
+                for _ in 28..num_rows_per_round {
                     let padding_cell = cell_manager.query_cell_value();
                     padding_cell.assign(&mut region, 0, F::zero());
                 }
@@ -644,7 +652,7 @@ pub(crate) fn keccak<F: Field>(
                 transform::value(&mut cell_manager, &mut region, packed, false, |v| *v, true);
             cell_manager.start_region();
             let mut is_paddings = Vec::new();
-            let mut data_rlcs = vec![Value::known(F::zero()); get_num_rows_per_round()];
+            let mut data_rlcs = vec![Value::known(F::zero()); num_rows_per_round];
             for _ in input_bytes.iter() {
                 is_paddings.push(cell_manager.query_cell_value());
             }
@@ -730,7 +738,7 @@ pub(crate) fn keccak<F: Field>(
                                 rho_pi_chi_cells[p][i][j]
                                     .push(cell_manager.query_cell_value_at_row(row_idx as i32));
                             }
-                            row_idx = (row_idx + 1) % get_num_rows_per_round();
+                            row_idx = (row_idx + 1) % num_rows_per_round;
                         }
                     }
                 }
@@ -851,7 +859,7 @@ pub(crate) fn keccak<F: Field>(
 
         for round in 0..NUM_ROUNDS + 1 {
             let round_cst = pack_u64(ROUND_CST[round]);
-            for row_idx in 0..get_num_rows_per_round() {
+            for row_idx in 0..num_rows_per_round {
                 rows.push(KeccakRow {
                     q_enable: row_idx == 0,
                     q_round: row_idx == 0 && round < NUM_ROUNDS,
@@ -893,16 +901,27 @@ pub fn multi_keccak<F: Field>(
     bytes: &[Vec<u8>],
     challenges: Challenges<Value<F>>,
     capacity: Option<usize>,
+) -> Result<Vec<KeccakRow<F>>, Error> {
+    multi_keccak_with_rows_per_round(bytes, challenges, capacity, get_num_rows_per_round())
+}
+
+/// Witness of the keccak circuit laid out with `num_rows_per_round` rows per
+/// inner round, which must match the rows per round the circuit is configured with.
+pub fn multi_keccak_with_rows_per_round<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+    num_rows_per_round: usize,
 ) -> Result<Vec<KeccakRow<F>>, Error> {
     log::info!("multi_keccak assign with capacity: {:?}", capacity);
 
     let mut rows: Vec<KeccakRow<F>> = Vec::new();
     if let Some(capacity) = capacity {
-        rows.reserve((1 + capacity * (NUM_ROUNDS + 1)) * get_num_rows_per_round());
+        rows.reserve((1 + capacity * (NUM_ROUNDS + 1)) * num_rows_per_round);
     }
     // Dummy first row so that the initial data is absorbed
     // The initial data doesn't really matter, `is_final` just needs to be disabled.
-    for idx in 0..get_num_rows_per_round() {
+    for idx in 0..num_rows_per_round {
         rows.push(KeccakRow {
             q_enable: idx == 0,
             q_round: false,
@@ -922,22 +941,22 @@ pub fn multi_keccak<F: Field>(
     // TODO: optimize the `extend` using Iter?
     let real_rows: Vec<_> = bytes
         .par_iter()
-        .flat_map_iter(|bytes| keccak_rows(bytes, challenges))
+        .flat_map_iter(|bytes| keccak_rows(bytes, challenges, num_rows_per_round))
         .collect();
     rows.extend(real_rows);
     debug!("keccak rows len without padding: {}", rows.len());
     if let Some(capacity) = capacity {
         let padding_rows = {
             let mut rows = Vec::new();
-            keccak(&mut rows, &[], challenges);
+            keccak(&mut rows, &[], challenges, num_rows_per_round);
             rows
         };
         // Pad with no data hashes to the expected capacity
-        while rows.len() < (1 + capacity * (NUM_ROUNDS + 1)) * get_num_rows_per_round() {
+        while rows.len() < (1 + capacity * (NUM_ROUNDS + 1)) * num_rows_per_round {
             rows.extend(padding_rows.clone());
         }
         // Check that we are not over capacity
-        if rows.len() > (1 + capacity * (NUM_ROUNDS + 1)) * get_num_rows_per_round() {
+        if rows.len() > (1 + capacity * (NUM_ROUNDS + 1)) * num_rows_per_round {
             return Err(Error::BoundsFailure);
         }
     }
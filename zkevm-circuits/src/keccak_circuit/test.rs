@@ -1,6 +1,9 @@
 #![allow(unused_imports)]
 use super::*;
-use crate::util::{unusable_rows, Field};
+use crate::{
+    table::KeccakTable,
+    util::{unusable_rows, Challenges, Field},
+};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
     dev::MockProver,
@@ -55,6 +58,73 @@ fn packed_multi_keccak_simple() {
     verify::<Fr>(k, inputs, true);
 }
 
+/// Keccak circuit laid out with `ROWS` rows per round, independently of the `KECCAK_ROWS` env var.
+#[derive(Clone)]
+struct RowsPerRoundCircuit<const ROWS: usize>(KeccakCircuit<Fr>);
+
+impl<const ROWS: usize> Circuit<Fr> for RowsPerRoundCircuit<ROWS> {
+    type Config = (KeccakCircuitConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self(KeccakCircuit::new_with_rows_per_round(0, vec![], ROWS))
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let keccak_table = KeccakTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+        let challenges_exprs = challenges.exprs(meta);
+        let config = KeccakCircuitConfig::new(
+            meta,
+            KeccakCircuitConfigArgs {
+                keccak_table,
+                challenges: challenges_exprs,
+                num_rows_per_round: ROWS,
+            },
+        );
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        self.0.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
+
+#[test]
+fn packed_multi_keccak_rows_per_round() {
+    const ROWS: usize = 20;
+    assert_ne!(get_num_rows_per_round(), ROWS);
+
+    let k = get_degree() as u32;
+    let inputs = vec![
+        vec![],
+        (0u8..135).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    let circuit = RowsPerRoundCircuit::<ROWS>(KeccakCircuit::new_with_rows_per_round(
+        2usize.pow(k) - keccak_unusable_rows(ROWS),
+        inputs,
+        ROWS,
+    ));
+    assert_eq!(
+        circuit.0.capacity(),
+        KeccakCircuit::<Fr>::capacity_for_row_with_rows_per_round(
+            2usize.pow(k) - keccak_unusable_rows(ROWS),
+            ROWS
+        )
+    );
+
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    prover.assert_satisfied_par();
+}
+
 #[test]
 fn variadic_size_check() {
     let k = get_degree() as u32;
@@ -1,6 +1,9 @@
 //! Utility traits, functions used in the crate.
 
-use super::{keccak_packed_multi::keccak_unusable_rows, param::*};
+use super::{
+    keccak_packed_multi::{get_num_rows_per_round, keccak_unusable_rows},
+    param::*,
+};
 use crate::util::Field;
 use eth_types::{ToScalar, Word};
 use halo2_proofs::circuit::Value;
@@ -225,7 +228,7 @@ pub fn get_num_bits_per_lookup(range: usize) -> usize {
 
 // Implementation of the above without environment dependency.
 pub(crate) fn get_num_bits_per_lookup_impl(range: usize, log_height: usize) -> usize {
-    let num_unusable_rows = keccak_unusable_rows();
+    let num_unusable_rows = keccak_unusable_rows(get_num_rows_per_round());
     let height = 2usize.pow(log_height as u32);
     let mut num_bits = 1;
     while range.pow(num_bits + 1) + num_unusable_rows <= height {
@@ -28,9 +28,12 @@ compile_error!("This program requires a 64-bit target architecture.");
 
 pub mod bytecode_circuit;
 pub mod copy_circuit;
+pub mod cost_model;
 pub mod ecc_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
+#[cfg(any(feature = "test", test))]
+pub mod failure_report;
 pub mod keccak_circuit;
 pub mod mpt_circuit;
 pub mod pi_circuit;
@@ -132,7 +132,8 @@ impl ModExpCircuitConfig {
     }
 }
 
-const MODEXPCONFIG_EACH_CHIP_ROWS: usize = 39962;
+/// Rows used by each modexp in the circuit.
+pub const MODEXPCONFIG_EACH_CHIP_ROWS: usize = 39962;
 
 /// ModExp circuit for precompile modexp
 #[derive(Clone, Debug, Default)]
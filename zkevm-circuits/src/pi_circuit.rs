@@ -57,6 +57,8 @@ use crate::{
     },
     util::rlc_be_bytes,
 };
+#[cfg(feature = "prevrandao")]
+use crate::{pi_circuit::param::PREVRANDAO_OFFSET, table::BlockContextFieldTag::PrevRandao};
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 use halo2_proofs::{circuit::SimpleFloorPlanner, plonk::Circuit};
 use itertools::Itertools;
@@ -173,6 +175,12 @@ impl PublicData {
                     .cloned()
                     .unwrap_or_else(|| panic!("get num_all_txs in block {block_num}"))
                     as u16;
+                // prevrandao is only committed to when DIFFICULTY returns it.
+                let prevrandao = if cfg!(feature = "prevrandao") {
+                    block.prevrandao.to_be_bytes().to_vec()
+                } else {
+                    vec![]
+                };
                 iter::empty()
                     // Block Values
                     .chain(block.number.as_u64().to_be_bytes())
@@ -180,6 +188,7 @@ impl PublicData {
                     .chain(block.base_fee.to_be_bytes())
                     .chain(block.gas_limit.to_be_bytes())
                     .chain(num_all_txs.to_be_bytes())
+                    .chain(prevrandao)
             }))
             // Tx Hashes
             .chain(
@@ -343,6 +352,7 @@ impl BlockContext {
             chain_id,
             coinbase,
             difficulty,
+            prevrandao: Default::default(),
             gas_limit: 0,
             number: Default::default(),
             timestamp: Default::default(),
@@ -847,6 +857,8 @@ impl<F: Field> PiCircuitConfig<F> {
     /// | CONSTS   | difficulty             |                          |
     /// |----------|------------------------|--------------------------|
     ///
+    /// With the `prevrandao` feature, block\[i\].num_all_txs is followed by block\[i\].prevrandao.
+    ///
     /// Where each one of the rows above, i.e. block\[0\].number, block\[0\].timestamp,
     /// ..., pi_hash_lo, coinbase, difficulty are assigned using the
     /// assign_field method.
@@ -972,7 +984,8 @@ impl<F: Field> PiCircuitConfig<F> {
                 .unwrap_or(0) as u16;
 
             // Assign fields in pi columns and connect them to block table
-            for (field_value_be_bytes, field_offset) in [
+            #[allow(unused_mut)]
+            let mut fields = vec![
                 // block number
                 (
                     block.number.as_u64().to_be_bytes().to_vec(),
@@ -989,7 +1002,11 @@ impl<F: Field> PiCircuitConfig<F> {
                 (block.gas_limit.to_be_bytes().to_vec(), GAS_LIMIT_OFFSET),
                 // num txs in block
                 (num_all_txs.to_be_bytes().to_vec(), NUM_ALL_TXS_OFFSET),
-            ] {
+            ];
+            // prevrandao
+            #[cfg(feature = "prevrandao")]
+            fields.push((block.prevrandao.to_be_bytes().to_vec(), PREVRANDAO_OFFSET));
+            for (field_value_be_bytes, field_offset) in fields {
                 let (tmp_offset, tmp_rpi_rlc_acc, tmp_rpi_length, cells) = self.assign_field(
                     region,
                     offset,
@@ -1678,8 +1695,18 @@ impl<F: Field> PiCircuitConfig<F> {
                 .cloned()
                 .unwrap_or(0);
             let tag = [
-                Coinbase, Timestamp, Number, Difficulty, GasLimit, BaseFee, ChainId, NumTxs,
-                CumNumTxs, NumAllTxs,
+                Coinbase,
+                Timestamp,
+                Number,
+                Difficulty,
+                GasLimit,
+                BaseFee,
+                ChainId,
+                NumTxs,
+                CumNumTxs,
+                NumAllTxs,
+                #[cfg(feature = "prevrandao")]
+                PrevRandao,
             ];
 
             // index_cells of same block are equal to block_number.
@@ -68,16 +68,17 @@ const MIN_PADDING_BYTES: usize = 9; // the additional bytes (a 0x80 byte)
                                     // and 8-byte length
 
 impl<F: Field> SHA256Circuit<F> {
+    /// Rows used to hash an input of `input_len` bytes.
+    pub fn min_num_rows_of_input(input_len: usize) -> usize {
+        (input_len + MIN_PADDING_BYTES + BLOCK_SIZE_IN_BYTES - 1) / BLOCK_SIZE_IN_BYTES
+            * TABLE16_BLOCK_ROWS
+    }
+
     fn expected_rows(&self) -> usize {
         self.0
             .iter()
-            .map(|evnt| {
-                (evnt.input.len() + MIN_PADDING_BYTES + BLOCK_SIZE_IN_BYTES - 1)
-                    / BLOCK_SIZE_IN_BYTES
-            })
-            .reduce(|acc, v| acc + v)
-            .unwrap_or_default()
-            * TABLE16_BLOCK_ROWS
+            .map(|evnt| Self::min_num_rows_of_input(evnt.input.len()))
+            .sum()
     }
 
     fn with_row_limit(self, row_limit: usize) -> Self {
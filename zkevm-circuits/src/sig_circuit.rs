@@ -9,6 +9,11 @@
 // - *_be: Big-Endian bytes
 // - *_le: Little-Endian bytes
 
+// The batched verification draws its coefficients from the keccak challenge, which is a
+// public constant with a single phase.
+#[cfg(all(feature = "batched-sig-verify", feature = "onephase"))]
+compile_error!("the batched ECDSA verification is unsound with the onephase feature");
+
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod dev;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
@@ -24,6 +29,7 @@ use crate::{
     table::{KeccakTable, SigTable},
     util::{Challenges, Expr, Field, SubCircuit, SubCircuitConfig},
 };
+use bus_mapping::circuit_input_builder::CircuitsParams;
 use eth_types::{
     self,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
@@ -42,8 +48,16 @@ use halo2_ecc::{
     },
 };
 
+#[cfg(feature = "batched-sig-verify")]
+mod batch;
 mod ecdsa;
 mod utils;
+#[cfg(feature = "batched-sig-verify")]
+use batch::{
+    batched_point_r, calc_required_advices_batched, calc_required_lookup_advices_batched,
+    ecdsa_verify_batch, ecdsa_verify_deferred,
+};
+pub use utils::MAX_NUM_SIG;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 pub(crate) use utils::*;
 
@@ -59,6 +73,23 @@ use itertools::Itertools;
 use log::error;
 use std::{iter, marker::PhantomData};
 
+/// How the ECDSA relations of the signatures are verified
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SigVerifyMode {
+    /// Each signature is verified with its own scalar multiplications
+    #[default]
+    Individual,
+    /// The verification equations of all signatures are combined with powers of
+    /// the keccak challenge and checked with multi-scalar multiplications and a
+    /// single fixed-base multiplication of the generator. The public key must be
+    /// the one recovered from the signature, otherwise no proof can be made.
+    ///
+    /// Experimental: its soundness is not proven when several signatures are
+    /// invalid, so it is only available with the `batched-sig-verify` feature.
+    #[cfg(feature = "batched-sig-verify")]
+    Batched,
+}
+
 /// Circuit configuration arguments
 pub struct SigCircuitConfigArgs<F: Field> {
     /// KeccakTable
@@ -67,6 +98,11 @@ pub struct SigCircuitConfigArgs<F: Field> {
     pub sig_table: SigTable,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
+    /// Max number of signature verifications, which determines the number of
+    /// advice columns of the ECDSA chip
+    pub max_verif: usize,
+    /// Verification mode of the ECDSA chip
+    pub mode: SigVerifyMode,
 }
 
 /// SignVerify Configuration
@@ -78,10 +114,17 @@ pub struct SigCircuitConfig<F: Field> {
     rlc_column: Column<Advice>,
     /// selector for keccak lookup table
     q_keccak: Selector,
+    /// selector binding the challenge of the batched verification, if enabled
+    #[cfg(feature = "batched-sig-verify")]
+    q_challenge: Option<Selector>,
     /// Used to lookup pk->pk_hash(addr)
     keccak_table: KeccakTable,
     /// The exposed table to be used by tx circuit and ecrecover
     sig_table: SigTable,
+    /// Max number of signature verifications the ECDSA chip is sized for
+    max_verif: usize,
+    /// Verification mode of the ECDSA chip
+    mode: SigVerifyMode,
 }
 
 impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
@@ -93,17 +136,29 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
         Self::ConfigArgs {
             keccak_table,
             sig_table,
-            challenges: _,
+            challenges: _challenges,
+            max_verif,
+            mode,
         }: Self::ConfigArgs,
     ) -> Self {
-        #[cfg(feature = "onephase")]
-        let num_advice = [calc_required_advices(MAX_NUM_SIG)];
-        #[cfg(not(feature = "onephase"))]
-        // need an additional phase 2 column/basic gate to hold the witnesses during RLC
-        // computations
-        let num_advice = [calc_required_advices(MAX_NUM_SIG), 1];
-
-        let num_lookup_advice = [calc_required_lookup_advices(MAX_NUM_SIG)];
+        let (num_advice, num_lookup_advice) = match mode {
+            SigVerifyMode::Individual => {
+                #[cfg(feature = "onephase")]
+                let num_advice = vec![calc_required_advices(max_verif)];
+                #[cfg(not(feature = "onephase"))]
+                // need an additional phase 2 column/basic gate to hold the witnesses during RLC
+                // computations
+                let num_advice = vec![calc_required_advices(max_verif), 1];
+
+                (num_advice, vec![calc_required_lookup_advices(max_verif)])
+            }
+            // the multi-scalar multiplications are done in phase 2, after the challenge
+            #[cfg(feature = "batched-sig-verify")]
+            SigVerifyMode::Batched => (
+                calc_required_advices_batched(max_verif).to_vec(),
+                calc_required_lookup_advices_batched(max_verif).to_vec(),
+            ),
+        };
 
         #[cfg(feature = "onephase")]
         log::info!("configuring ECDSA chip with single phase");
@@ -115,8 +170,8 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
         // get the following parameters by running
         // `cargo test --release --package zkevm-circuits --lib sig_circuit::test::sign_verify --
         // --nocapture`
-        // - num_advice: 56
-        // - num_lookup_advice: 8
+        // - num_advice: 57 for 128 signatures
+        // - num_lookup_advice: 8 for 128 signatures
         // - num_fixed: 1
         // - lookup_bits: 19
         // - limb_bits: 88
@@ -189,12 +244,29 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
             input.into_iter().zip(table).collect()
         });
 
+        // The batched verification takes its coefficients from the keccak challenge
+        // copied into the rlc column.
+        #[cfg(feature = "batched-sig-verify")]
+        let q_challenge = (mode == SigVerifyMode::Batched).then(|| {
+            let q_challenge = meta.selector();
+            meta.create_gate("batched ecdsa challenge", |meta| {
+                let q_challenge = meta.query_selector(q_challenge);
+                let challenge = meta.query_advice(rlc_column, Rotation::cur());
+                vec![q_challenge * (challenge - _challenges.keccak_input())]
+            });
+            q_challenge
+        });
+
         Self {
             ecdsa_config,
             rlc_column,
             q_keccak,
+            #[cfg(feature = "batched-sig-verify")]
+            q_challenge,
             keccak_table,
             sig_table,
+            max_verif,
+            mode,
         }
     }
 }
@@ -221,10 +293,11 @@ impl<F: Field> SubCircuit<F> for SigCircuit<F> {
     type Config = SigCircuitConfig<F>;
 
     fn new_from_block(block: &crate::witness::Block) -> Self {
-        assert!(block.circuits_params.max_txs <= MAX_NUM_SIG);
+        let max_verif = Self::max_verif(&block.circuits_params);
+        assert!(block.circuits_params.max_txs <= max_verif);
 
         SigCircuit {
-            max_verif: MAX_NUM_SIG,
+            max_verif,
             signatures: block.get_sign_data(true),
             _marker: Default::default(),
         }
@@ -270,10 +343,10 @@ impl<F: Field> SubCircuit<F> for SigCircuit<F> {
             .count()
             + block.precompile_events.get_ecrecover_events().len();
         // Reserve one ecdsa verification for padding tx such that the bad case in which some tx
-        // calls MAX_NUM_SIG - 1 ecrecover precompile won't happen. If that case happens, the sig
+        // calls max_verif - 1 ecrecover precompile won't happen. If that case happens, the sig
         // circuit won't have more space for the padding tx's ECDSA verification. Then the
         // prover won't be able to produce any valid proof.
-        let max_num_verif = MAX_NUM_SIG - 1;
+        let max_num_verif = Self::max_verif(&block.circuits_params) - 1;
 
         // Instead of showing actual minimum row usage,
         // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
@@ -294,6 +367,16 @@ impl<F: Field> SigCircuit<F> {
         }
     }
 
+    /// Return the number of signature verifications supported under the
+    /// given circuit parameters.
+    pub fn max_verif(params: &CircuitsParams) -> usize {
+        if params.max_sig_verifs == 0 {
+            MAX_NUM_SIG
+        } else {
+            params.max_sig_verifs
+        }
+    }
+
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
     pub fn min_num_rows() -> usize {
@@ -323,6 +406,9 @@ impl<F: Field> SigCircuit<F> {
     /// - message hashes
     /// - a boolean whether the signature is correct or not
     ///
+    /// In the batched mode, the verification equation is returned to be checked
+    /// later by `ecdsa_verify_batch`.
+    ///
     /// WARNING: this circuit does not enforce the returned value to be true
     /// make sure the caller checks this result!
     fn assign_ecdsa(
@@ -330,6 +416,7 @@ impl<F: Field> SigCircuit<F> {
         ctx: &mut Context<F>,
         ecdsa_chip: &FpChip<F>,
         sign_data: &SignData,
+        mode: SigVerifyMode,
     ) -> Result<AssignedECDSA<F, FpChip<F>>, Error> {
         let gate = ecdsa_chip.gate();
         let zero = gate.load_zero(ctx);
@@ -361,17 +448,38 @@ impl<F: Field> SigCircuit<F> {
         //
         // WARNING: this circuit does not enforce the returned value to be true
         // make sure the caller checks this result!
-        let (sig_is_valid, pk_is_zero, y_coord) =
-            ecdsa_verify_no_pubkey_check::<F, Fp, Fq, Secp256k1Affine>(
-                &ecc_chip.field_chip,
-                ctx,
-                &pk_assigned,
-                &integer_r,
-                &integer_s,
-                &msg_hash,
-                4,
-                4,
-            );
+        #[cfg(feature = "batched-sig-verify")]
+        let mut deferred = None;
+        let (sig_is_valid, pk_is_zero, y_coord) = match mode {
+            SigVerifyMode::Individual => {
+                ecdsa_verify_no_pubkey_check::<F, Fp, Fq, Secp256k1Affine>(
+                    &ecc_chip.field_chip,
+                    ctx,
+                    &pk_assigned,
+                    &integer_r,
+                    &integer_s,
+                    &msg_hash,
+                    4,
+                    4,
+                )
+            }
+            #[cfg(feature = "batched-sig-verify")]
+            SigVerifyMode::Batched => {
+                let point_r = batched_point_r(sign_data);
+                let (sig_is_valid, pk_is_zero, y_coord, deferred_ecdsa) =
+                    ecdsa_verify_deferred::<F, Fp, Fq, Secp256k1Affine>(
+                        &ecc_chip.field_chip,
+                        ctx,
+                        &pk_assigned,
+                        &integer_r,
+                        &integer_s,
+                        &msg_hash,
+                        (Value::known(point_r.x), Value::known(point_r.y)),
+                    );
+                deferred = Some(deferred_ecdsa);
+                (sig_is_valid, pk_is_zero, y_coord)
+            }
+        };
 
         // =======================================
         // constrains v == y.is_oddness()
@@ -443,9 +551,35 @@ impl<F: Field> SigCircuit<F> {
             integer_s,
             v: assigned_y_is_odd,
             sig_is_valid,
+            #[cfg(feature = "batched-sig-verify")]
+            deferred,
         })
     }
 
+    #[cfg(feature = "batched-sig-verify")]
+    fn enable_challenge_check(
+        &self,
+        config: &SigCircuitConfig<F>,
+        ctx: &mut Context<F>,
+        offset: usize,
+        challenge: &AssignedValue<F>,
+    ) -> Result<(), Error> {
+        let q_challenge = config
+            .q_challenge
+            .expect("challenge selector of the batched mode");
+        q_challenge.enable(&mut ctx.region, offset)?;
+        let tmp_cell = ctx.region.assign_advice(
+            || "batched ecdsa challenge",
+            config.rlc_column,
+            offset,
+            || challenge.value,
+        )?;
+        ctx.region
+            .constrain_equal(challenge.cell, tmp_cell.cell())?;
+
+        Ok(())
+    }
+
     fn enable_keccak_lookup(
         &self,
         config: &SigCircuitConfig<F>,
@@ -755,6 +889,13 @@ impl<F: Field> SigCircuit<F> {
             );
             return Err(Error::Synthesis);
         }
+        if self.max_verif > config.max_verif {
+            error!(
+                "max_verif = {} > configured max_verif = {}",
+                self.max_verif, config.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
         let mut first_pass = SKIP_FIRST_PASS;
         let ecdsa_chip = &config.ecdsa_config;
 
@@ -775,7 +916,9 @@ impl<F: Field> SigCircuit<F> {
                     .iter()
                     .chain(std::iter::repeat(&SignData::default()))
                     .take(self.max_verif)
-                    .map(|sign_data| self.assign_ecdsa(&mut ctx, ecdsa_chip, sign_data))
+                    .map(|sign_data| {
+                        self.assign_ecdsa(&mut ctx, ecdsa_chip, sign_data, config.mode)
+                    })
                     .collect::<Result<Vec<AssignedECDSA<F, FpChip<F>>>, Error>>()?;
 
                 // ================================================
@@ -807,6 +950,29 @@ impl<F: Field> SigCircuit<F> {
                     ctx.next_phase();
                 }
 
+                // in the batched mode, check the deferred equations with the challenge
+                #[cfg(feature = "batched-sig-verify")]
+                let batch_challenge = if config.mode == SigVerifyMode::Batched {
+                    let challenge = ecdsa_chip
+                        .range
+                        .gate
+                        .load_witness(&mut ctx, challenges.keccak_input());
+                    ecdsa_verify_batch::<F, Fp, Fq, Secp256k1Affine>(
+                        ecdsa_chip,
+                        &mut ctx,
+                        &assigned_ecdsas
+                            .iter()
+                            .filter_map(|assigned_ecdsa| assigned_ecdsa.deferred.as_ref())
+                            .collect_vec(),
+                        challenge,
+                        4,
+                        4,
+                    );
+                    Some(challenge)
+                } else {
+                    None
+                };
+
                 // ================================================
                 // step 3: compute RLC of keys and messages
                 // ================================================
@@ -852,6 +1018,15 @@ impl<F: Field> SigCircuit<F> {
                         pk_hash_rlc,
                     )?;
                 }
+                #[cfg(feature = "batched-sig-verify")]
+                if let Some(challenge) = batch_challenge {
+                    self.enable_challenge_check(
+                        config,
+                        &mut ctx,
+                        assigned_keccak_values.len() * 3,
+                        &challenge,
+                    )?;
+                }
 
                 // IMPORTANT: this assigns all constants to the fixed columns
                 // IMPORTANT: this copies cells to the lookup advice column to perform range
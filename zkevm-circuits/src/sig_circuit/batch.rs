@@ -0,0 +1,410 @@
+//! Experimental batched verification of the ECDSA signatures, see
+//! [`SigVerifyMode::Batched`](super::SigVerifyMode::Batched).
+//!
+//! The verification equations of the signatures are combined with coefficients derived from
+//! the powers of a single challenge. Their soundness is only argued for a single invalid
+//! signature, so this module is built with the `batched-sig-verify` feature only and must not
+//! be used by a production circuit.
+
+use super::utils::{calc_required_columns, COLUMN_NUM_LIMIT, LOOKUP_COLUMN_NUM_LIMIT};
+use eth_types::sign_types::SignData;
+use halo2_base::{
+    gates::{GateInstructions, RangeInstructions},
+    utils::{biguint_to_fe, fe_to_biguint, modulus, CurveAffineExt},
+    AssignedValue, Context,
+    QuantumCell::{Constant, Existing},
+};
+use halo2_ecc::{
+    bigint::{big_less_than, CRTInteger},
+    ecc::{fixed_base, EcPoint, EccChip},
+    fields::{fp::FpConfig, FieldChip, PrimeField, Selectable},
+};
+use halo2_proofs::{
+    circuit::Value,
+    halo2curves::{
+        group::{
+            ff::{Field as _, PrimeField as _},
+            prime::PrimeCurveAffine,
+            Curve,
+        },
+        secp256k1::{Fq, Secp256k1Affine},
+    },
+};
+use num_bigint::BigUint;
+
+// Upper estimates of the cells per signature in the batched mode, for phase 1 (the
+// checks on r, s and the public key) and phase 2 (its share of the multi-scalar
+// multiplications and the RLCs). Unlike the individual mode ones they are not exact
+// counts: the `sign_verify_batched_full_capacity` test checks that the columns they
+// size fit MAX_NUM_SIG signatures, and prints the actual usage in the stats of the
+// ECDSA context.
+const BATCHED_CELLS_PER_SIG: [usize; 2] = [90_000, 170_000];
+const BATCHED_LOOKUP_CELLS_PER_SIG: [usize; 2] = [15_000, 35_000];
+// Upper estimates of the phase 2 cells shared by all signatures in the batched mode:
+// the doublings of the multi-scalar multiplications and the fixed-base multiplication
+// of the generator, checked by the `sign_verify_batched` test which sizes the columns
+// for a few signatures only
+const BATCHED_CELLS_SHARED: usize = 900_000;
+const BATCHED_LOOKUP_CELLS_SHARED: usize = 120_000;
+
+/// Advice columns of each phase in the batched mode
+pub(super) fn calc_required_advices_batched(num_verif: usize) -> [usize; 2] {
+    [
+        calc_required_columns(
+            num_verif * BATCHED_CELLS_PER_SIG[0],
+            COLUMN_NUM_LIMIT,
+            "phase 1 advice",
+            num_verif,
+        ),
+        calc_required_columns(
+            num_verif * BATCHED_CELLS_PER_SIG[1] + BATCHED_CELLS_SHARED,
+            COLUMN_NUM_LIMIT,
+            "phase 2 advice",
+            num_verif,
+        ),
+    ]
+}
+
+/// Lookup advice columns of each phase in the batched mode
+pub(super) fn calc_required_lookup_advices_batched(num_verif: usize) -> [usize; 2] {
+    [
+        calc_required_columns(
+            num_verif * BATCHED_LOOKUP_CELLS_PER_SIG[0],
+            LOOKUP_COLUMN_NUM_LIMIT,
+            "phase 1 lookup advice",
+            num_verif,
+        ),
+        calc_required_columns(
+            num_verif * BATCHED_LOOKUP_CELLS_PER_SIG[1] + BATCHED_LOOKUP_CELLS_SHARED,
+            LOOKUP_COLUMN_NUM_LIMIT,
+            "phase 2 lookup advice",
+            num_verif,
+        ),
+    ]
+}
+
+/// The point R == u1.G + u2.pk expected by the batched verification of a
+/// signature. When there is no such point with x == r, the generator or its
+/// negation is used instead, whichever has the parity of v.
+pub(super) fn batched_point_r(sign_data: &SignData) -> Secp256k1Affine {
+    let (r, s, v) = sign_data.signature;
+    let generator = Secp256k1Affine::generator();
+    let dummy = if bool::from(generator.y.is_odd()) == (v == 1) {
+        generator
+    } else {
+        -generator
+    };
+
+    let Some(s_inv) = Option::<Fq>::from(s.invert()) else {
+        return dummy;
+    };
+    let u1 = sign_data.msg_hash * s_inv;
+    let u2 = r * s_inv;
+    let point = (generator * u1 + sign_data.pk * u2).to_affine();
+    if !bool::from(point.is_identity()) && point.x.to_bytes() == r.to_bytes() {
+        point
+    } else {
+        dummy
+    }
+}
+
+/// A signature whose verification equation `R == u1.G + u2.Pk` is deferred to
+/// [`ecdsa_verify_batch`].
+pub(crate) struct DeferredEcdsa<F: PrimeField> {
+    /// The public key, replaced by a random point when it is (0, 0)
+    pubkey: EcPoint<F, CRTInteger<F>>,
+    /// The point R whose x coordinate is r
+    point_r: EcPoint<F, CRTInteger<F>>,
+    u1: CRTInteger<F>,
+    u2: CRTInteger<F>,
+    /// Whether the signature takes part in the batched equation
+    is_valid: AssignedValue<F>,
+}
+
+// Same checks as [`ecdsa_verify_no_pubkey_check`], except that the point R of the
+// signature is given as a witness and the verification equation is deferred to
+// [`ecdsa_verify_batch`]. A signature is valid if r and s are in [1, n - 1], u1 and
+// u2 are reduced and the pubkey is not (0, 0); the equation is then enforced for
+// it, so that the prover cannot mark it invalid with a wrong R.
+//
+// returns
+// - if the signature is valid
+// - if the pubkey is (0, 0)
+// - the y coordinate of R (will be used for ECRecovery later)
+// - the deferred equation
+#[allow(clippy::type_complexity)]
+pub(crate) fn ecdsa_verify_deferred<F: PrimeField, CF: PrimeField, SF: PrimeField, GA>(
+    base_chip: &FpConfig<F, CF>,
+    ctx: &mut Context<F>,
+    pubkey: &EcPoint<F, <FpConfig<F, CF> as FieldChip<F>>::FieldPoint>,
+    r: &CRTInteger<F>,
+    s: &CRTInteger<F>,
+    msghash: &CRTInteger<F>,
+    point_r: (Value<CF>, Value<CF>),
+) -> (
+    AssignedValue<F>,
+    AssignedValue<F>,
+    CRTInteger<F>,
+    DeferredEcdsa<F>,
+)
+where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    let ecc_chip = EccChip::<F, FpConfig<F, CF>>::construct(base_chip.clone());
+    let scalar_chip = FpConfig::<F, SF>::construct(
+        base_chip.range.clone(),
+        base_chip.limb_bits,
+        base_chip.num_limbs,
+        modulus::<SF>(),
+    );
+    let gate = base_chip.range().gate();
+    let n = scalar_chip.load_constant(ctx, scalar_chip.p.to_biguint().unwrap());
+
+    let is_pubkey_zero = {
+        let is_pubkey_x_zero = ecc_chip.field_chip().is_zero(ctx, &pubkey.x);
+        let is_pubkey_y_zero = ecc_chip.field_chip().is_zero(ctx, &pubkey.y);
+        gate.and(ctx, Existing(is_pubkey_x_zero), Existing(is_pubkey_y_zero))
+    };
+    let is_pubkey_not_zero = gate.not(ctx, Existing(is_pubkey_zero));
+
+    // check r,s are in [1, n - 1]
+    let r_in_range = scalar_chip.is_soft_nonzero(ctx, r);
+    let s_is_zero = scalar_chip.is_soft_zero(ctx, s);
+    let s_in_range = scalar_chip.is_soft_nonzero(ctx, s);
+
+    // compute u1 = m * s^{-1} mod n and u2 = r * s^{-1} mod n
+    let zero = scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ZERO));
+    let one = scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ONE));
+    let s_prime = scalar_chip.select(ctx, &one, s, &s_is_zero);
+    let u1 = scalar_chip.divide(ctx, msghash, &s_prime);
+    let u1 = scalar_chip.select(ctx, &zero, &u1, &s_is_zero);
+    let u2 = scalar_chip.divide(ctx, r, &s_prime);
+    let u2 = scalar_chip.select(ctx, &zero, &u2, &s_is_zero);
+
+    let u1_small = big_less_than::assign::<F>(
+        base_chip.range(),
+        ctx,
+        &u1.truncation,
+        &n.truncation,
+        base_chip.limb_bits,
+        base_chip.limb_bases[1],
+    );
+    let u2_small = big_less_than::assign::<F>(
+        base_chip.range(),
+        ctx,
+        &u2.truncation,
+        &n.truncation,
+        base_chip.limb_bits,
+        base_chip.limb_bases[1],
+    );
+
+    let is_valid = gate.and_many(
+        ctx,
+        vec![
+            Existing(r_in_range),
+            Existing(s_in_range),
+            Existing(u1_small),
+            Existing(u2_small),
+            Existing(is_pubkey_not_zero),
+        ],
+    );
+
+    // R must be a curve point with a canonical y (its parity is checked against v),
+    // and its x coordinate must be r whenever the signature is valid
+    let point_r = ecc_chip.load_private(ctx, point_r);
+    let is_point_r_on_curve = ecc_chip.is_on_curve_or_infinity::<GA>(ctx, &point_r);
+    gate.assert_is_const(ctx, &is_point_r_on_curve, F::ONE);
+    base_chip.enforce_less_than(ctx, point_r.y());
+    let is_x_equal_r = base_chip.is_equal(ctx, point_r.x(), r);
+    let is_x_checked = gate.select(
+        ctx,
+        Existing(is_x_equal_r),
+        Constant(F::ONE),
+        Existing(is_valid),
+    );
+    gate.assert_is_const(ctx, &is_x_checked, F::ONE);
+
+    let pubkey_prime = ecc_chip.load_random_point::<GA>(ctx);
+    let pubkey_prime = ecc_chip.select(ctx, &pubkey_prime, pubkey, &is_pubkey_zero);
+
+    let y = point_r.y().clone();
+    (
+        is_valid,
+        is_pubkey_zero,
+        y,
+        DeferredEcdsa {
+            pubkey: pubkey_prime,
+            point_r,
+            u1,
+            u2,
+            is_valid,
+        },
+    )
+}
+
+// Verifies the deferred equations `R_i == u1_i.G + u2_i.Pk_i` of the valid signatures
+// at once. With c_i the low 128 bits of challenge^(i+1) (zero for invalid signatures),
+// it checks
+//
+//   sum(c_i * u2_i . Pk_i) + A + (1 + sum(c_i * u1_i)) . G == sum(c_i . R_i) + A + G
+//
+// where A is a random point which keeps both sides away from the point at infinity.
+// Both sums of points are multi-scalar multiplications sharing their doublings, and
+// G is multiplied once with its fixed-base tables.
+//
+// The challenge must be constrained by the caller to be a verifier challenge drawn
+// after all the deferred witnesses.
+//
+// Soundness: let D_i = R_i - u1_i.G - u2_i.Pk_i, the check is sum(c_i . D_i) == O as A
+// cancels out of both sides. Since the group of secp256k1 has prime order
+// n > 2^128, D_i == d_i.G and the check is sum(c_i * d_i) == 0 mod n, where every
+// d_i is fixed before the challenge is drawn.
+// - If a single D_j is not O, the check passes iff c_j == 0, i.e. iff the low 128 bits of
+//   challenge^(j+1) are zero, with probability about 2^-128 over the challenge.
+// - With several D_i not O, the bound assumes that the low 128 bits of the powers of the challenge
+//   behave as independent uniform values: fixing all of them but c_j, at most one value of c_j <
+//   2^128 < n satisfies the check, with probability 2^-128. The powers of one challenge are not
+//   independent, and the truncation rules out a Schwartz-Zippel bound, so this case is a heuristic
+//   rather than a proof.
+// Hence this check is experimental: it needs independent per-signature challenges, or
+// full-width coefficients with a proven bound, before it can be used by a production circuit.
+// The coefficients are only 128 bits long to halve the doublings of the sum of c_i.R_i.
+pub(crate) fn ecdsa_verify_batch<F: PrimeField, CF: PrimeField, SF: PrimeField, GA>(
+    base_chip: &FpConfig<F, CF>,
+    ctx: &mut Context<F>,
+    deferred: &[&DeferredEcdsa<F>],
+    challenge: AssignedValue<F>,
+    var_window_bits: usize,
+    fixed_window_bits: usize,
+) where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    let ecc_chip = EccChip::<F, FpConfig<F, CF>>::construct(base_chip.clone());
+    let scalar_chip = FpConfig::<F, SF>::construct(
+        base_chip.range.clone(),
+        base_chip.limb_bits,
+        base_chip.num_limbs,
+        modulus::<SF>(),
+    );
+    let range = base_chip.range();
+    let gate = range.gate();
+
+    let zero = gate.load_zero(ctx);
+    let one = gate.load_constant(ctx, F::ONE);
+
+    // challenge^(i+1) == lo + 2^128 * hi with lo == l0 + 2^88 * l1 is decomposed
+    // canonically, i.e. (hi, lo) is less than the modulus of F
+    let modulus_f = modulus::<F>();
+    let modulus_hi = &modulus_f >> 128;
+    let modulus_lo = &modulus_f - (&modulus_hi << 128);
+    let (modulus_hi, modulus_lo) = (
+        biguint_to_fe::<F>(&modulus_hi),
+        biguint_to_fe::<F>(&modulus_lo),
+    );
+    let split = |value: Value<&F>, shift: usize, bits: usize| {
+        value.map(|v| {
+            let mask = (BigUint::from(1u64) << bits) - 1u64;
+            biguint_to_fe::<F>(&((fe_to_biguint(v) >> shift) & mask))
+        })
+    };
+
+    let mut u1_sum = scalar_chip.load_constant(ctx, FpConfig::<F, SF>::fe_to_constant(SF::ONE));
+    let mut pk_points = Vec::with_capacity(deferred.len() + 1);
+    let mut pk_scalars = Vec::with_capacity(deferred.len() + 1);
+    let mut r_points = Vec::with_capacity(deferred.len() + 2);
+    let mut r_scalars = Vec::with_capacity(deferred.len() + 2);
+    let mut power = challenge;
+    for sig in deferred {
+        let l0 = gate.load_witness(ctx, split(power.value(), 0, 88));
+        let l1 = gate.load_witness(ctx, split(power.value(), 88, 40));
+        let hi = gate.load_witness(ctx, split(power.value(), 128, 126));
+        range.range_check(ctx, &l0, 88);
+        range.range_check(ctx, &l1, 40);
+        range.range_check(ctx, &hi, 126);
+        let lo = gate.mul_add(
+            ctx,
+            Existing(l1),
+            Constant(biguint_to_fe(&(BigUint::from(1u64) << 88))),
+            Existing(l0),
+        );
+        let recomposed = gate.mul_add(
+            ctx,
+            Existing(hi),
+            Constant(biguint_to_fe(&(BigUint::from(1u64) << 128))),
+            Existing(lo),
+        );
+        gate.assert_equal(ctx, Existing(recomposed), Existing(power));
+        let hi_lt = range.is_less_than(ctx, Existing(hi), Constant(modulus_hi), 126);
+        let hi_eq = gate.is_equal(ctx, Existing(hi), Constant(modulus_hi));
+        let lo_lt = range.is_less_than(ctx, Existing(lo), Constant(modulus_lo), 128);
+        let is_canonical = gate.or_and(ctx, Existing(hi_lt), Existing(hi_eq), Existing(lo_lt));
+        gate.assert_is_const(ctx, &is_canonical, F::ONE);
+
+        // the coefficient of an invalid signature is zero
+        let l0 = gate.mul(ctx, Existing(l0), Existing(sig.is_valid));
+        let l1 = gate.mul(ctx, Existing(l1), Existing(sig.is_valid));
+        let lo = gate.mul(ctx, Existing(lo), Existing(sig.is_valid));
+        let coeff = scalar_chip.load_private(
+            ctx,
+            FpConfig::<F, SF>::fe_to_witness(
+                &lo.value().map(|v| biguint_to_fe::<SF>(&fe_to_biguint(v))),
+            ),
+        );
+        gate.assert_equal(ctx, Existing(coeff.truncation.limbs[0]), Existing(l0));
+        gate.assert_equal(ctx, Existing(coeff.truncation.limbs[1]), Existing(l1));
+        for limb in coeff.truncation.limbs.iter().skip(2) {
+            gate.assert_equal(ctx, Existing(*limb), Existing(zero));
+        }
+
+        let u1_coeff = scalar_chip.mul(ctx, &sig.u1, &coeff);
+        let u1_sum_no_carry = scalar_chip.add_no_carry(ctx, &u1_sum, &u1_coeff);
+        u1_sum = scalar_chip.carry_mod(ctx, &u1_sum_no_carry);
+        let u2_coeff = scalar_chip.mul(ctx, &sig.u2, &coeff);
+
+        pk_points.push(sig.pubkey.clone());
+        pk_scalars.push(u2_coeff.truncation.limbs.clone());
+        r_points.push(sig.point_r.clone());
+        r_scalars.push(vec![lo]);
+
+        power = gate.mul(ctx, Existing(power), Existing(challenge));
+    }
+
+    let anchor = ecc_chip.load_random_point::<GA>(ctx);
+    let generator = {
+        let (x, y) = GA::generator().into_coordinates();
+        EcPoint::construct(
+            base_chip.load_constant(ctx, fe_to_biguint(&x)),
+            base_chip.load_constant(ctx, fe_to_biguint(&y)),
+        )
+    };
+    pk_points.push(anchor.clone());
+    pk_scalars.push(
+        std::iter::once(one)
+            .chain(std::iter::repeat(zero))
+            .take(base_chip.num_limbs)
+            .collect(),
+    );
+    r_points.push(anchor);
+    r_scalars.push(vec![one]);
+    r_points.push(generator);
+    r_scalars.push(vec![one]);
+
+    let pk_sum = ecc_chip.variable_base_msm::<GA>(
+        ctx,
+        &pk_points,
+        &pk_scalars,
+        base_chip.limb_bits,
+        var_window_bits,
+    );
+    let r_sum = ecc_chip.variable_base_msm::<GA>(ctx, &r_points, &r_scalars, 128, var_window_bits);
+    let u1_mul = fixed_base::scalar_multiply::<F, _, _>(
+        base_chip,
+        ctx,
+        &GA::generator(),
+        &u1_sum.truncation.limbs,
+        base_chip.limb_bits,
+        fixed_window_bits,
+    );
+    let lhs = ecc_chip.add_unequal(ctx, &pk_sum, &u1_mul, true);
+    ecc_chip.assert_equal(ctx, &lhs, &r_sum);
+}
@@ -10,12 +10,20 @@ use halo2_proofs::{circuit::SimpleFloorPlanner, plonk::Circuit};
 /// SigCircuitTesterConfig
 #[derive(Clone, Debug)]
 pub struct SigCircuitTesterConfig<F: Field> {
-    sign_verify: SigCircuitConfig<F>,
-    challenges: crate::util::Challenges,
+    pub(super) sign_verify: SigCircuitConfig<F>,
+    pub(super) challenges: crate::util::Challenges,
 }
 
 impl<F: Field> SigCircuitTesterConfig<F> {
     pub(crate) fn new(meta: &mut ConstraintSystem<F>) -> Self {
+        Self::new_with_args(meta, MAX_NUM_SIG, SigVerifyMode::Individual)
+    }
+
+    pub(crate) fn new_with_args(
+        meta: &mut ConstraintSystem<F>,
+        max_verif: usize,
+        mode: SigVerifyMode,
+    ) -> Self {
         let keccak_table = KeccakTable::construct(meta);
         let sig_table = SigTable::construct(meta);
         let challenges = Challenges::construct(meta);
@@ -26,6 +34,8 @@ impl<F: Field> SigCircuitTesterConfig<F> {
                 keccak_table,
                 challenges: challenges_expr,
                 sig_table,
+                max_verif,
+                mode,
             },
         );
 
@@ -1,24 +1,38 @@
-use crate::{sig_circuit::SigCircuit, util::Field};
+use super::{dev::SigCircuitTesterConfig, SigVerifyMode};
+use crate::{sig_circuit::SigCircuit, util::Field, witness::keccak::keccak_inputs_sign_verify};
 use eth_types::sign_types::{sign, SignData};
 use halo2_proofs::{
     arithmetic::Field as HaloField,
+    circuit::{Layouter, SimpleFloorPlanner},
     dev::MockProver,
     halo2curves::{
         group::Curve,
         secp256k1::{self, Secp256k1Affine},
     },
+    plonk::{Circuit, ConstraintSystem, Error},
 };
 use rand::{Rng, RngCore};
-use std::marker::PhantomData;
+use std::{cell::RefCell, marker::PhantomData};
 
 #[test]
 fn test_edge_cases() {
     use super::utils::LOG_TOTAL_NUM_ROWS;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    let signatures = edge_case_signatures();
+    log::debug!("signatures=");
+    log::debug!("{:#?}", signatures);
+
+    run::<Fr>(LOG_TOTAL_NUM_ROWS as u32, 10, signatures);
+}
+
+// Signatures of the edge cases of ecrecover
+fn edge_case_signatures() -> Vec<SignData> {
     use eth_types::{
         sign_types::{biguint_to_32bytes_le, recover_pk2, SECP256K1_Q},
         word, ToBigEndian, ToLittleEndian, Word,
     };
-    use halo2_proofs::halo2curves::{bn256::Fr, group::ff::PrimeField, secp256k1::Fq};
+    use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1::Fq};
     use num::{BigUint, Integer};
     use rand::SeedableRng;
     use rand_xorshift::XorShiftRng;
@@ -126,7 +140,7 @@ fn test_edge_cases() {
             )
         },
     ];
-    let signatures = ecrecover_data
+    ecrecover_data
         .iter()
         .map(|&(msg_hash, r, s, v)| SignData {
             signature: to_sig((r, s, v)),
@@ -140,11 +154,7 @@ fn test_edge_cases() {
             },
             ..Default::default()
         })
-        .collect();
-    log::debug!("signatures=");
-    log::debug!("{:#?}", signatures);
-
-    run::<Fr>(LOG_TOTAL_NUM_ROWS as u32, 10, signatures);
+        .collect()
 }
 
 #[test]
@@ -229,6 +239,108 @@ fn sign_verify() {
     }
 }
 
+#[test]
+fn sig_capacity_beyond_default() {
+    use super::utils::{LOG_TOTAL_NUM_ROWS, MAX_NUM_SIG};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    let mut rng = XorShiftRng::seed_from_u64(3);
+
+    // more signatures than the default capacity are served by more advice columns of the ECDSA
+    // chip, within the same number of rows.
+    const MAX_VERIF: usize = MAX_NUM_SIG + 8;
+    let signatures = (0..MAX_VERIF)
+        .map(|_| {
+            let (sk, pk) = gen_key_pair(&mut rng);
+            let msg_hash = gen_msg_hash(&mut rng);
+            let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+            SignData {
+                signature: (r, s, v),
+                pk,
+                msg_hash,
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let k = LOG_TOTAL_NUM_ROWS as u32;
+    let results = run_mode::<Fr, false, MAX_VERIF>(k, MAX_VERIF, signatures);
+    assert!(results.iter().all(|(is_valid, _)| *is_valid == Fr::one()));
+}
+
+#[cfg(feature = "batched-sig-verify")]
+#[test]
+fn sign_verify_batched() {
+    use super::utils::{LOG_TOTAL_NUM_ROWS, MAX_NUM_SIG};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    let mut rng = XorShiftRng::seed_from_u64(2);
+
+    let mut signatures = edge_case_signatures();
+    for _ in 0..6 {
+        let (sk, pk) = gen_key_pair(&mut rng);
+        let msg_hash = gen_msg_hash(&mut rng);
+        let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+        signatures.push(SignData {
+            signature: (r, s, v),
+            pk,
+            msg_hash,
+            ..Default::default()
+        });
+    }
+    // the same public key in several signatures
+    let (sk, pk) = gen_key_pair(&mut rng);
+    for _ in 0..2 {
+        let msg_hash = gen_msg_hash(&mut rng);
+        let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+        signatures.push(SignData {
+            signature: (r, s, v),
+            pk,
+            msg_hash,
+            ..Default::default()
+        });
+    }
+
+    // the batched verification gives the same results as the individual one
+    let k = LOG_TOTAL_NUM_ROWS as u32;
+    let max_verif = signatures.len() + 2;
+    let individual = run_mode::<Fr, false, MAX_NUM_SIG>(k, max_verif, signatures.clone());
+    let batched = run_mode::<Fr, true, MAX_NUM_SIG>(k, max_verif, signatures);
+    assert_eq!(individual, batched);
+    assert!(batched.iter().any(|(is_valid, _)| *is_valid == Fr::zero()));
+}
+
+#[cfg(feature = "batched-sig-verify")]
+#[test]
+fn sign_verify_batched_full_capacity() {
+    use super::utils::{LOG_TOTAL_NUM_ROWS, MAX_NUM_SIG};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    let mut rng = XorShiftRng::seed_from_u64(4);
+
+    // the cells of the batched mode fit in the columns sized by their estimates
+    let signatures = (0..MAX_NUM_SIG)
+        .map(|_| {
+            let (sk, pk) = gen_key_pair(&mut rng);
+            let msg_hash = gen_msg_hash(&mut rng);
+            let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+            SignData {
+                signature: (r, s, v),
+                pk,
+                msg_hash,
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let k = LOG_TOTAL_NUM_ROWS as u32;
+    let results = run_mode::<Fr, true, MAX_NUM_SIG>(k, MAX_NUM_SIG, signatures);
+    assert!(results.iter().all(|(is_valid, _)| *is_valid == Fr::one()));
+}
+
 // Generate a test key pair
 fn gen_key_pair(rng: impl RngCore) -> (secp256k1::Fq, Secp256k1Affine) {
     // generate a valid signature
@@ -277,3 +389,92 @@ fn run<F: Field>(k: u32, max_verif: usize, signatures: Vec<SignData>) {
     };
     assert_eq!(prover.verify(), Ok(()));
 }
+
+/// Sig circuit in the individual or the batched verification mode, configured for
+/// `MAX_VERIF` signatures, which records the validity and the address of each signature
+#[derive(Default)]
+struct VerifyModeTester<F: Field, const BATCHED: bool, const MAX_VERIF: usize> {
+    circuit: SigCircuit<F>,
+    results: RefCell<Vec<(F, F)>>,
+}
+
+impl<F: Field, const BATCHED: bool, const MAX_VERIF: usize> Circuit<F>
+    for VerifyModeTester<F, BATCHED, MAX_VERIF>
+{
+    type Config = SigCircuitTesterConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        #[cfg(feature = "batched-sig-verify")]
+        let mode = if BATCHED {
+            SigVerifyMode::Batched
+        } else {
+            SigVerifyMode::Individual
+        };
+        #[cfg(not(feature = "batched-sig-verify"))]
+        let mode = {
+            assert!(
+                !BATCHED,
+                "the batched mode requires the batched-sig-verify feature"
+            );
+            SigVerifyMode::Individual
+        };
+        SigCircuitTesterConfig::new_with_args(meta, MAX_VERIF, mode)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = config.challenges.values(&layouter);
+        config.sign_verify.load_range(&mut layouter)?;
+        let assigned_sig_verifs = self.circuit.assign(
+            &config.sign_verify,
+            &mut layouter,
+            &self.circuit.signatures,
+            &challenges,
+        )?;
+        *self.results.borrow_mut() = assigned_sig_verifs
+            .iter()
+            .map(|sig_verif| {
+                let mut result = (F::zero(), F::zero());
+                sig_verif.sig_is_valid.value().map(|&v| result.0 = v);
+                sig_verif.address.value().map(|&v| result.1 = v);
+                result
+            })
+            .collect();
+        config.sign_verify.keccak_table.dev_load(
+            &mut layouter,
+            &keccak_inputs_sign_verify(&self.circuit.signatures),
+            &challenges,
+        )?;
+        Ok(())
+    }
+}
+
+fn run_mode<F: Field, const BATCHED: bool, const MAX_VERIF: usize>(
+    k: u32,
+    max_verif: usize,
+    signatures: Vec<SignData>,
+) -> Vec<(F, F)> {
+    let circuit = VerifyModeTester::<F, BATCHED, MAX_VERIF> {
+        circuit: SigCircuit {
+            max_verif,
+            signatures,
+            _marker: PhantomData,
+        },
+        results: Default::default(),
+    };
+
+    let prover = match MockProver::run(k, &circuit, vec![]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{e:#?}"),
+    };
+    assert_eq!(prover.verify(), Ok(()));
+    circuit.results.into_inner()
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "batched-sig-verify")]
+use super::batch::DeferredEcdsa;
 use crate::util::Field;
 use halo2_base::{AssignedValue, QuantumCell};
 use halo2_ecc::{
@@ -10,54 +12,59 @@ use halo2_proofs::{
     halo2curves::secp256k1::{Fp, Fq},
 };
 
-// Hard coded parameters.
-// FIXME: allow for a configurable param.
-pub(super) const MAX_NUM_SIG: usize = 128;
+/// Default number of signatures verified by the sig circuit, used when
+/// `CircuitsParams::max_sig_verifs` is 0.
+pub const MAX_NUM_SIG: usize = 128;
 // Each ecdsa signature requires 461174 cells
 pub(super) const CELLS_PER_SIG: usize = 461174;
 // Each ecdsa signature requires 63276 lookup cells
 pub(super) const LOOKUP_CELLS_PER_SIG: usize = 63276;
 // Total number of rows allocated for ecdsa chip
 pub(super) const LOG_TOTAL_NUM_ROWS: usize = 20;
-// Max number of columns allowed
-pub(super) const COLUMN_NUM_LIMIT: usize = 58;
+// Max number of columns allowed, enough for ~340 signatures
+pub(super) const COLUMN_NUM_LIMIT: usize = 150;
 // Max number of lookup columns allowed
-pub(super) const LOOKUP_COLUMN_NUM_LIMIT: usize = 9;
+pub(super) const LOOKUP_COLUMN_NUM_LIMIT: usize = 24;
 
-pub(super) fn calc_required_advices(num_verif: usize) -> usize {
+pub(super) fn calc_required_columns(
+    total_cells: usize,
+    column_num_limit: usize,
+    kind: &str,
+    num_verif: usize,
+) -> usize {
     let mut num_adv = 1;
-    let total_cells = num_verif * CELLS_PER_SIG;
     let row_num = 1 << LOG_TOTAL_NUM_ROWS;
-    while num_adv < COLUMN_NUM_LIMIT {
+    while num_adv < column_num_limit {
         if num_adv * row_num > total_cells {
             log::debug!(
-                "ecdsa chip uses {} advice columns for {} signatures",
+                "ecdsa chip uses {} {} columns for {} signatures",
                 num_adv,
+                kind,
                 num_verif
             );
             return num_adv;
         }
         num_adv += 1;
     }
-    panic!("the required advice columns exceeds {COLUMN_NUM_LIMIT} for {num_verif} signatures");
+    panic!("the required {kind} columns exceeds {column_num_limit} for {num_verif} signatures");
+}
+
+pub(super) fn calc_required_advices(num_verif: usize) -> usize {
+    calc_required_columns(
+        num_verif * CELLS_PER_SIG,
+        COLUMN_NUM_LIMIT,
+        "advice",
+        num_verif,
+    )
 }
 
 pub(super) fn calc_required_lookup_advices(num_verif: usize) -> usize {
-    let mut num_adv = 1;
-    let total_cells = num_verif * LOOKUP_CELLS_PER_SIG;
-    let row_num = 1 << LOG_TOTAL_NUM_ROWS;
-    while num_adv < LOOKUP_COLUMN_NUM_LIMIT {
-        if num_adv * row_num > total_cells {
-            log::debug!(
-                "ecdsa chip uses {} lookup advice columns for {} signatures",
-                num_adv,
-                num_verif
-            );
-            return num_adv;
-        }
-        num_adv += 1;
-    }
-    panic!("the required lookup advice columns exceeds {LOOKUP_COLUMN_NUM_LIMIT} for {num_verif} signatures");
+    calc_required_columns(
+        num_verif * LOOKUP_CELLS_PER_SIG,
+        LOOKUP_COLUMN_NUM_LIMIT,
+        "lookup advice",
+        num_verif,
+    )
 }
 
 /// Chip to handle overflow integers of ECDSA::Fq, the scalar field
@@ -73,6 +80,9 @@ pub(crate) struct AssignedECDSA<F: Field, FC: FieldChip<F>> {
     pub(super) integer_s: CRTInteger<F>,
     pub(super) v: AssignedValue<F>,
     pub(super) sig_is_valid: AssignedValue<F>,
+    /// The verification equation deferred to the batched check, if any
+    #[cfg(feature = "batched-sig-verify")]
+    pub(super) deferred: Option<DeferredEcdsa<F>>,
 }
 
 #[derive(Debug, Clone)]
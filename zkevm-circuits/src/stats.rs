@@ -1,6 +1,10 @@
 use std::cmp::Ordering;
 
-use crate::evm_circuit::step::ExecutionState;
+use crate::{
+    cost_model::{Cost, OpcodeCost},
+    evm_circuit::step::ExecutionState,
+    keccak_circuit::{keccak_packed_multi::get_num_rows_per_round, param::NUM_ROUNDS},
+};
 use bus_mapping::{
     circuit_input_builder::{self, CircuitsParams, ExecState},
     mock::BlockData,
@@ -114,6 +118,143 @@ struct Row {
     height_per_gas: f64,
 }
 
+/// Implemented states, i.e. the ones with a known step height.
+fn implemented_states() -> Vec<ExecutionState> {
+    ExecutionState::iter()
+        .filter(|state| state.get_step_height_option().is_some())
+        .collect()
+}
+
+/// Code of `MOCK_ACCOUNTS[2]`: some stack setup followed by `bytecode_prefix_op` and, if any, the
+/// analyzed opcode.  Returns the code and the pc of the opcode.
+fn main_code(bytecode_prefix_op: &Bytecode, opcode: Option<OpcodeId>) -> (Bytecode, usize) {
+    let mut code = bytecode! {
+        PUSH2(0x00)
+        EXTCODESIZE // Warm up 0x0 address
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH1(0x00)
+        PUSH2(0x00)
+        PUSH2(0x10)
+        PUSH2(0x20)
+        PUSH2(0x30)
+    };
+    code.append(bytecode_prefix_op);
+    if let Some(opcode) = opcode {
+        code.write_op(opcode);
+    }
+    let opcode_pc = code.code.len() - 1;
+    code.op_stop();
+    (code, opcode_pc)
+}
+
+/// Build the circuit input block of the TestContext described in
+/// `print_circuit_stats_by_states`, with `code` as the main code.
+fn build_block(code: Bytecode) -> (GethData, circuit_input_builder::Block) {
+    let smallcode = bytecode! {
+        PUSH4(0x1000) // size
+        PUSH2(0x00) // offset
+        RETURN
+    };
+    let proxy_code = bytecode! {
+        PUSH2(0x1000) // retLength
+        PUSH1(0x00) // retOffset
+        PUSH1(0x00) // argsLength
+        PUSH1(0x00) // argsOffset
+        PUSH1(0x00) // value
+        PUSH32(MOCK_ACCOUNTS[2].to_word())
+        PUSH32(800_000) // gas
+        CALL
+        STOP
+    };
+    let block: GethData = TestContext::<10, 1>::new(
+        None,
+        |accs| {
+            accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+            accs[1]
+                .address(MOCK_ACCOUNTS[1])
+                .balance(eth(10))
+                .code(proxy_code);
+            accs[2]
+                .address(MOCK_ACCOUNTS[2])
+                .balance(eth(10))
+                .code(code.clone());
+            accs[3].address(MOCK_ACCOUNTS[3]).code(smallcode);
+            accs[4].address(Address::zero()).balance(eth(10)).code(code);
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[0].address)
+                .to(accs[1].address)
+                .input(vec![1, 2, 3, 4, 5, 6, 7].into());
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+    .into();
+    let mut builder = BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        CircuitsParams {
+            max_rws: 16_000,
+            max_copy_rows: 8_000,
+            ..CircuitsParams::default()
+        },
+    )
+    .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    (block, builder.block)
+}
+
+/// Find the step that executed our opcode by filtering on second call (because
+/// we run it via proxy) and the PC where we wrote the opcode.
+fn opcode_step_index(block: &circuit_input_builder::Block, opcode: OpcodeId, pc: usize) -> usize {
+    let (step_index, step) = block.txs[0]
+        .steps()
+        .iter()
+        .enumerate()
+        .find(|(_, s)| s.call_index == 1 && s.pc.0 == pc)
+        .unwrap();
+    assert_eq!(ExecState::Op(opcode), step.exec_state);
+    step_index
+}
+
+/// Gas cost of the step at `step_index`.
+fn opcode_gas_cost(block: &GethData, opcode: OpcodeId, step_index: usize) -> u64 {
+    // Substract 1 to step_index to remove the `BeginTx` step, which doesn't appear
+    // in the geth trace.
+    let geth_step = &block.geth_traces[0].struct_logs[step_index - 1];
+    assert_eq!(opcode, geth_step.op);
+    geth_step.gas_cost.0
+}
+
+/// Rows used in the Copy circuit by all the copy events of the block.
+fn copy_rows(block: &circuit_input_builder::Block) -> usize {
+    block
+        .copy_events
+        .iter()
+        .map(|c| c.full_length() as usize * 2)
+        .sum()
+}
+
+/// Rows used in the Keccak circuit by the hashes of the opcodes in the block.
+fn keccak_rows(block: &circuit_input_builder::Block) -> usize {
+    let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
+    block
+        .sha3_inputs
+        .iter()
+        .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
+        .sum()
+}
+
 /// This function prints to stdout a table with all the implemented states
 /// and their responsible opcodes with the following stats:
 /// - height: number of rows in a circuit used by the execution state
@@ -137,115 +278,19 @@ pub(crate) fn print_circuit_stats_by_states(
     // input builder tx.
     fn_height: impl Fn(&circuit_input_builder::Block, ExecutionState, usize) -> usize,
 ) {
-    let mut implemented_states = Vec::new();
-    for state in ExecutionState::iter() {
-        let height = state.get_step_height_option();
-        if height.is_some() {
-            implemented_states.push(state);
-        }
-    }
-    let smallcode = bytecode! {
-        PUSH4(0x1000) // size
-        PUSH2(0x00) // offset
-        RETURN
-    };
-    let proxy_code = bytecode! {
-        PUSH2(0x1000) // retLength
-        PUSH1(0x00) // retOffset
-        PUSH1(0x00) // argsLength
-        PUSH1(0x00) // argsOffset
-        PUSH1(0x00) // value
-        PUSH32(MOCK_ACCOUNTS[2].to_word())
-        PUSH32(800_000) // gas
-        CALL
-        STOP
-    };
-
     let mut table = DisplayTable::new(["state", "opcode", "h", "g", "h/g"].map(|s| s.into()));
     let mut rows = vec![];
-    for state in implemented_states {
+    for state in implemented_states() {
         if !fn_filter(state) {
             continue;
         }
         for responsible_op in state.responsible_opcodes() {
             let opcode = responsible_op.opcode();
-            let mut code = bytecode! {
-                PUSH2(0x00)
-                EXTCODESIZE // Warm up 0x0 address
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH1(0x00)
-                PUSH2(0x00)
-                PUSH2(0x10)
-                PUSH2(0x20)
-                PUSH2(0x30)
-            };
-            let bytecode_prefix_op = fn_bytecode_prefix_op(opcode);
-            code.append(&bytecode_prefix_op);
-            code.write_op(opcode);
-            let opcode_pc = code.code.len() - 1;
-            // let opcode_step_index = (proxy_code.num_opcodes - 1 + code.num_opcodes) - 1;
-            code.op_stop();
-            let block: GethData = TestContext::<10, 1>::new(
-                None,
-                |accs| {
-                    accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
-                    accs[1]
-                        .address(MOCK_ACCOUNTS[1])
-                        .balance(eth(10))
-                        .code(proxy_code.clone());
-                    accs[2]
-                        .address(MOCK_ACCOUNTS[2])
-                        .balance(eth(10))
-                        .code(code.clone());
-                    accs[3].address(MOCK_ACCOUNTS[3]).code(smallcode.clone());
-                    accs[4].address(Address::zero()).balance(eth(10)).code(code);
-                },
-                |mut txs, accs| {
-                    txs[0]
-                        .from(accs[0].address)
-                        .to(accs[1].address)
-                        .input(vec![1, 2, 3, 4, 5, 6, 7].into());
-                },
-                |block, _tx| block.number(0xcafeu64),
-            )
-            .unwrap()
-            .into();
-            let mut builder = BlockData::new_from_geth_data_with_params(
-                block.clone(),
-                CircuitsParams {
-                    max_rws: 16_000,
-                    max_copy_rows: 8_000,
-                    ..CircuitsParams::default()
-                },
-            )
-            .new_circuit_input_builder();
-            builder
-                .handle_block(&block.eth_block, &block.geth_traces)
-                .unwrap();
-            // Find the step that executed our opcode by filtering on second call (because
-            // we run it via proxy) and the PC where we wrote the opcode.
-            let (step_index, step) = builder.block.txs[0]
-                .steps()
-                .iter()
-                .enumerate()
-                .find(|(_, s)| s.call_index == 1 && s.pc.0 == opcode_pc)
-                .unwrap();
-            assert_eq!(ExecState::Op(opcode), step.exec_state);
-            let height = fn_height(&builder.block, state, step_index);
-
-            // Substract 1 to step_index to remove the `BeginTx` step, which doesn't appear
-            // in the geth trace.
-            let geth_step = &block.geth_traces[0].struct_logs[step_index - 1];
-            assert_eq!(opcode, geth_step.op);
-            let gas_cost = geth_step.gas_cost.0;
+            let (code, opcode_pc) = main_code(&fn_bytecode_prefix_op(opcode), Some(opcode));
+            let (block, builder_block) = build_block(code);
+            let step_index = opcode_step_index(&builder_block, opcode, opcode_pc);
+            let height = fn_height(&builder_block, state, step_index);
+            let gas_cost = opcode_gas_cost(&block, opcode, step_index);
             rows.push(Row {
                 state,
                 opcode,
@@ -274,3 +319,43 @@ pub(crate) fn print_circuit_stats_by_states(
 
     table.print();
 }
+
+/// Measure the [`OpcodeCost`] of the responsible opcodes of all the implemented states selected
+/// by `fn_filter`, in the TestContext described in `print_circuit_stats_by_states`.
+///
+/// The EVM rows and rw count are those of the opcode step, while the copy and keccak rows are
+/// the difference with a run of the same code without the opcode.
+pub(crate) fn collect_opcode_costs(
+    fn_filter: impl Fn(ExecutionState) -> bool,
+    fn_bytecode_prefix_op: impl Fn(OpcodeId) -> Bytecode,
+) -> Vec<OpcodeCost> {
+    let mut costs = vec![];
+    for state in implemented_states() {
+        if !fn_filter(state) {
+            continue;
+        }
+        for responsible_op in state.responsible_opcodes() {
+            let opcode = responsible_op.opcode();
+            let bytecode_prefix_op = fn_bytecode_prefix_op(opcode);
+            let (code, opcode_pc) = main_code(&bytecode_prefix_op, Some(opcode));
+            let (block, builder_block) = build_block(code);
+            let (_, base_block) = build_block(main_code(&bytecode_prefix_op, None).0);
+
+            let step_index = opcode_step_index(&builder_block, opcode, opcode_pc);
+            let steps = builder_block.txs[0].steps();
+            costs.push(OpcodeCost {
+                state,
+                opcode,
+                gas_cost: opcode_gas_cost(&block, opcode, step_index),
+                cost: Cost {
+                    evm_rows: state.get_step_height(),
+                    rw_count: steps[step_index + 1].rwc.0 - steps[step_index].rwc.0,
+                    copy_rows: copy_rows(&builder_block).saturating_sub(copy_rows(&base_block)),
+                    keccak_rows: keccak_rows(&builder_block)
+                        .saturating_sub(keccak_rows(&base_block)),
+                },
+            });
+        }
+    }
+    costs
+}
@@ -70,7 +70,7 @@ use crate::{
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitArgs, ExpCircuitConfig},
     keccak_circuit::{
-        keccak_packed_multi::get_num_rows_per_round, KeccakCircuit, KeccakCircuitConfig,
+        keccak_packed_multi::keccak_unusable_rows, KeccakCircuit, KeccakCircuitConfig,
         KeccakCircuitConfigArgs,
     },
     modexp_circuit::{ModExpCircuit, ModExpCircuitConfig},
@@ -81,7 +81,7 @@ use crate::{
         CircuitConfig as SHA256CircuitConfig, CircuitConfigArgs as SHA256CircuitConfigArgs,
         SHA256Circuit,
     },
-    sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
+    sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs, SigVerifyMode},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
         BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable, ModExpTable,
@@ -97,7 +97,9 @@ use crate::{
 use crate::mpt_circuit::{MptCircuit, MptCircuitConfig, MptCircuitConfigArgs};
 
 use crate::util::Field;
-use bus_mapping::circuit_input_builder::{CircuitInputBuilder, CircuitsParams};
+use bus_mapping::circuit_input_builder::{
+    CircuitInputBuilder, CircuitsParams, SubCircuitSelection,
+};
 use eth_types::geth_types::GethData;
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
@@ -105,7 +107,12 @@ use halo2_proofs::{
     plonk::{Circuit, ConstraintSystem, Error},
 };
 use itertools::Itertools;
+use params::{DefaultSuperCircuitParams, SuperCircuitParams};
 use snark_verifier_sdk::CircuitExt;
+use std::marker::PhantomData;
+
+/// ECC circuit of the Super Circuit, BN254 `Fq12` is built with the non-residue `9 + u`.
+pub type SuperCircuitEcc<F> = EccCircuit<F, 9>;
 
 /// Configuration of the Super Circuit
 #[derive(Clone)]
@@ -121,9 +128,9 @@ pub struct SuperCircuitConfig<F: Field> {
     state_circuit: StateCircuitConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
     sig_circuit: SigCircuitConfig<F>,
-    modexp_circuit: ModExpCircuitConfig,
-    ecc_circuit: EccCircuitConfig<F>,
-    sha256_circuit: SHA256CircuitConfig,
+    modexp_circuit: Option<ModExpCircuitConfig>,
+    ecc_circuit: Option<EccCircuitConfig<F>>,
+    sha256_circuit: Option<SHA256CircuitConfig>,
     #[cfg(not(feature = "poseidon-codehash"))]
     bytecode_circuit: BytecodeCircuitConfig<F>,
     #[cfg(feature = "poseidon-codehash")]
@@ -147,6 +154,12 @@ pub struct SuperCircuitConfigArgs {
     pub max_calldata: usize,
     /// Max inner blocks
     pub max_inner_blocks: usize,
+    /// Max ECDSA verifications in the sig circuit
+    pub max_sig_verifs: usize,
+    /// Rows per keccak_f inner round in the keccak circuit
+    pub keccak_rows_per_round: usize,
+    /// Optional sub circuits to configure
+    pub sub_circuits: SubCircuitSelection,
     /// Mock randomness
     pub mock_randomness: u64,
     /// Challenges
@@ -163,6 +176,9 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             max_txs: _,
             max_calldata: _,
             max_inner_blocks: _,
+            max_sig_verifs,
+            keccak_rows_per_round,
+            sub_circuits,
             mock_randomness: _mock_randomness,
             challenges,
         }: Self::ConfigArgs,
@@ -196,13 +212,15 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         log_circuit_info(meta, "rlp table");
         let keccak_table = KeccakTable::construct(meta);
         log_circuit_info(meta, "keccak table");
-        let sha256_table = SHA256Table::construct(meta);
+        // the disabled sub circuits are left out along with their tables, so that the EVM circuit
+        // can't prove a call to the corresponding precompiles.
+        let sha256_table = sub_circuits.sha256.then(|| SHA256Table::construct(meta));
         log_circuit_info(meta, "sha256 table");
         let sig_table = SigTable::construct(meta);
         log_circuit_info(meta, "sig table");
-        let modexp_table = ModExpTable::construct(meta);
+        let modexp_table = sub_circuits.modexp.then(|| ModExpTable::construct(meta));
         log_circuit_info(meta, "modexp table");
-        let ecc_table = EccTable::construct(meta);
+        let ecc_table = sub_circuits.ecc.then(|| EccTable::construct(meta));
         log_circuit_info(meta, "ecc table");
         let pow_of_rand_table = PowOfRandTable::construct(meta, &challenges_expr);
         log_circuit_info(meta, "power of randomness table");
@@ -212,23 +230,25 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         let u16_table = U16Table::construct(meta);
         log_circuit_info(meta, "u16 table");
 
-        assert!(get_num_rows_per_round() == 12);
         let keccak_circuit = KeccakCircuitConfig::new(
             meta,
             KeccakCircuitConfigArgs {
                 keccak_table: keccak_table.clone(),
                 challenges: challenges_expr.clone(),
+                num_rows_per_round: keccak_rows_per_round,
             },
         );
         log_circuit_info(meta, "keccak circuit");
 
-        let sha256_circuit = SHA256CircuitConfig::new(
-            meta,
-            SHA256CircuitConfigArgs {
-                sha256_table: sha256_table.clone(),
-                challenges: challenges_expr.clone(),
-            },
-        );
+        let sha256_circuit = sha256_table.clone().map(|sha256_table| {
+            SHA256CircuitConfig::new(
+                meta,
+                SHA256CircuitConfigArgs {
+                    sha256_table,
+                    challenges: challenges_expr.clone(),
+                },
+            )
+        });
         log_circuit_info(meta, "sha256 circuit");
 
         let poseidon_circuit =
@@ -321,7 +341,8 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         #[cfg(feature = "zktrie")]
         log_circuit_info(meta, "zktrie circuit");
 
-        let modexp_circuit = ModExpCircuitConfig::new(meta, modexp_table);
+        let modexp_circuit =
+            modexp_table.map(|modexp_table| ModExpCircuitConfig::new(meta, modexp_table));
         log_circuit_info(meta, "modexp circuit");
         let state_circuit = StateCircuitConfig::new(
             meta,
@@ -371,17 +392,21 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 keccak_table,
                 sig_table,
                 challenges: challenges_expr.clone(),
+                max_verif: max_sig_verifs,
+                mode: SigVerifyMode::Individual,
             },
         );
         log_circuit_info(meta, "sig circuit");
 
-        let ecc_circuit = EccCircuitConfig::new(
-            meta,
-            EccCircuitConfigArgs {
-                ecc_table,
-                challenges: challenges_expr,
-            },
-        );
+        let ecc_circuit = ecc_table.map(|ecc_table| {
+            EccCircuitConfig::new(
+                meta,
+                EccCircuitConfigArgs {
+                    ecc_table,
+                    challenges: challenges_expr,
+                },
+            )
+        });
         log_circuit_info(meta, "ecc circuit");
 
         #[cfg(feature = "onephase")]
@@ -417,6 +442,24 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
     }
 }
 
+/// Assign an optional sub circuit, which must be enabled both in the witness and in the config.
+fn synthesize_optional<C: SubCircuit<Fr>>(
+    name: &str,
+    circuit: &Option<C>,
+    config: &Option<C::Config>,
+    challenges: &Challenges<Value<Fr>>,
+    layouter: &mut impl Layouter<Fr>,
+) -> Result<(), Error> {
+    match (circuit, config) {
+        (Some(circuit), Some(config)) => circuit.synthesize_sub(config, challenges, layouter),
+        (None, None) => Ok(()),
+        _ => {
+            log::error!("{name} circuit selection of the block differs from the super circuit");
+            Err(Error::Synthesis)
+        }
+    }
+}
+
 /// Row usage for each sub circuit
 #[derive(Clone, Default, Debug)]
 pub struct SubcircuitRowUsage {
@@ -437,6 +480,7 @@ pub struct SuperCircuit<
     const MAX_CALLDATA: usize,
     const MAX_INNER_BLOCKS: usize,
     const MOCK_RANDOMNESS: u64,
+    P: SuperCircuitParams = DefaultSuperCircuitParams,
 > {
     /// EVM Circuit
     pub evm_circuit: EvmCircuit<F>,
@@ -454,16 +498,16 @@ pub struct SuperCircuit<
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
-    /// SHA256 Circuit
-    pub sha256_circuit: SHA256Circuit<F>,
+    /// SHA256 Circuit, if enabled
+    pub sha256_circuit: Option<SHA256Circuit<F>>,
     /// Poseidon hash Circuit
     pub poseidon_circuit: PoseidonCircuit<F>,
     /// Sig Circuit
     pub sig_circuit: SigCircuit<F>,
-    /// Modexp Circuit
-    pub modexp_circuit: ModExpCircuit<F>,
-    /// Ecc Circuit
-    pub ecc_circuit: EccCircuit<F, 9>,
+    /// Modexp Circuit, if enabled
+    pub modexp_circuit: Option<ModExpCircuit<F>>,
+    /// Ecc Circuit, if enabled
+    pub ecc_circuit: Option<SuperCircuitEcc<F>>,
     /// Rlp Circuit
     pub rlp_circuit: RlpCircuit<F, Transaction>,
     /// Mpt Circuit
@@ -471,6 +515,7 @@ pub struct SuperCircuit<
     pub mpt_circuit: MptCircuit<F>,
 
     circuit_params: CircuitsParams,
+    _params: PhantomData<P>,
 }
 
 impl<
@@ -479,7 +524,8 @@ impl<
         const MAX_CALLDATA: usize,
         const MAX_INNER_BLOCKS: usize,
         const MOCK_RANDOMNESS: u64,
-    > SuperCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+        P: SuperCircuitParams,
+    > SuperCircuit<F, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS, P>
 {
     /// Return the number of rows required to verify a given block
     pub fn get_num_rows_required(block: &Block) -> usize {
@@ -491,8 +537,18 @@ impl<
         // num_rows_evm_circuit.max(num_rows_tx_circuit)
         num_rows_evm_circuit
     }
+    /// Check that the circuit parameters of the block agree with the layout of the circuit.
+    fn check_circuits_params(block: &Block) {
+        assert_eq!(
+            SigCircuit::<Fr>::max_verif(&block.circuits_params),
+            P::MAX_SIG_VERIFS,
+            "max sig verifications of the block differ from the super circuit config"
+        );
+    }
+
     /// Return the minimum number of rows required to prove the block
     pub fn min_num_rows_block_subcircuits(block: &Block) -> Vec<SubcircuitRowUsage> {
+        Self::check_circuits_params(block);
         let warning_limit = 1_000_000;
         log::debug!("start min_num_rows_block_subcircuits");
         let mut rows = Vec::new();
@@ -518,26 +574,39 @@ impl<
         push("bytecode", bytecode);
         let copy = CopyCircuit::<Fr>::min_num_rows_block(block);
         push("copy", copy);
-        let keccak = KeccakCircuit::<Fr>::min_num_rows_block(block);
+        // the keccak rows depend on the layout of the keccak circuit
+        let keccak = KeccakCircuit::<Fr>::min_num_rows_block_with_rows_per_round(
+            block,
+            P::KECCAK_ROWS_PER_ROUND,
+        );
         push("keccak", keccak);
-        let sha256 = SHA256Circuit::<Fr>::min_num_rows_block(block);
-        push("sha256", sha256);
+        // the rows of the sub circuits laid out in the circuit, in the order of the limits of
+        // `params::sub_circuit_limit_and_confidence`
+        let sub_circuits = P::SUB_CIRCUITS;
+        if sub_circuits.sha256 {
+            let sha256 = SHA256Circuit::<Fr>::min_num_rows_block(block);
+            push("sha256", sha256);
+        }
         let tx = TxCircuit::<Fr>::min_num_rows_block(block);
         push("tx", tx);
         let rlp = RlpCircuit::<Fr, _>::min_num_rows_block(block);
         push("rlp", rlp);
         let exp = ExpCircuit::<Fr>::min_num_rows_block(block);
         push("exp", exp);
-        let mod_exp = ModExpCircuit::<Fr>::min_num_rows_block(block);
-        push("mod_exp", mod_exp);
+        if sub_circuits.modexp {
+            let mod_exp = ModExpCircuit::<Fr>::min_num_rows_block(block);
+            push("mod_exp", mod_exp);
+        }
         let pi = PiCircuit::<Fr>::min_num_rows_block(block);
         push("pi", pi);
         let poseidon = PoseidonCircuit::<Fr>::min_num_rows_block(block);
         push("poseidon", poseidon);
         let sig = SigCircuit::<Fr>::min_num_rows_block(block);
         push("sig", sig);
-        let ecc = EccCircuit::<Fr, 9>::min_num_rows_block(block);
-        push("ecc", ecc);
+        if sub_circuits.ecc {
+            let ecc = SuperCircuitEcc::<Fr>::min_num_rows_block(block);
+            push("ecc", ecc);
+        }
         #[cfg(feature = "zktrie")]
         {
             let mpt = MptCircuit::<Fr>::min_num_rows_block(block);
@@ -572,8 +641,9 @@ impl<
         const MAX_CALLDATA: usize,
         const MAX_INNER_BLOCKS: usize,
         const MOCK_RANDOMNESS: u64,
+        P: SuperCircuitParams,
     > SubCircuit<Fr>
-    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS, P>
 {
     type Config = SuperCircuitConfig<Fr>;
 
@@ -588,12 +658,13 @@ impl<
             BytecodeCircuit::<Fr>::unusable_rows(),
             CopyCircuit::<Fr>::unusable_rows(),
             ExpCircuit::<Fr>::unusable_rows(),
-            KeccakCircuit::<Fr>::unusable_rows(),
+            keccak_unusable_rows(P::KECCAK_ROWS_PER_ROUND),
         ])
         .unwrap()
     }
 
     fn new_from_block(block: &Block) -> Self {
+        Self::check_circuits_params(block);
         let evm_circuit = EvmCircuit::new_from_block(block);
         let state_circuit = StateCircuit::new_from_block(block);
         let tx_circuit = TxCircuit::new_from_block(block);
@@ -601,16 +672,24 @@ impl<
         let bytecode_circuit = BytecodeCircuit::new_from_block(block);
         let copy_circuit = CopyCircuit::new_from_block_no_external(block);
         let exp_circuit = ExpCircuit::new_from_block(block);
-        let modexp_circuit = ModExpCircuit::new_from_block(block);
-        let keccak_circuit = KeccakCircuit::new_from_block(block);
-        let sha256_circuit = SHA256Circuit::new_from_block(block);
+        let sub_circuits = block.circuits_params.sub_circuits;
+        let modexp_circuit = sub_circuits
+            .modexp
+            .then(|| ModExpCircuit::new_from_block(block));
+        let keccak_circuit =
+            KeccakCircuit::new_from_block_with_rows_per_round(block, P::KECCAK_ROWS_PER_ROUND);
+        let sha256_circuit = sub_circuits
+            .sha256
+            .then(|| SHA256Circuit::new_from_block(block));
         let poseidon_circuit = PoseidonCircuit::new_from_block(block);
         let rlp_circuit = RlpCircuit::new_from_block(block);
         let sig_circuit = SigCircuit::new_from_block(block);
-        let ecc_circuit = EccCircuit::new_from_block(block);
+        let ecc_circuit = sub_circuits
+            .ecc
+            .then(|| SuperCircuitEcc::new_from_block(block));
         #[cfg(feature = "zktrie")]
         let mpt_circuit = MptCircuit::new_from_block(block);
-        SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS> {
+        Self {
             evm_circuit,
             state_circuit,
             tx_circuit,
@@ -628,6 +707,7 @@ impl<
             #[cfg(feature = "zktrie")]
             mpt_circuit,
             circuit_params: block.circuits_params,
+            _params: PhantomData,
         }
     }
 
@@ -683,8 +763,13 @@ impl<
         self.keccak_circuit
             .synthesize_sub(&config.keccak_circuit, challenges, layouter)?;
         log::debug!("assigning sha256_circuit");
-        self.sha256_circuit
-            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        synthesize_optional(
+            "sha256",
+            &self.sha256_circuit,
+            &config.sha256_circuit,
+            challenges,
+            layouter,
+        )?;
         log::debug!("assigning poseidon_circuit");
         self.poseidon_circuit
             .synthesize_sub(&config.poseidon_circuit, challenges, layouter)?;
@@ -698,11 +783,21 @@ impl<
         self.sig_circuit
             .synthesize_sub(&config.sig_circuit, challenges, layouter)?;
         log::debug!("assigning ecc_circuit");
-        self.ecc_circuit
-            .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
+        synthesize_optional(
+            "ecc",
+            &self.ecc_circuit,
+            &config.ecc_circuit,
+            challenges,
+            layouter,
+        )?;
         log::debug!("assigning modexp_circuit");
-        self.modexp_circuit
-            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        synthesize_optional(
+            "modexp",
+            &self.modexp_circuit,
+            &config.modexp_circuit,
+            challenges,
+            layouter,
+        )?;
         log::debug!("assigning state_circuit");
         self.state_circuit
             .synthesize_sub(&config.state_circuit, challenges, layouter)?;
@@ -746,7 +841,9 @@ impl<
         const MAX_CALLDATA: usize,
         const MAX_INNER_BLOCKS: usize,
         const MOCK_RANDOMNESS: u64,
-    > Circuit<Fr> for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+        P: SuperCircuitParams,
+    > Circuit<Fr>
+    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS, P>
 {
     type Config = (SuperCircuitConfig<Fr>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
@@ -770,6 +867,9 @@ impl<
                     max_txs: MAX_TXS,
                     max_calldata: MAX_CALLDATA,
                     max_inner_blocks: MAX_INNER_BLOCKS,
+                    max_sig_verifs: P::MAX_SIG_VERIFS,
+                    keccak_rows_per_round: P::KECCAK_ROWS_PER_ROUND,
+                    sub_circuits: P::SUB_CIRCUITS,
                     mock_randomness: MOCK_RANDOMNESS,
                     challenges,
                 },
@@ -797,8 +897,9 @@ impl<
         const MAX_CALLDATA: usize,
         const MAX_INNER_BLOCKS: usize,
         const MOCK_RANDOMNESS: u64,
+        P: SuperCircuitParams,
     > CircuitExt<Fr>
-    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+    for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS, P>
 {
     fn num_instance(&self) -> Vec<usize> {
         self.instances().iter().map(|l| l.len()).collect_vec()
@@ -814,7 +915,8 @@ impl<
         const MAX_CALLDATA: usize,
         const MAX_INNER_BLOCKS: usize,
         const MOCK_RANDOMNESS: u64,
-    > SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+        P: SuperCircuitParams,
+    > SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS, P>
 {
     /// From the witness data, generate a SuperCircuit instance with all of the
     /// sub-circuits filled with their corresponding witnesses.
@@ -866,8 +968,7 @@ impl<
         let k = log2_ceil(Self::unusable_rows() + rows_needed);
         log::debug!("super circuit needs k = {}", k);
 
-        let circuit =
-            SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA,MAX_INNER_BLOCKS, MOCK_RANDOMNESS>::new_from_block(&block);
+        let circuit = Self::new_from_block(&block);
 
         let instance = circuit.instance();
         Ok((k, circuit, instance))
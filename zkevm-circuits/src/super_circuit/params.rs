@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
-use crate::exp_circuit::param::OFFSET_INCREMENT;
-use bus_mapping::circuit_input_builder::{CircuitsParams, PrecompileEcParams};
+use crate::{exp_circuit::param::OFFSET_INCREMENT, sig_circuit::MAX_NUM_SIG};
+use bus_mapping::circuit_input_builder::{CircuitsParams, PrecompileEcParams, SubCircuitSelection};
 use halo2_proofs::halo2curves::bn256::Fr;
 
 use super::SuperCircuit;
@@ -21,6 +21,14 @@ pub const MAX_RWS: usize = 1_000_000;
 pub const MAX_PRECOMPILE_EC_ADD: usize = 50;
 pub const MAX_PRECOMPILE_EC_MUL: usize = 50;
 pub const MAX_PRECOMPILE_EC_PAIRING: usize = 2;
+pub const MAX_PRECOMPILE_EC_PAIRING_CHUNKS: usize = 2;
+pub const MAX_SIG_VERIFS: usize = MAX_NUM_SIG;
+/// Rows per keccak_f inner round, a denser layout (less rows) fits more keccak_f's in the
+/// keccak circuit at the cost of more advice columns.
+pub const KECCAK_ROWS_PER_ROUND: usize = 12;
+/// Optional sub circuits of the super circuit, e.g. app-chains disabling the SHA256, MODEXP or
+/// BN254 precompiles can leave their sub circuits out.
+pub const SUB_CIRCUITS: SubCircuitSelection = SubCircuitSelection::all();
 
 /// default params for super circuit
 pub fn get_super_circuit_params() -> CircuitsParams {
@@ -42,34 +50,71 @@ pub fn get_super_circuit_params() -> CircuitsParams {
             ec_add: MAX_PRECOMPILE_EC_ADD,
             ec_mul: MAX_PRECOMPILE_EC_MUL,
             ec_pairing: MAX_PRECOMPILE_EC_PAIRING,
+            ec_pairing_chunks: MAX_PRECOMPILE_EC_PAIRING_CHUNKS,
         },
+        max_sig_verifs: MAX_SIG_VERIFS,
+        sub_circuits: SUB_CIRCUITS,
     }
 }
 
+/// Parameters of the Super Circuit layout which are not const generics of [`SuperCircuit`].
+/// The [`CircuitsParams`] of the blocks proven by the circuit must agree with them.
+pub trait SuperCircuitParams: Clone + std::fmt::Debug {
+    /// Max number of ECDSA verifications of the sig circuit, which sets its number of advice
+    /// columns.
+    const MAX_SIG_VERIFS: usize;
+    /// Rows per keccak_f inner round of the keccak circuit.
+    const KECCAK_ROWS_PER_ROUND: usize;
+    /// Optional sub circuits laid out in the circuit, the sub circuits of the blocks must match.
+    const SUB_CIRCUITS: SubCircuitSelection;
+}
+
+/// The Super Circuit parameters of this module.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultSuperCircuitParams;
+
+impl SuperCircuitParams for DefaultSuperCircuitParams {
+    const MAX_SIG_VERIFS: usize = MAX_SIG_VERIFS;
+    const KECCAK_ROWS_PER_ROUND: usize = KECCAK_ROWS_PER_ROUND;
+    const SUB_CIRCUITS: SubCircuitSelection = SUB_CIRCUITS;
+}
+
 /// The super circuit used for mainnet
 pub type ScrollSuperCircuit = SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, 0x100>;
 
-/// Capacity for each subcircuit
+/// Capacity for each subcircuit, following the order of
+/// `SuperCircuit::min_num_rows_block_subcircuits` for the sub circuits enabled in `SUB_CIRCUITS`.
 pub fn get_sub_circuit_limit_and_confidence() -> Vec<(usize, f64)> {
+    sub_circuit_limit_and_confidence::<DefaultSuperCircuitParams>()
+}
+
+/// Capacity for each subcircuit, following the order of
+/// `SuperCircuit::min_num_rows_block_subcircuits` for the sub circuits enabled in
+/// `P::SUB_CIRCUITS`.
+pub fn sub_circuit_limit_and_confidence<P: SuperCircuitParams>() -> Vec<(usize, f64)> {
+    let sub_circuits = P::SUB_CIRCUITS;
     // Change it to 0.99?
     let default_confidence = 0.95;
     [
-        (MAX_RWS, default_confidence),                          // evm
-        (MAX_RWS, default_confidence),                          // state
-        (MAX_BYTECODE, default_confidence),                     // bytecode
-        (MAX_RWS, default_confidence),                          // copy
-        (MAX_KECCAK_ROWS, default_confidence),                  // keccak
-        (MAX_KECCAK_ROWS, default_confidence),                  // sha256
-        (MAX_VERTICAL_ROWS, default_confidence),                // tx
-        (MAX_CALLDATA, default_confidence),                     // rlp
-        (OFFSET_INCREMENT * MAX_EXP_STEPS, default_confidence), // exp
-        (MAX_KECCAK_ROWS, default_confidence),                  // modexp
-        (MAX_RWS, default_confidence),                          // pi
-        (MAX_POSEIDON_ROWS, default_confidence),                // poseidon
-        (MAX_VERTICAL_ROWS, default_confidence),                // sig
-        (MAX_VERTICAL_ROWS, 1.0),                               // ecc
+        (true, MAX_RWS, default_confidence),                        // evm
+        (true, MAX_RWS, default_confidence),                        // state
+        (true, MAX_BYTECODE, default_confidence),                   // bytecode
+        (true, MAX_RWS, default_confidence),                        // copy
+        (true, MAX_KECCAK_ROWS, default_confidence),                // keccak
+        (sub_circuits.sha256, MAX_KECCAK_ROWS, default_confidence), // sha256
+        (true, MAX_VERTICAL_ROWS, default_confidence),              // tx
+        (true, MAX_CALLDATA, default_confidence),                   // rlp
+        (true, OFFSET_INCREMENT * MAX_EXP_STEPS, default_confidence), // exp
+        (sub_circuits.modexp, MAX_KECCAK_ROWS, default_confidence), // modexp
+        (true, MAX_RWS, default_confidence),                        // pi
+        (true, MAX_POSEIDON_ROWS, default_confidence),              // poseidon
+        (true, MAX_VERTICAL_ROWS, default_confidence),              // sig
+        (sub_circuits.ecc, MAX_VERTICAL_ROWS, 1.0),                 // ecc
         #[cfg(feature = "scroll")]
-        (MAX_MPT_ROWS, default_confidence), // mpt
+        (true, MAX_MPT_ROWS, default_confidence), // mpt
     ]
-    .to_vec()
+    .into_iter()
+    .filter(|(enabled, _, _)| *enabled)
+    .map(|(_, limit, confidence)| (limit, confidence))
+    .collect()
 }
@@ -1,5 +1,6 @@
 #![allow(unused_imports)]
 pub use super::*;
+use crate::failure_report::FailureReport;
 use bus_mapping::{
     circuit_input_builder::CircuitInputBuilder,
     evm::{OpcodeId, PrecompileCallArgs},
@@ -45,6 +46,57 @@ fn super_circuit_created_from_dummy_block() {
     let _circuit = SuperCircuit::<Fr, 128, 2_000_000, 64, 0x1000>::new_from_block(&dummy_block);
 }
 
+/// Super Circuit parameters leaving out the sub circuits of the SHA256, MODEXP and BN254
+/// precompiles.
+#[derive(Clone, Copy, Debug, Default)]
+struct NoPrecompileParams;
+
+impl SuperCircuitParams for NoPrecompileParams {
+    const MAX_SIG_VERIFS: usize = DefaultSuperCircuitParams::MAX_SIG_VERIFS;
+    const KECCAK_ROWS_PER_ROUND: usize = DefaultSuperCircuitParams::KECCAK_ROWS_PER_ROUND;
+    const SUB_CIRCUITS: SubCircuitSelection = SubCircuitSelection::all()
+        .with_sha256(false)
+        .with_modexp(false)
+        .with_ecc(false);
+}
+
+#[test]
+fn super_circuit_without_precompile_sub_circuits() {
+    let mut cs = ConstraintSystem::<Fr>::default();
+    SuperCircuit::<Fr, 1, 32, 64, 0x100>::configure(&mut cs);
+    let mut cs_no_precompile = ConstraintSystem::<Fr>::default();
+    SuperCircuit::<Fr, 1, 32, 64, 0x100, NoPrecompileParams>::configure(&mut cs_no_precompile);
+    // the sub circuits are left out along with their tables
+    assert!(cs_no_precompile.num_advice_columns() < cs.num_advice_columns());
+    assert!(cs_no_precompile.num_fixed_columns() < cs.num_fixed_columns());
+
+    let dummy_block = Block {
+        circuits_params: CircuitsParams {
+            sub_circuits: NoPrecompileParams::SUB_CIRCUITS,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let circuit =
+        SuperCircuit::<Fr, 1, 32, 64, 0x100, NoPrecompileParams>::new_from_block(&dummy_block);
+    assert!(circuit.sha256_circuit.is_none());
+    assert!(circuit.modexp_circuit.is_none());
+    assert!(circuit.ecc_circuit.is_none());
+
+    // the row usage follows the limits of the enabled sub circuits
+    let row_usage =
+        SuperCircuit::<Fr, 1, 32, 64, 0x100, NoPrecompileParams>::min_num_rows_block_subcircuits(
+            &dummy_block,
+        );
+    assert_eq!(
+        row_usage.len(),
+        params::sub_circuit_limit_and_confidence::<NoPrecompileParams>().len()
+    );
+    assert!(row_usage
+        .iter()
+        .all(|usage| !["sha256", "mod_exp", "ecc"].contains(&usage.name.as_str())));
+}
+
 #[test]
 fn super_circuit_degree() {
     let mut cs = ConstraintSystem::<Fr>::default();
@@ -57,15 +109,7 @@ fn super_circuit_degree() {
 }
 
 #[cfg(feature = "scroll")]
-fn test_super_circuit<
-    const MAX_TXS: usize,
-    const MAX_CALLDATA: usize,
-    const MAX_INNER_BLOCKS: usize,
-    const MOCK_RANDOMNESS: u64,
->(
-    l2_trace: BlockTrace,
-    circuits_params: CircuitsParams,
-) {
+fn witness_block(l2_trace: BlockTrace, circuits_params: CircuitsParams) -> Block {
     set_var("COINBASE", "0x0000000000000000000000000000000000000000");
     set_var("CHAIN_ID", MOCK_CHAIN_ID.to_string());
     let mut difficulty_be_bytes = [0u8; 32];
@@ -82,27 +126,77 @@ fn test_super_circuit<
 
     let mut block = block_convert(&builder.block, &builder.code_db).unwrap();
     block.apply_mpt_updates(&builder.mpt_init_state.expect("used non-light mode"));
+    block
+}
 
-    let active_row_num =SuperCircuit::<
+#[cfg(feature = "scroll")]
+fn test_super_circuit<
+    const MAX_TXS: usize,
+    const MAX_CALLDATA: usize,
+    const MAX_INNER_BLOCKS: usize,
+    const MOCK_RANDOMNESS: u64,
+>(
+    l2_trace: BlockTrace,
+    circuits_params: CircuitsParams,
+) {
+    test_super_circuit_with_params::<
+        MAX_TXS,
+        MAX_CALLDATA,
+        MAX_INNER_BLOCKS,
+        MOCK_RANDOMNESS,
+        DefaultSuperCircuitParams,
+    >(l2_trace, circuits_params);
+}
+
+#[cfg(feature = "scroll")]
+fn test_super_circuit_with_params<
+    const MAX_TXS: usize,
+    const MAX_CALLDATA: usize,
+    const MAX_INNER_BLOCKS: usize,
+    const MOCK_RANDOMNESS: u64,
+    P: SuperCircuitParams,
+>(
+    l2_trace: BlockTrace,
+    circuits_params: CircuitsParams,
+) {
+    let block = witness_block(l2_trace, circuits_params);
+
+    let active_row_num = SuperCircuit::<
         Fr,
         MAX_TXS,
         MAX_CALLDATA,
         MAX_INNER_BLOCKS,
         MOCK_RANDOMNESS,
-    >::min_num_rows_block(&block).0;
+        P,
+    >::min_num_rows_block(&block)
+    .0;
     let (k, circuit, instance) = SuperCircuit::<
         Fr,
         MAX_TXS,
         MAX_CALLDATA,
         MAX_INNER_BLOCKS,
         MOCK_RANDOMNESS,
-    >::build_from_witness_block(block)
+        P,
+    >::build_from_witness_block(block.clone())
     .unwrap();
     let prover = MockProver::run(k, &circuit, instance).unwrap();
 
     let res = prover.verify_at_rows_par(0..active_row_num, 0..active_row_num);
     if let Err(errs) = res {
-        error!("Verification failures: {:#?}", errs);
+        let mut cs = ConstraintSystem::default();
+        let config = SuperCircuit::<
+            Fr,
+            MAX_TXS,
+            MAX_CALLDATA,
+            MAX_INNER_BLOCKS,
+            MOCK_RANDOMNESS,
+            P,
+        >::configure(&mut cs);
+        let layout = config.evm_circuit.lookup_layout();
+        error!(
+            "Verification failures: {}",
+            FailureReport::new(&errs, Some(&block), Some(&layout)).render()
+        );
         prover.assert_satisfied_par();
         panic!("Failed verification");
     }
@@ -455,6 +549,78 @@ fn serial_test_super_circuit_2tx_2max_tx() {
     );
 }
 
+#[ignore]
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_super_circuit_1tx_without_precompile_sub_circuits() {
+    let block = block_1tx_trace();
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 256;
+    const MAX_INNER_BLOCKS: usize = 1;
+    let circuits_params = CircuitsParams {
+        max_txs: MAX_TXS,
+        max_calldata: MAX_CALLDATA,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_mpt_rows: 2049,
+        max_poseidon_rows: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+        max_inner_blocks: MAX_INNER_BLOCKS,
+        max_rlp_rows: 500,
+        sub_circuits: NoPrecompileParams::SUB_CIRCUITS,
+        ..Default::default()
+    };
+    test_super_circuit_with_params::<
+        MAX_TXS,
+        MAX_CALLDATA,
+        MAX_INNER_BLOCKS,
+        TEST_MOCK_RANDOMNESS,
+        NoPrecompileParams,
+    >(block, circuits_params);
+}
+
+#[ignore]
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_super_circuit_sub_circuits_mismatch() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 256;
+    const MAX_INNER_BLOCKS: usize = 1;
+    // the block is built with all the sub circuits, which the circuit leaves out
+    let circuits_params = CircuitsParams {
+        max_txs: MAX_TXS,
+        max_calldata: MAX_CALLDATA,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_mpt_rows: 2049,
+        max_poseidon_rows: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+        max_inner_blocks: MAX_INNER_BLOCKS,
+        max_rlp_rows: 500,
+        ..Default::default()
+    };
+    let block = witness_block(block_1tx_trace(), circuits_params);
+    let (k, circuit, instance) = SuperCircuit::<
+        Fr,
+        MAX_TXS,
+        MAX_CALLDATA,
+        MAX_INNER_BLOCKS,
+        TEST_MOCK_RANDOMNESS,
+        NoPrecompileParams,
+    >::build_from_witness_block(block)
+    .unwrap();
+    assert!(matches!(
+        MockProver::run(k, &circuit, instance),
+        Err(Error::Synthesis)
+    ));
+}
+
 #[cfg(feature = "scroll")]
 fn precomiple_super_circuits_params(max_txs: usize, max_calldata: usize) -> CircuitsParams {
     const MAX_RWS: usize = 4096;
@@ -5,7 +5,7 @@ use super::get_sign_data;
 pub use super::TxCircuit;
 
 use crate::{
-    sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
+    sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs, SigVerifyMode, MAX_NUM_SIG},
     table::{
         BlockTable, KeccakTable, PowOfRandTable, RlpFsmRlpTable as RlpTable, SigTable, TxTable,
         U16Table, U8Table,
@@ -76,6 +76,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
                 sig_table,
                 challenges: challenges.clone(),
                 keccak_table: keccak_table.clone(),
+                max_verif: MAX_NUM_SIG,
+                mode: SigVerifyMode::Individual,
             },
         );
         let tx_config = TxCircuitConfig::new(
@@ -187,6 +189,8 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
                     sig_table,
                     challenges: challenges.clone(),
                     keccak_table: keccak_table.clone(),
+                    max_verif: MAX_NUM_SIG,
+                    mode: SigVerifyMode::Individual,
                 },
             );
             let tx_config = TxCircuitConfig::new(
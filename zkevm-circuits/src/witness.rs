@@ -3,7 +3,7 @@
 //! used to generate witnesses for circuits.
 
 mod block;
-pub use block::{block_convert, Block, BlockContext, BlockContexts};
+pub use block::{block_convert, Block, BlockContext, BlockContexts, WITNESS_BLOCK_DUMP_VERSION};
 
 /// Keccak witness
 pub mod keccak;
@@ -14,6 +14,9 @@ pub use bytecode::Bytecode;
 mod call;
 pub use call::Call;
 
+/// Human-readable views of a witness block
+pub mod explorer;
+
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates, WithdrawProof};
 
@@ -35,4 +38,4 @@ pub use step::ExecStep;
 mod l1_msg;
 mod tx;
 
-pub use tx::Transaction;
+pub use tx::{tx_convert, Transaction};
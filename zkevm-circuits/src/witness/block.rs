@@ -1,5 +1,8 @@
 use ethers_core::types::Signature;
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
 #[cfg(any(feature = "test", test))]
 use crate::evm_circuit::{detect_fixed_table_tags, EvmCircuit};
@@ -15,6 +18,7 @@ use bus_mapping::{
         self, BigModExp, CircuitsParams, CopyEvent, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
         PrecompileEvents, SHA256,
     },
+    util::{dump_versioned, load_versioned},
     Error,
 };
 use eth_types::{
@@ -22,6 +26,7 @@ use eth_types::{
 };
 use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use super::{
     mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, ExecStep,
@@ -32,7 +37,7 @@ use crate::util::Challenges;
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
 /// data for witness generation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Block {
     /// Transactions in the block
     pub txs: Vec<Transaction>,
@@ -74,7 +79,7 @@ pub struct Block {
 }
 
 /// ...
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BlockContexts {
     /// Hashmap that maps block number to its block context.
     pub ctxs: BTreeMap<u64, BlockContext>,
@@ -82,7 +87,24 @@ pub struct BlockContexts {
     pub relax_mode: bool,
 }
 
+/// Format version of witness blocks written by [`Block::dump`]. Bump it whenever a change to the
+/// witness types makes older dumps unreadable.
+pub const WITNESS_BLOCK_DUMP_VERSION: u32 = 3;
+
 impl Block {
+    /// Dump the witness block to `path`, so that it can be reloaded with [`Block::load`] and
+    /// proved again without the original traces.
+    pub fn dump(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        dump_versioned(path, WITNESS_BLOCK_DUMP_VERSION, self)
+    }
+
+    /// Load a witness block written by [`Block::dump`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut block: Self = load_versioned(path, WITNESS_BLOCK_DUMP_VERSION)?;
+        block.mpt_updates.restore_proof_types();
+        Ok(block)
+    }
+
     /// The state root after this chunk
     pub fn post_state_root(&self) -> H256 {
         let post_state_root_in_trie = H256(self.mpt_updates.new_root().to_be_bytes());
@@ -327,7 +349,7 @@ impl Block {
 }
 
 /// Block context for execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockContext {
     /// The address of the miner for the block
     pub coinbase: Address,
@@ -339,6 +361,8 @@ pub struct BlockContext {
     pub timestamp: Word,
     /// The difficulty of the block
     pub difficulty: Word,
+    /// The prevrandao (mix hash) of the block
+    pub prevrandao: Word,
     /// The base fee, the minimum amount of gas fee for a transaction
     pub base_fee: Word,
     /// The hash of previous blocks
@@ -360,63 +384,66 @@ impl BlockContext {
     ) -> Vec<[Value<F>; 3]> {
         let current_block_number = self.number.to_scalar().unwrap();
         let randomness = challenges.evm_word();
-        [
-            vec![
-                [
-                    Value::known(F::from(BlockContextFieldTag::Coinbase as u64)),
-                    Value::known(current_block_number),
-                    Value::known(self.coinbase.to_scalar().unwrap()),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::Timestamp as u64)),
-                    Value::known(current_block_number),
-                    Value::known(self.timestamp.to_scalar().unwrap()),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::Number as u64)),
-                    Value::known(current_block_number),
-                    Value::known(current_block_number),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::Difficulty as u64)),
-                    Value::known(current_block_number),
-                    randomness.map(|rand| rlc::value(&self.difficulty.to_le_bytes(), rand)),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::GasLimit as u64)),
-                    Value::known(current_block_number),
-                    Value::known(F::from(self.gas_limit)),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::BaseFee as u64)),
-                    Value::known(current_block_number),
-                    randomness
-                        .map(|randomness| rlc::value(&self.base_fee.to_le_bytes(), randomness)),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::ChainId as u64)),
-                    Value::known(current_block_number),
-                    Value::known(F::from(self.chain_id)),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::NumTxs as u64)),
-                    Value::known(current_block_number),
-                    Value::known(F::from(num_txs as u64)),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::CumNumTxs as u64)),
-                    Value::known(current_block_number),
-                    Value::known(F::from(cum_num_txs as u64)),
-                ],
-                [
-                    Value::known(F::from(BlockContextFieldTag::NumAllTxs as u64)),
-                    Value::known(current_block_number),
-                    Value::known(F::from(num_all_txs)),
-                ],
+        #[allow(unused_mut)]
+        let mut assignments = vec![
+            [
+                Value::known(F::from(BlockContextFieldTag::Coinbase as u64)),
+                Value::known(current_block_number),
+                Value::known(self.coinbase.to_scalar().unwrap()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::Timestamp as u64)),
+                Value::known(current_block_number),
+                Value::known(self.timestamp.to_scalar().unwrap()),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::Number as u64)),
+                Value::known(current_block_number),
+                Value::known(current_block_number),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::Difficulty as u64)),
+                Value::known(current_block_number),
+                randomness.map(|rand| rlc::value(&self.difficulty.to_le_bytes(), rand)),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::GasLimit as u64)),
+                Value::known(current_block_number),
+                Value::known(F::from(self.gas_limit)),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::BaseFee as u64)),
+                Value::known(current_block_number),
+                randomness.map(|randomness| rlc::value(&self.base_fee.to_le_bytes(), randomness)),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::ChainId as u64)),
+                Value::known(current_block_number),
+                Value::known(F::from(self.chain_id)),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::NumTxs as u64)),
+                Value::known(current_block_number),
+                Value::known(F::from(num_txs as u64)),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::CumNumTxs as u64)),
+                Value::known(current_block_number),
+                Value::known(F::from(cum_num_txs as u64)),
+            ],
+            [
+                Value::known(F::from(BlockContextFieldTag::NumAllTxs as u64)),
+                Value::known(current_block_number),
+                Value::known(F::from(num_all_txs)),
             ],
-            self.block_hash_assignments(randomness),
-        ]
-        .concat()
+        ];
+        #[cfg(feature = "prevrandao")]
+        assignments.push([
+            Value::known(F::from(BlockContextFieldTag::PrevRandao as u64)),
+            Value::known(current_block_number),
+            randomness.map(|rand| rlc::value(&self.prevrandao.to_le_bytes(), rand)),
+        ]);
+        [assignments, self.block_hash_assignments(randomness)].concat()
     }
 
     fn block_hash_assignments<F: Field>(&self, randomness: Value<F>) -> Vec<[Value<F>; 3]> {
@@ -466,6 +493,7 @@ impl From<&circuit_input_builder::Block> for BlockContexts {
                             number: block.number,
                             timestamp: block.timestamp,
                             difficulty: block.difficulty,
+                            prevrandao: block.prevrandao,
                             base_fee: block.base_fee,
                             history_hashes: block.history_hashes.clone(),
                             chain_id: block.chain_id,
@@ -2,11 +2,12 @@ use crate::util::Field;
 use bus_mapping::evm::OpcodeId;
 use eth_types::{ToLittleEndian, Word};
 use halo2_proofs::circuit::Value;
+use serde::{Deserialize, Serialize};
 
 use crate::{evm_circuit::util::rlc, table::BytecodeFieldTag, util::Challenges};
 
 /// Bytecode
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Bytecode {
     /// Hash of bytecode
     pub hash: Word,
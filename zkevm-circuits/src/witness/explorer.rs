@@ -0,0 +1,397 @@
+//! Human-readable views of a witness [`Block`], used to debug circuit failures.
+//!
+//! `MockProver` failures only report regions and row offsets. The [`TraceExplorer`] renders the
+//! transactions, calls, steps, rw operations and copy events of a block, and maps an EVM circuit
+//! row back to the [`ExecStep`] that assigned it.
+
+use std::fmt::{self, Write};
+
+use bus_mapping::{circuit_input_builder::NumberOrHash, evm::OpcodeId};
+use strum::IntoEnumIterator;
+
+use crate::{
+    evm_circuit::{
+        execution::{chunking_fn, REGION1_MIN_CHUNK_SIZE, REGION2_MIN_CHUNK_SIZE},
+        step::ExecutionState,
+    },
+    table::RwTableTag,
+};
+
+use super::{Block, ExecStep};
+
+/// The step that assigned a row of the EVM circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmRowLocation {
+    /// Index of the transaction in `block.txs`. `None` for the padding and `EndBlock` rows.
+    pub tx_index: Option<usize>,
+    /// Index of the step in the transaction steps, or of the row among the padding rows.
+    pub step_index: usize,
+    /// Offset of the row within the rows of the step.
+    pub offset_in_step: usize,
+    /// Execution state of the step
+    pub execution_state: ExecutionState,
+    /// Opcode of the step, if any
+    pub opcode: Option<OpcodeId>,
+    /// Program counter of the step
+    pub program_counter: u64,
+    /// Id of the call the step belongs to
+    pub call_id: usize,
+    /// The Read/Write counter before the step
+    pub rw_counter: usize,
+}
+
+impl fmt::Display for EvmRowLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tx_index {
+            Some(tx_index) => write!(f, "tx[{}].step[{}]", tx_index, self.step_index)?,
+            None => write!(f, "{:?}[{}]", self.execution_state, self.step_index)?,
+        }
+        write!(
+            f,
+            " (+{}) {:?} opcode={} pc={} call_id={} rwc={}",
+            self.offset_in_step,
+            self.execution_state,
+            self.opcode
+                .map_or_else(|| "-".to_string(), |opcode| opcode.to_string()),
+            self.program_counter,
+            self.call_id,
+            self.rw_counter,
+        )
+    }
+}
+
+/// A region assigned by the EVM circuit, in assignment order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmRegion {
+    /// Name of the region, as passed to the layouter.
+    pub name: &'static str,
+    /// First row of the region, counted from the first EVM circuit step row.
+    pub start_row: usize,
+    /// Number of rows of the region.
+    pub height: usize,
+}
+
+/// Renders a witness [`Block`] and maps circuit rows back to its execution trace.
+#[derive(Debug)]
+pub struct TraceExplorer<'a> {
+    block: &'a Block,
+    /// `(first row, tx index, step index)` of every real step, in EVM circuit order.
+    step_rows: Vec<(usize, usize, usize)>,
+    /// Number of rows used by the real steps.
+    steps_height: usize,
+}
+
+impl<'a> TraceExplorer<'a> {
+    /// Create an explorer for the given block.
+    pub fn new(block: &'a Block) -> Self {
+        // Same layout as `ExecutionConfig::assign_block`.
+        let mut step_rows = Vec::new();
+        let mut offset = 0;
+        for (tx_idx, tx) in block.txs.iter().enumerate() {
+            for (step_idx, step) in tx.steps.iter().enumerate() {
+                step_rows.push((offset, tx_idx, step_idx));
+                offset += step.execution_state.get_step_height();
+            }
+        }
+        Self {
+            block,
+            step_rows,
+            steps_height: offset,
+        }
+    }
+
+    /// Number of padding rows between the real steps and the `EndBlock` step.
+    fn padding_height(&self) -> usize {
+        let evm_rows = self.block.circuits_params.max_evm_rows;
+        let end_block_height = ExecutionState::EndBlock.get_step_height() + 1;
+        if evm_rows == 0 {
+            1
+        } else {
+            evm_rows.saturating_sub(end_block_height + self.steps_height)
+        }
+    }
+
+    /// The regions the EVM circuit assigns for this block, in the same order and with the same
+    /// chunking as `ExecutionConfig::assign_block`.
+    pub fn evm_regions(&self) -> Vec<EvmRegion> {
+        let mut regions = vec![];
+        let (chunk_size, chunk_num) =
+            chunking_fn("region1", self.step_rows.len(), REGION1_MIN_CHUNK_SIZE);
+        for chunk_idx in 0..chunk_num {
+            let start_row = self.step_rows[chunk_idx * chunk_size].0;
+            let end_row = self
+                .step_rows
+                .get((chunk_idx + 1) * chunk_size)
+                .map_or(self.steps_height, |(row, _, _)| *row);
+            regions.push(EvmRegion {
+                name: "Execution step region1",
+                start_row,
+                height: end_row - start_row,
+            });
+        }
+        let padding_height = self.padding_height();
+        let (chunk_size, chunk_num) =
+            chunking_fn("region2", padding_height, REGION2_MIN_CHUNK_SIZE);
+        for chunk_idx in 0..chunk_num {
+            let begin = chunk_idx * chunk_size;
+            let end = ((chunk_idx + 1) * chunk_size).min(padding_height);
+            regions.push(EvmRegion {
+                name: "Execution step region2",
+                start_row: self.steps_height + begin,
+                height: end - begin,
+            });
+        }
+        regions.push(EvmRegion {
+            name: "Execution step region3",
+            start_row: self.steps_height + padding_height,
+            height: ExecutionState::EndBlock.get_step_height() + 1,
+        });
+        regions
+    }
+
+    /// Map a row of the EVM circuit, counted from its first step row, to the step that assigned
+    /// it. The real steps, the padding and the `EndBlock` step are laid out contiguously, so the
+    /// row of a failure in a chunked region is the sum of the preceding region heights plus the
+    /// offset inside the region.
+    pub fn locate_evm_row(&self, row: usize) -> Option<EvmRowLocation> {
+        if row < self.steps_height {
+            let idx = self
+                .step_rows
+                .partition_point(|(offset, _, _)| *offset <= row)
+                - 1;
+            let (offset, tx_idx, step_idx) = self.step_rows[idx];
+            let tx = &self.block.txs[tx_idx];
+            let step = &tx.steps[step_idx];
+            return Some(Self::location(
+                Some(tx_idx),
+                step_idx,
+                row - offset,
+                step,
+                tx.calls[step.call_index].id,
+            ));
+        }
+
+        let last_call_id = self
+            .block
+            .txs
+            .last()
+            .map(|tx| tx.calls[0].id)
+            .unwrap_or_default();
+        let padding_end = self.steps_height + self.padding_height();
+        if row < padding_end {
+            let step = &self.block.padding_step;
+            return Some(Self::location(
+                None,
+                row - self.steps_height,
+                0,
+                step,
+                last_call_id,
+            ));
+        }
+        // The last row after `EndBlock` is a dummy row only used for rotations.
+        let end_block_height = ExecutionState::EndBlock.get_step_height();
+        (row < padding_end + end_block_height).then(|| {
+            Self::location(
+                None,
+                0,
+                row - padding_end,
+                &self.block.end_block_step,
+                last_call_id,
+            )
+        })
+    }
+
+    fn location(
+        tx_index: Option<usize>,
+        step_index: usize,
+        offset_in_step: usize,
+        step: &ExecStep,
+        call_id: usize,
+    ) -> EvmRowLocation {
+        EvmRowLocation {
+            tx_index,
+            step_index,
+            offset_in_step,
+            execution_state: step.execution_state,
+            opcode: step.opcode,
+            program_counter: step.program_counter,
+            call_id,
+            rw_counter: step.rw_counter,
+        }
+    }
+
+    /// Render the transactions, their calls and steps. With `with_rws`, the rw operations of
+    /// every step are listed under it.
+    pub fn render_txs(&self, with_rws: bool) -> String {
+        let mut out = String::new();
+        let mut step_rows = self.step_rows.iter();
+        for (tx_idx, tx) in self.block.txs.iter().enumerate() {
+            writeln!(
+                out,
+                "tx[{}] id={} hash={:?} type={:?} from={:?} to={:?} gas={} steps={}",
+                tx_idx,
+                tx.id,
+                tx.hash,
+                tx.tx_type,
+                tx.caller_address,
+                tx.callee_address,
+                tx.gas,
+                tx.steps.len(),
+            )
+            .unwrap();
+            for call in tx.calls.iter() {
+                writeln!(
+                    out,
+                    "  call id={} caller_id={} depth={} {:?} -> {:?} is_create={} is_static={} \
+                     is_success={} is_persistent={}",
+                    call.id,
+                    call.caller_id,
+                    call.depth,
+                    call.caller_address,
+                    call.callee_address,
+                    call.is_create,
+                    call.is_static,
+                    call.is_success,
+                    call.is_persistent,
+                )
+                .unwrap();
+            }
+            for (step_idx, step) in tx.steps.iter().enumerate() {
+                let (row, _, _) = step_rows.next().expect("one row offset per step");
+                writeln!(
+                    out,
+                    "  {}",
+                    Self::location(
+                        Some(tx_idx),
+                        step_idx,
+                        0,
+                        step,
+                        tx.calls[step.call_index].id
+                    )
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "    row={} height={} gas_left={} gas_cost={} stack_pointer={} memory_size={}",
+                    row,
+                    step.execution_state.get_step_height(),
+                    step.gas_left,
+                    step.gas_cost,
+                    step.stack_pointer,
+                    step.memory_size,
+                )
+                .unwrap();
+                if with_rws {
+                    for (tag, idx) in step.rw_indices.iter() {
+                        writeln!(out, "    {:?}", self.block.rws[(*tag, *idx)]).unwrap();
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Render all rw operations of the block, grouped by [`RwTableTag`].
+    pub fn render_rws(&self) -> String {
+        let mut out = String::new();
+        for tag in RwTableTag::iter() {
+            let Some(rws) = self.block.rws.0.get(&tag) else {
+                continue;
+            };
+            writeln!(out, "{:?} ({} rows)", tag, rws.len()).unwrap();
+            for rw in rws.iter() {
+                writeln!(out, "  {:?}", rw).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Render the copy events of the block.
+    pub fn render_copy_events(&self) -> String {
+        let fmt_id = |id: &NumberOrHash| match id {
+            NumberOrHash::Number(n) => n.to_string(),
+            NumberOrHash::Hash(h) => format!("{:?}", h),
+        };
+        let mut out = String::new();
+        for (idx, event) in self.block.copy_events.iter().enumerate() {
+            writeln!(
+                out,
+                "copy[{}] {:?}({})[{}..{}] -> {:?}({})[{}..] bytes={} rwc={} log_id={:?}",
+                idx,
+                event.src_type,
+                fmt_id(&event.src_id),
+                event.src_addr,
+                event.src_addr_end,
+                event.dst_type,
+                fmt_id(&event.dst_id),
+                event.dst_addr,
+                event.copy_bytes.bytes.len(),
+                event.rw_counter_start.0,
+                event.log_id,
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use mock::TestContext;
+
+    #[test]
+    fn locate_evm_rows() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert(&builder.block, &builder.code_db).unwrap();
+        let explorer = TraceExplorer::new(&block);
+
+        let first = explorer.locate_evm_row(0).unwrap();
+        assert_eq!(first.tx_index, Some(0));
+        assert_eq!(first.execution_state, ExecutionState::BeginTx);
+
+        let mut row = ExecutionState::BeginTx.get_step_height();
+        let mut states = vec![];
+        while let Some(location) = explorer.locate_evm_row(row) {
+            if location.offset_in_step == 0 {
+                states.push(location.execution_state);
+            }
+            row += 1;
+        }
+        assert_eq!(
+            states,
+            vec![
+                ExecutionState::PUSH,
+                ExecutionState::PUSH,
+                ExecutionState::ADD_SUB,
+                ExecutionState::STOP,
+                ExecutionState::EndTx,
+                ExecutionState::Padding,
+                ExecutionState::EndBlock,
+            ]
+        );
+
+        let add_height = ExecutionState::ADD_SUB.get_step_height();
+        let add = explorer
+            .locate_evm_row(explorer.step_rows[3].0 + add_height - 1)
+            .unwrap();
+        assert_eq!(add.opcode, Some(OpcodeId::ADD));
+        assert_eq!(add.program_counter, 4);
+        assert_eq!(add.offset_in_step, add_height - 1);
+    }
+}
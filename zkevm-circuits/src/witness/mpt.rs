@@ -29,7 +29,7 @@ pub struct WithdrawProof {
 }
 
 /// An MPT update whose validity is proved by the MptCircuit
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MptUpdate {
     key: Key,
     old_value: Word,
@@ -38,6 +38,7 @@ pub struct MptUpdate {
     new_root: Word,
     // for debugging
     #[cfg(debug_assertions)]
+    #[serde(default)]
     original_rws: Vec<Rw>,
 }
 
@@ -92,19 +93,42 @@ impl Default for MptUpdate {
 }
 
 /// All the MPT updates in the MptCircuit, accessible by their key
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct MptUpdates {
     old_root: Word,
     new_root: Word,
+    #[serde(with = "updates_as_seq")]
     updates: BTreeMap<Key, MptUpdate>,
     /// TODO: is here the best place for this?
     /// Withdraw proof after this block
     pub withdraw_proof: WithdrawProof,
     /// The detailed mpt witness
     pub smt_traces: Vec<SMTTrace>,
+    /// Derived from `updates`, see `restore_proof_types`
+    #[serde(skip)]
     pub(crate) proof_types: Vec<MPTProofType>,
 }
 
+/// `Key` is not a string, so `updates` is stored as a list of pairs
+mod updates_as_seq {
+    use super::{Key, MptUpdate};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub(super) fn serialize<S: Serializer>(
+        updates: &BTreeMap<Key, MptUpdate>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(updates.iter())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<Key, MptUpdate>, D::Error> {
+        Vec::<(Key, MptUpdate)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
 /// The field element encoding of an MPT update, which is used by the MptTable
 #[derive(Debug, Clone, Copy)]
 pub struct MptUpdateRow<F>(pub(crate) [F; 7]);
@@ -122,6 +146,16 @@ impl MptUpdates {
         self.new_root
     }
 
+    /// Rebuild `proof_types` after deserialization. They are one per update, in key order,
+    /// once the state roots have been filled.
+    pub(crate) fn restore_proof_types(&mut self) {
+        self.proof_types = if self.smt_traces.is_empty() {
+            Vec::new()
+        } else {
+            self.updates.values().map(MptUpdate::proof_type).collect()
+        };
+    }
+
     pub(crate) fn get(&self, row: &Rw) -> Option<MptUpdate> {
         key(row).map(|key| {
             self.updates
@@ -422,7 +456,7 @@ impl MptUpdate {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 enum Key {
     Account {
         address: Address,
@@ -8,6 +8,7 @@ use bus_mapping::{
     operation,
     precompile::{PrecompileAuxData, PrecompileCalls},
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     evm_circuit::{
@@ -18,7 +19,7 @@ use crate::{
 };
 
 /// Step executed in a transaction
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecStep {
     /// The index in the Transaction calls
     pub call_index: usize,
@@ -47,6 +48,7 @@ pub struct ExecStep {
     /// The counter for log index within tx
     pub log_id: usize,
     /// The opcode corresponds to the step
+    #[serde(with = "eth_types::evm_types::opcode_ids::serde_opcode_byte::option")]
     pub opcode: Option<OpcodeId>,
     /// The block number in which this step exists.
     pub block_num: u64,
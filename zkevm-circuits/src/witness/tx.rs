@@ -36,12 +36,13 @@ use halo2_proofs::{
 use mock::MockTransaction;
 use num::Integer;
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::BTreeMap};
 
 use super::{step::step_convert, Call, ExecStep};
 
 /// Transaction in a witness block
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// The block number in which this tx is included in
     pub block_number: u64,
@@ -1206,7 +1207,9 @@ impl From<MockTransaction> for Transaction {
     }
 }
 
-pub(super) fn tx_convert(
+/// Convert a transaction of the circuit input builder into a witness transaction, with the
+/// given id in its block.
+pub fn tx_convert(
     tx: &circuit_input_builder::Transaction,
     id: usize,
     chain_id: u64,